@@ -0,0 +1,362 @@
+use crate::client::Claude;
+use crate::error::{Error, Result};
+use crate::request::{MessageRequest, MessageResponse};
+use chrono::{DateTime, Utc};
+use reqwest::Method;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::time::Duration;
+
+/// API endpoint for the Message Batches API
+pub const BATCHES_ENDPOINT: &str = "https://api.anthropic.com/v1/messages/batches";
+
+/// Interval [`Claude::wait_for_batch`] polls at unless told otherwise
+pub const DEFAULT_POLL_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Identifier of a message batch, e.g. "msgbatch_013Zva2CMHLNnXjNJJKqJ2EF"
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct BatchId(pub String);
+
+impl std::fmt::Display for BatchId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+/// Where a batch is in its lifecycle
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ProcessingStatus {
+    /// Requests are still being processed
+    InProgress,
+    /// Cancellation was requested and is taking effect
+    Canceling,
+    /// Every request has finished; results can be fetched
+    Ended,
+}
+
+/// How many of a batch's requests are in each state
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RequestCounts {
+    /// Requests still being processed
+    pub processing: u32,
+    /// Requests that produced a message
+    pub succeeded: u32,
+    /// Requests that failed
+    pub errored: u32,
+    /// Requests canceled before they were processed
+    pub canceled: u32,
+    /// Requests that weren't processed before the batch expired
+    pub expired: u32,
+}
+
+/// A batch of message requests, as reported by the API
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MessageBatch {
+    /// Batch identifier
+    pub id: BatchId,
+    /// Where the batch is in its lifecycle
+    pub processing_status: ProcessingStatus,
+    /// How many requests are in each state
+    pub request_counts: RequestCounts,
+    /// When the batch was created
+    pub created_at: DateTime<Utc>,
+    /// When processing ended, once it has
+    pub ended_at: Option<DateTime<Utc>>,
+    /// When unprocessed requests will expire
+    pub expires_at: DateTime<Utc>,
+    /// URL of the JSONL results, once the batch has ended
+    pub results_url: Option<String>,
+}
+
+impl MessageBatch {
+    /// Whether every request has finished
+    pub fn is_ended(&self) -> bool {
+        self.processing_status == ProcessingStatus::Ended
+    }
+}
+
+/// Outcome of one request in a batch
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum BatchOutcome {
+    /// The request produced a message
+    Succeeded {
+        /// Claude's response
+        message: MessageResponse,
+    },
+    /// The request failed; `error` is the API's error object
+    Errored {
+        /// Error details as returned by the API
+        error: Value,
+    },
+    /// The batch was canceled before the request was processed
+    Canceled,
+    /// The batch expired before the request was processed
+    Expired,
+}
+
+/// Result of one request in a batch, matched to it by `custom_id`
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BatchResult {
+    /// The custom id the request was submitted with
+    pub custom_id: String,
+    /// What happened to the request
+    pub result: BatchOutcome,
+}
+
+/// Parse a JSONL batch results file, skipping blank lines
+///
+/// # Example
+///
+/// ```rust
+/// use claude::batches::{parse_batch_results, BatchOutcome};
+///
+/// let jsonl = r#"{"custom_id":"doc-2","result":{"type":"errored","error":{"type":"error","error":{"type":"invalid_request_error","message":"max_tokens: Field required"}}}}
+/// {"custom_id":"doc-1","result":{"type":"expired"}}
+/// "#;
+/// let results = parse_batch_results(jsonl).unwrap();
+/// assert_eq!(results.len(), 2);
+/// assert_eq!(results[0].custom_id, "doc-2");
+/// assert!(matches!(results[0].result, BatchOutcome::Errored { .. }));
+/// assert!(matches!(results[1].result, BatchOutcome::Expired));
+///
+/// assert!(parse_batch_results("not json").is_err());
+/// ```
+pub fn parse_batch_results(jsonl: &str) -> Result<Vec<BatchResult>> {
+    jsonl
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(number, line)| {
+            serde_json::from_str(line).map_err(|e| {
+                Error::Other(format!(
+                    "Failed to parse batch result on line {}: {}",
+                    number + 1,
+                    e
+                ))
+            })
+        })
+        .collect()
+}
+
+impl Claude {
+    /// Submit requests for asynchronous processing as one batch
+    ///
+    /// Each request is paired with a custom id, unique within the batch, that
+    /// its [`BatchResult`] carries so results can be matched to requests.
+    /// Batches usually finish well within 24 hours at half the usual price.
+    ///
+    /// # Errors
+    ///
+    /// - [`Error::Other`] - If `requests` is empty or a custom id is repeated
+    /// - [`Error::Request`] - If the HTTP request fails
+    /// - [`Error::Api`] - If the API returns a structured error
+    /// - [`Error::Response`] - If the API returns some other non-success response
+    /// - [`Error::Parse`] - If the API response can't be parsed
+    ///
+    /// # Example
+    ///
+    /// Classifying documents, with the API mocked so the batch is still in
+    /// progress on the first poll:
+    ///
+    /// ```rust
+    /// use claude::batches::BatchOutcome;
+    /// use claude::test_util::MockTransport;
+    /// use claude::transport::RawResponse;
+    /// use claude::{Claude, Message, MessageRequest};
+    /// use serde_json::{json, Value};
+    /// use std::sync::Arc;
+    /// use std::time::Duration;
+    ///
+    /// let batch = |ended: bool| json!({
+    ///     "id": "msgbatch_1",
+    ///     "type": "message_batch",
+    ///     "processing_status": if ended { "ended" } else { "in_progress" },
+    ///     "request_counts": {"processing": if ended { 0 } else { 2 }, "succeeded": if ended { 1 } else { 0 },
+    ///                        "errored": if ended { 1 } else { 0 }, "canceled": 0, "expired": 0},
+    ///     "created_at": "2025-06-01T12:00:00Z",
+    ///     "ended_at": if ended { json!("2025-06-01T12:05:00Z") } else { json!(null) },
+    ///     "expires_at": "2025-06-02T12:00:00Z",
+    ///     "results_url": if ended {
+    ///         json!("https://api.anthropic.com/v1/messages/batches/msgbatch_1/results")
+    ///     } else {
+    ///         json!(null)
+    ///     }
+    /// });
+    /// let succeeded = json!({"custom_id": "doc-1", "result": {"type": "succeeded", "message": {
+    ///     "id": "msg_1", "model": "claude-3-5-haiku-20241022", "role": "assistant",
+    ///     "content": [{"type": "text", "text": "positive"}],
+    ///     "stop_reason": "end_turn", "stop_sequence": null,
+    ///     "usage": {"input_tokens": 20, "output_tokens": 2}
+    /// }}});
+    /// let errored = json!({"custom_id": "doc-2", "result": {"type": "errored", "error": {
+    ///     "type": "error", "error": {"type": "overloaded_error", "message": "Overloaded"}
+    /// }}});
+    ///
+    /// let transport = Arc::new(MockTransport::new());
+    /// transport.push_json(batch(false)); // create
+    /// transport.push_json(batch(false)); // first poll
+    /// transport.push_json(batch(true)); // second poll
+    /// transport.push_json(batch(true)); // status check before fetching results
+    /// transport.push_response(RawResponse::new(200, format!("{}\n{}\n", succeeded, errored)));
+    ///
+    /// let client = Claude::new("test-key".to_string(), "claude-3-5-haiku-20241022".to_string())
+    ///     .with_transport(transport.clone());
+    /// let classify = |text: &str| MessageRequest {
+    ///     model: client.model().to_string(),
+    ///     messages: vec![Message::user(vec![format!("Sentiment of: {}", text).into()])],
+    ///     tools: vec![],
+    ///     max_tokens: 10,
+    ///     system: None,
+    ///     temperature: None,
+    ///     tool_choice: None,
+    ///     thinking: None,
+    /// };
+    ///
+    /// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+    /// let id = client
+    ///     .create_batch(vec![
+    ///         ("doc-1".to_string(), classify("I love it")),
+    ///         ("doc-2".to_string(), classify("Meh")),
+    ///     ])
+    ///     .await?;
+    /// assert_eq!(id.to_string(), "msgbatch_1");
+    /// let create = &transport.requests()[0];
+    /// assert!(create.url.ends_with("/v1/messages/batches"));
+    /// let sent: Value = serde_json::from_str(&create.body_text()).unwrap();
+    /// assert_eq!(sent["requests"][1]["custom_id"], "doc-2");
+    /// assert_eq!(sent["requests"][1]["params"]["max_tokens"], 10);
+    ///
+    /// let batch = client.wait_for_batch(&id, Duration::from_millis(10)).await?;
+    /// assert!(batch.is_ended());
+    /// assert_eq!(batch.request_counts.succeeded, 1);
+    ///
+    /// let results = client.batch_results(&id).await?;
+    /// assert_eq!(results.len(), 2);
+    /// match &results[0].result {
+    ///     BatchOutcome::Succeeded { message } => assert_eq!(message.text(), "positive"),
+    ///     other => panic!("expected success, got {:?}", other),
+    /// }
+    /// assert_eq!(results[1].custom_id, "doc-2");
+    /// assert!(matches!(results[1].result, BatchOutcome::Errored { .. }));
+    /// assert!(transport.requests()[4].url.ends_with("/msgbatch_1/results"));
+    ///
+    /// // Duplicate custom ids are caught before anything is sent
+    /// let duplicate = vec![("a".to_string(), classify("x")), ("a".to_string(), classify("y"))];
+    /// assert!(client.create_batch(duplicate).await.is_err());
+    /// assert_eq!(transport.requests().len(), 5);
+    /// # Ok::<(), claude::Error>(())
+    /// # }).unwrap();
+    /// ```
+    pub async fn create_batch(&self, requests: Vec<(String, MessageRequest)>) -> Result<BatchId> {
+        if requests.is_empty() {
+            return Err(Error::Other(
+                "A batch needs at least one request".to_string(),
+            ));
+        }
+        let mut seen = std::collections::HashSet::new();
+        if let Some((custom_id, _)) = requests.iter().find(|(id, _)| !seen.insert(id)) {
+            return Err(Error::Other(format!(
+                "Custom id '{}' is used by more than one request in the batch",
+                custom_id
+            )));
+        }
+
+        let headers = self.build_request_headers(requests.iter().map(|(_, request)| request))?;
+        let body = json!({
+            "requests": requests
+                .iter()
+                .map(|(custom_id, params)| json!({"custom_id": custom_id, "params": params}))
+                .collect::<Vec<_>>(),
+        });
+
+        let response = self
+            .send(
+                Method::POST,
+                self.url(BATCHES_ENDPOINT),
+                headers,
+                Some(serde_json::to_vec(&body)?),
+            )
+            .await?;
+
+        let batch: MessageBatch = serde_json::from_str(&response.body)?;
+        Ok(batch.id)
+    }
+
+    /// Fetch the current status of a batch
+    ///
+    /// See [`create_batch`](Self::create_batch) for an example.
+    pub async fn get_batch(&self, id: &BatchId) -> Result<MessageBatch> {
+        let response = self
+            .send(
+                Method::GET,
+                format!("{}/{}", self.url(BATCHES_ENDPOINT), id),
+                self.build_headers()?,
+                None,
+            )
+            .await?;
+
+        Ok(serde_json::from_str(&response.body)?)
+    }
+
+    /// Poll a batch every `poll_interval` until it has ended
+    ///
+    /// See [`DEFAULT_POLL_INTERVAL`] for a sensible interval.
+    pub async fn wait_for_batch(
+        &self,
+        id: &BatchId,
+        poll_interval: Duration,
+    ) -> Result<MessageBatch> {
+        loop {
+            let batch = self.get_batch(id).await?;
+            if batch.is_ended() {
+                return Ok(batch);
+            }
+            tokio::time::sleep(poll_interval).await;
+        }
+    }
+
+    /// Ask the API to stop processing a batch
+    ///
+    /// Requests already being processed still finish; the rest end up
+    /// [`BatchOutcome::Canceled`].
+    pub async fn cancel_batch(&self, id: &BatchId) -> Result<MessageBatch> {
+        let response = self
+            .send(
+                Method::POST,
+                format!("{}/{}/cancel", self.url(BATCHES_ENDPOINT), id),
+                self.build_headers()?,
+                None,
+            )
+            .await?;
+
+        Ok(serde_json::from_str(&response.body)?)
+    }
+
+    /// Download the results of an ended batch
+    ///
+    /// Results are not necessarily in submission order; match them to
+    /// requests by [`BatchResult::custom_id`].
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Other`] if the batch hasn't ended yet, besides the
+    /// errors of [`get_batch`](Self::get_batch).
+    pub async fn batch_results(&self, id: &BatchId) -> Result<Vec<BatchResult>> {
+        let batch = self.get_batch(id).await?;
+        let results_url = batch.results_url.ok_or_else(|| {
+            Error::Other(format!(
+                "Batch {} has not ended yet ({} requests still processing)",
+                id, batch.request_counts.processing
+            ))
+        })?;
+
+        let response = self
+            .send(Method::GET, results_url, self.build_headers()?, None)
+            .await?;
+
+        parse_batch_results(&response.body)
+    }
+}
@@ -58,7 +58,10 @@ impl ChatUI {
         println!("{}", "Available tools:".yellow());
         println!("  • {} - Apply patches/diffs to files", "patch_file".cyan());
         println!("  • {} - Read content from files", "read_file".cyan());
+        println!("  • {} - Write whole files", "write_file".cyan());
+        #[cfg(feature = "search")]
         println!("  • {} - List directory contents", "list_directory".cyan());
+        println!("  • {} - Inspect git repositories", "git".cyan());
         println!("  • {} - Execute bash commands", "bash".cyan());
         println!("  • {} - Get system information", "system_info".cyan());
         println!(
@@ -82,26 +85,30 @@ impl ChatUI {
             "  • {} - Search Wikipedia articles and get summaries",
             "wikipedia".cyan()
         );
+        #[cfg(feature = "z3")]
         println!(
             "  • {} - Z3 SMT/SAT constraint solver for logic and optimization",
             "z3_solver".cyan()
         );
-        println!(
-            "  • {} - Crawl websites and extract content using Firecrawl",
-            "firecrawl_crawl".cyan()
-        );
-        println!(
-            "  • {} - Search the web using Firecrawl's search API",
-            "firecrawl_search".cyan()
-        );
-        println!(
-            "  • {} - Map website structure using Firecrawl",
-            "firecrawl_map".cyan()
-        );
-        println!(
-            "  • {} - Extract structured data from web pages using Firecrawl",
-            "firecrawl_extract".cyan()
-        );
+        #[cfg(feature = "firecrawl")]
+        {
+            println!(
+                "  • {} - Crawl websites and extract content using Firecrawl",
+                "firecrawl_crawl".cyan()
+            );
+            println!(
+                "  • {} - Search the web using Firecrawl's search API",
+                "firecrawl_search".cyan()
+            );
+            println!(
+                "  • {} - Map website structure using Firecrawl",
+                "firecrawl_map".cyan()
+            );
+            println!(
+                "  • {} - Extract structured data from web pages using Firecrawl",
+                "firecrawl_extract".cyan()
+            );
+        }
         println!();
         println!(
             "{} {}",
@@ -1,14 +1,284 @@
 use crate::error::{Error, Result};
-use crate::message::{ContentBlock, Message};
-use crate::request::{MessageRequest, MessageResponse};
-use crate::tool::ToolRegistry;
+use crate::events::TurnEvent;
+use crate::execution::ExecutionState;
+use crate::message::{ContentBlock, Message, ToolResultContent, ToolUse};
+use crate::models::{ModelInfo, ModelPage};
+use crate::request::{
+    MessageRequest, MessageResponse, ThinkingConfig, ToolChoice, ToolPairing, Usage,
+};
+use crate::retry::RetryPolicy;
+use crate::tool::{call_key, ToolRegistry};
+use crate::tools::ThinkingBudget;
+use crate::transport::{RawRequest, RawResponse, ReqwestTransport, Transport};
 use reqwest::header::{HeaderMap, HeaderValue};
-use serde_json::Value;
+use reqwest::Method;
+use serde_json::{json, Value};
 use std::collections::HashMap;
+use std::fmt;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::mpsc;
+use tokio_util::sync::CancellationToken;
+
+/// Default base URL of the Anthropic API
+pub const API_BASE_URL: &str = "https://api.anthropic.com";
 
 /// API endpoint for the Claude Messages API
 pub const MESSAGES_ENDPOINT: &str = "https://api.anthropic.com/v1/messages";
 
+/// API endpoint for counting the input tokens of a Messages API request
+pub const COUNT_TOKENS_ENDPOINT: &str = "https://api.anthropic.com/v1/messages/count_tokens";
+
+/// API endpoint listing the available models
+pub const MODELS_ENDPOINT: &str = "https://api.anthropic.com/v1/models";
+
+/// Model used by [`Claude::from_env`] when `ANTHROPIC_MODEL` isn't set
+pub const DEFAULT_MODEL: &str = "claude-sonnet-4-20250514";
+
+/// Environment variables [`Claude::from_env`] reads the API key from, in order
+pub const API_KEY_VARS: &[&str] = &["ANTHROPIC_API_KEY", "CLAUDE_API_KEY"];
+
+/// Output tokens allowed per request of a turn unless [`TurnOptions::max_tokens`] is set
+const DEFAULT_MAX_TOKENS: u32 = 4096;
+
+/// User message sent to continue a response that was cut off by `max_tokens`
+const CONTINUE_PROMPT: &str = "Continue exactly where you left off.";
+
+/// `anthropic-beta` header value enabling prompt caching
+pub const PROMPT_CACHING_BETA: &str = "prompt-caching-2024-07-31";
+
+/// `anthropic-beta` header value enabling PDF document blocks
+pub const PDFS_BETA: &str = "pdfs-2024-09-25";
+
+/// `anthropic-beta` header value enabling the `count_tokens` endpoint on API
+/// deployments where it is still in beta
+pub const TOKEN_COUNTING_BETA: &str = "token-counting-2024-11-01";
+
+/// Header carrying a key unique to each logical POST request
+///
+/// Retries under the client's [`RetryPolicy`] resend the same key, while a new
+/// request gets a fresh one.
+pub const IDEMPOTENCY_KEY_HEADER: &str = "idempotency-key";
+
+/// Options controlling a single conversation turn
+///
+/// Used with [`Claude::run_conversation_turn_with_options`]. All fields default to
+/// the same behaviour as [`Claude::run_conversation_turn`].
+///
+/// # Example
+///
+/// ```rust
+/// use claude::{ToolChoice, TurnOptions};
+///
+/// let options = TurnOptions {
+///     system_prompt: Some("Answer without using tools.".to_string()),
+///     tool_choice: Some(ToolChoice::None),
+///     ..Default::default()
+/// };
+/// assert_eq!(options.max_iterations, None);
+/// ```
+///
+/// With `max_continuations`, a text response cut off by `max_tokens` is
+/// continued and the pieces joined, until a response ends on its own or the
+/// limit is used up:
+///
+/// ```rust
+/// use claude::test_util::MockTransport;
+/// use claude::{Claude, ToolRegistry, TurnOptions};
+/// use serde_json::{json, Value};
+/// use std::sync::Arc;
+///
+/// let transport = Arc::new(MockTransport::new());
+/// transport.push_message(json!([{"type": "text", "text": "The quick brown "}]), "max_tokens");
+/// transport.push_message(json!([{"type": "text", "text": "fox jumps"}]), "end_turn");
+/// let client = Claude::new("test-key".to_string(), "claude-3-5-haiku-20241022".to_string())
+///     .with_transport(transport.clone());
+/// let mut registry = ToolRegistry::new();
+/// let options = TurnOptions {
+///     max_continuations: Some(2),
+///     ..Default::default()
+/// };
+///
+/// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+/// let outcome = client
+///     .run_conversation_turn_detailed("Write a pangram", &mut registry, None, &options)
+///     .await?;
+/// assert_eq!(outcome.text, "The quick brown fox jumps");
+/// assert!(!outcome.is_truncated());
+/// assert_eq!(outcome.iterations, 2);
+///
+/// // The second request asks Claude to pick up where it stopped
+/// let second: Value = serde_json::from_str(&transport.requests()[1].body_text()).unwrap();
+/// assert_eq!(second["messages"][1]["content"][0]["text"], "The quick brown ");
+/// assert_eq!(second["messages"][2]["role"], "user");
+///
+/// // Once the limit is used up, the turn ends with whatever was generated
+/// for piece in ["one ", "two ", "three "] {
+///     transport.push_message(json!([{"type": "text", "text": piece}]), "max_tokens");
+/// }
+/// let outcome = client
+///     .run_conversation_turn_detailed("Count", &mut registry, None, &options)
+///     .await?;
+/// assert_eq!(outcome.text, "one two three ");
+/// assert!(outcome.is_truncated());
+/// assert_eq!(outcome.iterations, 3);
+/// assert_eq!(transport.remaining(), 0);
+/// # Ok::<(), claude::Error>(())
+/// # }).unwrap();
+/// ```
+///
+/// A `tool_use` block cut off by `max_tokens` has incomplete input, so it is
+/// never run or continued; the turn fails instead:
+///
+/// ```rust
+/// use claude::test_util::MockTransport;
+/// use claude::tools::BashTool;
+/// use claude::{Claude, ToolRegistry, TurnOptions};
+/// use serde_json::json;
+/// use std::sync::Arc;
+///
+/// let transport = Arc::new(MockTransport::new());
+/// transport.push_message(
+///     json!([{"type": "tool_use", "id": "toolu_1", "name": "bash", "input": {}}]),
+///     "max_tokens",
+/// );
+/// let client = Claude::new("test-key".to_string(), "claude-3-5-haiku-20241022".to_string())
+///     .with_transport(transport.clone());
+/// let mut registry = ToolRegistry::new();
+/// registry.register(Arc::new(BashTool::new()))?;
+/// let options = TurnOptions {
+///     max_continuations: Some(2),
+///     ..Default::default()
+/// };
+///
+/// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+/// let Err(err) = client
+///     .run_conversation_turn_detailed("List the files", &mut registry, None, &options)
+///     .await
+/// else {
+///     panic!("expected the turn to fail");
+/// };
+/// assert!(err.to_string().contains("max_tokens while generating input for tool 'bash'"));
+/// assert!(registry.execution_history().is_empty());
+/// assert_eq!(transport.requests().len(), 1);
+/// # Ok::<(), claude::Error>(())
+/// # }).unwrap();
+/// # Ok::<(), claude::Error>(())
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct TurnOptions {
+    /// Optional system prompt to guide Claude's behavior
+    pub system_prompt: Option<String>,
+    /// Maximum tool execution rounds (default: 10)
+    pub max_iterations: Option<usize>,
+    /// Maximum output tokens per request, not counting any thinking budget
+    /// (default: 4096)
+    pub max_tokens: Option<u32>,
+    /// Sampling temperature for every request of the turn (default: the API's)
+    pub temperature: Option<f32>,
+    /// Optional control over whether and which tools Claude must use
+    pub tool_choice: Option<ToolChoice>,
+    /// How many times to ask Claude to continue a response cut off by
+    /// `max_tokens`, concatenating the pieces (default: 0, disabled)
+    pub max_continuations: Option<usize>,
+    /// Optional extended thinking for every request of the turn
+    pub thinking: Option<ThinkingConfig>,
+    /// Budget handle shared with a [`ThinkTool`](crate::tools::ThinkTool); a
+    /// pending request overrides `thinking` for this turn and is consumed
+    pub thinking_budget: Option<ThinkingBudget>,
+    /// What to do with unmatched `tool_use`/`tool_result` blocks in the
+    /// history before each request (default: send it unchecked)
+    pub tool_pairing: ToolPairing,
+    /// Send `tool_choice: none` on the last allowed iteration, so that a turn
+    /// which keeps calling tools ends with a text answer instead of
+    /// [`Error::MaxIterations`] (default: false)
+    pub final_answer_nudge: bool,
+    /// `anthropic-beta` values sent with this turn's requests instead of the
+    /// client's [`beta_headers`](Claude::beta_headers) (default: the client's)
+    pub beta_headers: Option<Vec<String>>,
+    /// Run the tool calls of each response concurrently with
+    /// [`ToolRegistry::execute_tools_parallel`] instead of one after another;
+    /// results still go back in the order Claude made the calls (default: false)
+    pub parallel_tools: bool,
+    /// Token that stops the turn once cancelled: it is passed to every tool
+    /// through [`Tool::execute_with_cancel`](crate::Tool::execute_with_cancel),
+    /// and no further request is sent (default: never cancelled)
+    pub cancel: Option<CancellationToken>,
+}
+
+/// Result of a conversation turn with usage details
+///
+/// Returned by [`Claude::run_conversation_turn_detailed`].
+#[derive(Debug, Clone)]
+pub struct TurnOutcome {
+    /// Claude's final text response
+    pub text: String,
+    /// Token usage summed over every request made during the turn
+    pub total_usage: Usage,
+    /// Number of requests sent to the API
+    pub iterations: usize,
+    /// Number of tool calls Claude made
+    pub tool_calls: usize,
+    /// Stop reason of the final response (`"max_tokens"` if the text was cut off)
+    pub stop_reason: String,
+}
+
+impl TurnOutcome {
+    /// Check if the final text was cut off by `max_tokens`
+    pub fn is_truncated(&self) -> bool {
+        self.stop_reason == "max_tokens"
+    }
+}
+
+/// A secret, such as an API key, that is never shown in formatted output
+///
+/// Both `Debug` and `Display` print `sk-***`, so a client or error that holds
+/// one can be logged safely. Use [`expose`](Self::expose) where the real value
+/// is needed.
+///
+/// # Example
+///
+/// ```rust
+/// use claude::SecretString;
+///
+/// let key = SecretString::new("sk-ant-api03-abcdef");
+/// assert_eq!(format!("{:?}", key), "\"sk-***\"");
+/// assert_eq!(key.to_string(), "sk-***");
+/// assert_eq!(key.expose(), "sk-ant-api03-abcdef");
+/// ```
+#[derive(Clone, PartialEq, Eq)]
+pub struct SecretString(String);
+
+impl SecretString {
+    /// Wrap a secret value
+    pub fn new(secret: impl Into<String>) -> Self {
+        Self(secret.into())
+    }
+
+    /// The secret value itself
+    pub fn expose(&self) -> &str {
+        &self.0
+    }
+}
+
+impl From<String> for SecretString {
+    fn from(secret: String) -> Self {
+        Self(secret)
+    }
+}
+
+impl fmt::Debug for SecretString {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("\"sk-***\"")
+    }
+}
+
+impl fmt::Display for SecretString {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("sk-***")
+    }
+}
+
 /// Claude API client for interacting with Anthropic's AI models
 ///
 /// The main entry point for using the Claude API. This struct handles authentication,
@@ -24,14 +294,64 @@ pub const MESSAGES_ENDPOINT: &str = "https://api.anthropic.com/v1/messages";
 ///     "claude-3-haiku-20240307".to_string()
 /// );
 /// ```
+///
+/// The API key is redacted from the client's `Debug` output and from errors:
+///
+/// ```rust
+/// use claude::{Claude, Message, MessageRequest};
+///
+/// // A newline makes the key an invalid header value
+/// let client = Claude::new(
+///     "sk-ant-api03-secret\n".to_string(),
+///     "claude-3-haiku-20240307".to_string()
+/// );
+/// let debug = format!("{:?}", client);
+/// assert!(debug.contains("api_key: \"sk-***\""));
+/// assert!(!debug.contains("secret"));
+///
+/// let request = MessageRequest {
+///     model: client.model().to_string(),
+///     messages: vec![Message::user(vec!["Hello!".to_string().into()])],
+///     tools: vec![],
+///     max_tokens: 1024,
+///     system: None,
+///     temperature: None,
+///     tool_choice: None,
+///     thinking: None,
+/// };
+/// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+/// let err = client.count_tokens(&request).await.unwrap_err();
+/// assert!(matches!(err, claude::Error::Header(_)));
+/// assert!(!err.to_string().contains("secret"));
+/// assert!(!format!("{:?}", err).contains("secret"));
+/// # });
+/// ```
 #[derive(Clone)]
 pub struct Claude {
     /// Anthropic API key
-    api_key: String,
-    /// HTTP client for making API requests
-    client: reqwest::Client,
+    api_key: SecretString,
+    /// Sends HTTP requests, [`ReqwestTransport`] unless overridden
+    transport: Arc<dyn Transport>,
     /// Default Claude model to use for requests
     model: String,
+    /// Base URL requests are sent to, [`API_BASE_URL`] unless overridden
+    base_url: String,
+    /// `anthropic-beta` values sent with every request
+    beta_headers: Vec<String>,
+    /// When to resend a request that failed with a retryable error
+    retry_policy: RetryPolicy,
+}
+
+impl fmt::Debug for Claude {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Claude")
+            .field("api_key", &self.api_key)
+            .field("model", &self.model)
+            .field("base_url", &self.base_url)
+            .field("beta_headers", &self.beta_headers)
+            .field("retry_policy", &self.retry_policy)
+            .finish_non_exhaustive()
+    }
 }
 
 impl Claude {
@@ -60,17 +380,266 @@ impl Claude {
     /// ```
     pub fn new(api_key: String, model: String) -> Self {
         Self {
-            api_key,
-            client: reqwest::Client::new(),
+            api_key: api_key.into(),
+            transport: Arc::new(ReqwestTransport::new()),
             model,
+            base_url: API_BASE_URL.to_string(),
+            beta_headers: Vec::new(),
+            retry_policy: RetryPolicy::none(),
         }
     }
 
+    /// Create a client configured from environment variables
+    ///
+    /// The API key comes from the first of [`API_KEY_VARS`] that is set:
+    /// `ANTHROPIC_API_KEY`, then `CLAUDE_API_KEY`. The model comes from
+    /// `ANTHROPIC_MODEL`, defaulting to [`DEFAULT_MODEL`], and requests go to
+    /// `ANTHROPIC_BASE_URL` if it is set. Empty variables count as unset.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error naming the variables it looked for if no API key is set
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use claude::{Claude, DEFAULT_MODEL};
+    /// use std::env;
+    ///
+    /// for var in ["ANTHROPIC_API_KEY", "CLAUDE_API_KEY", "ANTHROPIC_MODEL", "ANTHROPIC_BASE_URL"] {
+    ///     env::remove_var(var);
+    /// }
+    /// let err = Claude::from_env().unwrap_err();
+    /// assert!(err.to_string().contains("ANTHROPIC_API_KEY, CLAUDE_API_KEY"));
+    ///
+    /// env::set_var("CLAUDE_API_KEY", "sk-claude");
+    /// let client = Claude::from_env()?;
+    /// assert_eq!(client.model(), DEFAULT_MODEL);
+    ///
+    /// // ANTHROPIC_API_KEY takes precedence over CLAUDE_API_KEY
+    /// env::set_var("ANTHROPIC_API_KEY", "sk-anthropic");
+    /// env::set_var("ANTHROPIC_MODEL", "claude-3-5-haiku-latest");
+    /// env::set_var("ANTHROPIC_BASE_URL", "http://localhost:8080/");
+    /// let client = Claude::from_env()?;
+    /// assert_eq!(client.model(), "claude-3-5-haiku-latest");
+    /// assert_eq!(client.api_key().expose(), "sk-anthropic");
+    ///
+    /// // An empty variable is skipped
+    /// env::set_var("ANTHROPIC_API_KEY", "");
+    /// assert_eq!(Claude::from_env()?.api_key().expose(), "sk-claude");
+    /// # Ok::<(), claude::Error>(())
+    /// ```
+    pub fn from_env() -> Result<Self> {
+        let var = |name: &str| std::env::var(name).ok().filter(|value| !value.is_empty());
+        let api_key = API_KEY_VARS
+            .iter()
+            .find_map(|name| var(name))
+            .ok_or_else(|| {
+                Error::Other(format!(
+                    "No API key found; set one of {}",
+                    API_KEY_VARS.join(", ")
+                ))
+            })?;
+        let model = var("ANTHROPIC_MODEL").unwrap_or_else(|| DEFAULT_MODEL.to_string());
+
+        let client = Self::new(api_key, model);
+        Ok(match var("ANTHROPIC_BASE_URL") {
+            Some(base_url) => client.with_base_url(base_url),
+            None => client,
+        })
+    }
+
+    /// Use `model` by default instead of the model given at construction
+    pub fn with_model(mut self, model: impl Into<String>) -> Self {
+        self.model = model.into();
+        self
+    }
+
+    /// Send requests to `base_url` instead of [`API_BASE_URL`]
+    ///
+    /// Useful for proxies and for testing against a mock server.
+    pub fn with_base_url(mut self, base_url: impl Into<String>) -> Self {
+        self.base_url = base_url.into().trim_end_matches('/').to_string();
+        self
+    }
+
+    /// Send requests through `transport` instead of a default [`ReqwestTransport`]
+    ///
+    /// See [`MockTransport`](crate::test_util::MockTransport) for testing
+    /// without a network.
+    pub fn with_transport(mut self, transport: Arc<dyn Transport>) -> Self {
+        self.transport = transport;
+        self
+    }
+
+    /// Send `betas` in the `anthropic-beta` header of every request
+    ///
+    /// The values are joined with commas into a single header, which is left
+    /// out when there are none. Betas the request itself needs are added
+    /// automatically and needn't be listed:
+    ///
+    /// - [`PROMPT_CACHING_BETA`] when a system prompt or tool has `cache_control`
+    /// - [`PDFS_BETA`] when a message contains a document block
+    ///
+    /// Pass [`TOKEN_COUNTING_BETA`] if [`count_tokens`](Self::count_tokens) is
+    /// rejected by a deployment where the endpoint is still in beta. A single
+    /// turn can use its own list through [`TurnOptions::beta_headers`], and a
+    /// single request through [`next_message_with_betas`](Self::next_message_with_betas).
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use claude::test_util::MockTransport;
+    /// use claude::{Claude, Message, MessageRequest, TurnOptions, ToolRegistry};
+    /// use serde_json::json;
+    /// use std::sync::Arc;
+    ///
+    /// let transport = Arc::new(MockTransport::new());
+    /// for _ in 0..4 {
+    ///     transport.push_message(json!([{"type": "text", "text": "Hi!"}]), "end_turn");
+    /// }
+    /// let beta = |i: usize| {
+    ///     transport.requests()[i]
+    ///         .headers
+    ///         .get("anthropic-beta")
+    ///         .map(|value| value.to_str().unwrap().to_string())
+    /// };
+    /// let request = || {
+    ///     MessageRequest::builder("claude-3-5-haiku-20241022")
+    ///         .message(Message::user(vec!["Hello".to_string().into()]))
+    ///         .build()
+    /// };
+    ///
+    /// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+    /// // No betas, no header
+    /// let client = Claude::new("test-key".to_string(), "claude-3-5-haiku-20241022".to_string())
+    ///     .with_transport(transport.clone());
+    /// client.next_message(request()).await?;
+    /// assert_eq!(beta(0), None);
+    ///
+    /// // Several betas share one header
+    /// let client = client.with_beta_headers(vec![
+    ///     "token-counting-2024-11-01".to_string(),
+    ///     "output-128k-2025-02-19".to_string(),
+    /// ]);
+    /// client.next_message(request()).await?;
+    /// assert_eq!(beta(1).as_deref(), Some("token-counting-2024-11-01,output-128k-2025-02-19"));
+    ///
+    /// // A single request can replace the list...
+    /// client.next_message_with_betas(request(), &["interleaved-thinking-2025-05-14".to_string()]).await?;
+    /// assert_eq!(beta(2).as_deref(), Some("interleaved-thinking-2025-05-14"));
+    ///
+    /// // ...and so can a turn, here with an empty list
+    /// let options = TurnOptions {
+    ///     beta_headers: Some(vec![]),
+    ///     ..Default::default()
+    /// };
+    /// client
+    ///     .run_conversation_turn_with_options("Hello", &mut ToolRegistry::new(), None, &options)
+    ///     .await?;
+    /// assert_eq!(beta(3), None);
+    /// # Ok::<(), claude::Error>(())
+    /// # })?;
+    /// # Ok::<(), claude::Error>(())
+    /// ```
+    pub fn with_beta_headers(mut self, betas: Vec<String>) -> Self {
+        self.beta_headers = betas;
+        self
+    }
+
+    /// `anthropic-beta` values sent with every request
+    pub fn beta_headers(&self) -> &[String] {
+        &self.beta_headers
+    }
+
+    /// Retry requests that fail with a retryable error according to `policy`
+    ///
+    /// A new client doesn't retry. Each retried POST resends the
+    /// [`IDEMPOTENCY_KEY_HEADER`] of its first attempt.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use claude::test_util::MockTransport;
+    /// use claude::{Claude, Message, MessageRequest, RawResponse, RetryPolicy, IDEMPOTENCY_KEY_HEADER};
+    /// use serde_json::json;
+    /// use std::sync::Arc;
+    /// use std::time::Duration;
+    ///
+    /// let transport = Arc::new(MockTransport::new());
+    /// let overloaded = r#"{"type": "error", "error": {"type": "overloaded_error", "message": "Overloaded"}}"#;
+    /// transport.push_response(RawResponse::new(529, overloaded));
+    /// transport.push_response(RawResponse::new(529, overloaded));
+    /// transport.push_message(json!([{"type": "text", "text": "Hi!"}]), "end_turn");
+    /// transport.push_message(json!([{"type": "text", "text": "Hi again!"}]), "end_turn");
+    ///
+    /// let policy = RetryPolicy {
+    ///     max_retries: 2,
+    ///     initial_backoff: Duration::from_millis(1),
+    ///     max_backoff: Duration::from_millis(5),
+    /// };
+    /// let client = Claude::new("test-key".to_string(), "claude-3-5-haiku-20241022".to_string())
+    ///     .with_transport(transport.clone())
+    ///     .with_retry_policy(policy);
+    /// assert_eq!(client.retry_policy(), policy);
+    /// let request = || {
+    ///     MessageRequest::builder("claude-3-5-haiku-20241022")
+    ///         .message(Message::user(vec!["Hello".to_string().into()]))
+    ///         .build()
+    /// };
+    ///
+    /// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+    /// // Two overloaded responses, then success on the last retry
+    /// client.next_message(request()).await?;
+    /// client.next_message(request()).await?;
+    ///
+    /// let keys: Vec<String> = transport
+    ///     .requests()
+    ///     .iter()
+    ///     .map(|r| r.headers[IDEMPOTENCY_KEY_HEADER].to_str().unwrap().to_string())
+    ///     .collect();
+    /// assert_eq!(keys.len(), 4);
+    ///
+    /// // Retries reuse the first attempt's key; the next request gets its own
+    /// assert_eq!(keys[0], keys[1]);
+    /// assert_eq!(keys[1], keys[2]);
+    /// assert_ne!(keys[2], keys[3]);
+    ///
+    /// // Errors that can't succeed on a retry are returned at once
+    /// transport.push_response(RawResponse::new(400, r#"{"type": "error", "error": {"type": "invalid_request_error", "message": "Bad"}}"#));
+    /// assert!(client.next_message(request()).await.is_err());
+    /// assert_eq!(transport.requests().len(), 5);
+    /// # Ok::<(), claude::Error>(())
+    /// # }).unwrap();
+    /// ```
+    pub fn with_retry_policy(mut self, policy: RetryPolicy) -> Self {
+        self.retry_policy = policy;
+        self
+    }
+
+    /// When requests that fail with a retryable error are resent
+    pub fn retry_policy(&self) -> RetryPolicy {
+        self.retry_policy
+    }
+
     /// Get the model name for this client
     pub fn model(&self) -> &str {
         &self.model
     }
 
+    /// Get the API key for this client
+    pub fn api_key(&self) -> &SecretString {
+        &self.api_key
+    }
+
+    /// `endpoint` with the default API host replaced by this client's base URL
+    pub(crate) fn url(&self, endpoint: &str) -> String {
+        match endpoint.strip_prefix(API_BASE_URL) {
+            Some(path) => format!("{}{}", self.base_url, path),
+            None => endpoint.to_string(),
+        }
+    }
+
     /// Send a message to the Claude API
     ///
     /// Makes a direct request to the Anthropic Messages API using the provided MessageRequest.
@@ -89,7 +658,8 @@ impl Claude {
     ///
     /// - [`Error::Header`] - If the API key header can't be created
     /// - [`Error::Request`] - If the HTTP request fails
-    /// - [`Error::Response`] - If the API returns a non-success status code
+    /// - [`Error::Api`] - If the API returns a structured error
+    /// - [`Error::Response`] - If the API returns some other non-success response
     /// - [`Error::Parse`] - If the API response can't be parsed
     ///
     /// # Example
@@ -114,6 +684,8 @@ impl Claude {
     ///     max_tokens: 1024,
     ///     system: None,
     ///     temperature: None,
+    ///     tool_choice: None,
+    ///     thinking: None,
     /// };
     ///
     /// let response = client.next_message(request).await?;
@@ -121,15 +693,377 @@ impl Claude {
     /// # }
     /// ```
     pub async fn next_message(&self, request: MessageRequest) -> Result<MessageResponse> {
+        self.next_message_with_betas(request, &self.beta_headers)
+            .await
+    }
+
+    /// Send a message with `betas` in place of the client's
+    /// [`beta_headers`](Self::beta_headers)
+    ///
+    /// Betas the request itself needs are still added. See
+    /// [`with_beta_headers`](Self::with_beta_headers).
+    #[tracing::instrument(skip_all, fields(model = %request.model))]
+    pub async fn next_message_with_betas(
+        &self,
+        request: MessageRequest,
+        betas: &[String],
+    ) -> Result<MessageResponse> {
+        let body = serde_json::to_vec(&request)?;
+        tracing::debug!(request_bytes = body.len(), "sending request");
+        let started = Instant::now();
+
+        let result = self
+            .send(
+                Method::POST,
+                self.url(MESSAGES_ENDPOINT),
+                self.headers_with_betas(betas, [&request])?,
+                Some(body),
+            )
+            .await;
+        let latency_ms = started.elapsed().as_millis() as u64;
+        let response = result.inspect_err(
+            |e| tracing::warn!(status = e.status_code(), latency_ms, error = %e, "request failed"),
+        )?;
+        let status = response.status;
+
+        let response_text = response.body;
+        let message_response: MessageResponse = serde_json::from_str(&response_text)?;
+
+        let usage = message_response.usage.as_ref();
+        tracing::info!(
+            status,
+            latency_ms,
+            input_tokens = usage.map(|u| u.input_tokens),
+            output_tokens = usage.map(|u| u.output_tokens),
+            stop_reason = %message_response.stop_reason,
+            "response received"
+        );
+
+        Ok(message_response)
+    }
+
+    /// Count the input tokens a request would consume without sending it
+    ///
+    /// Posts the request's model, messages, tools, and system prompt to the
+    /// `count_tokens` endpoint. Use this to check whether a conversation will fit
+    /// in the context window before calling [`next_message`](Self::next_message).
+    ///
+    /// # Errors
+    ///
+    /// - [`Error::Header`] - If the API key header can't be created
+    /// - [`Error::Request`] - If the HTTP request fails
+    /// - [`Error::Api`] - If the API returns a structured error
+    /// - [`Error::Response`] - If the API returns some other non-success response
+    /// - [`Error::Parse`] - If the API response can't be parsed
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use claude::test_util::MockTransport;
+    /// use claude::{Claude, Message, MessageRequest, COUNT_TOKENS_ENDPOINT, TOKEN_COUNTING_BETA};
+    /// use serde_json::{json, Value};
+    /// use std::sync::Arc;
+    ///
+    /// let transport = Arc::new(MockTransport::new());
+    /// transport.push_json(json!({"input_tokens": 1234}));
+    /// let client = Claude::new("test-key".to_string(), "claude-3-5-haiku-20241022".to_string())
+    ///     .with_transport(transport.clone())
+    ///     .with_beta_headers(vec![TOKEN_COUNTING_BETA.to_string()]);
+    ///
+    /// let request = MessageRequest::builder(client.model())
+    ///     .system("Be brief.")
+    ///     .message(Message::user(vec!["Hello!".to_string().into()]))
+    ///     .max_tokens(1024)
+    ///     .build();
+    ///
+    /// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+    /// let tokens = client.count_tokens(&request).await?;
+    /// assert_eq!(tokens, 1234);
+    ///
+    /// let sent = &transport.requests()[0];
+    /// assert_eq!(sent.url, COUNT_TOKENS_ENDPOINT);
+    /// assert_eq!(sent.headers["anthropic-beta"], TOKEN_COUNTING_BETA);
+    /// let body: Value = serde_json::from_str(&sent.body_text()).unwrap();
+    /// assert_eq!(body, json!({
+    ///     "model": "claude-3-5-haiku-20241022",
+    ///     "system": "Be brief.",
+    ///     "messages": [{"role": "user", "content": [{"type": "text", "text": "Hello!"}]}]
+    /// }));
+    ///
+    /// // `max_tokens` isn't counted; the request is sent as it is once it fits
+    /// if tokens < 200_000 {
+    ///     transport.push_message(json!([{"type": "text", "text": "Hi!"}]), "end_turn");
+    ///     client.next_message(request).await?;
+    /// }
+    /// # Ok::<(), claude::Error>(())
+    /// # }).unwrap();
+    /// ```
+    pub async fn count_tokens(&self, request: &MessageRequest) -> Result<usize> {
+        let response = self
+            .send(
+                Method::POST,
+                self.url(COUNT_TOKENS_ENDPOINT),
+                self.build_request_headers([request])?,
+                Some(serde_json::to_vec(&Self::count_tokens_body(request))?),
+            )
+            .await?;
+
+        let response_text = response.body;
+        Self::parse_token_count(&response_text)
+    }
+
+    /// List the models available to this API key, newest first
+    ///
+    /// Follows the endpoint's pagination until every model has been fetched.
+    /// Combine with [`model_capabilities`](crate::models::model_capabilities)
+    /// to find out what each model supports.
+    ///
+    /// # Errors
+    ///
+    /// - [`Error::Header`] - If the API key header can't be created
+    /// - [`Error::Request`] - If the HTTP request fails
+    /// - [`Error::Api`] - If the API returns a structured error
+    /// - [`Error::Response`] - If the API returns some other non-success response
+    /// - [`Error::Parse`] - If the API response can't be parsed
+    ///
+    /// # Example
+    ///
+    /// Listing models from a mock transport that returns two pages:
+    ///
+    /// ```rust
+    /// use claude::test_util::MockTransport;
+    /// use claude::Claude;
+    /// use serde_json::json;
+    /// use std::sync::Arc;
+    ///
+    /// let model = |id: &str, name: &str| json!({
+    ///     "type": "model", "id": id, "display_name": name,
+    ///     "created_at": "2025-05-22T00:00:00Z"
+    /// });
+    /// let transport = Arc::new(MockTransport::new());
+    /// transport.push_json(json!({
+    ///     "data": [model("claude-sonnet-4-20250514", "Claude Sonnet 4")],
+    ///     "has_more": true,
+    ///     "last_id": "claude-sonnet-4-20250514"
+    /// }));
+    /// transport.push_json(json!({
+    ///     "data": [model("claude-3-5-haiku-20241022", "Claude Haiku 3.5")],
+    ///     "has_more": false,
+    ///     "last_id": "claude-3-5-haiku-20241022"
+    /// }));
+    ///
+    /// let client = Claude::new("test-key".to_string(), "claude-sonnet-4-20250514".to_string())
+    ///     .with_transport(transport.clone());
+    /// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+    /// let models = client.list_models().await?;
+    ///
+    /// let ids: Vec<&str> = models.iter().map(|m| m.id.as_str()).collect();
+    /// assert_eq!(ids, ["claude-sonnet-4-20250514", "claude-3-5-haiku-20241022"]);
+    /// assert_eq!(models[0].display_name, "Claude Sonnet 4");
+    /// assert!(models[0].capabilities().unwrap().supports_thinking);
+    /// assert!(!models[1].capabilities().unwrap().supports_thinking);
+    ///
+    /// // The second page starts after the last model of the first
+    /// let requests = transport.requests();
+    /// assert_eq!(requests.len(), 2);
+    /// assert!(!requests[0].url.contains("after_id"));
+    /// assert!(requests[1].url.contains("after_id=claude-sonnet-4-20250514"));
+    /// # Ok::<(), claude::Error>(())
+    /// # }).unwrap();
+    /// ```
+    pub async fn list_models(&self) -> Result<Vec<ModelInfo>> {
+        let mut models = Vec::new();
+        let mut after_id: Option<String> = None;
+
+        loop {
+            let mut url = format!("{}?limit=1000", self.url(MODELS_ENDPOINT));
+            if let Some(after_id) = &after_id {
+                url.push_str(&format!("&after_id={}", urlencoding::encode(after_id)));
+            }
+
+            let response = self
+                .send(Method::GET, url, self.build_headers()?, None)
+                .await?;
+            let response_text = response.body;
+            let page: ModelPage = serde_json::from_str(&response_text)?;
+            models.extend(page.data);
+
+            match page.last_id {
+                Some(last_id) if page.has_more => after_id = Some(last_id),
+                _ => return Ok(models),
+            }
+        }
+    }
+
+    /// Check that the API key works and the client's model is available
+    ///
+    /// Fetches the model from the models endpoint, which costs no tokens. Call
+    /// it before starting a session to fail early with a clear message.
+    ///
+    /// # Errors
+    ///
+    /// - [`Error::Api`] with status 401 or 403 - If the API key is rejected;
+    ///   the message says which environment variables to check
+    /// - [`Error::Api`] with status 404 - If the model doesn't exist
+    /// - Any other error [`list_models`](Self::list_models) can return, e.g.
+    ///   [`Error::Request`] if the API can't be reached
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use claude::test_util::MockTransport;
+    /// use claude::transport::RawResponse;
+    /// use claude::{Claude, Error};
+    /// use serde_json::json;
+    /// use std::sync::Arc;
+    ///
+    /// let transport = Arc::new(MockTransport::new());
+    /// transport.push_json(json!({
+    ///     "type": "model",
+    ///     "id": "claude-3-5-haiku-20241022",
+    ///     "display_name": "Claude Haiku 3.5",
+    ///     "created_at": "2024-10-22T00:00:00Z"
+    /// }));
+    /// transport.push_response(RawResponse::new(
+    ///     401,
+    ///     r#"{"type": "error", "error": {"type": "authentication_error", "message": "invalid x-api-key"}}"#,
+    /// ));
+    /// transport.push_error(Error::Other("connection refused".to_string()));
+    ///
+    /// let client = Claude::new("sk-ant-test".to_string(), "claude-3-5-haiku-20241022".to_string())
+    ///     .with_transport(transport.clone());
+    /// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+    /// client.health_check().await?;
+    /// assert!(transport.requests()[0].url.ends_with("/v1/models/claude-3-5-haiku-20241022"));
+    ///
+    /// match client.health_check().await.unwrap_err() {
+    ///     Error::Api { status, error_type, message, .. } => {
+    ///         assert_eq!(status, 401);
+    ///         assert_eq!(error_type, "authentication_error");
+    ///         assert!(message.contains("invalid x-api-key"), "{}", message);
+    ///         assert!(message.contains("ANTHROPIC_API_KEY"), "{}", message);
+    ///     }
+    ///     other => panic!("expected an API error, got {:?}", other),
+    /// }
+    ///
+    /// let err = client.health_check().await.unwrap_err();
+    /// assert_eq!(err.to_string(), "connection refused");
+    /// # Ok::<(), claude::Error>(())
+    /// # })?;
+    /// # Ok::<(), claude::Error>(())
+    /// ```
+    pub async fn health_check(&self) -> Result<()> {
+        let url = format!(
+            "{}/{}",
+            self.url(MODELS_ENDPOINT),
+            urlencoding::encode(&self.model)
+        );
+        match self
+            .send(Method::GET, url, self.build_headers()?, None)
+            .await
+        {
+            Ok(_) => Ok(()),
+            Err(Error::Api {
+                status: status @ (401 | 403),
+                error_type,
+                message,
+                request_id,
+            }) => Err(Error::Api {
+                status,
+                error_type,
+                message: format!(
+                    "The API key was rejected ({}). Check {}",
+                    message,
+                    API_KEY_VARS.join(" or ")
+                ),
+                request_id,
+            }),
+            Err(Error::Api {
+                status: 404,
+                error_type,
+                message,
+                request_id,
+            }) => Err(Error::Api {
+                status: 404,
+                error_type,
+                message: format!(
+                    "Model '{}' is not available to this API key ({})",
+                    self.model, message
+                ),
+                request_id,
+            }),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Build the JSON body accepted by the `count_tokens` endpoint
+    ///
+    /// The endpoint rejects generation settings such as `max_tokens`, so only the
+    /// fields that contribute to the prompt are included.
+    fn count_tokens_body(request: &MessageRequest) -> Value {
+        let mut body = json!({
+            "model": request.model,
+            "messages": request.messages,
+        });
+        if !request.tools.is_empty() {
+            body["tools"] = json!(request.tools);
+        }
+        if let Some(system) = &request.system {
+            body["system"] = json!(system);
+        }
+        body
+    }
+
+    /// Extract `input_tokens` from a `count_tokens` response body
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use claude::Claude;
+    /// let tokens = Claude::parse_token_count(r#"{"input_tokens": 1234}"#).unwrap();
+    /// assert_eq!(tokens, 1234);
+    ///
+    /// assert!(Claude::parse_token_count(r#"{"unexpected": true}"#).is_err());
+    /// ```
+    pub fn parse_token_count(body: &str) -> Result<usize> {
+        let value: Value = serde_json::from_str(body)?;
+        value
+            .get("input_tokens")
+            .and_then(|t| t.as_u64())
+            .map(|t| t as usize)
+            .ok_or_else(|| Error::Other("count_tokens response missing 'input_tokens'".to_string()))
+    }
+
+    /// Build the headers required by every Anthropic API request, plus the
+    /// client's `anthropic-beta` values
+    pub(crate) fn build_headers(&self) -> Result<HeaderMap> {
+        self.headers_with_betas(&self.beta_headers, [])
+    }
+
+    /// Standard headers plus the `anthropic-beta` features `requests` rely on
+    pub(crate) fn build_request_headers<'a>(
+        &self,
+        requests: impl IntoIterator<Item = &'a MessageRequest>,
+    ) -> Result<HeaderMap> {
+        self.headers_with_betas(&self.beta_headers, requests)
+    }
+
+    /// Standard headers, with `configured` and the betas `requests` rely on joined
+    /// into one `anthropic-beta` header
+    fn headers_with_betas<'a>(
+        &self,
+        configured: &[String],
+        requests: impl IntoIterator<Item = &'a MessageRequest>,
+    ) -> Result<HeaderMap> {
         // According to Anthropic docs, we need three headers:
         let mut headers = HeaderMap::new();
 
-        // 1. x-api-key
-        headers.insert(
-            "x-api-key",
-            HeaderValue::from_str(&self.api_key)
-                .map_err(|_| Error::Header("Failed to create x-api-key header".to_string()))?,
-        );
+        // 1. x-api-key, marked sensitive so it's redacted from debug output.
+        // The error deliberately leaves out the value that failed to parse.
+        let mut api_key = HeaderValue::from_str(self.api_key.expose())
+            .map_err(|_| Error::Header("Failed to create x-api-key header".to_string()))?;
+        api_key.set_sensitive(true);
+        headers.insert("x-api-key", api_key);
 
         // 2. content-type
         headers.insert("content-type", HeaderValue::from_static("application/json"));
@@ -137,42 +1071,161 @@ impl Claude {
         // 3. anthropic-version
         headers.insert("anthropic-version", HeaderValue::from_static("2023-06-01"));
 
-        let response = self
-            .client
-            .post(MESSAGES_ENDPOINT)
-            .headers(headers)
-            .json(&request)
-            .send()
-            .await?;
+        // 4. anthropic-beta, only if some beta is in use
+        let mut betas: Vec<&str> = Vec::new();
+        for beta in configured.iter().map(String::as_str) {
+            if !betas.contains(&beta) {
+                betas.push(beta);
+            }
+        }
+        for request in requests {
+            if request.uses_prompt_caching() && !betas.contains(&PROMPT_CACHING_BETA) {
+                betas.push(PROMPT_CACHING_BETA);
+            }
+            if request.uses_documents() && !betas.contains(&PDFS_BETA) {
+                betas.push(PDFS_BETA);
+            }
+        }
+        if !betas.is_empty() {
+            headers.insert(
+                "anthropic-beta",
+                HeaderValue::from_str(&betas.join(",")).map_err(|_| {
+                    Error::Header("Failed to create anthropic-beta header".to_string())
+                })?,
+            );
+        }
 
-        if !response.status().is_success() {
-            let status = response.status();
-            let text = response
-                .text()
-                .await
-                .unwrap_or_else(|_| "Unknown error".to_string());
-
-            // Try to parse error details from response
-            if let Ok(error_json) = serde_json::from_str::<Value>(&text) {
-                if let Some(error_msg) = error_json
-                    .get("error")
-                    .and_then(|e| e.get("message"))
-                    .and_then(|m| m.as_str())
-                {
-                    return Err(Error::Response(
-                        error_msg.to_string(),
-                        Some(status.as_u16()),
-                    ));
-                }
+        Ok(headers)
+    }
+
+    /// Send a request through the transport, turning non-success responses
+    /// into an [`Error`]
+    ///
+    /// Retryable failures are retried under the client's [`RetryPolicy`]. A
+    /// POST gets an [`IDEMPOTENCY_KEY_HEADER`] that every attempt shares.
+    pub(crate) async fn send(
+        &self,
+        method: Method,
+        url: String,
+        mut headers: HeaderMap,
+        body: Option<Vec<u8>>,
+    ) -> Result<RawResponse> {
+        if method == Method::POST {
+            let key = uuid::Uuid::new_v4().to_string();
+            headers.insert(
+                IDEMPOTENCY_KEY_HEADER,
+                HeaderValue::from_str(&key).map_err(|_| {
+                    Error::Header("Failed to create idempotency-key header".to_string())
+                })?,
+            );
+        }
+        let request = RawRequest {
+            method,
+            url,
+            headers,
+            body,
+        };
+
+        let mut retry = 0;
+        loop {
+            let (error, retry_after) = match self.transport.send(request.clone()).await {
+                Ok(response) if response.is_success() => return Ok(response),
+                Ok(response) => (
+                    Self::error_from_response(&response),
+                    Self::retry_after(&response),
+                ),
+                Err(error) => (error, None),
+            };
+            if retry >= self.retry_policy.max_retries || !error.is_retryable() {
+                return Err(error);
             }
 
-            return Err(Error::Response(text, Some(status.as_u16())));
+            let delay = retry_after
+                .unwrap_or_else(|| self.retry_policy.backoff(retry))
+                .min(self.retry_policy.max_backoff);
+            tracing::warn!(
+                retry = retry + 1,
+                delay_ms = delay.as_millis() as u64,
+                error = %error,
+                "retrying request"
+            );
+            tokio::time::sleep(delay).await;
+            retry += 1;
         }
+    }
 
-        let response_text = response.text().await?;
-        let message_response: MessageResponse = serde_json::from_str(&response_text)?;
+    /// The wait a `retry-after` header asks for, if it gives one in seconds
+    fn retry_after(response: &RawResponse) -> Option<Duration> {
+        let seconds = response.headers.get("retry-after")?.to_str().ok()?;
+        seconds.trim().parse().ok().map(Duration::from_secs)
+    }
 
-        Ok(message_response)
+    /// Convert a non-success HTTP response into an [`Error`]
+    pub(crate) fn error_from_response(response: &RawResponse) -> Error {
+        let request_id = response
+            .headers
+            .get("request-id")
+            .and_then(|v| v.to_str().ok())
+            .map(|v| v.to_string());
+
+        Self::parse_api_error(response.status, &response.body, request_id)
+    }
+
+    /// Build an [`Error`] from an error response's status, body, and `request-id`
+    ///
+    /// Bodies of the form `{"type": "error", "error": {"type": ..., "message": ...}}`
+    /// become [`Error::Api`]; anything else becomes [`Error::Response`] with the
+    /// raw body.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use claude::{Claude, Error};
+    ///
+    /// let body = r#"{
+    ///     "type": "error",
+    ///     "error": {
+    ///         "type": "invalid_request_error",
+    ///         "message": "max_tokens: Field required"
+    ///     }
+    /// }"#;
+    /// match Claude::parse_api_error(400, body, Some("req_011CSHoEeqs5C35K2UUqR7Fy".to_string())) {
+    ///     Error::Api { status, error_type, message, request_id } => {
+    ///         assert_eq!(status, 400);
+    ///         assert_eq!(error_type, "invalid_request_error");
+    ///         assert_eq!(message, "max_tokens: Field required");
+    ///         assert_eq!(request_id.as_deref(), Some("req_011CSHoEeqs5C35K2UUqR7Fy"));
+    ///     }
+    ///     other => panic!("expected an API error, got {:?}", other),
+    /// }
+    ///
+    /// let body = r#"{"type": "error", "error": {"type": "overloaded_error", "message": "Overloaded"}}"#;
+    /// let error = Claude::parse_api_error(529, body, None);
+    /// assert!(matches!(&error, Error::Api { error_type, .. } if error_type == "overloaded_error"));
+    /// assert_eq!(error.to_string(), "API error (status 529, overloaded_error): Overloaded");
+    ///
+    /// // Anything else keeps the raw body
+    /// let error = Claude::parse_api_error(502, "<html>Bad Gateway</html>", None);
+    /// assert!(matches!(error, Error::Response(body, Some(502)) if body.contains("Bad Gateway")));
+    /// ```
+    pub fn parse_api_error(status: u16, body: &str, request_id: Option<String>) -> Error {
+        let details = serde_json::from_str::<Value>(body).ok().and_then(|json| {
+            let error = json.get("error")?;
+            Some((
+                error.get("type")?.as_str()?.to_string(),
+                error.get("message")?.as_str()?.to_string(),
+            ))
+        });
+
+        match details {
+            Some((error_type, message)) => Error::Api {
+                status,
+                error_type,
+                message,
+                request_id,
+            },
+            None => Error::Response(body.to_string(), Some(status)),
+        }
     }
 
     /// Run a complete conversation turn with automatic tool handling
@@ -210,7 +1263,7 @@ impl Claude {
     /// );
     ///
     /// let mut registry = ToolRegistry::new();
-    /// registry.register(Arc::new(WeatherTool))?;
+    /// registry.register(Arc::new(WeatherTool::new()))?;
     ///
     /// let response = client.run_conversation_turn(
     ///     "What's the weather in London?",
@@ -232,76 +1285,704 @@ impl Claude {
         conversation_history: Option<Vec<Message>>,
         max_iterations: Option<usize>,
     ) -> Result<String> {
-        let max_iterations = max_iterations.unwrap_or(10);
+        let options = TurnOptions {
+            system_prompt: system_prompt.map(|s| s.to_string()),
+            max_iterations,
+            ..Default::default()
+        };
+        self.run_turn(
+            user_message,
+            tool_registry,
+            conversation_history,
+            &options,
+            None,
+        )
+        .await
+        .map(|outcome| outcome.text)
+    }
+
+    /// Run a complete conversation turn with additional request options
+    ///
+    /// Like [`run_conversation_turn`](Self::run_conversation_turn), but takes a
+    /// [`TurnOptions`] so that settings such as `tool_choice` can be controlled.
+    ///
+    /// A forcing `tool_choice` ([`ToolChoice::Any`] or [`ToolChoice::Tool`]) only
+    /// applies to the first request of the turn; follow-up requests after tool
+    /// results use automatic tool choice so that Claude can produce a final answer.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// use claude::{Claude, ToolChoice, ToolRegistry, TurnOptions};
+    ///
+    /// let client = Claude::new(
+    ///     "your-api-key".to_string(),
+    ///     "claude-3-haiku-20240307".to_string()
+    /// );
+    /// let mut registry = ToolRegistry::new();
+    ///
+    /// let options = TurnOptions {
+    ///     tool_choice: Some(ToolChoice::Tool { name: "z3_solver".to_string() }),
+    ///     ..Default::default()
+    /// };
+    /// let response = client.run_conversation_turn_with_options(
+    ///     "Is x > 2 && x < 1 satisfiable?",
+    ///     &mut registry,
+    ///     None,
+    ///     &options,
+    /// ).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// The options go into every request of the turn:
+    ///
+    /// ```rust
+    /// use claude::test_util::MockTransport;
+    /// use claude::{Claude, ToolRegistry, TurnOptions};
+    /// use serde_json::{json, Value};
+    /// use std::sync::Arc;
+    ///
+    /// let transport = Arc::new(MockTransport::new());
+    /// transport.push_message(json!([{"type": "text", "text": "Arr, hello!"}]), "end_turn");
+    /// let client = Claude::new("test-key".to_string(), "claude-3-5-haiku-20241022".to_string())
+    ///     .with_transport(transport.clone());
+    /// let options = TurnOptions {
+    ///     system_prompt: Some("You are a pirate.".to_string()),
+    ///     max_tokens: Some(256),
+    ///     temperature: Some(0.5),
+    ///     ..Default::default()
+    /// };
+    ///
+    /// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+    /// let text = client
+    ///     .run_conversation_turn_with_options("Hello", &mut ToolRegistry::new(), None, &options)
+    ///     .await?;
+    /// assert_eq!(text, "Arr, hello!");
+    ///
+    /// let sent: Value = serde_json::from_str(&transport.requests()[0].body_text()).unwrap();
+    /// assert_eq!(sent["max_tokens"], 256);
+    /// assert_eq!(sent["temperature"], 0.5);
+    /// assert_eq!(sent["system"], "You are a pirate.");
+    /// # Ok::<(), claude::Error>(())
+    /// # }).unwrap();
+    /// ```
+    ///
+    /// A turn that would call tools forever ends in [`Error::MaxIterations`],
+    /// unless `final_answer_nudge` makes the last request forbid tools:
+    ///
+    /// ```rust
+    /// use async_trait::async_trait;
+    /// use claude::test_util::MockTransport;
+    /// use claude::{Claude, Error, Tool, ToolRegistry, TurnOptions};
+    /// use serde_json::{json, Value};
+    /// use std::sync::Arc;
+    ///
+    /// struct Lookup;
+    ///
+    /// #[async_trait]
+    /// impl Tool for Lookup {
+    ///     fn name(&self) -> &str { "lookup" }
+    ///     fn description(&self) -> &str { "Looks something up" }
+    ///     fn input_schema(&self) -> Value { json!({"type": "object"}) }
+    ///     async fn execute(&self, _input: Value) -> Result<String, claude::Error> {
+    ///         Ok("Nothing found".to_string())
+    ///     }
+    /// }
+    ///
+    /// let transport = Arc::new(MockTransport::new());
+    /// let call_tool = |id: &str| {
+    ///     transport.push_message(
+    ///         json!([{"type": "tool_use", "id": id, "name": "lookup", "input": {}}]),
+    ///         "tool_use",
+    ///     )
+    /// };
+    /// let client = Claude::new("test-key".to_string(), "claude-3-5-haiku-20241022".to_string())
+    ///     .with_transport(transport.clone());
+    /// let mut registry = ToolRegistry::new();
+    /// registry.register(Arc::new(Lookup))?;
+    ///
+    /// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+    /// // Every response calls the tool
+    /// call_tool("toolu_1");
+    /// call_tool("toolu_2");
+    /// call_tool("toolu_3");
+    /// let mut options = TurnOptions {
+    ///     max_iterations: Some(3),
+    ///     ..Default::default()
+    /// };
+    /// let err = client
+    ///     .run_conversation_turn_with_options("Find it", &mut registry, None, &options)
+    ///     .await
+    ///     .unwrap_err();
+    /// assert!(matches!(
+    ///     &err,
+    ///     Error::MaxIterations { iterations: 3, last_tool: Some(tool) } if tool == "lookup"
+    /// ));
+    ///
+    /// // With the nudge, the third request forbids tools and gets an answer
+    /// call_tool("toolu_4");
+    /// call_tool("toolu_5");
+    /// transport.push_message(json!([{"type": "text", "text": "I couldn't find it."}]), "end_turn");
+    /// options.final_answer_nudge = true;
+    /// let text = client
+    ///     .run_conversation_turn_with_options("Find it", &mut registry, None, &options)
+    ///     .await?;
+    /// assert_eq!(text, "I couldn't find it.");
+    ///
+    /// let last: Value = serde_json::from_str(&transport.requests()[5].body_text()).unwrap();
+    /// assert_eq!(last["tool_choice"]["type"], "none");
+    /// # Ok::<(), claude::Error>(())
+    /// # }).unwrap();
+    /// # Ok::<(), claude::Error>(())
+    /// ```
+    ///
+    /// With `parallel_tools`, the calls of one response run at the same time:
+    ///
+    /// ```rust
+    /// use async_trait::async_trait;
+    /// use claude::test_util::MockTransport;
+    /// use claude::{Claude, Tool, ToolRegistry, TurnOptions};
+    /// use serde_json::{json, Value};
+    /// use std::sync::Arc;
+    /// use std::time::{Duration, Instant};
+    ///
+    /// struct Slow;
+    ///
+    /// #[async_trait]
+    /// impl Tool for Slow {
+    ///     fn name(&self) -> &str { "slow" }
+    ///     fn description(&self) -> &str { "Takes a while" }
+    ///     fn input_schema(&self) -> Value { json!({"type": "object"}) }
+    ///     async fn execute(&self, input: Value) -> Result<String, claude::Error> {
+    ///         tokio::time::sleep(Duration::from_millis(300)).await;
+    ///         Ok(format!("done {}", input["n"]))
+    ///     }
+    /// }
+    ///
+    /// let transport = Arc::new(MockTransport::new());
+    /// transport.push_message(
+    ///     json!([
+    ///         {"type": "tool_use", "id": "toolu_1", "name": "slow", "input": {"n": 1}},
+    ///         {"type": "tool_use", "id": "toolu_2", "name": "slow", "input": {"n": 2}},
+    ///         {"type": "tool_use", "id": "toolu_3", "name": "slow", "input": {"n": 3}}
+    ///     ]),
+    ///     "tool_use",
+    /// );
+    /// transport.push_message(json!([{"type": "text", "text": "All done"}]), "end_turn");
+    /// let client = Claude::new("test-key".to_string(), "claude-3-5-haiku-20241022".to_string())
+    ///     .with_transport(transport.clone());
+    /// let mut registry = ToolRegistry::new();
+    /// registry.register(Arc::new(Slow))?;
+    ///
+    /// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+    /// let options = TurnOptions {
+    ///     parallel_tools: true,
+    ///     ..Default::default()
+    /// };
+    /// let started = Instant::now();
+    /// let text = client
+    ///     .run_conversation_turn_with_options("Run them", &mut registry, None, &options)
+    ///     .await?;
+    /// assert_eq!(text, "All done");
+    /// assert!(started.elapsed() < Duration::from_millis(800));
+    ///
+    /// // Results are sent back in the order of the calls
+    /// let second: Value = serde_json::from_str(&transport.requests()[1].body_text()).unwrap();
+    /// let results = second["messages"][2]["content"].as_array().unwrap();
+    /// let ids: Vec<&str> = results.iter().map(|r| r["tool_use_id"].as_str().unwrap()).collect();
+    /// assert_eq!(ids, ["toolu_1", "toolu_2", "toolu_3"]);
+    /// assert_eq!(results[2]["content"], "done 3");
+    /// # Ok::<(), claude::Error>(())
+    /// # }).unwrap();
+    /// # Ok::<(), claude::Error>(())
+    /// ```
+    ///
+    /// Cancelling `cancel` stops tools that watch it, such as
+    /// [`BashTool`](crate::tools::BashTool), and ends the turn before another
+    /// request is sent:
+    ///
+    /// ```rust
+    /// use claude::test_util::MockTransport;
+    /// use claude::tools::BashTool;
+    /// use claude::{CancellationToken, Claude, ToolRegistry, TurnOptions};
+    /// use serde_json::json;
+    /// use std::sync::Arc;
+    /// use std::time::{Duration, Instant};
+    ///
+    /// let transport = Arc::new(MockTransport::new());
+    /// transport.push_message(
+    ///     json!([{"type": "tool_use", "id": "toolu_1", "name": "bash", "input": {"command": "sleep 30"}}]),
+    ///     "tool_use",
+    /// );
+    /// let client = Claude::new("test-key".to_string(), "claude-3-5-haiku-20241022".to_string())
+    ///     .with_transport(transport.clone());
+    /// let mut registry = ToolRegistry::new();
+    /// registry.register(Arc::new(BashTool::new()))?;
+    ///
+    /// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+    /// let cancel = CancellationToken::new();
+    /// let options = TurnOptions {
+    ///     cancel: Some(cancel.clone()),
+    ///     ..Default::default()
+    /// };
+    /// tokio::spawn(async move {
+    ///     tokio::time::sleep(Duration::from_millis(200)).await;
+    ///     cancel.cancel();
+    /// });
+    ///
+    /// let started = Instant::now();
+    /// let err = client
+    ///     .run_conversation_turn_with_options("Wait a bit", &mut registry, None, &options)
+    ///     .await
+    ///     .unwrap_err();
+    /// assert!(err.to_string().contains("cancelled"), "{}", err);
+    /// assert!(started.elapsed() < Duration::from_secs(10));
+    /// assert_eq!(transport.requests().len(), 1);
+    ///
+    /// let execution = registry.execution_history().last().unwrap();
+    /// assert!(execution.error().unwrap().contains("cancelled"));
+    /// # Ok::<(), claude::Error>(())
+    /// # }).unwrap();
+    /// # Ok::<(), claude::Error>(())
+    /// ```
+    pub async fn run_conversation_turn_with_options(
+        &self,
+        user_message: &str,
+        tool_registry: &mut ToolRegistry,
+        conversation_history: Option<Vec<Message>>,
+        options: &TurnOptions,
+    ) -> Result<String> {
+        self.run_turn(
+            user_message,
+            tool_registry,
+            conversation_history,
+            options,
+            None,
+        )
+        .await
+        .map(|outcome| outcome.text)
+    }
+
+    /// Run a complete conversation turn and report usage across all requests
+    ///
+    /// Like [`run_conversation_turn_with_options`](Self::run_conversation_turn_with_options),
+    /// but returns a [`TurnOutcome`] with token usage summed over every
+    /// `next_message` call made during the turn, along with iteration and tool
+    /// call counts.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use async_trait::async_trait;
+    /// use claude::test_util::MockTransport;
+    /// use claude::{Claude, Tool, ToolRegistry, TurnOptions};
+    /// use serde_json::{json, Value};
+    /// use std::sync::Arc;
+    ///
+    /// struct Weather;
+    ///
+    /// #[async_trait]
+    /// impl Tool for Weather {
+    ///     fn name(&self) -> &str { "weather" }
+    ///     fn description(&self) -> &str { "Current weather for a city" }
+    ///     fn input_schema(&self) -> Value { json!({"type": "object"}) }
+    ///     async fn execute(&self, _input: Value) -> Result<String, claude::Error> {
+    ///         Ok("Light rain, 12°C".to_string())
+    ///     }
+    /// }
+    ///
+    /// let transport = Arc::new(MockTransport::new());
+    /// // First request: 10 input and 5 output tokens
+    /// transport.push_message(
+    ///     json!([{"type": "tool_use", "id": "toolu_1", "name": "weather", "input": {"city": "London"}}]),
+    ///     "tool_use",
+    /// );
+    /// // Second request: 42 input and 9 output tokens
+    /// transport.push_json(json!({
+    ///     "id": "msg_2",
+    ///     "type": "message",
+    ///     "role": "assistant",
+    ///     "model": "claude-3-5-haiku-20241022",
+    ///     "content": [{"type": "text", "text": "It's raining in London."}],
+    ///     "stop_reason": "end_turn",
+    ///     "stop_sequence": null,
+    ///     "usage": {"input_tokens": 42, "output_tokens": 9}
+    /// }));
+    /// let client = Claude::new("test-key".to_string(), "claude-3-5-haiku-20241022".to_string())
+    ///     .with_transport(transport.clone());
+    /// let mut registry = ToolRegistry::new();
+    /// registry.register(Arc::new(Weather))?;
+    ///
+    /// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+    /// let outcome = client.run_conversation_turn_detailed(
+    ///     "What's the weather in London?",
+    ///     &mut registry,
+    ///     None,
+    ///     &TurnOptions::default(),
+    /// ).await?;
+    /// assert_eq!(outcome.text, "It's raining in London.");
+    /// assert_eq!(outcome.iterations, 2);
+    /// assert_eq!(outcome.tool_calls, 1);
+    /// assert_eq!(outcome.stop_reason, "end_turn");
+    /// assert_eq!(outcome.total_usage.input_tokens, 52);
+    /// assert_eq!(outcome.total_usage.output_tokens, 14);
+    /// # Ok::<(), claude::Error>(())
+    /// # }).unwrap();
+    /// # Ok::<(), claude::Error>(())
+    /// ```
+    pub async fn run_conversation_turn_detailed(
+        &self,
+        user_message: &str,
+        tool_registry: &mut ToolRegistry,
+        conversation_history: Option<Vec<Message>>,
+        options: &TurnOptions,
+    ) -> Result<TurnOutcome> {
+        self.run_turn(
+            user_message,
+            tool_registry,
+            conversation_history,
+            options,
+            None,
+        )
+        .await
+    }
+
+    /// Run a complete conversation turn, reporting progress through a channel
+    ///
+    /// Behaves exactly like [`run_conversation_turn`](Self::run_conversation_turn),
+    /// but sends a [`TurnEvent`] for each assistant text block, each tool
+    /// request, result, or denial, and finally the finished response. Events are
+    /// dropped silently if the receiver has been closed.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use async_trait::async_trait;
+    /// use claude::test_util::MockTransport;
+    /// use claude::{Claude, Tool, ToolRegistry, TurnEvent};
+    /// use serde_json::{json, Value};
+    /// use std::sync::Arc;
+    /// use tokio::sync::mpsc;
+    ///
+    /// struct Weather;
+    ///
+    /// #[async_trait]
+    /// impl Tool for Weather {
+    ///     fn name(&self) -> &str { "weather" }
+    ///     fn description(&self) -> &str { "Current weather for a city" }
+    ///     fn input_schema(&self) -> Value { json!({"type": "object"}) }
+    ///     async fn execute(&self, _input: Value) -> Result<String, claude::Error> {
+    ///         Ok("Light rain".to_string())
+    ///     }
+    /// }
+    ///
+    /// let transport = Arc::new(MockTransport::new());
+    /// transport.push_message(
+    ///     json!([
+    ///         {"type": "text", "text": "Let me check."},
+    ///         {"type": "tool_use", "id": "toolu_1", "name": "weather", "input": {"city": "London"}}
+    ///     ]),
+    ///     "tool_use",
+    /// );
+    /// transport.push_message(json!([{"type": "text", "text": "It's raining."}]), "end_turn");
+    /// let client = Claude::new("test-key".to_string(), "claude-3-5-haiku-20241022".to_string())
+    ///     .with_transport(transport);
+    /// let mut registry = ToolRegistry::new();
+    /// registry.register(Arc::new(Weather))?;
+    ///
+    /// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+    /// let (tx, mut rx) = mpsc::channel(32);
+    /// let response = client.run_conversation_turn_with_events(
+    ///     "What's the weather in London?",
+    ///     &mut registry,
+    ///     None,
+    ///     None,
+    ///     None,
+    ///     tx,
+    /// ).await?;
+    /// assert_eq!(response, "It's raining.");
+    ///
+    /// let mut seen = Vec::new();
+    /// while let Some(event) = rx.recv().await {
+    ///     seen.push(match event {
+    ///         TurnEvent::AssistantText { text } => format!("text: {}", text),
+    ///         TurnEvent::ToolStarted { tool_name, input, .. } => {
+    ///             format!("started: {} {}", tool_name, input["city"])
+    ///         }
+    ///         TurnEvent::ToolCompleted { tool_name, content, is_error, .. } => {
+    ///             format!("completed: {} {} {}", tool_name, content, is_error)
+    ///         }
+    ///         TurnEvent::ToolDenied { tool_name, .. } => format!("denied: {}", tool_name),
+    ///         TurnEvent::Finished { text } => format!("finished: {}", text),
+    ///     });
+    /// }
+    /// assert_eq!(seen, [
+    ///     "text: Let me check.",
+    ///     "started: weather \"London\"",
+    ///     "completed: weather Light rain false",
+    ///     "text: It's raining.",
+    ///     "finished: It's raining.",
+    /// ]);
+    /// # Ok::<(), claude::Error>(())
+    /// # }).unwrap();
+    /// # Ok::<(), claude::Error>(())
+    /// ```
+    pub async fn run_conversation_turn_with_events(
+        &self,
+        user_message: &str,
+        tool_registry: &mut ToolRegistry,
+        system_prompt: Option<&str>,
+        conversation_history: Option<Vec<Message>>,
+        max_iterations: Option<usize>,
+        events: mpsc::Sender<TurnEvent>,
+    ) -> Result<String> {
+        let options = TurnOptions {
+            system_prompt: system_prompt.map(|s| s.to_string()),
+            max_iterations,
+            ..Default::default()
+        };
+        self.run_turn(
+            user_message,
+            tool_registry,
+            conversation_history,
+            &options,
+            Some(&events),
+        )
+        .await
+        .map(|outcome| outcome.text)
+    }
+
+    /// Shared conversation loop behind the `run_conversation_turn*` methods
+    #[tracing::instrument(name = "conversation_turn", skip_all, fields(model = %self.model))]
+    async fn run_turn(
+        &self,
+        user_message: &str,
+        tool_registry: &mut ToolRegistry,
+        conversation_history: Option<Vec<Message>>,
+        options: &TurnOptions,
+        events: Option<&mpsc::Sender<TurnEvent>>,
+    ) -> Result<TurnOutcome> {
+        let max_iterations = options.max_iterations.unwrap_or(10);
         let mut messages = conversation_history.unwrap_or_default();
 
+        // Thinking is fixed for the whole turn: the API rejects a tool result
+        // follow-up with thinking enabled unless the tool use came with thinking
+        let thinking = options
+            .thinking_budget
+            .as_ref()
+            .and_then(|budget| budget.take())
+            .map(|budget_tokens| ThinkingConfig::Enabled { budget_tokens })
+            .or_else(|| options.thinking.clone());
+        let max_tokens = options.max_tokens.unwrap_or(DEFAULT_MAX_TOKENS)
+            + thinking
+                .as_ref()
+                .and_then(|t| t.budget_tokens())
+                .unwrap_or(0);
+
         // Add the user's message
         messages.push(Message::user(vec![ContentBlock::Text {
             text: user_message.to_string(),
         }]));
+        tool_registry.begin_turn(user_message);
 
         let mut iteration = 0;
+        let mut total_usage = Usage::default();
+        let mut tool_calls = 0;
+        let mut requests = 0;
+        let mut continuations = 0;
+        let mut continued_text = String::new();
+        let mut last_tool = None;
 
         loop {
+            if options
+                .cancel
+                .as_ref()
+                .is_some_and(|cancel| cancel.is_cancelled())
+            {
+                tracing::info!(iterations = requests, tool_calls, "turn cancelled");
+                return Err(Error::Other("Turn cancelled".to_string()));
+            }
             if iteration >= max_iterations {
-                return Err(Error::Other(format!(
-                    "Maximum iterations ({}) reached without completion",
-                    max_iterations
-                )));
+                tracing::warn!(max_iterations, "maximum iterations reached");
+                return Err(Error::MaxIterations {
+                    iterations: max_iterations,
+                    last_tool,
+                });
             }
+            let nudge = options.final_answer_nudge && iteration + 1 == max_iterations;
 
             // Create request with current conversation state
-            let request = MessageRequest {
+            let mut request = MessageRequest {
                 model: self.model.to_string(),
                 messages: messages.clone(),
                 tools: tool_registry.get_tool_defs(),
-                max_tokens: 4096,
-                system: system_prompt.map(|s| s.to_string()),
-                temperature: None,
+                max_tokens,
+                system: options.system_prompt.as_deref().map(|s| s.into()),
+                temperature: options.temperature,
+                tool_choice: match &options.tool_choice {
+                    _ if nudge => Some(ToolChoice::None),
+                    Some(ToolChoice::Any | ToolChoice::Tool { .. }) if requests > 0 => None,
+                    choice => choice.clone(),
+                },
+                thinking: thinking.clone(),
             };
+            request.enforce_tool_pairing(options.tool_pairing)?;
 
             // Get Claude's response
-            let response = self.next_message(request).await?;
+            tracing::debug!(iteration, "requesting response");
+            let betas = options.beta_headers.as_ref().unwrap_or(&self.beta_headers);
+            let response = self.next_message_with_betas(request, betas).await?;
+            requests += 1;
+            if let Some(usage) = &response.usage {
+                total_usage += usage.clone();
+            }
+
+            // A tool_use block cut off by max_tokens has incomplete input
+            if let Some(tool_name) = response.truncated_tool_use() {
+                return Err(Error::Other(format!(
+                    "Response hit max_tokens while generating input for tool '{}'",
+                    tool_name
+                )));
+            }
 
             // Add Claude's response to conversation history
             messages.push((&response).into());
 
+            for block in &response.content {
+                if let ContentBlock::Text { text } = block {
+                    emit(events, TurnEvent::AssistantText { text: text.clone() }).await;
+                }
+            }
+
             // Check if Claude wants to use any tools
-            let tool_uses = response
-                .content
-                .iter()
-                .filter_map(|block| match block {
-                    ContentBlock::ToolUse { name, input, id } => {
-                        Some((name.clone(), input.clone(), id.clone()))
-                    }
-                    _ => None,
-                })
-                .collect::<Vec<_>>();
+            let tool_uses = response.tool_uses();
 
             // If no tool uses, return the response
             if tool_uses.is_empty() {
-                // Extract text content from response
-                let text_content = response
-                    .content
-                    .iter()
-                    .filter_map(|block| match block {
-                        ContentBlock::Text { text } => Some(text.clone()),
-                        _ => None,
-                    })
-                    .collect::<Vec<_>>()
-                    .join("\n");
+                let text_content = response.text();
+                continued_text.push_str(&text_content);
+
+                // Ask Claude to carry on from a response cut off by max_tokens
+                if response.stop_reason == "max_tokens"
+                    && continuations < options.max_continuations.unwrap_or(0)
+                {
+                    continuations += 1;
+                    messages.push(Message::user(vec![ContentBlock::Text {
+                        text: CONTINUE_PROMPT.to_string(),
+                    }]));
+                    continue;
+                }
 
-                return Ok(text_content);
+                emit(
+                    events,
+                    TurnEvent::Finished {
+                        text: continued_text.clone(),
+                    },
+                )
+                .await;
+
+                tracing::info!(
+                    iterations = requests,
+                    tool_calls,
+                    stop_reason = %response.stop_reason,
+                    "turn finished"
+                );
+
+                return Ok(TurnOutcome {
+                    text: continued_text,
+                    total_usage,
+                    iterations: requests,
+                    tool_calls,
+                    stop_reason: response.stop_reason,
+                });
             }
+            continued_text.clear();
 
             // Execute tools and collect results
-            let mut tool_results = Vec::new();
-            for (tool_name, input, tool_use_id) in tool_uses {
-                let result = tool_registry
-                    .execute_tool(&tool_name, input, tool_use_id)
+            let tool_results = if options.parallel_tools {
+                for tool_use in &tool_uses {
+                    tool_calls += 1;
+                    last_tool = Some(tool_use.name.clone());
+                    emit(
+                        events,
+                        TurnEvent::ToolStarted {
+                            tool_use_id: tool_use.id.clone(),
+                            tool_name: tool_use.name.clone(),
+                            input: tool_use.input.clone(),
+                        },
+                    )
+                    .await;
+                }
+                let calls = tool_uses
+                    .iter()
+                    .map(|tool_use| {
+                        (
+                            tool_use.name.clone(),
+                            tool_use.input.clone(),
+                            tool_use.id.clone(),
+                        )
+                    })
+                    .collect();
+                let results = tool_registry
+                    .execute_tools_parallel(calls, options.cancel.clone())
                     .await?;
-                tool_results.push(result);
-            }
+                for (tool_use, result) in tool_uses.into_iter().zip(&results) {
+                    let event = tool_event(tool_registry, tool_use.id, tool_use.name, result);
+                    emit(events, event).await;
+                }
+                results
+            } else {
+                let mut tool_results = Vec::new();
+                // Results of the calls made so far in this response, for deduplication
+                let mut first_results: HashMap<String, ContentBlock> = HashMap::new();
+                for ToolUse {
+                    name: tool_name,
+                    input,
+                    id: tool_use_id,
+                } in tool_uses
+                {
+                    tool_calls += 1;
+                    last_tool = Some(tool_name.clone());
+                    emit(
+                        events,
+                        TurnEvent::ToolStarted {
+                            tool_use_id: tool_use_id.clone(),
+                            tool_name: tool_name.clone(),
+                            input: input.clone(),
+                        },
+                    )
+                    .await;
+
+                    let key = tool_registry
+                        .deduplicate_calls()
+                        .then(|| call_key(&tool_name, &input));
+                    let result = match key.as_ref().and_then(|key| first_results.get(key)) {
+                        Some(first) => tool_registry.reuse_result(first, tool_use_id.clone()),
+                        None => {
+                            let result = tool_registry
+                                .execute_tool(
+                                    &tool_name,
+                                    input,
+                                    tool_use_id.clone(),
+                                    options.cancel.clone(),
+                                )
+                                .await?;
+                            if let Some(key) = key {
+                                first_results.insert(key, result.clone());
+                            }
+                            result
+                        }
+                    };
+
+                    let event = tool_event(tool_registry, tool_use_id, tool_name, &result);
+                    emit(events, event).await;
+
+                    tool_results.push(result);
+                }
+                tool_results
+            };
 
             // Add tool results to conversation
             messages.push(Message::user(tool_results));
@@ -324,6 +2005,14 @@ impl Claude {
     /// let stats = client.conversation_stats(&messages);
     /// println!("Total messages: {}", stats.get("total_messages").unwrap());
     /// println!("Tool uses: {}", stats.get("tool_uses").unwrap());
+    ///
+    /// # use claude::ImageSource;
+    /// let with_image = vec![Message::user_with_image(
+    ///     ImageSource::Url { url: "https://example.com/chart.png".to_string() },
+    ///     "Describe this chart",
+    /// )];
+    /// let stats = client.conversation_stats(&with_image);
+    /// assert_eq!(stats["images"], 1);
     /// ```
     pub fn conversation_stats(&self, messages: &[Message]) -> HashMap<String, usize> {
         let mut stats = HashMap::new();
@@ -348,9 +2037,73 @@ impl Claude {
             .filter(|block| matches!(block, ContentBlock::ToolResult { .. }))
             .count();
 
+        let images = messages
+            .iter()
+            .flat_map(|m| &m.content)
+            .filter(|block| matches!(block, ContentBlock::Image { .. }))
+            .count();
+
+        let documents = messages
+            .iter()
+            .flat_map(|m| &m.content)
+            .filter(|block| matches!(block, ContentBlock::Document { .. }))
+            .count();
+
         stats.insert("tool_uses".to_string(), tool_uses);
         stats.insert("tool_results".to_string(), tool_results);
+        stats.insert("images".to_string(), images);
+        stats.insert("documents".to_string(), documents);
 
         stats
     }
 }
+
+/// Send a turn event if a listener is attached, ignoring a closed receiver
+/// The event reporting how the call `tool_use_id` ended, from its result and
+/// its record in the registry's history
+fn tool_event(
+    registry: &ToolRegistry,
+    tool_use_id: String,
+    tool_name: String,
+    result: &ContentBlock,
+) -> TurnEvent {
+    let denial = registry
+        .execution_history()
+        .iter()
+        .rev()
+        .find(|exec| exec.id == tool_use_id)
+        .and_then(|exec| match &exec.state {
+            ExecutionState::Denied { reason } => Some(reason.clone()),
+            _ => None,
+        });
+    match (denial, result) {
+        (Some(reason), _) => TurnEvent::ToolDenied {
+            tool_use_id,
+            tool_name,
+            reason,
+        },
+        (
+            None,
+            ContentBlock::ToolResult {
+                content, is_error, ..
+            },
+        ) => TurnEvent::ToolCompleted {
+            tool_use_id,
+            tool_name,
+            content: ToolResultContent::text_of(content),
+            is_error: is_error.unwrap_or(false),
+        },
+        (None, _) => TurnEvent::ToolCompleted {
+            tool_use_id,
+            tool_name,
+            content: String::new(),
+            is_error: false,
+        },
+    }
+}
+
+async fn emit(events: Option<&mpsc::Sender<TurnEvent>>, event: TurnEvent) {
+    if let Some(sender) = events {
+        let _ = sender.send(event).await;
+    }
+}
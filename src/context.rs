@@ -0,0 +1,348 @@
+use crate::client::Claude;
+use crate::error::{Error, Result};
+use crate::message::{ContentBlock, Message};
+use crate::request::MessageRequest;
+
+/// Maximum tokens requested when summarizing dropped messages
+const SUMMARY_MAX_TOKENS: u32 = 512;
+
+/// Strategy used by [`ContextManager`] to shrink a conversation
+///
+/// The system prompt is not part of the message list, so every strategy keeps it.
+#[derive(Debug, Clone, PartialEq)]
+pub enum TruncationStrategy {
+    /// Drop the oldest messages until the conversation fits
+    DropOldest,
+    /// Replace the oldest messages with a summary generated by Claude
+    SummarizeOldest,
+    /// Keep at most the `n` most recent messages, then drop more if still too large
+    KeepSystemAndRecent {
+        /// Maximum number of recent messages to keep
+        n: usize,
+    },
+}
+
+/// Trims conversations so they fit in the model's context window
+///
+/// Uses [`Claude::count_tokens`] to measure the conversation and applies a
+/// [`TruncationStrategy`] when it is too large. Truncation only ever cuts at a
+/// user message that carries no tool results, so a `tool_use` block is never
+/// separated from its `tool_result`.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// # async fn example(history: Vec<claude::Message>) -> Result<(), Box<dyn std::error::Error>> {
+/// use claude::{Claude, ContextManager, TruncationStrategy};
+///
+/// let client = Claude::new(
+///     "your-api-key".to_string(),
+///     "claude-3-haiku-20240307".to_string()
+/// );
+/// let manager = ContextManager::new(client, TruncationStrategy::DropOldest);
+///
+/// // Leave room for the system prompt, tools, and the response
+/// let history = manager.fit(history, 150_000).await?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone)]
+pub struct ContextManager {
+    /// Client used for token counting and summarization
+    client: Claude,
+    /// How to shrink conversations that are too large
+    strategy: TruncationStrategy,
+}
+
+impl ContextManager {
+    /// Create a new context manager
+    pub fn new(client: Claude, strategy: TruncationStrategy) -> Self {
+        Self { client, strategy }
+    }
+
+    /// Get the truncation strategy
+    pub fn strategy(&self) -> &TruncationStrategy {
+        &self.strategy
+    }
+
+    /// Shrink `messages` until they use at most `max_tokens` input tokens
+    ///
+    /// Returns the messages unchanged if they already fit.
+    ///
+    /// # Example
+    ///
+    /// A mock API that counts 100 tokens per message, with a tool exchange in
+    /// the middle of the conversation:
+    ///
+    /// ```rust
+    /// use claude::test_util::MockServer;
+    /// use claude::transport::RawResponse;
+    /// use claude::{Claude, ContentBlock, ContextManager, Message, TruncationStrategy};
+    /// use serde_json::{json, Value};
+    ///
+    /// fn first_text(messages: &[Message]) -> &str {
+    ///     match &messages[0].content[0] {
+    ///         ContentBlock::Text { text } => text,
+    ///         other => panic!("expected text, got {:?}", other),
+    ///     }
+    /// }
+    ///
+    /// let messages = vec![
+    ///     Message::user(vec!["Plan a trip to Lisbon".into()]),
+    ///     Message::assistant(vec!["When would you like to go?".into()]),
+    ///     Message::user(vec!["Next week, if the weather is good".into()]),
+    ///     Message::assistant(vec![ContentBlock::ToolUse {
+    ///         name: "weather".to_string(),
+    ///         input: json!({"city": "Lisbon"}),
+    ///         id: "toolu_1".to_string(),
+    ///     }]),
+    ///     Message::user(vec![ContentBlock::ToolResult {
+    ///         content: vec!["Sunny all week".into()],
+    ///         tool_use_id: "toolu_1".to_string(),
+    ///         is_error: None,
+    ///         cache_control: None,
+    ///     }]),
+    ///     Message::assistant(vec!["It'll be sunny.".into()]),
+    ///     Message::user(vec!["Book it".into()]),
+    ///     Message::assistant(vec!["Booked!".into()]),
+    /// ];
+    ///
+    /// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+    /// let server = MockServer::start(|request| {
+    ///     let body: Value = serde_json::from_str(&request.body_text()).unwrap();
+    ///     if request.url.ends_with("/count_tokens") {
+    ///         let tokens = 100 * body["messages"].as_array().unwrap().len();
+    ///         RawResponse::new(200, json!({"input_tokens": tokens}).to_string())
+    ///     } else {
+    ///         RawResponse::new(200, json!({
+    ///             "id": "msg_1",
+    ///             "type": "message",
+    ///             "role": "assistant",
+    ///             "model": "claude-3-5-haiku-20241022",
+    ///             "content": [{"type": "text", "text": "The user wants to visit Lisbon."}],
+    ///             "stop_reason": "end_turn",
+    ///             "stop_sequence": null,
+    ///             "usage": {"input_tokens": 10, "output_tokens": 5}
+    ///         }).to_string())
+    ///     }
+    /// })
+    /// .await?;
+    /// let client = Claude::new("test-key".to_string(), "claude-3-5-haiku-20241022".to_string())
+    ///     .with_base_url(server.url());
+    /// let manager = |strategy| ContextManager::new(client.clone(), strategy);
+    ///
+    /// // A conversation that fits comes back unchanged
+    /// let fitted = manager(TruncationStrategy::DropOldest).fit(messages.clone(), 800).await?;
+    /// assert_eq!(fitted.len(), 8);
+    ///
+    /// // Dropping messages 0-3 would fit in 500 tokens, but the tool result
+    /// // would lose its tool_use, so the whole exchange goes
+    /// let fitted = manager(TruncationStrategy::DropOldest).fit(messages.clone(), 500).await?;
+    /// assert_eq!(fitted.len(), 2);
+    /// assert_eq!(first_text(&fitted), "Book it");
+    ///
+    /// // With room for 600 tokens the exchange is kept whole
+    /// let fitted = manager(TruncationStrategy::DropOldest).fit(messages.clone(), 600).await?;
+    /// assert_eq!(fitted.len(), 6);
+    /// assert!(matches!(fitted[1].content[0], ContentBlock::ToolUse { .. }));
+    /// assert!(matches!(fitted[2].content[0], ContentBlock::ToolResult { .. }));
+    ///
+    /// // The recent window starts at a safe cut at or after the last `n`
+    /// let strategy = TruncationStrategy::KeepSystemAndRecent { n: 6 };
+    /// let fitted = manager(strategy).fit(messages.clone(), 700).await?;
+    /// assert_eq!(fitted.len(), 6);
+    /// assert_eq!(first_text(&fitted), "Next week, if the weather is good");
+    /// // The last 5 would start at the tool_use, so only the last exchange is kept
+    /// let strategy = TruncationStrategy::KeepSystemAndRecent { n: 5 };
+    /// let fitted = manager(strategy).fit(messages.clone(), 700).await?;
+    /// assert_eq!(fitted.len(), 2);
+    /// // A window that is still too large is trimmed further
+    /// let strategy = TruncationStrategy::KeepSystemAndRecent { n: 6 };
+    /// let fitted = manager(strategy).fit(messages.clone(), 300).await?;
+    /// assert_eq!(fitted.len(), 2);
+    ///
+    /// // Summarizing keeps 512 tokens free for the summary, which goes before
+    /// // the first message kept
+    /// let fitted = manager(TruncationStrategy::SummarizeOldest)
+    ///     .fit(messages.clone(), 712)
+    ///     .await?;
+    /// assert_eq!(fitted.len(), 2);
+    /// assert_eq!(
+    ///     first_text(&fitted),
+    ///     "[Summary of earlier conversation]\nThe user wants to visit Lisbon."
+    /// );
+    /// assert!(matches!(&fitted[0].content[1], ContentBlock::Text { text } if text == "Book it"));
+    /// let summarized = server.requests().into_iter().filter(|r| r.url.ends_with("/v1/messages"));
+    /// assert_eq!(summarized.count(), 1);
+    ///
+    /// // Nothing shorter than the last exchange can be kept
+    /// let err = manager(TruncationStrategy::DropOldest).fit(messages, 100).await.unwrap_err();
+    /// assert!(err.to_string().contains("cannot be truncated to fit in 100 tokens"));
+    /// # Ok::<(), claude::Error>(())
+    /// # }).unwrap();
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if token counting or summarization fails, or if even the
+    /// shortest valid suffix of the conversation does not fit.
+    pub async fn fit(&self, messages: Vec<Message>, max_tokens: usize) -> Result<Vec<Message>> {
+        if self.count(&messages).await? <= max_tokens {
+            return Ok(messages);
+        }
+
+        match &self.strategy {
+            TruncationStrategy::DropOldest => self.drop_oldest(messages, max_tokens).await,
+            TruncationStrategy::KeepSystemAndRecent { n } => {
+                // Prefer the first safe cut inside the recent window; if there is
+                // none, keep from the last safe cut so the turn structure survives
+                let start = messages.len().saturating_sub(*n);
+                let cuts = Self::cut_points(&messages);
+                let cut = cuts
+                    .iter()
+                    .copied()
+                    .find(|&cut| cut >= start)
+                    .or_else(|| cuts.last().copied())
+                    .unwrap_or(0);
+                let recent = messages[cut..].to_vec();
+                if self.count(&recent).await? <= max_tokens {
+                    Ok(recent)
+                } else {
+                    self.drop_oldest(recent, max_tokens).await
+                }
+            }
+            TruncationStrategy::SummarizeOldest => {
+                let budget = max_tokens.saturating_sub(SUMMARY_MAX_TOKENS as usize);
+                let cut = self.find_cut(&messages, budget).await?;
+                let mut kept = messages[cut..].to_vec();
+                if cut > 0 {
+                    let summary = self.summarize(&messages[..cut]).await?;
+                    kept[0].content.insert(
+                        0,
+                        ContentBlock::Text {
+                            text: format!("[Summary of earlier conversation]\n{}", summary),
+                        },
+                    );
+                }
+                Ok(kept)
+            }
+        }
+    }
+
+    /// Indices at which a conversation can be cut without orphaning tool results
+    ///
+    /// A suffix starting at one of these indices begins with a user message that
+    /// carries no `tool_result` blocks, so every tool result it contains refers to
+    /// a tool use that is also kept.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use claude::{ContentBlock, ContextManager, Message};
+    /// use serde_json::json;
+    ///
+    /// let messages = vec![
+    ///     Message::user(vec!["What's 2+2?".into()]),
+    ///     Message::assistant(vec![ContentBlock::ToolUse {
+    ///         name: "calculator".to_string(),
+    ///         input: json!({"expression": "2+2"}),
+    ///         id: "tool_1".to_string(),
+    ///     }]),
+    ///     Message::user(vec![ContentBlock::ToolResult {
+    ///         content: vec!["4".into()],
+    ///         tool_use_id: "tool_1".to_string(),
+    ///         is_error: None,
+    ///         cache_control: None,
+    ///     }]),
+    ///     Message::assistant(vec!["2+2 is 4.".into()]),
+    ///     Message::user(vec!["Thanks!".into()]),
+    ///     Message::assistant(vec!["You're welcome.".into()]),
+    /// ];
+    ///
+    /// // Cutting at index 2 would orphan the tool result, so it is never offered
+    /// assert_eq!(ContextManager::cut_points(&messages), vec![0, 4]);
+    /// ```
+    pub fn cut_points(messages: &[Message]) -> Vec<usize> {
+        messages
+            .iter()
+            .enumerate()
+            .filter(|(_, message)| {
+                message.role == "user"
+                    && !message
+                        .content
+                        .iter()
+                        .any(|block| matches!(block, ContentBlock::ToolResult { .. }))
+            })
+            .map(|(index, _)| index)
+            .collect()
+    }
+
+    /// Drop the oldest messages at a safe cut point so the rest fit the budget
+    async fn drop_oldest(&self, messages: Vec<Message>, max_tokens: usize) -> Result<Vec<Message>> {
+        let cut = self.find_cut(&messages, max_tokens).await?;
+        Ok(messages[cut..].to_vec())
+    }
+
+    /// Find the earliest safe cut point whose suffix fits in `max_tokens`
+    ///
+    /// Token counts shrink as the cut moves later, so the cut points are binary
+    /// searched to keep the number of `count_tokens` requests small.
+    async fn find_cut(&self, messages: &[Message], max_tokens: usize) -> Result<usize> {
+        let cuts = Self::cut_points(messages);
+        let (mut low, mut high) = (0, cuts.len());
+        while low < high {
+            let mid = (low + high) / 2;
+            if self.count(&messages[cuts[mid]..]).await? <= max_tokens {
+                high = mid;
+            } else {
+                low = mid + 1;
+            }
+        }
+
+        cuts.get(low).copied().ok_or_else(|| {
+            Error::Other(format!(
+                "Conversation cannot be truncated to fit in {} tokens",
+                max_tokens
+            ))
+        })
+    }
+
+    /// Count the input tokens used by `messages`
+    async fn count(&self, messages: &[Message]) -> Result<usize> {
+        let request = MessageRequest {
+            model: self.client.model().to_string(),
+            messages: messages.to_vec(),
+            tools: vec![],
+            max_tokens: 1,
+            system: None,
+            temperature: None,
+            tool_choice: None,
+            thinking: None,
+        };
+        self.client.count_tokens(&request).await
+    }
+
+    /// Ask Claude for a short summary of the dropped messages
+    async fn summarize(&self, messages: &[Message]) -> Result<String> {
+        let transcript = serde_json::to_string(messages)?;
+        let request = MessageRequest {
+            model: self.client.model().to_string(),
+            messages: vec![Message::user(vec![ContentBlock::Text {
+                text: format!(
+                    "Summarize the following conversation transcript (JSON) in a few \
+                     sentences, preserving facts, decisions, and open tasks:\n\n{}",
+                    transcript
+                ),
+            }])],
+            tools: vec![],
+            max_tokens: SUMMARY_MAX_TOKENS,
+            system: None,
+            temperature: None,
+            tool_choice: None,
+            thinking: None,
+        };
+        let response = self.client.next_message(request).await?;
+
+        Ok(response.text())
+    }
+}
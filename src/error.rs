@@ -14,11 +14,17 @@ use std::fmt;
 /// fn handle_api_error(error: Error) {
 ///     match error {
 ///         Error::Request(e) => eprintln!("Network error: {}", e),
+///         Error::Api { status, error_type, message, request_id } => {
+///             eprintln!("{} ({}, status {}): {}", error_type, request_id.unwrap_or_default(), status, message)
+///         },
 ///         Error::Response(msg, status) => {
 ///             eprintln!("API error: {} (status: {:?})", msg, status)
 ///         },
 ///         Error::Parse(e) => eprintln!("Failed to parse response: {}", e),
 ///         Error::Header(msg) => eprintln!("Header error: {}", msg),
+///         Error::MaxIterations { iterations, last_tool } => {
+///             eprintln!("Gave up after {} rounds (last tool: {:?})", iterations, last_tool)
+///         },
 ///         Error::Other(msg) => eprintln!("Error: {}", msg),
 ///     }
 /// }
@@ -27,12 +33,31 @@ use std::fmt;
 pub enum Error {
     /// HTTP request error
     Request(reqwest::Error),
-    /// API response error with message and optional status code
+    /// Structured error returned by the Anthropic API
+    Api {
+        /// HTTP status code
+        status: u16,
+        /// Error type, e.g. `invalid_request_error` or `overloaded_error`
+        error_type: String,
+        /// Human-readable error message
+        message: String,
+        /// Value of the `request-id` response header, for support requests
+        request_id: Option<String>,
+    },
+    /// API response error with message and optional status code, used when the
+    /// body isn't a structured API error
     Response(String, Option<u16>),
     /// JSON parsing error
     Parse(serde_json::Error),
     /// Header configuration error
     Header(String),
+    /// A conversation turn kept calling tools until its iteration limit
+    MaxIterations {
+        /// Number of tool rounds run before giving up
+        iterations: usize,
+        /// Name of the last tool Claude called, if any
+        last_tool: Option<String>,
+    },
     /// Other errors
     Other(String),
 }
@@ -41,17 +66,145 @@ impl fmt::Display for Error {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             Error::Request(e) => write!(f, "Request error: {}", e),
+            Error::Api {
+                status,
+                error_type,
+                message,
+                request_id,
+            } => {
+                write!(
+                    f,
+                    "API error (status {}, {}): {}",
+                    status, error_type, message
+                )?;
+                if let Some(id) = request_id {
+                    write!(f, " [request id: {}]", id)?;
+                }
+                Ok(())
+            }
             Error::Response(msg, status) => match status {
                 Some(code) => write!(f, "API error (status {}): {}", code, msg),
                 None => write!(f, "API error: {}", msg),
             },
             Error::Parse(e) => write!(f, "Parse error: {}", e),
             Error::Header(msg) => write!(f, "Header error: {}", msg),
+            Error::MaxIterations {
+                iterations,
+                last_tool,
+            } => {
+                write!(
+                    f,
+                    "Maximum iterations ({}) reached without a final answer",
+                    iterations
+                )?;
+                if let Some(tool) = last_tool {
+                    write!(f, "; last tool called: {}", tool)?;
+                }
+                Ok(())
+            }
             Error::Other(msg) => write!(f, "{}", msg),
         }
     }
 }
 
+/// HTTP statuses worth retrying: rate limiting, server errors, and overload
+const RETRYABLE_STATUSES: [u16; 5] = [429, 500, 502, 503, 529];
+
+impl Error {
+    /// The HTTP status code of an API error, if there was one
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use claude::Error;
+    ///
+    /// assert_eq!(Error::Response("Bad Gateway".to_string(), Some(502)).status_code(), Some(502));
+    /// assert_eq!(Error::Other("no status".to_string()).status_code(), None);
+    /// ```
+    pub fn status_code(&self) -> Option<u16> {
+        match self {
+            Error::Api { status, .. } => Some(*status),
+            Error::Response(_, status) => *status,
+            Error::Request(e) => e.status().map(|s| s.as_u16()),
+            _ => None,
+        }
+    }
+
+    /// Whether the API rejected the request for exceeding a rate limit
+    ///
+    /// True for status 429 and for `rate_limit_error` API errors.
+    pub fn is_rate_limited(&self) -> bool {
+        match self {
+            Error::Api { error_type, .. } if error_type == "rate_limit_error" => true,
+            _ => self.status_code() == Some(429),
+        }
+    }
+
+    /// Whether sending the same request again might succeed
+    ///
+    /// True for rate limiting, server errors and overload (429, 500, 502, 503,
+    /// 529, or the matching API error types), and for transport timeouts and
+    /// connection failures. Invalid requests, authentication failures, parse
+    /// errors and tool errors are not retryable.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use claude::Error;
+    ///
+    /// let api = |status: u16, error_type: &str| Error::Api {
+    ///     status,
+    ///     error_type: error_type.to_string(),
+    ///     message: String::new(),
+    ///     request_id: None,
+    /// };
+    ///
+    /// let rate_limited = api(429, "rate_limit_error");
+    /// assert!(rate_limited.is_retryable());
+    /// assert!(rate_limited.is_rate_limited());
+    /// assert_eq!(rate_limited.status_code(), Some(429));
+    ///
+    /// let overloaded = api(529, "overloaded_error");
+    /// assert!(overloaded.is_retryable());
+    /// assert!(!overloaded.is_rate_limited());
+    ///
+    /// let invalid = api(400, "invalid_request_error");
+    /// assert!(!invalid.is_retryable());
+    /// assert!(!api(401, "authentication_error").is_retryable());
+    ///
+    /// // Unstructured responses are classified by status alone
+    /// for status in [429, 500, 502, 503, 529] {
+    ///     assert!(Error::Response(String::new(), Some(status)).is_retryable());
+    /// }
+    /// assert!(Error::Response(String::new(), Some(429)).is_rate_limited());
+    /// assert!(!Error::Response(String::new(), Some(404)).is_retryable());
+    /// assert!(!Error::Response(String::new(), None).is_retryable());
+    ///
+    /// // Local failures never are
+    /// let parse = Error::Parse(serde_json::from_str::<serde_json::Value>("{").unwrap_err());
+    /// assert!(!parse.is_retryable());
+    /// assert_eq!(parse.status_code(), None);
+    /// assert!(!Error::Header("bad key".to_string()).is_retryable());
+    /// assert!(!Error::Other("tool failed".to_string()).is_retryable());
+    /// ```
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            Error::Request(e) if e.is_timeout() || e.is_connect() => true,
+            Error::Api { error_type, .. }
+                if matches!(
+                    error_type.as_str(),
+                    "rate_limit_error" | "api_error" | "overloaded_error"
+                ) =>
+            {
+                true
+            }
+            _ => self
+                .status_code()
+                .is_some_and(|status| RETRYABLE_STATUSES.contains(&status)),
+        }
+    }
+}
+
 impl StdError for Error {
     fn source(&self) -> Option<&(dyn StdError + 'static)> {
         match self {
@@ -0,0 +1,69 @@
+use serde_json::Value;
+
+/// Progress events emitted while running a conversation turn
+///
+/// Sent by [`Claude::run_conversation_turn_with_events`](crate::Claude::run_conversation_turn_with_events)
+/// as the turn progresses, so applications can render assistant output and tool
+/// activity in real time without reimplementing the conversation loop.
+///
+/// # Example
+///
+/// ```rust
+/// use claude::TurnEvent;
+///
+/// fn render(event: &TurnEvent) {
+///     match event {
+///         TurnEvent::AssistantText { text } => println!("Claude: {}", text),
+///         TurnEvent::ToolStarted { tool_name, .. } => println!("Running {}...", tool_name),
+///         TurnEvent::ToolCompleted { tool_name, is_error, .. } => {
+///             println!("{} {}", tool_name, if *is_error { "failed" } else { "completed" })
+///         }
+///         TurnEvent::ToolDenied { tool_name, reason, .. } => {
+///             println!("{} was denied: {}", tool_name, reason)
+///         }
+///         TurnEvent::Finished { .. } => println!("Done"),
+///     }
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub enum TurnEvent {
+    /// Claude produced a text block
+    AssistantText {
+        /// The text content
+        text: String,
+    },
+    /// Claude requested a tool; emitted before the permission check
+    ToolStarted {
+        /// Unique identifier for this tool use
+        tool_use_id: String,
+        /// Name of the requested tool
+        tool_name: String,
+        /// Input parameters for the tool
+        input: Value,
+    },
+    /// A tool finished executing, successfully or with an error
+    ToolCompleted {
+        /// Unique identifier for this tool use
+        tool_use_id: String,
+        /// Name of the executed tool
+        tool_name: String,
+        /// Result content sent back to Claude
+        content: String,
+        /// Whether the tool reported an error
+        is_error: bool,
+    },
+    /// The permission handler refused to run a tool
+    ToolDenied {
+        /// Unique identifier for this tool use
+        tool_use_id: String,
+        /// Name of the denied tool
+        tool_name: String,
+        /// Reason given for the denial
+        reason: String,
+    },
+    /// The turn finished with Claude's final response
+    Finished {
+        /// Final text response
+        text: String,
+    },
+}
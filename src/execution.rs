@@ -1,4 +1,5 @@
 use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
 /// Represents the execution state of a tool
@@ -12,7 +13,11 @@ use serde_json::Value;
 /// - `Completed`: Execution finished successfully with result
 /// - `Failed`: Execution failed with an error
 /// - `Denied`: Execution was denied by permission handler
-#[derive(Debug, Clone, PartialEq)]
+///
+/// Serializes as an object tagged by `"status"`, e.g.
+/// `{"status": "completed", "result": "4"}`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
 pub enum ExecutionState {
     /// Tool execution is pending
     Pending,
@@ -66,12 +71,14 @@ pub enum ExecutionState {
 ///     }
 /// }
 /// ```
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ToolExecution {
     /// Unique identifier for this execution
     pub id: String,
     /// Name of the tool being executed
     pub tool_name: String,
+    /// Version of the tool being executed, from [`Tool::version`](crate::Tool::version)
+    pub tool_version: String,
     /// Input parameters provided to the tool
     pub input: Value,
     /// Current execution state
@@ -90,6 +97,7 @@ impl ToolExecution {
         Self {
             id,
             tool_name,
+            tool_version: "1".to_string(),
             input,
             state: ExecutionState::Pending,
             started_at: Utc::now(),
@@ -0,0 +1,307 @@
+use crate::{ContentBlock, DocumentSource, ImageSource, Message, ToolResultContent};
+use std::collections::HashMap;
+
+/// Longest tool result shown in an export before it is truncated
+pub const MAX_RESULT_CHARS: usize = 2000;
+
+/// Cut `text` to [`MAX_RESULT_CHARS`] characters, noting how much was left out
+fn truncate(text: &str) -> String {
+    match text.char_indices().nth(MAX_RESULT_CHARS) {
+        Some((end, _)) => format!(
+            "{}\n… ({} more characters)",
+            &text[..end],
+            text.chars().count() - MAX_RESULT_CHARS
+        ),
+        None => text.to_string(),
+    }
+}
+
+/// Map every tool use id to the name of its tool
+fn tool_names(messages: &[Message]) -> HashMap<&str, &str> {
+    messages
+        .iter()
+        .flat_map(|message| &message.content)
+        .filter_map(|block| match block {
+            ContentBlock::ToolUse { name, id, .. } => Some((id.as_str(), name.as_str())),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Heading for a message, telling tool results apart from what the user typed
+fn role_heading(message: &Message) -> &'static str {
+    let only_results = !message.content.is_empty()
+        && message
+            .content
+            .iter()
+            .all(|block| matches!(block, ContentBlock::ToolResult { .. }));
+    match message.role.as_str() {
+        "assistant" => "Assistant",
+        _ if only_results => "Tool results",
+        _ => "User",
+    }
+}
+
+/// The diff of a `patch_file` call, if that's what this tool use is
+fn patch_diff<'a>(name: &str, input: &'a serde_json::Value) -> Option<(&'a str, &'a str)> {
+    if name != "patch_file" {
+        return None;
+    }
+    let diff = input.get("diff")?.as_str()?;
+    let path = input.get("path").and_then(|v| v.as_str()).unwrap_or("");
+    Some((path, diff))
+}
+
+/// A fence longer than any run of backticks in `text`
+fn fence_for(text: &str) -> String {
+    let longest = text.split(|c| c != '`').map(str::len).max().unwrap_or(0);
+    "`".repeat(longest.max(2) + 1)
+}
+
+fn fenced(language: &str, text: &str) -> String {
+    let fence = fence_for(text);
+    format!("{}{}\n{}\n{}\n", fence, language, text.trim_end(), fence)
+}
+
+/// Render a conversation as Markdown
+///
+/// See [`ChatbotState::to_markdown`](crate::ChatbotState::to_markdown).
+pub fn to_markdown(messages: &[Message], title: &str) -> String {
+    let names = tool_names(messages);
+    let mut out = format!("# {}\n", title);
+
+    for message in messages {
+        out.push_str(&format!("\n## {}\n\n", role_heading(message)));
+        for block in &message.content {
+            match block {
+                ContentBlock::Text { text } => {
+                    out.push_str(text.trim_end());
+                    out.push_str("\n\n");
+                }
+                ContentBlock::ToolUse { name, input, .. } => {
+                    out.push_str(&format!("**Tool call:** `{}`\n\n", name));
+                    if let Some((path, diff)) = patch_diff(name, input) {
+                        out.push_str(&format!("Patch for `{}`:\n\n", path));
+                        out.push_str(&fenced("diff", diff));
+                    } else {
+                        let input = serde_json::to_string_pretty(input).unwrap_or_default();
+                        out.push_str(&fenced("json", &input));
+                    }
+                    out.push('\n');
+                }
+                ContentBlock::ToolResult {
+                    content,
+                    tool_use_id,
+                    is_error,
+                    ..
+                } => {
+                    let name = names.get(tool_use_id.as_str()).copied().unwrap_or("tool");
+                    let label = if *is_error == Some(true) {
+                        "error"
+                    } else {
+                        "result"
+                    };
+                    out.push_str(&format!(
+                        "<details>\n<summary>{} {}</summary>\n\n{}\n</details>\n\n",
+                        name,
+                        label,
+                        fenced("", &truncate(&ToolResultContent::text_of(content)))
+                    ));
+                }
+                ContentBlock::Image { source } => {
+                    let description = match source {
+                        ImageSource::Url { url } => format!("![image]({})", url),
+                        ImageSource::Base64 { media_type, .. } => {
+                            format!("*[{} image]*", media_type)
+                        }
+                    };
+                    out.push_str(&description);
+                    out.push_str("\n\n");
+                }
+                ContentBlock::Document { source } => {
+                    let description = match source {
+                        DocumentSource::Url { url } => format!("[document]({})", url),
+                        DocumentSource::Base64 { media_type, .. }
+                        | DocumentSource::Text { media_type, .. } => {
+                            format!("*[{} document]*", media_type)
+                        }
+                    };
+                    out.push_str(&description);
+                    out.push_str("\n\n");
+                }
+                ContentBlock::Thinking { text, .. } => {
+                    out.push_str(&format!(
+                        "<details>\n<summary>Thinking</summary>\n\n{}\n\n</details>\n\n",
+                        text.trim_end()
+                    ));
+                }
+                ContentBlock::RedactedThinking { .. } => {}
+            }
+        }
+    }
+
+    out
+}
+
+fn escape_html(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Colour a diff the way the permission prompt does, with CSS classes
+fn diff_to_html(diff: &str) -> String {
+    let mut html = String::from("<pre class=\"diff\">");
+    for line in diff.lines() {
+        let class = if line.starts_with("+++") || line.starts_with("---") {
+            "file"
+        } else if line.starts_with("@@") {
+            "hunk"
+        } else if line.starts_with('+') {
+            "add"
+        } else if line.starts_with('-') {
+            "del"
+        } else if line.starts_with(' ') {
+            "context"
+        } else {
+            "other"
+        };
+        html.push_str(&format!(
+            "<span class=\"{}\">{}</span>\n",
+            class,
+            escape_html(line)
+        ));
+    }
+    html.push_str("</pre>\n");
+    html
+}
+
+const STYLE: &str = "\
+body { font-family: system-ui, sans-serif; max-width: 50rem; margin: 2rem auto; padding: 0 1rem; line-height: 1.5; }
+section { border-left: 4px solid #ccc; padding: 0 1rem; margin: 1.5rem 0; }
+section.user { border-color: #3b82f6; }
+section.assistant { border-color: #10b981; }
+section.tool-results { border-color: #a3a3a3; }
+.text { white-space: pre-wrap; }
+pre { background: #f5f5f5; padding: 0.75rem; overflow-x: auto; }
+details.error summary { color: #b91c1c; }
+.diff .file { color: #2563eb; }
+.diff .hunk { color: #0891b2; }
+.diff .add { color: #15803d; }
+.diff .del { color: #b91c1c; }
+.diff .context { color: #737373; }
+";
+
+/// Render a conversation as a standalone HTML page
+///
+/// See [`ChatbotState::to_html`](crate::ChatbotState::to_html).
+pub fn to_html(messages: &[Message], title: &str) -> String {
+    let names = tool_names(messages);
+    let mut out = format!(
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{title}</title>\n<style>\n{STYLE}</style>\n</head>\n<body>\n<h1>{title}</h1>\n",
+        title = escape_html(title),
+    );
+
+    for message in messages {
+        let heading = role_heading(message);
+        out.push_str(&format!(
+            "<section class=\"{}\">\n<h2>{}</h2>\n",
+            heading.to_lowercase().replace(' ', "-"),
+            heading
+        ));
+        for block in &message.content {
+            match block {
+                ContentBlock::Text { text } => {
+                    out.push_str(&format!(
+                        "<div class=\"text\">{}</div>\n",
+                        escape_html(text.trim_end())
+                    ));
+                }
+                ContentBlock::ToolUse { name, input, .. } => {
+                    out.push_str(&format!(
+                        "<p><strong>Tool call:</strong> <code>{}</code></p>\n",
+                        escape_html(name)
+                    ));
+                    if let Some((path, diff)) = patch_diff(name, input) {
+                        out.push_str(&format!(
+                            "<p>Patch for <code>{}</code>:</p>\n",
+                            escape_html(path)
+                        ));
+                        out.push_str(&diff_to_html(diff));
+                    } else {
+                        let input = serde_json::to_string_pretty(input).unwrap_or_default();
+                        out.push_str(&format!("<pre>{}</pre>\n", escape_html(&input)));
+                    }
+                }
+                ContentBlock::ToolResult {
+                    content,
+                    tool_use_id,
+                    is_error,
+                    ..
+                } => {
+                    let name = names.get(tool_use_id.as_str()).copied().unwrap_or("tool");
+                    let label = if *is_error == Some(true) {
+                        "error"
+                    } else {
+                        "result"
+                    };
+                    out.push_str(&format!(
+                        "<details class=\"{label}\">\n<summary>{} {label}</summary>\n<pre>{}</pre>\n</details>\n",
+                        escape_html(name),
+                        escape_html(&truncate(&ToolResultContent::text_of(content)))
+                    ));
+                }
+                ContentBlock::Image { source } => match source {
+                    ImageSource::Url { url } => {
+                        out.push_str(&format!(
+                            "<img src=\"{}\" alt=\"image\">\n",
+                            escape_html(url)
+                        ));
+                    }
+                    ImageSource::Base64 { media_type, data } => {
+                        out.push_str(&format!(
+                            "<img src=\"data:{};base64,{}\" alt=\"image\">\n",
+                            escape_html(media_type),
+                            escape_html(data)
+                        ));
+                    }
+                },
+                ContentBlock::Document { source } => match source {
+                    DocumentSource::Url { url } => {
+                        out.push_str(&format!(
+                            "<p><a href=\"{}\">document</a></p>\n",
+                            escape_html(url)
+                        ));
+                    }
+                    DocumentSource::Base64 { media_type, .. }
+                    | DocumentSource::Text { media_type, .. } => {
+                        out.push_str(&format!(
+                            "<p><em>[{} document]</em></p>\n",
+                            escape_html(media_type)
+                        ));
+                    }
+                },
+                ContentBlock::Thinking { text, .. } => {
+                    out.push_str(&format!(
+                        "<details class=\"thinking\">\n<summary>Thinking</summary>\n<div class=\"text\">{}</div>\n</details>\n",
+                        escape_html(text.trim_end())
+                    ));
+                }
+                ContentBlock::RedactedThinking { .. } => {}
+            }
+        }
+        out.push_str("</section>\n");
+    }
+
+    out.push_str("</body>\n</html>\n");
+    out
+}
@@ -46,9 +46,102 @@ let response = client.run_conversation_turn(
 - **Tool support**: Define custom tools that Claude can use during conversations
 - **Permission control**: Fine-grained control over tool execution with permission handlers
 - **Conversation management**: Track multi-turn conversations with message history
-- **Real-time execution**: Process tool calls as they happen
+- **Context management**: Truncate long conversations to fit the context window
+- **Real-time execution**: Process tool calls as they happen, with progress events
 - **Comprehensive error handling**: Detailed error types for debugging
 
+## Cargo Features
+
+The client, message types, and lightweight tools are always available. Tools
+with heavy dependencies are behind features, all enabled by default through
+`all-tools`:
+
+- `z3`: [`tools::Z3SolverTool`], which builds the Z3 C library
+- `firecrawl`: the Firecrawl crawl, search, map, and extract tools
+- `search`: the gitignore-aware [`tools::ListDirectoryTool`] and [`tools::SearchFilesTool`]
+
+Depend on the crate with `default-features = false` to leave them out. This
+example builds either way; `cargo test --no-default-features --doc` checks
+the crate without them:
+
+```rust
+use claude::tools::{CalculatorTool, ReadFileTool};
+use claude::ToolRegistry;
+use std::sync::Arc;
+
+let mut registry = ToolRegistry::new();
+registry.register(Arc::new(CalculatorTool))?;
+registry.register(Arc::new(ReadFileTool))?;
+#[cfg(feature = "z3")]
+registry.register(Arc::new(claude::tools::Z3SolverTool))?;
+
+assert_eq!(registry.has_tool("z3_solver"), cfg!(feature = "z3"));
+# Ok::<(), claude::Error>(())
+```
+
+## Logging
+
+Requests, conversation turns, and tool executions are instrumented with
+[`tracing`](https://docs.rs/tracing) spans and events: `next_message` logs the
+model, request size, status, latency, and token usage; each turn logs its
+iterations; and each tool execution logs the permission decision, duration,
+and any error. Nothing is recorded unless a subscriber is installed.
+
+```rust
+use async_trait::async_trait;
+use claude::test_util::MockTransport;
+use claude::{Claude, Tool, ToolRegistry};
+use serde_json::{json, Value};
+use std::sync::Arc;
+use tracing_test::traced_test;
+
+struct Echo;
+
+#[async_trait]
+impl Tool for Echo {
+    fn name(&self) -> &str { "echo" }
+    fn description(&self) -> &str { "Echoes its input" }
+    fn input_schema(&self) -> Value { json!({"type": "object"}) }
+    async fn execute(&self, input: Value) -> Result<String, claude::Error> {
+        Ok(input.to_string())
+    }
+}
+
+#[traced_test]
+fn mock_turn() {
+    // The first response calls the echo tool, the second finishes the turn
+    let transport = Arc::new(MockTransport::new());
+    transport.push_message(
+        json!([{"type": "tool_use", "id": "toolu_1", "name": "echo", "input": {"text": "hi"}}]),
+        "tool_use",
+    );
+    transport.push_message(json!([{"type": "text", "text": "Done"}]), "end_turn");
+
+    let client = Claude::new("test-key".to_string(), "claude-3-5-haiku-20241022".to_string())
+        .with_transport(transport);
+    let mut registry = ToolRegistry::new();
+    registry.register(Arc::new(Echo)).unwrap();
+    tokio::runtime::Runtime::new().unwrap().block_on(async {
+        let text = client
+            .run_conversation_turn("Say hi", &mut registry, None, None, None)
+            .await
+            .unwrap();
+        assert_eq!(text, "Done");
+    });
+
+    assert!(logs_contain("conversation_turn{model=claude-3-5-haiku-20241022}"));
+    assert!(logs_contain("sending request request_bytes="));
+    assert!(logs_contain("response received status=200"));
+    assert!(logs_contain("input_tokens=10 output_tokens=5 stop_reason=tool_use"));
+    assert!(logs_contain("tool{tool=echo tool_use_id=toolu_1}"));
+    assert!(logs_contain("permission decided decision=Allow"));
+    assert!(logs_contain("tool completed duration_ms="));
+    assert!(logs_contain("turn finished iterations=2 tool_calls=1 stop_reason=end_turn"));
+}
+
+mock_turn();
+```
+
 ## Main Components
 
 - [`Claude`]: The main client for interacting with the API
@@ -56,30 +149,63 @@ let response = client.run_conversation_turn(
 - [`ToolRegistry`]: Manages available tools and tracks execution history
 - [`Message`] and [`ContentBlock`]: Core types for conversation messages
 - [`ToolPermissionHandler`]: Control whether tools can be executed
+- [`ContextManager`]: Keep conversations within the context window
 */
 
 // Re-export main types from submodules
-pub use client::{Claude, MESSAGES_ENDPOINT};
+pub use batches::{BatchId, BatchOutcome, BatchResult, MessageBatch};
+pub use client::{
+    Claude, SecretString, TurnOptions, TurnOutcome, API_BASE_URL, API_KEY_VARS,
+    COUNT_TOKENS_ENDPOINT, DEFAULT_MODEL, IDEMPOTENCY_KEY_HEADER, MESSAGES_ENDPOINT,
+    MODELS_ENDPOINT, PDFS_BETA, PROMPT_CACHING_BETA, TOKEN_COUNTING_BETA,
+};
+pub use context::{ContextManager, TruncationStrategy};
 pub use error::{Error, Result};
+pub use events::TurnEvent;
 pub use execution::{ExecutionState, ToolExecution};
-pub use message::{ContentBlock, Message, ToolUse};
+pub use message::{
+    ContentBlock, DocumentSource, ImageSource, Message, PartialToolUse, ToolResultContent, ToolUse,
+    MAX_PDF_BYTES,
+};
+pub use models::{ModelCapabilities, ModelInfo};
 pub use permissions::{
-    AlwaysAllowPermissions, AlwaysDenyPermissions, InteractivePermissions, LoggingPermissions,
-    MemoryPermissionHandler, PermissionDecision, PolicyPermissions, ToolExecutionRequest,
+    AlwaysAllowPermissions, AlwaysDenyPermissions, AsyncInteractivePermissions, ChainMode,
+    ChainedPermissions, InputMatcher, InteractivePermissions, LoggingPermissions,
+    MemoryPermissionHandler, PermissionDecision, PolicyPermissions, PolicyRule, PromptChoice,
+    RateLimitPermissions, RuleEffect, SharedMemoryPermissions, ToolExecutionRequest,
     ToolPermissionHandler,
 };
-pub use request::{MessageRequest, MessageResponse, ToolDef, Usage};
-pub use state::ChatbotState;
-pub use tool::{Tool, ToolRegistry};
+pub use request::{
+    CacheControl, MessageRequest, MessageRequestBuilder, MessageResponse, SystemBlock,
+    SystemPrompt, ThinkingConfig, ToolChoice, ToolDef, ToolDefBuilder, ToolPairing, Usage,
+};
+pub use retry::RetryPolicy;
+pub use state::{ChatbotState, Checkpoint, CheckpointId};
+pub use tokio_util::sync::CancellationToken;
+pub use tool::{Tool, ToolOutput, ToolRegistry};
+pub use transcript::{Transcript, TranscriptEntry};
+pub use transport::{RawRequest, RawResponse, ReqwestTransport, Transport};
 
 // Modules
+pub mod batches;
 pub mod chat_ui;
 pub mod client;
+pub mod context;
 pub mod error;
+pub mod events;
 pub mod execution;
+pub mod export;
 pub mod message;
+pub mod models;
+pub mod paths;
 pub mod permissions;
+pub mod pricing;
 pub mod request;
+pub mod retry;
+pub mod schema;
 pub mod state;
+pub mod test_util;
 pub mod tool;
 pub mod tools;
+pub mod transcript;
+pub mod transport;
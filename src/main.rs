@@ -1,12 +1,11 @@
 use chrono::Local;
 use claude::{
-    tools::*, ChatbotState, Claude, ContentBlock, Error, MemoryPermissionHandler, Message, Result,
-    ToolRegistry,
+    paths, tools::*, ChatbotState, Claude, ContentBlock, Error, MemoryPermissionHandler, Message,
+    Result, RetryPolicy, ToolRegistry, ToolResultContent,
 };
 use colored::*;
 use dialoguer::{theme::ColorfulTheme, Input, Select};
 use indicatif::{ProgressBar, ProgressStyle};
-use std::env;
 use std::fs;
 use std::path::PathBuf;
 use std::sync::Arc;
@@ -17,8 +16,7 @@ use chat_ui::ChatUI;
 
 // Conversation history management
 fn get_history_dir() -> PathBuf {
-    let home_dir = env::home_dir().expect("Unable to determine home directory");
-    let history_dir = home_dir.join(".chatbot_history");
+    let history_dir = paths::in_home(".chatbot_history");
     fs::create_dir_all(&history_dir).ok();
     history_dir
 }
@@ -71,6 +69,23 @@ fn load_state(filename: &str) -> Result<ChatbotState> {
     }
 }
 
+/// Write the conversation to `path`, as HTML if it ends in `.html` and
+/// Markdown otherwise
+fn export_conversation(state: &ChatbotState, path: &str) -> Result<()> {
+    let is_html = path.ends_with(".html") || path.ends_with(".htm");
+    let rendered = if is_html {
+        state.to_html()
+    } else {
+        state.to_markdown()
+    };
+
+    fs::write(path, rendered)
+        .map_err(|e| Error::Other(format!("Failed to write export file: {}", e)))?;
+
+    println!("{} Conversation exported to: {}", "✓".green(), path);
+    Ok(())
+}
+
 fn list_saved_conversations() -> Vec<String> {
     let history_dir = get_history_dir();
     let mut conversations = Vec::new();
@@ -89,44 +104,48 @@ fn list_saved_conversations() -> Vec<String> {
     conversations
 }
 
+/// Largest tool result sent back to Claude; the history keeps the full output
+const MAX_TOOL_RESULT_BYTES: usize = 100_000;
+
+/// Models offered when the API's model list can't be fetched
+const FALLBACK_MODELS: &[&str] = &[
+    "claude-3-7-sonnet-latest",
+    "claude-opus-4-20250514",
+    "claude-sonnet-4-20250514",
+];
+
+/// Model ids for the model picker, from the API when it can be reached
+async fn available_models(client: &Claude) -> Vec<String> {
+    match client.list_models().await {
+        Ok(models) if !models.is_empty() => models.into_iter().map(|m| m.id).collect(),
+        _ => FALLBACK_MODELS.iter().map(|m| m.to_string()).collect(),
+    }
+}
+
 #[tokio::main]
 async fn main() -> Result<()> {
-    // Load environment from ~/.generalist.env
-    let home_dir = env::home_dir().expect("Unable to determine home directory");
-    let env_path = home_dir.join(".generalist.env");
-
-    // Check if the file exists
-    if !env_path.exists() {
-        eprintln!("{}", "Error: ~/.generalist.env file not found".red());
-        eprintln!("Please create the file with your API key:");
-        eprintln!("  echo 'CLAUDE_API_KEY=your-api-key-here' > ~/.generalist.env");
-        std::process::exit(1);
+    // Load environment from ~/.generalist.env, if there is one
+    let env_path = paths::in_home(".generalist.env");
+    if env_path.exists() {
+        dotenv::from_path(&env_path).expect("Failed to load ~/.generalist.env");
     }
 
-    // Load the env file
-    dotenv::from_path(&env_path).expect("Failed to load ~/.generalist.env");
-
-    // Get API key
-    let api_key = env::var("CLAUDE_API_KEY").unwrap_or_else(|_| {
-        eprintln!(
-            "{}",
-            "Error: CLAUDE_API_KEY not found in ~/.generalist.env".red()
-        );
-        eprintln!("Please add your API key to ~/.generalist.env:");
-        eprintln!("  echo 'CLAUDE_API_KEY=your-api-key-here' >> ~/.generalist.env");
-        std::process::exit(1);
-    });
+    // Get API key from ANTHROPIC_API_KEY or CLAUDE_API_KEY
+    let base_client = Claude::from_env()
+        .unwrap_or_else(|e| {
+            eprintln!("{}", format!("Error: {}", e).red());
+            eprintln!("Export ANTHROPIC_API_KEY, or add your API key to ~/.generalist.env:");
+            eprintln!("  echo 'CLAUDE_API_KEY=your-api-key-here' >> ~/.generalist.env");
+            std::process::exit(1);
+        })
+        .with_retry_policy(RetryPolicy::default());
 
     // Initialize UI
     let ui = ChatUI::new();
     ui.print_welcome();
 
     // Select model
-    let models = vec![
-        "claude-3-7-sonnet-latest",
-        "claude-opus-4-20250514",
-        "claude-sonnet-4-20250514",
-    ];
+    let models = available_models(&base_client).await;
 
     let model_selection = Select::with_theme(&ColorfulTheme::default())
         .with_prompt("Select Claude model")
@@ -135,17 +154,23 @@ async fn main() -> Result<()> {
         .interact()
         .unwrap();
 
-    let mut model = models[model_selection].to_string();
+    let mut model = models[model_selection].clone();
     println!("{} Using model: {}\n", "✓".green(), model.cyan());
 
     // Initialize state
     let mut state = ChatbotState::new(model.clone());
 
     // Initialize permission handler
-    let permission_handler = Arc::new(MemoryPermissionHandler::new());
+    let permission_handler = MemoryPermissionHandler::new();
 
     // Initialize Claude client
-    let mut client = Claude::new(api_key.clone(), model.clone());
+    let mut client = base_client.clone().with_model(model.clone());
+
+    // Fail now, not on the first message, if the key or model is unusable
+    if let Err(e) = client.health_check().await {
+        eprintln!("{}", format!("Error: {}", e).red());
+        std::process::exit(1);
+    }
 
     // Initialize tool registry with memory permission handler
     println!("{} Using interactive permissions with memory", "🔐".cyan());
@@ -155,34 +180,24 @@ async fn main() -> Result<()> {
             .dimmed()
     );
 
-    // Create a shared handler for the registry
-    let shared_handler = MemoryPermissionHandler::with_shared_state(
-        permission_handler.always_allow(),
-        permission_handler.always_deny(),
-    );
-    let mut registry = ToolRegistry::with_permission_handler(Box::new(shared_handler));
-
-    registry.register(Arc::new(PatchFileTool))?;
-    registry.register(Arc::new(ReadFileTool))?;
-    registry.register(Arc::new(ListDirectoryTool))?;
-    registry.register(Arc::new(BashTool))?;
-    registry.register(Arc::new(SystemInfoTool))?;
-    registry.register(Arc::new(CalculatorTool))?;
-    registry.register(Arc::new(WeatherTool))?;
-    registry.register(Arc::new(HttpFetchTool))?;
-    registry.register(Arc::new(EnhancedMemoryTool::new()?))?;
-    registry.register(Arc::new(ThinkTool))?;
-    registry.register(Arc::new(WikipediaTool))?;
-    registry.register(Arc::new(Z3SolverTool))?;
-    registry.register(Arc::new(TodoTool))?;
-    registry.register(Arc::new(FirecrawlCrawlTool))?;
-    registry.register(Arc::new(FirecrawlSearchTool))?;
-    registry.register(Arc::new(FirecrawlMapTool))?;
-    registry.register(Arc::new(FirecrawlExtractTool))?;
+    // The registry's clone shares remembered decisions with ours
+    let mut registry = ToolRegistry::with_permission_handler(Box::new(permission_handler.clone()));
+
+    registry.register_default_tools()?;
+    // The think tool shares its budget with the requests made below
+    let thinking_budget = ThinkingBudget::new();
+    registry.replace(Arc::new(ThinkTool::with_budget(thinking_budget.clone())))?;
+    // Keep a single huge page or listing from filling the context window
+    registry.set_max_tool_result_bytes(Some(MAX_TOOL_RESULT_BYTES));
 
     // Load system prompt
-    let system_prompt = include_str!("../SYSTEM_PROMPT.md");
-    state.system_prompt = Some(system_prompt.to_string());
+    let usage_guide = registry.tool_usage_guide();
+    let system_prompt = if usage_guide.is_empty() {
+        include_str!("../SYSTEM_PROMPT.md").to_string()
+    } else {
+        format!("{}\n\n{}", include_str!("../SYSTEM_PROMPT.md"), usage_guide)
+    };
+    state.system_prompt = Some(system_prompt.clone());
 
     // Main conversation loop
     loop {
@@ -208,11 +223,17 @@ async fn main() -> Result<()> {
             // Update state with current permissions
             state.always_allow_tools = permission_handler.always_allow().lock().unwrap().clone();
             state.always_deny_tools = permission_handler.always_deny().lock().unwrap().clone();
+            state.tool_history = registry.execution_history().to_vec();
 
             if let Err(e) = save_state(&state, &name) {
                 ui.print_error(&format!("Failed to save state: {}", e));
             }
             continue;
+        } else if let Some(path) = input_trimmed.strip_prefix("/export ") {
+            if let Err(e) = export_conversation(&state, path.trim()) {
+                ui.print_error(&format!("Failed to export conversation: {}", e));
+            }
+            continue;
         } else if input_trimmed.eq_ignore_ascii_case("/load") {
             let saved = list_saved_conversations();
             if saved.is_empty() {
@@ -240,7 +261,7 @@ async fn main() -> Result<()> {
                         // Update model if different
                         if state.model != model {
                             model = state.model.clone();
-                            client = Claude::new(api_key.clone(), model.clone());
+                            client = base_client.clone().with_model(model.clone());
                             println!("{} Switched to model: {}", "✓".green(), model.cyan());
                         }
 
@@ -254,6 +275,9 @@ async fn main() -> Result<()> {
                             state.always_deny_tools.len()
                         );
 
+                        // Restore what tools ran
+                        registry.load_history(state.tool_history.clone());
+
                         // Display loaded conversation
                         for msg in &state.conversation_history {
                             match msg.role.as_str() {
@@ -279,14 +303,10 @@ async fn main() -> Result<()> {
             }
             continue;
         } else if input_trimmed.eq_ignore_ascii_case("/model") {
-            let models = vec![
-                "claude-3-7-sonnet-latest",
-                "claude-opus-4-20250514",
-                "claude-sonnet-4-20250514",
-            ];
+            let models = available_models(&base_client).await;
 
             // Find current model index
-            let current_idx = models.iter().position(|&m| m == model).unwrap_or(0);
+            let current_idx = models.iter().position(|m| *m == model).unwrap_or(0);
 
             let model_selection = Select::with_theme(&ColorfulTheme::default())
                 .with_prompt("Select new Claude model")
@@ -295,21 +315,84 @@ async fn main() -> Result<()> {
                 .interact()
                 .unwrap();
 
-            let new_model = models[model_selection].to_string();
+            let new_model = models[model_selection].clone();
             if new_model != model {
                 model = new_model;
                 state.model = model.clone();
-                client = Claude::new(api_key.clone(), model.clone());
+                client = base_client.clone().with_model(model.clone());
                 println!("{} Switched to model: {}", "✓".green(), model.cyan());
             } else {
                 println!("{} Already using model: {}", "ℹ".blue(), model.cyan());
             }
             continue;
+        } else if let Some(label) = input_trimmed.strip_prefix("/checkpoint ") {
+            let id = state.checkpoint(label.trim());
+            println!("{} Saved checkpoint {} ({})", "✓".green(), id, label.trim());
+            continue;
+        } else if input_trimmed.eq_ignore_ascii_case("/checkpoints") {
+            if state.checkpoints.is_empty() {
+                println!("{} No checkpoints yet", "ℹ".blue());
+            }
+            for checkpoint in &state.checkpoints {
+                println!(
+                    "  {} {} ({} messages, {})",
+                    checkpoint.id.to_string().cyan(),
+                    checkpoint.label,
+                    checkpoint.messages.len(),
+                    checkpoint.created_at.format("%Y-%m-%d %H:%M")
+                );
+            }
+            continue;
+        } else if let Some(id) = input_trimmed.strip_prefix("/restore ") {
+            let restored = match id.trim().parse() {
+                Ok(id) => state.restore(claude::CheckpointId(id)),
+                Err(_) => Err(Error::Other(format!(
+                    "Invalid checkpoint id: {}",
+                    id.trim()
+                ))),
+            };
+            match restored {
+                Ok(branch) => {
+                    println!(
+                        "{} Restored checkpoint {} ({} messages)",
+                        "✓".green(),
+                        id.trim(),
+                        state.conversation_history.len()
+                    );
+                    if let Some(branch) = branch {
+                        println!(
+                            "{} The previous conversation was kept as checkpoint {}",
+                            "ℹ".blue(),
+                            branch
+                        );
+                    }
+                }
+                Err(e) => ui.print_error(&e.to_string()),
+            }
+            continue;
+        } else if input_trimmed.eq_ignore_ascii_case("/stats") {
+            println!("\n{}", "Session summary:".yellow().bold());
+            println!("{}\n", state.summary());
+            continue;
         } else if input_trimmed.eq_ignore_ascii_case("/help") {
             println!("\n{}", "Available commands:".yellow().bold());
             println!("  {} - Save current conversation", "/save".cyan());
             println!("  {} - Load a saved conversation", "/load".cyan());
+            println!(
+                "  {} - Export as Markdown, or HTML for a .html file",
+                "/export <file>".cyan()
+            );
             println!("  {} - Switch Claude model", "/model".cyan());
+            println!("  {} - Show session statistics", "/stats".cyan());
+            println!(
+                "  {} - Save the conversation so far",
+                "/checkpoint <label>".cyan()
+            );
+            println!("  {} - List saved checkpoints", "/checkpoints".cyan());
+            println!(
+                "  {} - Rewind to a checkpoint, keeping the current branch",
+                "/restore <id>".cyan()
+            );
             println!("  {} - Show this help message", "/help".cyan());
             println!(
                 "  {} or {} - Exit the chatbot",
@@ -321,6 +404,7 @@ async fn main() -> Result<()> {
         }
 
         ui.print_message("user", &input);
+        registry.begin_turn(input.clone());
 
         // Add user message to history
         state
@@ -341,6 +425,16 @@ async fn main() -> Result<()> {
 
         // Manual conversation handling for real-time display
         let mut current_messages = state.conversation_history.clone();
+
+        // Extended thinking requested by the think tool applies to this whole turn
+        let thinking = thinking_budget
+            .take()
+            .map(|budget_tokens| claude::ThinkingConfig::Enabled { budget_tokens });
+        let max_tokens = 1024
+            + thinking
+                .as_ref()
+                .and_then(|t| t.budget_tokens())
+                .unwrap_or(0);
         let max_iterations = 100;
         let mut iterations = 0;
         let mut final_response = None;
@@ -353,19 +447,43 @@ async fn main() -> Result<()> {
             }
 
             // Create request
-            let request = claude::MessageRequest {
+            let mut request = claude::MessageRequest {
                 model: client.model().to_string(),
                 messages: current_messages.clone(),
                 tools: registry.get_tool_defs(),
-                max_tokens: 1024,
-                system: Some(system_prompt.to_string()),
+                max_tokens,
+                system: None,
                 temperature: None,
-            };
+                tool_choice: None,
+                thinking: thinking.clone(),
+            }
+            .with_cached_system(system_prompt.as_str());
+
+            // Restored checkpoints and loaded files may have lost one half of a tool call
+            let dropped = request.repair_tool_pairing();
+            if dropped > 0 {
+                println!(
+                    "{} Dropped {} unmatched tool call block(s) from the request",
+                    "ℹ".blue(),
+                    dropped
+                );
+            }
 
             // Send message
             match client.next_message(request).await {
                 Ok(response) => {
                     thinking_pb.finish_and_clear();
+                    if let Some(usage) = &response.usage {
+                        state.record_usage(usage);
+                    }
+
+                    if let Some(tool_name) = response.truncated_tool_use() {
+                        ui.print_error(&format!(
+                            "Response hit max_tokens while generating input for tool '{}'",
+                            tool_name
+                        ));
+                        break;
+                    }
 
                     // Process response content in real-time
                     let mut has_tool_uses = false;
@@ -380,10 +498,14 @@ async fn main() -> Result<()> {
                             }
                             ContentBlock::ToolUse { name, input, id } => {
                                 has_tool_uses = true;
+                                state.record_tool_call(name);
                                 // Don't show tool use until after permission check
 
                                 // Execute tool (permission check happens inside)
-                                match registry.execute_tool(name, input.clone(), id.clone()).await {
+                                match registry
+                                    .execute_tool(name, input.clone(), id.clone(), None)
+                                    .await
+                                {
                                     Ok(result) => {
                                         // Check if this is a permission denial (is_error = true and content contains "denied")
                                         if let ContentBlock::ToolResult {
@@ -392,6 +514,7 @@ async fn main() -> Result<()> {
                                             ..
                                         } = &result
                                         {
+                                            let content = ToolResultContent::text_of(content);
                                             if content.contains("denied") {
                                                 // Permission was denied - don't show progress bar
                                                 println!(
@@ -411,7 +534,7 @@ async fn main() -> Result<()> {
                                                 println!(
                                                     "   {} Error: {}",
                                                     "→".red(),
-                                                    ui.shorten_result_public(content).dimmed()
+                                                    ui.shorten_result_public(&content).dimmed()
                                                 );
                                             }
                                         } else {
@@ -424,10 +547,11 @@ async fn main() -> Result<()> {
                                             if let ContentBlock::ToolResult { content, .. } =
                                                 &result
                                             {
+                                                let content = ToolResultContent::text_of(content);
                                                 println!(
                                                     "   {} Result: {}",
                                                     "→".cyan(),
-                                                    ui.shorten_result_public(content).dimmed()
+                                                    ui.shorten_result_public(&content).dimmed()
                                                 );
                                             }
                                         }
@@ -443,13 +567,20 @@ async fn main() -> Result<()> {
                                         );
                                         tool_results.push(ContentBlock::ToolResult {
                                             tool_use_id: id.clone(),
-                                            content: format!("Error: {}", e),
+                                            content: vec![format!("Error: {}", e).into()],
                                             is_error: Some(true),
+                                            cache_control: None,
                                         });
                                     }
                                 }
                             }
-                            ContentBlock::ToolResult { .. } => {
+                            ContentBlock::Thinking { .. }
+                            | ContentBlock::RedactedThinking { .. } => {
+                                // Kept in history for the API but not shown
+                            }
+                            ContentBlock::ToolResult { .. }
+                            | ContentBlock::Image { .. }
+                            | ContentBlock::Document { .. } => {
                                 // Should not appear in assistant responses
                             }
                         }
@@ -1,6 +1,14 @@
 use crate::error::{Error, Result};
+use crate::request::CacheControl;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
+use std::path::Path;
+
+/// Largest PDF [`Message::user_with_pdf`] will attach
+///
+/// Base64 encoding grows the file by a third, so this keeps the document under
+/// the API's 32 MB request size limit.
+pub const MAX_PDF_BYTES: u64 = 24 * 1024 * 1024;
 
 /// Message representation for Claude API interactions
 ///
@@ -13,6 +21,7 @@ use serde_json::Value;
 /// - **Text**: Simple text content
 /// - **Tool Use**: Requests from Claude to use a specific tool
 /// - **Tool Results**: Results from tool executions sent back to Claude
+/// - **Images**: Base64-encoded or URL-referenced images for vision-capable models
 ///
 /// # Example
 ///
@@ -136,6 +145,257 @@ impl Message {
             })
             .collect()
     }
+
+    /// Create a new user message containing an image followed by text
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use claude::{Message, ContentBlock, ImageSource};
+    ///
+    /// let msg = Message::user_with_image(
+    ///     ImageSource::Url { url: "https://example.com/cat.png".to_string() },
+    ///     "What animal is this?",
+    /// );
+    /// assert_eq!(msg.role, "user");
+    /// assert!(matches!(msg.content[0], ContentBlock::Image { .. }));
+    /// assert!(matches!(msg.content[1], ContentBlock::Text { .. }));
+    /// ```
+    pub fn user_with_image(source: ImageSource, text: &str) -> Self {
+        Self::user(vec![
+            ContentBlock::Image { source },
+            ContentBlock::Text {
+                text: text.to_string(),
+            },
+        ])
+    }
+
+    /// Create a new user message containing a PDF read from `path` followed by text
+    ///
+    /// The file is base64-encoded into a [`ContentBlock::Document`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file can't be read, is larger than
+    /// [`MAX_PDF_BYTES`], or doesn't start with the PDF header.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use claude::{ContentBlock, DocumentSource, Message};
+    /// use std::io::Write;
+    ///
+    /// let mut pdf = tempfile::NamedTempFile::new().unwrap();
+    /// pdf.write_all(b"%PDF-1.4\n%%EOF\n").unwrap();
+    ///
+    /// let msg = Message::user_with_pdf(pdf.path(), "Summarize this paper").unwrap();
+    /// match &msg.content[0] {
+    ///     ContentBlock::Document { source: DocumentSource::Base64 { media_type, data } } => {
+    ///         assert_eq!(media_type, "application/pdf");
+    ///         assert_eq!(data, "JVBERi0xLjQKJSVFT0YK");
+    ///     }
+    ///     _ => panic!("Expected document block"),
+    /// }
+    /// assert_eq!(msg.visible_text(), "Summarize this paper");
+    ///
+    /// // Files that aren't PDFs, or are too big to send, are rejected
+    /// let mut text = tempfile::NamedTempFile::new().unwrap();
+    /// text.write_all(b"just some notes").unwrap();
+    /// assert!(Message::user_with_pdf(text.path(), "Summarize").is_err());
+    ///
+    /// let huge = tempfile::NamedTempFile::new().unwrap();
+    /// huge.as_file().set_len(claude::MAX_PDF_BYTES + 1).unwrap();
+    /// let error = Message::user_with_pdf(huge.path(), "Summarize").unwrap_err();
+    /// assert!(error.to_string().contains("limit"), "{}", error);
+    /// ```
+    pub fn user_with_pdf(path: impl AsRef<Path>, text: &str) -> Result<Self> {
+        use base64::Engine;
+
+        let path = path.as_ref();
+        let size = std::fs::metadata(path)
+            .map_err(|e| Error::Other(format!("Failed to read {}: {}", path.display(), e)))?
+            .len();
+        if size > MAX_PDF_BYTES {
+            return Err(Error::Other(format!(
+                "{} is {} bytes, over the {} byte limit for PDFs",
+                path.display(),
+                size,
+                MAX_PDF_BYTES
+            )));
+        }
+
+        let bytes = std::fs::read(path)
+            .map_err(|e| Error::Other(format!("Failed to read {}: {}", path.display(), e)))?;
+        if !bytes.starts_with(b"%PDF-") {
+            return Err(Error::Other(format!(
+                "{} is not a PDF file",
+                path.display()
+            )));
+        }
+
+        Ok(Self::user(vec![
+            ContentBlock::Document {
+                source: DocumentSource::Base64 {
+                    media_type: "application/pdf".to_string(),
+                    data: base64::engine::general_purpose::STANDARD.encode(bytes),
+                },
+            },
+            ContentBlock::Text {
+                text: text.to_string(),
+            },
+        ]))
+    }
+
+    /// The text meant for the end user, joined by newlines
+    ///
+    /// Only text blocks are included: thinking, tool use and other blocks
+    /// stay in the history but are not shown.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use claude::{Message, ContentBlock};
+    ///
+    /// let msg = Message::assistant(vec![
+    ///     ContentBlock::Thinking {
+    ///         text: "The user wants a greeting.".to_string(),
+    ///         signature: "sig==".to_string(),
+    ///     },
+    ///     ContentBlock::Text { text: "Hello!".to_string() },
+    /// ]);
+    /// assert_eq!(msg.visible_text(), "Hello!");
+    /// ```
+    pub fn visible_text(&self) -> String {
+        self.content
+            .iter()
+            .filter_map(|block| match block {
+                ContentBlock::Text { text } => Some(text.as_str()),
+                _ => None,
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+/// Source of an image content block
+///
+/// Serializes to the `source` object of Anthropic's image content block,
+/// tagged by `type`.
+///
+/// # Example
+///
+/// ```rust
+/// use claude::{ContentBlock, ImageSource};
+/// use serde_json::json;
+///
+/// let block = ContentBlock::Image {
+///     source: ImageSource::Base64 {
+///         media_type: "image/png".to_string(),
+///         data: "iVBORw0KGgo=".to_string(),
+///     },
+/// };
+/// let value = serde_json::to_value(&block).unwrap();
+/// assert_eq!(value, json!({
+///     "type": "image",
+///     "source": {"type": "base64", "media_type": "image/png", "data": "iVBORw0KGgo="}
+/// }));
+///
+/// let parsed: ContentBlock = serde_json::from_value(value).unwrap();
+/// match parsed {
+///     ContentBlock::Image { source: ImageSource::Base64 { media_type, data } } => {
+///         assert_eq!(media_type, "image/png");
+///         assert_eq!(data, "iVBORw0KGgo=");
+///     }
+///     _ => panic!("Expected base64 image block"),
+/// }
+///
+/// let block = ContentBlock::Image {
+///     source: ImageSource::Url { url: "https://example.com/cat.png".to_string() },
+/// };
+/// let value = serde_json::to_value(&block).unwrap();
+/// assert_eq!(value, json!({
+///     "type": "image",
+///     "source": {"type": "url", "url": "https://example.com/cat.png"}
+/// }));
+///
+/// let parsed: ContentBlock = serde_json::from_value(value).unwrap();
+/// match parsed {
+///     ContentBlock::Image { source: ImageSource::Url { url } } => {
+///         assert_eq!(url, "https://example.com/cat.png");
+///     }
+///     _ => panic!("Expected URL image block"),
+/// }
+/// ```
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ImageSource {
+    /// Inline base64-encoded image data
+    Base64 {
+        /// MIME type of the image (e.g., "image/png", "image/jpeg")
+        media_type: String,
+        /// Base64-encoded image bytes
+        data: String,
+    },
+    /// Image referenced by URL
+    Url {
+        /// Publicly reachable URL of the image
+        url: String,
+    },
+}
+
+/// Source of a document content block
+///
+/// Serializes to the `source` object of Anthropic's document content block,
+/// tagged by `type`.
+///
+/// # Example
+///
+/// ```rust
+/// use claude::{ContentBlock, DocumentSource};
+/// use serde_json::json;
+///
+/// let wire = json!({
+///     "type": "document",
+///     "source": {"type": "base64", "media_type": "application/pdf", "data": "JVBERi0xLjQK"}
+/// });
+/// let block: ContentBlock = serde_json::from_value(wire.clone()).unwrap();
+/// assert!(matches!(
+///     &block,
+///     ContentBlock::Document { source: DocumentSource::Base64 { media_type, .. } }
+///         if media_type == "application/pdf"
+/// ));
+/// assert_eq!(serde_json::to_value(&block).unwrap(), wire);
+///
+/// let block = ContentBlock::Document {
+///     source: DocumentSource::Url { url: "https://arxiv.org/pdf/1706.03762".to_string() },
+/// };
+/// assert_eq!(
+///     serde_json::to_value(&block).unwrap(),
+///     json!({"type": "document", "source": {"type": "url", "url": "https://arxiv.org/pdf/1706.03762"}})
+/// );
+/// ```
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum DocumentSource {
+    /// Inline base64-encoded document data
+    Base64 {
+        /// MIME type of the document ("application/pdf")
+        media_type: String,
+        /// Base64-encoded document bytes
+        data: String,
+    },
+    /// Document referenced by URL
+    Url {
+        /// Publicly reachable URL of the PDF
+        url: String,
+    },
+    /// Plain text document
+    Text {
+        /// MIME type of the text ("text/plain")
+        media_type: String,
+        /// The document text
+        data: String,
+    },
 }
 
 /// Content block types used in messages
@@ -144,6 +404,9 @@ impl Message {
 /// - Text content
 /// - Tool usage requests from Claude
 /// - Tool execution results
+/// - Images
+/// - Documents such as PDFs
+/// - Extended thinking
 ///
 /// # Example
 ///
@@ -164,11 +427,35 @@ impl Message {
 ///
 /// // Tool result
 /// let tool_result = ContentBlock::ToolResult {
-///     content: "4".to_string(),
+///     content: vec!["4".into()],
 ///     tool_use_id: "tool_123".to_string(),
 ///     is_error: None,
+///     cache_control: None,
 /// };
 /// ```
+///
+/// Thinking blocks round-trip through the API's wire format:
+///
+/// ```rust
+/// use claude::ContentBlock;
+/// use serde_json::json;
+///
+/// let wire = json!({"type": "thinking", "thinking": "2+2 is 4", "signature": "sig=="});
+/// let block: ContentBlock = serde_json::from_value(wire.clone()).unwrap();
+/// match &block {
+///     ContentBlock::Thinking { text, signature } => {
+///         assert_eq!(text, "2+2 is 4");
+///         assert_eq!(signature, "sig==");
+///     }
+///     _ => panic!("Expected thinking block"),
+/// }
+/// assert_eq!(serde_json::to_value(&block).unwrap(), wire);
+///
+/// let wire = json!({"type": "redacted_thinking", "data": "EmwKAhgB"});
+/// let block: ContentBlock = serde_json::from_value(wire.clone()).unwrap();
+/// assert!(matches!(block, ContentBlock::RedactedThinking { .. }));
+/// assert_eq!(serde_json::to_value(&block).unwrap(), wire);
+/// ```
 #[derive(Debug, Serialize, Deserialize, Clone)]
 #[serde(tag = "type", rename_all = "snake_case")]
 pub enum ContentBlock {
@@ -188,13 +475,46 @@ pub enum ContentBlock {
     },
     /// Result from executing a tool
     ToolResult {
-        /// Content from the tool execution (must be a string)
-        content: String,
+        /// Content from the tool execution; a lone text item is sent as a
+        /// plain string
+        #[serde(with = "tool_result_content")]
+        content: Vec<ToolResultContent>,
         /// ID of the corresponding tool use request
         tool_use_id: String,
         /// Optional error flag if the tool execution failed
         #[serde(skip_serializing_if = "Option::is_none")]
         is_error: Option<bool>,
+        /// Optional prompt caching marker, so a large result that stays in
+        /// the history isn't billed in full on every later turn
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        cache_control: Option<CacheControl>,
+    },
+    /// Image content for vision-capable models
+    Image {
+        /// Where the image data comes from
+        source: ImageSource,
+    },
+    /// Document content such as a PDF
+    Document {
+        /// Where the document data comes from
+        source: DocumentSource,
+    },
+    /// Claude's extended thinking, returned when thinking is enabled
+    ///
+    /// Must be sent back unchanged, signature included, when the message is
+    /// replayed as history.
+    Thinking {
+        /// The reasoning text
+        #[serde(rename = "thinking")]
+        text: String,
+        /// Opaque signature verifying the thinking came from Claude
+        #[serde(default)]
+        signature: String,
+    },
+    /// Extended thinking that was encrypted by the safety systems
+    RedactedThinking {
+        /// Encrypted thinking data to send back unchanged
+        data: String,
     },
 }
 
@@ -238,6 +558,174 @@ impl Into<ContentBlock> for &str {
     }
 }
 
+/// One item of a tool result's content
+///
+/// A tool result is usually a single text item, which goes over the wire as a
+/// plain string just as before. Results with several items, or with images
+/// such as screenshots, are sent as an array of blocks.
+///
+/// # Example
+///
+/// ```rust
+/// use claude::{CacheControl, ContentBlock, ImageSource, ToolResultContent};
+/// use serde_json::json;
+///
+/// let round_trip = |block: &ContentBlock, wire: serde_json::Value| {
+///     assert_eq!(serde_json::to_value(block).unwrap(), wire);
+///     let parsed: ContentBlock = serde_json::from_value(wire.clone()).unwrap();
+///     assert_eq!(serde_json::to_value(&parsed).unwrap(), wire);
+/// };
+///
+/// // A single string is still sent the old way
+/// let text = ContentBlock::ToolResult {
+///     content: vec!["4".into()],
+///     tool_use_id: "tool_1".to_string(),
+///     is_error: None,
+///     cache_control: None,
+/// };
+/// round_trip(&text, json!({"type": "tool_result", "content": "4", "tool_use_id": "tool_1"}));
+///
+/// // Several text items
+/// let multi = ContentBlock::ToolResult {
+///     content: vec!["Page 1".into(), "Page 2".into()],
+///     tool_use_id: "tool_2".to_string(),
+///     is_error: None,
+///     cache_control: None,
+/// };
+/// round_trip(&multi, json!({
+///     "type": "tool_result",
+///     "content": [{"type": "text", "text": "Page 1"}, {"type": "text", "text": "Page 2"}],
+///     "tool_use_id": "tool_2"
+/// }));
+///
+/// // An error with a screenshot attached
+/// let image = ContentBlock::ToolResult {
+///     content: vec![
+///         "Login button not found".into(),
+///         ToolResultContent::Image {
+///             source: ImageSource::Base64 {
+///                 media_type: "image/png".to_string(),
+///                 data: "iVBORw0KGgo=".to_string(),
+///             },
+///         },
+///     ],
+///     tool_use_id: "tool_3".to_string(),
+///     is_error: Some(true),
+///     cache_control: None,
+/// };
+/// round_trip(&image, json!({
+///     "type": "tool_result",
+///     "content": [
+///         {"type": "text", "text": "Login button not found"},
+///         {"type": "image", "source": {"type": "base64", "media_type": "image/png", "data": "iVBORw0KGgo="}}
+///     ],
+///     "tool_use_id": "tool_3",
+///     "is_error": true
+/// }));
+///
+/// if let ContentBlock::ToolResult { content, .. } = &image {
+///     assert_eq!(
+///         ToolResultContent::text_of(content),
+///         "Login button not found\n[image/png image]"
+///     );
+/// }
+///
+/// // A large result marked for prompt caching
+/// let cached = ContentBlock::ToolResult {
+///     content: vec!["<a long fetched document>".into()],
+///     tool_use_id: "tool_4".to_string(),
+///     is_error: None,
+///     cache_control: Some(CacheControl::Ephemeral),
+/// };
+/// round_trip(&cached, json!({
+///     "type": "tool_result",
+///     "content": "<a long fetched document>",
+///     "tool_use_id": "tool_4",
+///     "cache_control": {"type": "ephemeral"}
+/// }));
+/// ```
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ToolResultContent {
+    /// Text output
+    Text {
+        /// The text content
+        text: String,
+    },
+    /// Image output, e.g. a screenshot
+    Image {
+        /// Where the image data comes from
+        source: ImageSource,
+    },
+}
+
+impl ToolResultContent {
+    /// Render tool result items as text, one per line
+    ///
+    /// Images are replaced by a short placeholder naming their type.
+    pub fn text_of(items: &[ToolResultContent]) -> String {
+        items
+            .iter()
+            .map(|item| match item {
+                ToolResultContent::Text { text } => text.clone(),
+                ToolResultContent::Image {
+                    source: ImageSource::Base64 { media_type, .. },
+                } => format!("[{} image]", media_type),
+                ToolResultContent::Image {
+                    source: ImageSource::Url { url },
+                } => format!("[image: {}]", url),
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+impl From<String> for ToolResultContent {
+    fn from(text: String) -> Self {
+        ToolResultContent::Text { text }
+    }
+}
+
+impl From<&str> for ToolResultContent {
+    fn from(text: &str) -> Self {
+        ToolResultContent::Text {
+            text: text.to_string(),
+        }
+    }
+}
+
+/// Serde for `ToolResult.content`: a lone text item is a plain string
+mod tool_result_content {
+    use super::ToolResultContent;
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Wire {
+        Text(String),
+        Items(Vec<ToolResultContent>),
+    }
+
+    pub fn serialize<S: Serializer>(
+        items: &[ToolResultContent],
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        match items {
+            [ToolResultContent::Text { text }] => serializer.serialize_str(text),
+            items => items.serialize(serializer),
+        }
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Vec<ToolResultContent>, D::Error> {
+        Ok(match Wire::deserialize(deserializer)? {
+            Wire::Text(text) => vec![ToolResultContent::Text { text }],
+            Wire::Items(items) => items,
+        })
+    }
+}
+
 /// Tool use information extracted from a content block
 #[derive(Debug, Clone)]
 pub struct ToolUse {
@@ -268,3 +756,159 @@ impl TryInto<ToolUse> for &ContentBlock {
         }
     }
 }
+
+/// A tool use being received from a streamed response
+///
+/// Streaming sends a `tool_use` block's input as a series of
+/// `input_json_delta` fragments that are only valid JSON once all of them have
+/// arrived. This collects the fragments, making the tool's name available from
+/// the block's `content_block_start` event and the parsed input once the block
+/// is complete. [`bytes_received`](Self::bytes_received) can drive a progress
+/// display while a large input, such as a `patch_file` diff, is arriving.
+///
+/// # Example
+///
+/// ```rust
+/// use claude::PartialToolUse;
+/// use serde_json::json;
+///
+/// let start = json!({
+///     "type": "content_block_start",
+///     "index": 1,
+///     "content_block": {"type": "tool_use", "id": "toolu_1", "name": "patch_file", "input": {}}
+/// });
+/// let mut partial = PartialToolUse::from_event(&start).unwrap();
+/// assert_eq!(partial.name(), "patch_file");
+///
+/// // Fragments split wherever the stream happens to break, even mid-string
+/// for fragment in ["", "{\"path\": \"src/", "lib.rs\", \"di", "ff\": \"@@ -1 +1 @@\\n-a\\n+b\"", "}"] {
+///     let delta = json!({
+///         "type": "content_block_delta",
+///         "index": 1,
+///         "delta": {"type": "input_json_delta", "partial_json": fragment}
+///     });
+///     assert!(partial.apply_event(&delta));
+/// }
+/// assert_eq!(partial.bytes_received(), 53);
+///
+/// // Events for other blocks are left alone
+/// let text = json!({"type": "content_block_delta", "index": 0, "delta": {"type": "text_delta", "text": "hi"}});
+/// assert!(!partial.apply_event(&text));
+///
+/// let tool_use = partial.finish()?;
+/// assert_eq!(tool_use.id, "toolu_1");
+/// assert_eq!(tool_use.input, json!({"path": "src/lib.rs", "diff": "@@ -1 +1 @@\n-a\n+b"}));
+///
+/// // A tool called without arguments streams no input at all
+/// let mut empty = PartialToolUse::new("toolu_2", "system_info");
+/// empty.push_json("");
+/// assert_eq!(empty.finish()?.input, json!({}));
+/// # Ok::<(), claude::Error>(())
+/// ```
+#[derive(Debug, Clone)]
+pub struct PartialToolUse {
+    id: String,
+    name: String,
+    /// Index of the content block, when started from an event
+    index: Option<u64>,
+    input_json: String,
+}
+
+impl PartialToolUse {
+    /// Start collecting the input of a tool use
+    pub fn new(id: impl Into<String>, name: impl Into<String>) -> Self {
+        Self {
+            id: id.into(),
+            name: name.into(),
+            index: None,
+            input_json: String::new(),
+        }
+    }
+
+    /// Start from a `content_block_start` event, if it begins a `tool_use` block
+    pub fn from_event(event: &Value) -> Option<Self> {
+        if event.get("type")?.as_str()? != "content_block_start" {
+            return None;
+        }
+        let block = event.get("content_block")?;
+        if block.get("type")?.as_str()? != "tool_use" {
+            return None;
+        }
+        Some(Self {
+            id: block.get("id")?.as_str()?.to_string(),
+            name: block.get("name")?.as_str()?.to_string(),
+            index: event.get("index").and_then(|i| i.as_u64()),
+            input_json: String::new(),
+        })
+    }
+
+    /// Add the fragment from an `input_json_delta` event for this block
+    ///
+    /// Returns whether the event was one; other events are ignored.
+    pub fn apply_event(&mut self, event: &Value) -> bool {
+        if event.get("type").and_then(|t| t.as_str()) != Some("content_block_delta") {
+            return false;
+        }
+        if self.index.is_some() && event.get("index").and_then(|i| i.as_u64()) != self.index {
+            return false;
+        }
+        let Some(delta) = event.get("delta") else {
+            return false;
+        };
+        if delta.get("type").and_then(|t| t.as_str()) != Some("input_json_delta") {
+            return false;
+        }
+        match delta.get("partial_json").and_then(|p| p.as_str()) {
+            Some(fragment) => {
+                self.push_json(fragment);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Add a fragment of the input's JSON
+    pub fn push_json(&mut self, fragment: &str) {
+        self.input_json.push_str(fragment);
+    }
+
+    /// Tool use identifier
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    /// Name of the tool
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Number of bytes of input JSON received so far
+    pub fn bytes_received(&self) -> usize {
+        self.input_json.len()
+    }
+
+    /// Parse the collected input once the block has finished
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the fragments don't form a complete JSON value
+    pub fn finish(self) -> Result<ToolUse> {
+        let input = if self.input_json.trim().is_empty() {
+            Value::Object(Default::default())
+        } else {
+            serde_json::from_str(&self.input_json).map_err(|e| {
+                Error::Other(format!(
+                    "Incomplete input for tool '{}' after {} bytes: {}",
+                    self.name,
+                    self.input_json.len(),
+                    e
+                ))
+            })?
+        };
+        Ok(ToolUse {
+            name: self.name,
+            input,
+            id: self.id,
+        })
+    }
+}
@@ -0,0 +1,150 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// A model available through the API
+///
+/// Returned by [`Claude::list_models`](crate::Claude::list_models).
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ModelInfo {
+    /// Model identifier to use in requests, e.g. "claude-sonnet-4-20250514"
+    pub id: String,
+    /// Human-readable name, e.g. "Claude Sonnet 4"
+    pub display_name: String,
+    /// When the model was released
+    pub created_at: DateTime<Utc>,
+}
+
+impl ModelInfo {
+    /// The built-in capabilities of this model, if it is in the table
+    pub fn capabilities(&self) -> Option<ModelCapabilities> {
+        model_capabilities(&self.id)
+    }
+}
+
+/// One page of the `/v1/models` listing
+#[derive(Debug, Deserialize)]
+pub(crate) struct ModelPage {
+    pub data: Vec<ModelInfo>,
+    #[serde(default)]
+    pub has_more: bool,
+    pub last_id: Option<String>,
+}
+
+/// What a model family supports
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ModelCapabilities {
+    /// Accepts image content blocks
+    pub supports_vision: bool,
+    /// Accepts tool definitions
+    pub supports_tools: bool,
+    /// Accepts an extended thinking configuration
+    pub supports_thinking: bool,
+    /// Maximum input plus output tokens
+    pub context_window: u32,
+    /// Maximum value of `max_tokens`
+    pub max_output_tokens: u32,
+}
+
+/// Built-in capability table, matched against model names by prefix
+///
+/// More specific prefixes must come before the prefixes they extend.
+const CAPABILITY_TABLE: &[(&str, ModelCapabilities)] = &[
+    (
+        "claude-opus-4",
+        ModelCapabilities {
+            supports_vision: true,
+            supports_tools: true,
+            supports_thinking: true,
+            context_window: 200_000,
+            max_output_tokens: 32_000,
+        },
+    ),
+    (
+        "claude-sonnet-4",
+        ModelCapabilities {
+            supports_vision: true,
+            supports_tools: true,
+            supports_thinking: true,
+            context_window: 200_000,
+            max_output_tokens: 64_000,
+        },
+    ),
+    (
+        "claude-3-7-sonnet",
+        ModelCapabilities {
+            supports_vision: true,
+            supports_tools: true,
+            supports_thinking: true,
+            context_window: 200_000,
+            max_output_tokens: 64_000,
+        },
+    ),
+    (
+        "claude-3-5-sonnet",
+        ModelCapabilities {
+            supports_vision: true,
+            supports_tools: true,
+            supports_thinking: false,
+            context_window: 200_000,
+            max_output_tokens: 8_192,
+        },
+    ),
+    (
+        "claude-3-5-haiku",
+        ModelCapabilities {
+            supports_vision: true,
+            supports_tools: true,
+            supports_thinking: false,
+            context_window: 200_000,
+            max_output_tokens: 8_192,
+        },
+    ),
+    (
+        "claude-3-opus",
+        ModelCapabilities {
+            supports_vision: true,
+            supports_tools: true,
+            supports_thinking: false,
+            context_window: 200_000,
+            max_output_tokens: 4_096,
+        },
+    ),
+    (
+        "claude-3-haiku",
+        ModelCapabilities {
+            supports_vision: true,
+            supports_tools: true,
+            supports_thinking: false,
+            context_window: 200_000,
+            max_output_tokens: 4_096,
+        },
+    ),
+];
+
+/// Look up the built-in capabilities of a model
+///
+/// Dated snapshots and `-latest` aliases match their family. Returns `None`
+/// for models that are not in the table.
+///
+/// # Example
+///
+/// ```rust
+/// use claude::models::model_capabilities;
+///
+/// let sonnet = model_capabilities("claude-sonnet-4-20250514").unwrap();
+/// assert!(sonnet.supports_vision && sonnet.supports_tools && sonnet.supports_thinking);
+/// assert_eq!(sonnet.context_window, 200_000);
+/// assert_eq!(sonnet.max_output_tokens, 64_000);
+///
+/// let haiku = model_capabilities("claude-3-5-haiku-latest").unwrap();
+/// assert!(!haiku.supports_thinking);
+/// assert_eq!(haiku.max_output_tokens, 8_192);
+///
+/// assert!(model_capabilities("gpt-4").is_none());
+/// ```
+pub fn model_capabilities(model: &str) -> Option<ModelCapabilities> {
+    CAPABILITY_TABLE
+        .iter()
+        .find(|(prefix, _)| model.starts_with(prefix))
+        .map(|(_, capabilities)| *capabilities)
+}
@@ -0,0 +1,161 @@
+use std::collections::VecDeque;
+use std::fs;
+use std::io::Write;
+use std::path::{Component, Path, PathBuf};
+
+/// The current user's home directory
+///
+/// Follows the platform's convention through the `dirs` crate: `$HOME` on
+/// Unix and the user's profile folder on Windows. Returns `None` if it can't
+/// be determined. Use this rather than the deprecated `std::env::home_dir`,
+/// which gives wrong answers on Windows.
+///
+/// # Example
+///
+/// ```rust
+/// use claude::paths::home_dir;
+///
+/// let home = home_dir().expect("a home directory");
+/// assert!(home.is_absolute());
+/// assert!(home.is_dir());
+/// ```
+pub fn home_dir() -> Option<PathBuf> {
+    dirs::home_dir()
+}
+
+/// `name` inside the home directory, or inside the current directory if
+/// there is no home directory
+///
+/// # Example
+///
+/// ```rust
+/// use claude::paths::{home_dir, in_home};
+/// use claude::tools::{EnhancedMemoryTool, TodoTool};
+///
+/// let home = home_dir().unwrap();
+/// assert_eq!(in_home(".generalist.env"), home.join(".generalist.env"));
+///
+/// // Tools keep their default storage under it
+/// assert!(EnhancedMemoryTool::default_path().starts_with(&home));
+/// # std::env::remove_var("CLAUDE_TODO_FILE");
+/// assert!(TodoTool::default_path().starts_with(&home));
+/// ```
+pub fn in_home(name: impl AsRef<Path>) -> PathBuf {
+    home_dir().unwrap_or_else(|| PathBuf::from(".")).join(name)
+}
+
+/// `path` resolved inside `root`, or `None` if it would leave it
+///
+/// Relative paths are joined to `root`. Components are resolved one at a
+/// time: `.` and `..` are applied as they come, and every symlink met along
+/// the way is replaced by its target, including a dangling link whose target
+/// doesn't exist yet, so a link pointing out of `root` is caught wherever it
+/// is. The rest of the path doesn't have to exist. Fails if `root` itself
+/// can't be resolved or a link can't be read.
+///
+/// # Example
+///
+/// ```rust
+/// use claude::paths::resolve_within;
+///
+/// let dir = tempfile::tempdir().unwrap();
+/// let root = dir.path().canonicalize().unwrap().join("root");
+/// std::fs::create_dir(&root).unwrap();
+///
+/// assert_eq!(
+///     resolve_within(&root, "src/new/../lib.rs").unwrap(),
+///     Some(root.join("src/lib.rs"))
+/// );
+/// assert_eq!(resolve_within(&root, "../escape.txt").unwrap(), None);
+/// assert_eq!(resolve_within(&root, "/etc/passwd").unwrap(), None);
+/// assert!(resolve_within(root.join("missing"), "file.txt").is_err());
+///
+/// # #[cfg(unix)] {
+/// // Links are followed, even to files that don't exist yet
+/// let outside = dir.path().join("outside.txt");
+/// std::os::unix::fs::symlink(&outside, root.join("dangling")).unwrap();
+/// assert_eq!(resolve_within(&root, "dangling").unwrap(), None);
+/// std::os::unix::fs::symlink(dir.path(), root.join("up")).unwrap();
+/// assert_eq!(resolve_within(&root, "up/outside.txt").unwrap(), None);
+///
+/// std::os::unix::fs::symlink("src/lib.rs", root.join("inside")).unwrap();
+/// assert_eq!(resolve_within(&root, "inside").unwrap(), Some(root.join("src/lib.rs")));
+/// # }
+/// ```
+pub fn resolve_within(
+    root: impl AsRef<Path>,
+    path: impl AsRef<Path>,
+) -> std::io::Result<Option<PathBuf>> {
+    /// Links followed before giving up, as `ELOOP` does
+    const MAX_LINKS: usize = 40;
+
+    let root = root.as_ref().canonicalize()?;
+    let mut pending: VecDeque<PathBuf> = root
+        .join(path)
+        .components()
+        .map(|c| PathBuf::from(c.as_os_str()))
+        .collect();
+    let mut resolved = PathBuf::new();
+    let mut links = 0;
+
+    while let Some(component) = pending.pop_front() {
+        match component.components().next() {
+            Some(Component::CurDir) | None => {}
+            Some(Component::ParentDir) => {
+                resolved.pop();
+            }
+            Some(Component::Normal(name)) => {
+                let candidate = resolved.join(name);
+                let is_link = fs::symlink_metadata(&candidate)
+                    .is_ok_and(|metadata| metadata.file_type().is_symlink());
+                if !is_link {
+                    resolved = candidate;
+                    continue;
+                }
+
+                links += 1;
+                if links > MAX_LINKS {
+                    return Err(std::io::Error::other(format!(
+                        "Too many levels of symbolic links resolving {}",
+                        candidate.display()
+                    )));
+                }
+                // Resolve the target in place of the link
+                let target = fs::read_link(&candidate)?;
+                for part in target.components().rev() {
+                    pending.push_front(PathBuf::from(part.as_os_str()));
+                }
+            }
+            // An absolute path or target starts over, keeping any drive prefix
+            Some(Component::RootDir) => {
+                resolved = resolved
+                    .components()
+                    .take_while(|c| matches!(c, Component::Prefix(_)))
+                    .collect();
+                resolved.push(Component::RootDir);
+            }
+            Some(prefix @ Component::Prefix(_)) => resolved = PathBuf::from(prefix.as_os_str()),
+        }
+    }
+
+    Ok(resolved.starts_with(&root).then_some(resolved))
+}
+
+/// Write `contents` to `path`, refusing to follow a symlink at `path` itself
+///
+/// Used by the file-writing tools after [`resolve_within`], so a link swapped
+/// in after the check still can't redirect the write.
+pub(crate) fn write_no_follow(path: &Path, contents: impl AsRef<[u8]>) -> std::io::Result<()> {
+    let mut options = fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.custom_flags(libc::O_NOFOLLOW);
+    }
+    #[cfg(not(unix))]
+    if fs::symlink_metadata(path).is_ok_and(|metadata| metadata.file_type().is_symlink()) {
+        return Err(std::io::Error::other("refusing to write through a symlink"));
+    }
+    options.open(path)?.write_all(contents.as_ref())
+}
@@ -1,9 +1,15 @@
+use crate::{Error, Result};
 use async_trait::async_trait;
 use colored::*;
 use dialoguer::{theme::ColorfulTheme, Select};
+use futures::future::BoxFuture;
+use regex::Regex;
 use serde_json::Value;
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::future::Future;
+use std::marker::PhantomData;
 use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 
 /// Decision on whether to allow a tool execution
 ///
@@ -65,8 +71,20 @@ pub enum PermissionDecision {
 ///     
 ///     PermissionDecision::Allow
 /// }
+///
+/// // Ask again once a turn has made many tool calls
+/// fn check_busy_turn(request: &ToolExecutionRequest) -> PermissionDecision {
+///     if request.prior_executions_this_turn >= 20 {
+///         return PermissionDecision::DenyWithReason(format!(
+///             "Already ran {} tools for: {}",
+///             request.prior_executions_this_turn,
+///             request.recent_user_text.as_deref().unwrap_or("(unknown request)")
+///         ));
+///     }
+///     PermissionDecision::Allow
+/// }
 /// ```
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default)]
 pub struct ToolExecutionRequest {
     /// Unique identifier for this tool use
     pub tool_use_id: String,
@@ -76,6 +94,13 @@ pub struct ToolExecutionRequest {
     pub input: Value,
     /// Description of what the tool does
     pub tool_description: String,
+    /// Zero-based index of the current conversation turn, if the caller
+    /// reported turns with [`ToolRegistry::begin_turn`](crate::ToolRegistry::begin_turn)
+    pub turn_index: Option<usize>,
+    /// The user message that started the current turn, if known
+    pub recent_user_text: Option<String>,
+    /// Number of tool calls already made during the current turn
+    pub prior_executions_this_turn: usize,
 }
 
 /// Trait for handling tool execution permissions
@@ -90,7 +115,10 @@ pub struct ToolExecutionRequest {
 /// - [`AlwaysDenyPermissions`]: Denies all tool executions
 /// - [`LoggingPermissions`]: Logs tool requests before allowing
 /// - [`InteractivePermissions`]: Prompts for user approval
-/// - [`PolicyPermissions`]: Allows/denies based on tool names
+/// - [`AsyncInteractivePermissions`]: Awaits an asynchronous approval callback
+/// - [`PolicyPermissions`]: Allows/denies based on tool names and input rules
+/// - [`RateLimitPermissions`]: Limits how often each tool may run
+/// - [`ChainedPermissions`]: Combines several handlers in order
 ///
 /// # Example
 ///
@@ -244,10 +272,232 @@ where
     }
 }
 
+/// Interactive permission handler whose prompt is asynchronous
+///
+/// Like [`InteractivePermissions`], but the callback returns a future, so the
+/// prompt can be awaited instead of blocking the runtime. Use it for approvals
+/// that come from a network service, a chat UI, or a terminal prompt run with
+/// `tokio::task::spawn_blocking`. The callback gets its own copy of the request
+/// so the future can hold on to it.
+///
+/// # Example
+///
+/// ```rust
+/// use claude::{AsyncInteractivePermissions, PermissionDecision, ToolExecutionRequest, ToolPermissionHandler};
+/// use serde_json::json;
+///
+/// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+/// let permissions = AsyncInteractivePermissions::new(|request: ToolExecutionRequest| async move {
+///     // Stand-in for waiting on a remote approver
+///     tokio::task::yield_now().await;
+///     request.tool_name != "bash"
+/// });
+///
+/// let request = |tool: &str| ToolExecutionRequest {
+///     tool_use_id: "toolu_1".to_string(),
+///     tool_name: tool.to_string(),
+///     input: json!({}),
+///     ..Default::default()
+/// };
+/// assert_eq!(
+///     permissions.check_permission(&request("calculator")).await,
+///     PermissionDecision::Allow
+/// );
+/// assert_eq!(
+///     permissions.check_permission(&request("bash")).await,
+///     PermissionDecision::DenyWithReason("User denied permission".to_string())
+/// );
+/// # });
+/// ```
+pub struct AsyncInteractivePermissions<F, Fut>
+where
+    F: Fn(ToolExecutionRequest) -> Fut + Send + Sync,
+    Fut: Future<Output = bool> + Send,
+{
+    /// Callback whose future resolves to true to allow, false to deny
+    prompt_callback: F,
+    _future: PhantomData<fn() -> Fut>,
+}
+
+impl<F, Fut> AsyncInteractivePermissions<F, Fut>
+where
+    F: Fn(ToolExecutionRequest) -> Fut + Send + Sync,
+    Fut: Future<Output = bool> + Send,
+{
+    /// Create a new asynchronous permission handler with a custom prompt callback
+    ///
+    /// The callback's future should resolve to true to allow execution, false
+    /// to deny.
+    pub fn new(prompt_callback: F) -> Self {
+        Self {
+            prompt_callback,
+            _future: PhantomData,
+        }
+    }
+}
+
+#[async_trait]
+impl<F, Fut> ToolPermissionHandler for AsyncInteractivePermissions<F, Fut>
+where
+    F: Fn(ToolExecutionRequest) -> Fut + Send + Sync,
+    Fut: Future<Output = bool> + Send,
+{
+    async fn check_permission(&self, request: &ToolExecutionRequest) -> PermissionDecision {
+        if (self.prompt_callback)(request.clone()).await {
+            PermissionDecision::Allow
+        } else {
+            PermissionDecision::DenyWithReason("User denied permission".to_string())
+        }
+    }
+}
+
+/// A check on one value inside a tool's input
+///
+/// Values are addressed with a JSON pointer such as `/command` or
+/// `/options/path`. A matcher whose pointer is missing from the input does
+/// not match.
+#[derive(Debug, Clone)]
+pub enum InputMatcher {
+    /// The value matches a regular expression; non-string values are matched
+    /// against their JSON text
+    Regex {
+        /// JSON pointer to the value
+        pointer: String,
+        /// Pattern the value must match somewhere
+        pattern: Regex,
+    },
+    /// The value equals the given JSON value
+    Equals {
+        /// JSON pointer to the value
+        pointer: String,
+        /// Value to compare with
+        value: Value,
+    },
+}
+
+impl InputMatcher {
+    /// Check whether `input` satisfies this matcher
+    pub fn matches(&self, input: &Value) -> bool {
+        match self {
+            InputMatcher::Regex { pointer, pattern } => match input.pointer(pointer) {
+                Some(Value::String(text)) => pattern.is_match(text),
+                Some(other) => pattern.is_match(&other.to_string()),
+                None => false,
+            },
+            InputMatcher::Equals { pointer, value } => input.pointer(pointer) == Some(value),
+        }
+    }
+}
+
+/// Whether a matching [`PolicyRule`] allows or denies the call
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RuleEffect {
+    /// Allow the tool call
+    Allow,
+    /// Deny the tool call
+    Deny,
+}
+
+/// A per-tool rule for [`PolicyPermissions`], optionally conditioned on input
+///
+/// A rule applies when the tool name matches (`"*"` matches every tool) and
+/// all of its input matchers match. A rule without matchers applies to every
+/// call of the tool.
+///
+/// # Example
+///
+/// ```rust
+/// use claude::PolicyRule;
+/// use serde_json::json;
+///
+/// let rule = PolicyRule::deny("read_file")
+///     .when_matches("/path", r"^/etc(/|$)")
+///     .unwrap()
+///     .with_reason("System configuration is off limits");
+///
+/// assert!(rule.applies_to("read_file", &json!({"path": "/etc/shadow"})));
+/// assert!(!rule.applies_to("read_file", &json!({"path": "/home/me/etc/notes"})));
+/// assert!(!rule.applies_to("bash", &json!({"path": "/etc/shadow"})));
+///
+/// let rule = PolicyRule::allow("system_info").when_equals("/info_type", json!("time"));
+/// assert!(rule.applies_to("system_info", &json!({"info_type": "time"})));
+/// assert!(!rule.applies_to("system_info", &json!({"info_type": "disk"})));
+/// ```
+#[derive(Debug, Clone)]
+pub struct PolicyRule {
+    /// Tool the rule applies to, or `"*"` for every tool
+    pub tool: String,
+    /// Whether the rule allows or denies matching calls
+    pub effect: RuleEffect,
+    /// Conditions on the input that must all hold
+    pub matchers: Vec<InputMatcher>,
+    /// Optional explanation reported when the rule denies a call
+    pub reason: Option<String>,
+}
+
+impl PolicyRule {
+    /// Create a rule allowing calls to `tool`
+    pub fn allow(tool: impl Into<String>) -> Self {
+        Self {
+            tool: tool.into(),
+            effect: RuleEffect::Allow,
+            matchers: Vec::new(),
+            reason: None,
+        }
+    }
+
+    /// Create a rule denying calls to `tool`
+    pub fn deny(tool: impl Into<String>) -> Self {
+        Self {
+            effect: RuleEffect::Deny,
+            ..Self::allow(tool)
+        }
+    }
+
+    /// Only apply when the value at `pointer` matches the regex `pattern`
+    pub fn when_matches(mut self, pointer: impl Into<String>, pattern: &str) -> Result<Self> {
+        let pattern = Regex::new(pattern)
+            .map_err(|e| Error::Other(format!("Invalid rule pattern '{}': {}", pattern, e)))?;
+        self.matchers.push(InputMatcher::Regex {
+            pointer: pointer.into(),
+            pattern,
+        });
+        Ok(self)
+    }
+
+    /// Only apply when the value at `pointer` equals `value`
+    pub fn when_equals(mut self, pointer: impl Into<String>, value: Value) -> Self {
+        self.matchers.push(InputMatcher::Equals {
+            pointer: pointer.into(),
+            value,
+        });
+        self
+    }
+
+    /// Set the explanation reported when this rule denies a call
+    pub fn with_reason(mut self, reason: impl Into<String>) -> Self {
+        self.reason = Some(reason.into());
+        self
+    }
+
+    /// Check whether this rule applies to a call of `tool_name` with `input`
+    pub fn applies_to(&self, tool_name: &str, input: &Value) -> bool {
+        (self.tool == "*" || self.tool == tool_name)
+            && self.matchers.iter().all(|matcher| matcher.matches(input))
+    }
+}
+
 /// Policy-based permission handler that allows or denies based on tool names
+/// and inputs
 ///
-/// Maintains an allow-list of tool names and can be configured with a default
-/// policy for tools not in the list.
+/// Decisions are made in this order:
+///
+/// 1. Tools in the deny-list are denied.
+/// 2. A matching deny [`PolicyRule`] denies the call.
+/// 3. Tools in the allow-list, or calls matching an allow rule, are allowed.
+/// 4. Anything else follows `default_allow`.
+///
+/// Denials therefore always take precedence over allows.
 ///
 /// # Example
 ///
@@ -265,9 +515,61 @@ where
 ///     Box::new(policy)
 /// );
 /// ```
+///
+/// Deny-lists and input rules:
+///
+/// ```rust
+/// use claude::{PermissionDecision, PolicyPermissions, PolicyRule, ToolExecutionRequest, ToolPermissionHandler};
+/// use serde_json::{json, Value};
+///
+/// async fn check(policy: &PolicyPermissions, tool: &str, input: Value) -> PermissionDecision {
+///     let request = ToolExecutionRequest {
+///         tool_use_id: "toolu_1".to_string(),
+///         tool_name: tool.to_string(),
+///         input,
+///         ..Default::default()
+///     };
+///     policy.check_permission(&request).await
+/// }
+///
+/// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+/// let policy = PolicyPermissions::new(vec!["bash".to_string(), "read_file".to_string()], true)
+///     .with_denied_tools(vec!["read_file".to_string(), "http_fetch".to_string()])
+///     .with_rule(
+///         PolicyRule::deny("bash")
+///             .when_matches("/command", r"rm\s+-rf")
+///             .unwrap()
+///             .with_reason("Recursive deletes are not allowed"),
+///     );
+///
+/// // The deny-list wins over both the allow-list and default_allow
+/// assert!(matches!(
+///     check(&policy, "read_file", json!({"path": "README.md"})).await,
+///     PermissionDecision::DenyWithReason(_)
+/// ));
+/// assert!(matches!(
+///     check(&policy, "http_fetch", json!({})).await,
+///     PermissionDecision::DenyWithReason(_)
+/// ));
+///
+/// // bash is allowed, except for commands matching the deny rule
+/// assert_eq!(check(&policy, "bash", json!({"command": "ls -la"})).await, PermissionDecision::Allow);
+/// assert_eq!(
+///     check(&policy, "bash", json!({"command": "cd /tmp && rm -rf build"})).await,
+///     PermissionDecision::DenyWithReason("Recursive deletes are not allowed".to_string())
+/// );
+///
+/// // Everything else falls back to default_allow
+/// assert_eq!(check(&policy, "calculator", json!({})).await, PermissionDecision::Allow);
+/// # });
+/// ```
 pub struct PolicyPermissions {
     /// Set of allowed tool names
     allowed_tools: HashSet<String>,
+    /// Set of denied tool names, checked before anything else
+    denied_tools: HashSet<String>,
+    /// Input-based rules, checked after the deny-list
+    rules: Vec<PolicyRule>,
     /// Whether to allow tools not in the allowed set
     default_allow: bool,
 }
@@ -282,17 +584,55 @@ impl PolicyPermissions {
     pub fn new(allowed_tools: Vec<String>, default_allow: bool) -> Self {
         Self {
             allowed_tools: allowed_tools.into_iter().collect(),
+            denied_tools: HashSet::new(),
+            rules: Vec::new(),
             default_allow,
         }
     }
+
+    /// Deny these tools outright, even if they are also allowed
+    pub fn with_denied_tools(mut self, denied_tools: Vec<String>) -> Self {
+        self.denied_tools.extend(denied_tools);
+        self
+    }
+
+    /// Add an input-based rule
+    pub fn with_rule(mut self, rule: PolicyRule) -> Self {
+        self.rules.push(rule);
+        self
+    }
 }
 
 #[async_trait]
 impl ToolPermissionHandler for PolicyPermissions {
     async fn check_permission(&self, request: &ToolExecutionRequest) -> PermissionDecision {
-        if self.allowed_tools.contains(&request.tool_name) {
-            PermissionDecision::Allow
-        } else if self.default_allow {
+        if self.denied_tools.contains(&request.tool_name) {
+            return PermissionDecision::DenyWithReason(format!(
+                "Tool '{}' is in the denied tools list",
+                request.tool_name
+            ));
+        }
+
+        let mut matching = self
+            .rules
+            .iter()
+            .filter(|rule| rule.applies_to(&request.tool_name, &request.input));
+        if let Some(rule) = matching
+            .clone()
+            .find(|rule| rule.effect == RuleEffect::Deny)
+        {
+            return PermissionDecision::DenyWithReason(rule.reason.clone().unwrap_or_else(|| {
+                format!(
+                    "Tool '{}' was denied by a policy rule for this input",
+                    request.tool_name
+                )
+            }));
+        }
+
+        if self.allowed_tools.contains(&request.tool_name)
+            || matching.any(|rule| rule.effect == RuleEffect::Allow)
+            || self.default_allow
+        {
             PermissionDecision::Allow
         } else {
             PermissionDecision::DenyWithReason(format!(
@@ -303,6 +643,315 @@ impl ToolPermissionHandler for PolicyPermissions {
     }
 }
 
+/// Permission handler that caps how often each tool may run
+///
+/// Each limited tool gets a maximum number of calls within a sliding time
+/// window; further calls are denied until older ones fall out of the window.
+/// Tools without a limit are not restricted. Calls within the limit are passed
+/// to an inner handler ([`AlwaysAllowPermissions`] by default), and only calls
+/// it allows count towards the limit.
+///
+/// # Example
+///
+/// ```rust
+/// use claude::{
+///     PermissionDecision, PolicyPermissions, RateLimitPermissions, ToolExecutionRequest,
+///     ToolPermissionHandler,
+/// };
+/// use serde_json::json;
+/// use std::collections::HashMap;
+/// use std::time::Duration;
+///
+/// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+/// let request = |tool: &str| ToolExecutionRequest {
+///     tool_use_id: "toolu_1".to_string(),
+///     tool_name: tool.to_string(),
+///     input: json!({}),
+///     ..Default::default()
+/// };
+///
+/// let limits = HashMap::from([("bash".to_string(), (3, Duration::from_secs(60)))]);
+/// let permissions = RateLimitPermissions::new(limits);
+///
+/// for _ in 0..3 {
+///     assert_eq!(permissions.check_permission(&request("bash")).await, PermissionDecision::Allow);
+/// }
+/// for _ in 0..2 {
+///     let decision = permissions.check_permission(&request("bash")).await;
+///     assert!(matches!(
+///         decision,
+///         PermissionDecision::DenyWithReason(reason) if reason.starts_with("rate limit exceeded")
+///     ));
+/// }
+/// // Unlimited tools are unaffected
+/// assert_eq!(permissions.check_permission(&request("calculator")).await, PermissionDecision::Allow);
+///
+/// // Wrapping another handler keeps its decisions
+/// let limits = HashMap::from([("bash".to_string(), (1, Duration::from_millis(50)))]);
+/// let policy = PolicyPermissions::new(vec!["bash".to_string()], false);
+/// let permissions = RateLimitPermissions::wrap(Box::new(policy), limits);
+/// assert_eq!(permissions.check_permission(&request("bash")).await, PermissionDecision::Allow);
+/// assert_ne!(permissions.check_permission(&request("bash")).await, PermissionDecision::Allow);
+/// assert_ne!(permissions.check_permission(&request("read_file")).await, PermissionDecision::Allow);
+///
+/// // Once the window has passed, the tool may run again
+/// tokio::time::sleep(Duration::from_millis(60)).await;
+/// assert_eq!(permissions.check_permission(&request("bash")).await, PermissionDecision::Allow);
+/// # });
+/// ```
+///
+/// A call is counted before the inner handler is asked, so checks running
+/// concurrently can't exceed the limit, and handed back if it's denied:
+///
+/// ```rust
+/// use async_trait::async_trait;
+/// use claude::{PermissionDecision, RateLimitPermissions, ToolExecutionRequest, ToolPermissionHandler};
+/// use std::collections::HashMap;
+/// use std::time::Duration;
+///
+/// /// Takes a while to decide, and denies calls with the id "deny"
+/// struct SlowReviewer;
+///
+/// #[async_trait]
+/// impl ToolPermissionHandler for SlowReviewer {
+///     async fn check_permission(&self, request: &ToolExecutionRequest) -> PermissionDecision {
+///         tokio::time::sleep(Duration::from_millis(20)).await;
+///         if request.tool_use_id == "deny" {
+///             PermissionDecision::Deny
+///         } else {
+///             PermissionDecision::Allow
+///         }
+///     }
+/// }
+///
+/// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+/// let request = |id: &str| ToolExecutionRequest {
+///     tool_use_id: id.to_string(),
+///     tool_name: "bash".to_string(),
+///     ..Default::default()
+/// };
+/// let limits = HashMap::from([("bash".to_string(), (1, Duration::from_secs(60)))]);
+/// let permissions = RateLimitPermissions::wrap(Box::new(SlowReviewer), limits);
+///
+/// // The denied call doesn't use up the limit...
+/// assert_eq!(permissions.check_permission(&request("deny")).await, PermissionDecision::Deny);
+///
+/// // ...but of two calls checked at once, only one gets it
+/// let (one, two) = (request("toolu_1"), request("toolu_2"));
+/// let (first, second) = tokio::join!(
+///     permissions.check_permission(&one),
+///     permissions.check_permission(&two),
+/// );
+/// let allowed = [first, second]
+///     .into_iter()
+///     .filter(|decision| *decision == PermissionDecision::Allow)
+///     .count();
+/// assert_eq!(allowed, 1);
+/// # });
+/// ```
+pub struct RateLimitPermissions {
+    /// Maximum calls and window length per tool name
+    limits: HashMap<String, (u32, Duration)>,
+    /// Times of recent allowed calls per tool name, oldest first
+    calls: Mutex<HashMap<String, VecDeque<Instant>>>,
+    /// Handler making the decision for calls within the limit
+    inner: Box<dyn ToolPermissionHandler>,
+}
+
+impl RateLimitPermissions {
+    /// Create a rate limiter that allows every call within the limits
+    ///
+    /// # Arguments
+    ///
+    /// * `limits` - Maximum number of calls and window length per tool name
+    pub fn new(limits: HashMap<String, (u32, Duration)>) -> Self {
+        Self::wrap(Box::new(AlwaysAllowPermissions), limits)
+    }
+
+    /// Rate-limit another handler, which decides calls within the limits
+    pub fn wrap(
+        inner: Box<dyn ToolPermissionHandler>,
+        limits: HashMap<String, (u32, Duration)>,
+    ) -> Self {
+        Self {
+            limits,
+            calls: Mutex::new(HashMap::new()),
+            inner,
+        }
+    }
+
+    /// Claim one of `tool_name`'s calls in the current window, returning the
+    /// time it was claimed at, or `None` if the window is already full
+    ///
+    /// The claim is made under the lock, so concurrent checks can't both take
+    /// the last call.
+    fn reserve(&self, tool_name: &str, max_calls: u32, window: Duration) -> Option<Instant> {
+        let mut calls = self.calls.lock().unwrap();
+        let times = calls.entry(tool_name.to_string()).or_default();
+        let now = Instant::now();
+        while times
+            .front()
+            .is_some_and(|&time| now.duration_since(time) >= window)
+        {
+            times.pop_front();
+        }
+        if times.len() >= max_calls as usize {
+            return None;
+        }
+        times.push_back(now);
+        Some(now)
+    }
+
+    /// Give back a call claimed by [`reserve`](Self::reserve) that didn't run
+    fn release(&self, tool_name: &str, time: Instant) {
+        let mut calls = self.calls.lock().unwrap();
+        if let Some(times) = calls.get_mut(tool_name) {
+            if let Some(index) = times.iter().position(|&t| t == time) {
+                times.remove(index);
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl ToolPermissionHandler for RateLimitPermissions {
+    async fn check_permission(&self, request: &ToolExecutionRequest) -> PermissionDecision {
+        let Some(&(max_calls, window)) = self.limits.get(&request.tool_name) else {
+            return self.inner.check_permission(request).await;
+        };
+
+        let Some(reserved) = self.reserve(&request.tool_name, max_calls, window) else {
+            return PermissionDecision::DenyWithReason(format!(
+                "rate limit exceeded: '{}' may run at most {} times per {:?}",
+                request.tool_name, max_calls, window
+            ));
+        };
+
+        let decision = self.inner.check_permission(request).await;
+        if decision != PermissionDecision::Allow {
+            self.release(&request.tool_name, reserved);
+        }
+        decision
+    }
+}
+
+/// How [`ChainedPermissions`] combines the decisions of its handlers
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ChainMode {
+    /// Every handler must allow; the first denial is returned and later
+    /// handlers are not consulted
+    #[default]
+    AllMustAllow,
+    /// The first handler to allow wins; if none does, the last denial is
+    /// returned
+    AnyAllows,
+}
+
+/// Permission handler that consults several handlers in order
+///
+/// Handlers run in the order they were added and evaluation stops as soon as
+/// the outcome is known, so a prompting handler placed last is only reached
+/// when the earlier ones haven't already decided. An empty chain allows
+/// everything.
+///
+/// # Example
+///
+/// ```rust
+/// use claude::{
+///     AsyncInteractivePermissions, ChainMode, ChainedPermissions, LoggingPermissions,
+///     PermissionDecision, PolicyPermissions, ToolExecutionRequest, ToolPermissionHandler,
+/// };
+/// use serde_json::json;
+/// use std::sync::atomic::{AtomicUsize, Ordering};
+/// use std::sync::Arc;
+///
+/// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+/// let request = |tool: &str| ToolExecutionRequest {
+///     tool_use_id: "toolu_1".to_string(),
+///     tool_name: tool.to_string(),
+///     input: json!({}),
+///     ..Default::default()
+/// };
+///
+/// // Log everything, apply a deny-list, then "prompt" for the rest
+/// let prompts = Arc::new(AtomicUsize::new(0));
+/// let counter = prompts.clone();
+/// let chain = ChainedPermissions::new(ChainMode::AllMustAllow)
+///     .with(Box::new(LoggingPermissions))
+///     .with(Box::new(
+///         PolicyPermissions::new(vec![], true).with_denied_tools(vec!["bash".to_string()]),
+///     ))
+///     .with(Box::new(AsyncInteractivePermissions::new(move |_| {
+///         counter.fetch_add(1, Ordering::SeqCst);
+///         async { true }
+///     })));
+///
+/// // The first deny wins and the prompt is never shown
+/// assert_eq!(
+///     chain.check_permission(&request("bash")).await,
+///     PermissionDecision::DenyWithReason("Tool 'bash' is in the denied tools list".to_string())
+/// );
+/// assert_eq!(prompts.load(Ordering::SeqCst), 0);
+///
+/// // Anything else must also pass the prompt
+/// assert_eq!(chain.check_permission(&request("calculator")).await, PermissionDecision::Allow);
+/// assert_eq!(prompts.load(Ordering::SeqCst), 1);
+///
+/// // With AnyAllows, one allowing handler is enough
+/// let chain = ChainedPermissions::new(ChainMode::AnyAllows)
+///     .with(Box::new(PolicyPermissions::new(vec!["calculator".to_string()], false)))
+///     .with(Box::new(PolicyPermissions::new(vec!["weather".to_string()], false)));
+/// assert_eq!(chain.check_permission(&request("weather")).await, PermissionDecision::Allow);
+/// assert_ne!(chain.check_permission(&request("bash")).await, PermissionDecision::Allow);
+/// # });
+/// ```
+pub struct ChainedPermissions {
+    /// Handlers in the order they are consulted
+    handlers: Vec<Box<dyn ToolPermissionHandler>>,
+    /// How their decisions are combined
+    mode: ChainMode,
+}
+
+impl ChainedPermissions {
+    /// Create an empty chain combining decisions with `mode`
+    pub fn new(mode: ChainMode) -> Self {
+        Self {
+            handlers: Vec::new(),
+            mode,
+        }
+    }
+
+    /// Append a handler to the end of the chain
+    pub fn with(mut self, handler: Box<dyn ToolPermissionHandler>) -> Self {
+        self.handlers.push(handler);
+        self
+    }
+
+    /// Append a handler to the end of the chain in place
+    pub fn push(&mut self, handler: Box<dyn ToolPermissionHandler>) {
+        self.handlers.push(handler);
+    }
+}
+
+#[async_trait]
+impl ToolPermissionHandler for ChainedPermissions {
+    async fn check_permission(&self, request: &ToolExecutionRequest) -> PermissionDecision {
+        let mut last_denial = None;
+        for handler in &self.handlers {
+            let decision = handler.check_permission(request).await;
+            match (self.mode, decision) {
+                (ChainMode::AllMustAllow, PermissionDecision::Allow) => {}
+                (ChainMode::AllMustAllow, denial) => return denial,
+                (ChainMode::AnyAllows, PermissionDecision::Allow) => {
+                    return PermissionDecision::Allow
+                }
+                (ChainMode::AnyAllows, denial) => last_denial = Some(denial),
+            }
+        }
+        last_denial.unwrap_or(PermissionDecision::Allow)
+    }
+}
+
 /// Format a diff for pretty display
 fn format_diff_for_display(diff: &str) -> String {
     let mut formatted = String::new();
@@ -332,28 +981,126 @@ fn format_diff_for_display(diff: &str) -> String {
     formatted
 }
 
-/// Advanced permission handler with memory for always/never decisions
-pub struct MemoryPermissionHandler {
+/// Answer from a [`SharedMemoryPermissions`] prompt
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PromptChoice {
+    /// Allow this call and every later call of the tool
+    AllowAlways,
+    /// Allow just this call
+    AllowOnce,
+    /// Deny this call and every later call of the tool
+    DenyAlways,
+    /// Deny just this call
+    DenyOnce,
+}
+
+/// Permission handler that remembers "always" and "never" answers per tool
+///
+/// Tools in the always-allow or always-deny set are decided without asking.
+/// Otherwise the injected async prompt is awaited, and `AllowAlways` or
+/// `DenyAlways` answers are remembered. The sets live behind shared handles,
+/// so clones of the handler (and anyone holding [`always_allow`] or
+/// [`always_deny`]) see the same decisions. Locks are never held while the
+/// prompt runs.
+///
+/// [`always_allow`]: SharedMemoryPermissions::always_allow
+/// [`always_deny`]: SharedMemoryPermissions::always_deny
+///
+/// # Example
+///
+/// ```rust
+/// use claude::{
+///     PermissionDecision, PromptChoice, SharedMemoryPermissions, ToolExecutionRequest,
+///     ToolPermissionHandler,
+/// };
+/// use serde_json::json;
+/// use std::sync::atomic::{AtomicUsize, Ordering};
+/// use std::sync::Arc;
+///
+/// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+/// let request = |tool: &str| ToolExecutionRequest {
+///     tool_use_id: "toolu_1".to_string(),
+///     tool_name: tool.to_string(),
+///     input: json!({}),
+///     ..Default::default()
+/// };
+///
+/// // A scripted "user" who always allows the calculator, never allows bash,
+/// // and allows anything else once
+/// let prompts = Arc::new(AtomicUsize::new(0));
+/// let counter = prompts.clone();
+/// let permissions = SharedMemoryPermissions::new(move |request: ToolExecutionRequest| {
+///     counter.fetch_add(1, Ordering::SeqCst);
+///     async move {
+///         tokio::task::yield_now().await;
+///         match request.tool_name.as_str() {
+///             "calculator" => PromptChoice::AllowAlways,
+///             "bash" => PromptChoice::DenyAlways,
+///             _ => PromptChoice::AllowOnce,
+///         }
+///     }
+/// });
+///
+/// // Prompt path: the first call of each tool asks
+/// assert_eq!(permissions.check_permission(&request("calculator")).await, PermissionDecision::Allow);
+/// assert!(matches!(
+///     permissions.check_permission(&request("bash")).await,
+///     PermissionDecision::DenyWithReason(_)
+/// ));
+/// assert_eq!(permissions.check_permission(&request("weather")).await, PermissionDecision::Allow);
+/// assert_eq!(prompts.load(Ordering::SeqCst), 3);
+///
+/// // Remembered allow and deny: no further prompts
+/// assert_eq!(permissions.check_permission(&request("calculator")).await, PermissionDecision::Allow);
+/// assert_eq!(
+///     permissions.check_permission(&request("bash")).await,
+///     PermissionDecision::DenyWithReason("Tool was previously set to never allow".to_string())
+/// );
+/// assert_eq!(prompts.load(Ordering::SeqCst), 3);
+/// assert!(permissions.always_allow().lock().unwrap().contains("calculator"));
+/// assert!(permissions.always_deny().lock().unwrap().contains("bash"));
+///
+/// // "Once" answers are not remembered
+/// permissions.check_permission(&request("weather")).await;
+/// assert_eq!(prompts.load(Ordering::SeqCst), 4);
+/// # });
+/// ```
+pub struct SharedMemoryPermissions<F, Fut>
+where
+    F: Fn(ToolExecutionRequest) -> Fut + Send + Sync,
+    Fut: Future<Output = PromptChoice> + Send,
+{
     always_allow: Arc<Mutex<HashSet<String>>>,
     always_deny: Arc<Mutex<HashSet<String>>>,
+    prompt: F,
+    _future: PhantomData<fn() -> Fut>,
 }
 
-impl MemoryPermissionHandler {
-    pub fn new() -> Self {
-        Self {
-            always_allow: Arc::new(Mutex::new(HashSet::new())),
-            always_deny: Arc::new(Mutex::new(HashSet::new())),
-        }
+impl<F, Fut> SharedMemoryPermissions<F, Fut>
+where
+    F: Fn(ToolExecutionRequest) -> Fut + Send + Sync,
+    Fut: Future<Output = PromptChoice> + Send,
+{
+    /// Create a handler with empty always-allow and always-deny sets
+    pub fn new(prompt: F) -> Self {
+        Self::with_shared_state(
+            Arc::new(Mutex::new(HashSet::new())),
+            Arc::new(Mutex::new(HashSet::new())),
+            prompt,
+        )
     }
 
-    /// Create a new handler that shares state with an existing one
+    /// Create a handler that shares its sets with existing handles
     pub fn with_shared_state(
         always_allow: Arc<Mutex<HashSet<String>>>,
         always_deny: Arc<Mutex<HashSet<String>>>,
+        prompt: F,
     ) -> Self {
         Self {
             always_allow,
             always_deny,
+            prompt,
+            _future: PhantomData,
         }
     }
 
@@ -376,62 +1123,91 @@ impl MemoryPermissionHandler {
     pub fn set_always_deny(&self, tools: HashSet<String>) {
         *self.always_deny.lock().unwrap() = tools;
     }
+
+    /// The remembered decision for `tool_name`, if there is one
+    pub fn remembered(&self, tool_name: &str) -> Option<PermissionDecision> {
+        if self.always_allow.lock().unwrap().contains(tool_name) {
+            Some(PermissionDecision::Allow)
+        } else if self.always_deny.lock().unwrap().contains(tool_name) {
+            Some(PermissionDecision::DenyWithReason(
+                "Tool was previously set to never allow".to_string(),
+            ))
+        } else {
+            None
+        }
+    }
+}
+
+impl<F, Fut> Clone for SharedMemoryPermissions<F, Fut>
+where
+    F: Fn(ToolExecutionRequest) -> Fut + Send + Sync + Clone,
+    Fut: Future<Output = PromptChoice> + Send,
+{
+    fn clone(&self) -> Self {
+        Self::with_shared_state(self.always_allow(), self.always_deny(), self.prompt.clone())
+    }
 }
 
 #[async_trait]
-impl ToolPermissionHandler for MemoryPermissionHandler {
+impl<F, Fut> ToolPermissionHandler for SharedMemoryPermissions<F, Fut>
+where
+    F: Fn(ToolExecutionRequest) -> Fut + Send + Sync,
+    Fut: Future<Output = PromptChoice> + Send,
+{
     async fn check_permission(&self, request: &ToolExecutionRequest) -> PermissionDecision {
-        // Check if we have a remembered decision
-        {
-            let always_allow = self.always_allow.lock().unwrap();
-            if always_allow.contains(&request.tool_name) {
-                eprintln!(
-                    "{} Automatically allowing '{}' (previously set to always allow)",
-                    "✓".green(),
-                    request.tool_name.cyan()
-                );
-                return PermissionDecision::Allow;
-            }
+        if let Some(decision) = self.remembered(&request.tool_name) {
+            return decision;
         }
 
-        {
-            let always_deny = self.always_deny.lock().unwrap();
-            if always_deny.contains(&request.tool_name) {
-                eprintln!(
-                    "{} Automatically denying '{}' (previously set to never allow)",
-                    "✗".red(),
-                    request.tool_name.cyan()
-                );
-                return PermissionDecision::DenyWithReason(
-                    "Tool was previously set to never allow".to_string(),
-                );
+        match (self.prompt)(request.clone()).await {
+            PromptChoice::AllowAlways => {
+                self.always_allow
+                    .lock()
+                    .unwrap()
+                    .insert(request.tool_name.clone());
+                PermissionDecision::Allow
+            }
+            PromptChoice::AllowOnce => PermissionDecision::Allow,
+            PromptChoice::DenyAlways => {
+                self.always_deny
+                    .lock()
+                    .unwrap()
+                    .insert(request.tool_name.clone());
+                PermissionDecision::DenyWithReason(
+                    "User chose to never allow this tool".to_string(),
+                )
             }
+            PromptChoice::DenyOnce => PermissionDecision::DenyWithReason(
+                "User denied permission for this execution".to_string(),
+            ),
         }
+    }
+}
+
+/// Terminal prompt used by [`MemoryPermissionHandler`]
+type TerminalPrompt = fn(ToolExecutionRequest) -> BoxFuture<'static, PromptChoice>;
 
-        // No remembered decision, prompt the user
+/// Show the request on the terminal and ask whether to allow it
+fn terminal_prompt(request: ToolExecutionRequest) -> BoxFuture<'static, PromptChoice> {
+    Box::pin(async move {
         println!("\n{}", "⚠️  Tool Permission Request".yellow().bold());
         println!("{}", "─".repeat(50).dimmed());
         println!("Tool: {}", request.tool_name.cyan().bold());
         println!("Description: {}", request.tool_description.dimmed());
 
         // Special formatting for patch_file tool
+        let mut diff = None;
         if request.tool_name == "patch_file" {
             if let Some(path) = request.input.get("path").and_then(|v| v.as_str()) {
                 println!("Target file: {}", path.yellow());
             }
-            if let Some(diff) = request.input.get("diff").and_then(|v| v.as_str()) {
-                println!("\n{}", "Proposed changes:".bold());
-                println!("{}", "─".repeat(50).dimmed());
-                print!("{}", format_diff_for_display(diff));
-                println!("{}", "─".repeat(50).dimmed());
-            } else {
-                println!(
-                    "Input: {}",
-                    serde_json::to_string_pretty(&request.input)
-                        .unwrap_or_default()
-                        .dimmed()
-                );
-            }
+            diff = request.input.get("diff").and_then(|v| v.as_str());
+        }
+        if let Some(diff) = diff {
+            println!("\n{}", "Proposed changes:".bold());
+            println!("{}", "─".repeat(50).dimmed());
+            print!("{}", format_diff_for_display(diff));
+            println!("{}", "─".repeat(50).dimmed());
         } else {
             println!(
                 "Input: {}",
@@ -449,49 +1225,115 @@ impl ToolPermissionHandler for MemoryPermissionHandler {
             "No (just this once)",
         ];
 
-        let selection = Select::with_theme(&ColorfulTheme::default())
-            .with_prompt("Allow this tool to execute?")
-            .items(&choices)
-            .default(1) // Default to "Yes (just this once)"
-            .interact()
-            .unwrap();
-
-        match selection {
-            0 => {
-                // Yes (always)
-                let mut always_allow = self.always_allow.lock().unwrap();
-                always_allow.insert(request.tool_name.clone());
+        // dialoguer blocks on terminal input, so keep it off the async workers
+        let prompt = tokio::task::spawn_blocking(move || {
+            Select::with_theme(&ColorfulTheme::default())
+                .with_prompt("Allow this tool to execute?")
+                .items(&choices)
+                .default(1) // Default to "Yes (just this once)"
+                .interact()
+        })
+        .await;
+
+        match prompt
+            .map_err(|e| e.to_string())
+            .and_then(|selection| selection.map_err(|e| e.to_string()))
+        {
+            Ok(0) => {
                 println!(
                     "{} Tool '{}' will be automatically allowed in the future",
                     "✓".green(),
                     request.tool_name.cyan()
                 );
-                PermissionDecision::Allow
+                PromptChoice::AllowAlways
             }
-            1 => {
-                // Yes (once)
-                PermissionDecision::Allow
-            }
-            2 => {
-                // No (never)
-                let mut always_deny = self.always_deny.lock().unwrap();
-                always_deny.insert(request.tool_name.clone());
+            Ok(1) => PromptChoice::AllowOnce,
+            Ok(2) => {
                 println!(
                     "{} Tool '{}' will be automatically denied in the future",
                     "✗".red(),
                     request.tool_name.cyan()
                 );
-                PermissionDecision::DenyWithReason(
-                    "User chose to never allow this tool".to_string(),
-                )
+                PromptChoice::DenyAlways
             }
-            3 => {
-                // No (once)
-                PermissionDecision::DenyWithReason(
-                    "User denied permission for this execution".to_string(),
-                )
+            Ok(_) => PromptChoice::DenyOnce,
+            Err(e) => {
+                eprintln!("{} Permission prompt failed: {}", "✗".red(), e);
+                PromptChoice::DenyOnce
             }
-            _ => unreachable!(),
         }
+    })
+}
+
+/// Advanced permission handler with memory for always/never decisions
+///
+/// A [`SharedMemoryPermissions`] that prompts on the terminal. Clones share
+/// the remembered decisions, so one clone can be given to a
+/// [`ToolRegistry`](crate::ToolRegistry) while another is kept for saving and
+/// restoring state.
+#[derive(Clone)]
+pub struct MemoryPermissionHandler {
+    inner: SharedMemoryPermissions<TerminalPrompt, BoxFuture<'static, PromptChoice>>,
+}
+
+impl MemoryPermissionHandler {
+    pub fn new() -> Self {
+        Self {
+            inner: SharedMemoryPermissions::new(terminal_prompt as TerminalPrompt),
+        }
+    }
+
+    /// Create a new handler that shares state with an existing one
+    pub fn with_shared_state(
+        always_allow: Arc<Mutex<HashSet<String>>>,
+        always_deny: Arc<Mutex<HashSet<String>>>,
+    ) -> Self {
+        Self {
+            inner: SharedMemoryPermissions::with_shared_state(
+                always_allow,
+                always_deny,
+                terminal_prompt as TerminalPrompt,
+            ),
+        }
+    }
+
+    /// Get the always_allow set for state management
+    pub fn always_allow(&self) -> Arc<Mutex<HashSet<String>>> {
+        self.inner.always_allow()
+    }
+
+    /// Get the always_deny set for state management
+    pub fn always_deny(&self) -> Arc<Mutex<HashSet<String>>> {
+        self.inner.always_deny()
+    }
+
+    /// Update the always_allow set
+    pub fn set_always_allow(&self, tools: HashSet<String>) {
+        self.inner.set_always_allow(tools);
+    }
+
+    /// Update the always_deny set
+    pub fn set_always_deny(&self, tools: HashSet<String>) {
+        self.inner.set_always_deny(tools);
+    }
+}
+
+#[async_trait]
+impl ToolPermissionHandler for MemoryPermissionHandler {
+    async fn check_permission(&self, request: &ToolExecutionRequest) -> PermissionDecision {
+        match self.inner.remembered(&request.tool_name) {
+            Some(PermissionDecision::Allow) => eprintln!(
+                "{} Automatically allowing '{}' (previously set to always allow)",
+                "✓".green(),
+                request.tool_name.cyan()
+            ),
+            Some(_) => eprintln!(
+                "{} Automatically denying '{}' (previously set to never allow)",
+                "✗".red(),
+                request.tool_name.cyan()
+            ),
+            None => {}
+        }
+        self.inner.check_permission(request).await
     }
 }
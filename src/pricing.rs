@@ -0,0 +1,201 @@
+use crate::request::Usage;
+
+/// Per-million-token prices in USD for a model family
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ModelPricing {
+    /// Price per million uncached input tokens
+    pub input_per_mtok: f64,
+    /// Price per million output tokens
+    pub output_per_mtok: f64,
+    /// Price per million tokens written to the prompt cache
+    pub cache_write_per_mtok: f64,
+    /// Price per million tokens read from the prompt cache
+    pub cache_read_per_mtok: f64,
+}
+
+/// Estimated cost of a request in USD
+///
+/// Returned by [`estimate_cost`]. Input costs are broken out into uncached input,
+/// prompt cache writes, and prompt cache reads.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct CostEstimate {
+    /// Cost of uncached input tokens
+    pub input_cost: f64,
+    /// Cost of input tokens written to the prompt cache
+    pub cache_creation_cost: f64,
+    /// Cost of input tokens read from the prompt cache
+    pub cache_read_cost: f64,
+    /// Cost of generated output tokens
+    pub output_cost: f64,
+    /// Sum of all of the above
+    pub total_cost: f64,
+}
+
+/// Built-in price table, keyed by model name without its snapshot date
+///
+/// See [`model_pricing`] for how model names are matched.
+const PRICE_TABLE: &[(&str, ModelPricing)] = &[
+    (
+        "claude-opus-4-1",
+        ModelPricing {
+            input_per_mtok: 15.0,
+            output_per_mtok: 75.0,
+            cache_write_per_mtok: 18.75,
+            cache_read_per_mtok: 1.50,
+        },
+    ),
+    (
+        "claude-opus-4",
+        ModelPricing {
+            input_per_mtok: 15.0,
+            output_per_mtok: 75.0,
+            cache_write_per_mtok: 18.75,
+            cache_read_per_mtok: 1.50,
+        },
+    ),
+    (
+        "claude-sonnet-4",
+        ModelPricing {
+            input_per_mtok: 3.0,
+            output_per_mtok: 15.0,
+            cache_write_per_mtok: 3.75,
+            cache_read_per_mtok: 0.30,
+        },
+    ),
+    (
+        "claude-3-7-sonnet",
+        ModelPricing {
+            input_per_mtok: 3.0,
+            output_per_mtok: 15.0,
+            cache_write_per_mtok: 3.75,
+            cache_read_per_mtok: 0.30,
+        },
+    ),
+    (
+        "claude-3-5-sonnet",
+        ModelPricing {
+            input_per_mtok: 3.0,
+            output_per_mtok: 15.0,
+            cache_write_per_mtok: 3.75,
+            cache_read_per_mtok: 0.30,
+        },
+    ),
+    (
+        "claude-3-5-haiku",
+        ModelPricing {
+            input_per_mtok: 0.80,
+            output_per_mtok: 4.0,
+            cache_write_per_mtok: 1.0,
+            cache_read_per_mtok: 0.08,
+        },
+    ),
+    (
+        "claude-3-opus",
+        ModelPricing {
+            input_per_mtok: 15.0,
+            output_per_mtok: 75.0,
+            cache_write_per_mtok: 18.75,
+            cache_read_per_mtok: 1.50,
+        },
+    ),
+    (
+        "claude-3-haiku",
+        ModelPricing {
+            input_per_mtok: 0.25,
+            output_per_mtok: 1.25,
+            cache_write_per_mtok: 0.30,
+            cache_read_per_mtok: 0.03,
+        },
+    ),
+];
+
+/// Look up the built-in pricing for a model
+///
+/// A model matches a table entry when its name is the entry itself, or the
+/// entry followed by a snapshot date (`-20250514`) or `-latest`. Newer models
+/// that merely share a prefix with an entry, such as `claude-opus-4-5` and
+/// `claude-opus-4`, may be priced differently, so they return `None` instead
+/// of a guess.
+///
+/// # Example
+///
+/// ```rust
+/// use claude::pricing::model_pricing;
+///
+/// let pricing = model_pricing("claude-sonnet-4-20250514").unwrap();
+/// assert_eq!(pricing.input_per_mtok, 3.0);
+/// assert_eq!(model_pricing("claude-3-5-haiku-latest").unwrap().output_per_mtok, 4.0);
+/// assert_eq!(model_pricing("claude-opus-4-1-20250805").unwrap().input_per_mtok, 15.0);
+///
+/// // Unlisted models aren't priced by their family's older entry
+/// assert!(model_pricing("claude-opus-4-5-20251101").is_none());
+/// assert!(model_pricing("claude-sonnet-4-5").is_none());
+/// assert!(model_pricing("claude-opus-4-2025").is_none());
+/// assert!(model_pricing("gpt-4").is_none());
+/// ```
+pub fn model_pricing(model: &str) -> Option<ModelPricing> {
+    PRICE_TABLE
+        .iter()
+        .find(|(name, _)| model.strip_prefix(name).is_some_and(is_snapshot_suffix))
+        .map(|(_, pricing)| *pricing)
+}
+
+/// Whether `rest` is nothing, `-latest`, or a `-YYYYMMDD` snapshot date
+fn is_snapshot_suffix(rest: &str) -> bool {
+    match rest.strip_prefix('-') {
+        None => rest.is_empty(),
+        Some("latest") => true,
+        Some(date) => date.len() == 8 && date.bytes().all(|b| b.is_ascii_digit()),
+    }
+}
+
+/// Estimate the USD cost of a request from its token usage
+///
+/// Returns `None` for unknown models rather than guessing a price.
+///
+/// # Example
+///
+/// ```rust
+/// use claude::Usage;
+/// use claude::pricing::estimate_cost;
+///
+/// let usage = Usage {
+///     input_tokens: 1_000_000,
+///     output_tokens: 100_000,
+///     cache_creation_input_tokens: Some(200_000),
+///     cache_read_input_tokens: Some(500_000),
+///     service_tier: None,
+/// };
+///
+/// let cost = estimate_cost("claude-3-7-sonnet-latest", &usage).unwrap();
+/// assert!((cost.input_cost - 3.0).abs() < 1e-9);
+/// assert!((cost.cache_creation_cost - 0.75).abs() < 1e-9);
+/// assert!((cost.cache_read_cost - 0.15).abs() < 1e-9);
+/// assert!((cost.output_cost - 1.5).abs() < 1e-9);
+/// assert!((cost.total_cost - 5.4).abs() < 1e-9);
+///
+/// assert!(estimate_cost("unknown-model", &usage).is_none());
+/// ```
+pub fn estimate_cost(model: &str, usage: &Usage) -> Option<CostEstimate> {
+    let pricing = model_pricing(model)?;
+    let cost = |tokens: u32, per_mtok: f64| tokens as f64 * per_mtok / 1_000_000.0;
+
+    let input_cost = cost(usage.input_tokens, pricing.input_per_mtok);
+    let cache_creation_cost = cost(
+        usage.cache_creation_input_tokens.unwrap_or(0),
+        pricing.cache_write_per_mtok,
+    );
+    let cache_read_cost = cost(
+        usage.cache_read_input_tokens.unwrap_or(0),
+        pricing.cache_read_per_mtok,
+    );
+    let output_cost = cost(usage.output_tokens, pricing.output_per_mtok);
+
+    Some(CostEstimate {
+        input_cost,
+        cache_creation_cost,
+        cache_read_cost,
+        output_cost,
+        total_cost: input_cost + cache_creation_cost + cache_read_cost + output_cost,
+    })
+}
@@ -1,6 +1,8 @@
-use crate::message::{ContentBlock, Message};
+use crate::error::{Error, Result};
+use crate::message::{ContentBlock, Message, ToolUse};
 use serde::{Deserialize, Serialize};
-use serde_json::Value;
+use serde_json::{json, Value};
+use std::ops::{Add, AddAssign};
 
 /// Tool definition for Claude to understand how to use a tool
 ///
@@ -26,6 +28,7 @@ use serde_json::Value;
 ///         },
 ///         "required": ["expression"]
 ///     }),
+///     cache_control: None,
 /// };
 /// ```
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -36,6 +39,259 @@ pub struct ToolDef {
     pub description: String,
     /// JSON Schema describing the required input format for the tool
     pub input_schema: Value,
+    /// Optional prompt caching breakpoint after this tool definition
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cache_control: Option<CacheControl>,
+}
+
+impl ToolDef {
+    /// Define a tool from its name, description and input schema
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use claude::ToolDef;
+    /// use serde_json::json;
+    ///
+    /// let def = ToolDef::new(
+    ///     "lookup_order",
+    ///     "Look up an order by id",
+    ///     json!({"type": "object", "properties": {"id": {"type": "string"}}}),
+    /// );
+    /// assert_eq!(def.name, "lookup_order");
+    /// assert!(def.cache_control.is_none());
+    /// ```
+    pub fn new(
+        name: impl Into<String>,
+        description: impl Into<String>,
+        input_schema: Value,
+    ) -> Self {
+        Self {
+            name: name.into(),
+            description: description.into(),
+            input_schema,
+            cache_control: None,
+        }
+    }
+
+    /// Start building a tool definition
+    ///
+    /// See [`ToolDefBuilder`].
+    pub fn builder() -> ToolDefBuilder {
+        ToolDefBuilder::new()
+    }
+}
+
+/// Builds a [`ToolDef`] a property at a time
+///
+/// The input schema starts as an object with no properties. Use
+/// [`input_schema`](Self::input_schema) to supply a whole schema instead.
+///
+/// # Example
+///
+/// ```rust
+/// use claude::{CacheControl, ToolDef};
+/// use serde_json::json;
+///
+/// let def = ToolDef::builder()
+///     .name("lookup_order")
+///     .description("Look up an order by id")
+///     .required_property("id", json!({"type": "string", "description": "Order id"}))
+///     .property("include_items", json!({"type": "boolean"}))
+///     .cache_control(CacheControl::Ephemeral)
+///     .build();
+///
+/// assert_eq!(serde_json::to_value(&def)?, json!({
+///     "name": "lookup_order",
+///     "description": "Look up an order by id",
+///     "input_schema": {
+///         "type": "object",
+///         "properties": {
+///             "id": {"type": "string", "description": "Order id"},
+///             "include_items": {"type": "boolean"}
+///         },
+///         "required": ["id"]
+///     },
+///     "cache_control": {"type": "ephemeral"}
+/// }));
+/// assert!(claude::schema::check_schema(&def.input_schema).is_empty());
+/// # Ok::<(), serde_json::Error>(())
+/// ```
+#[derive(Debug, Clone)]
+#[must_use]
+pub struct ToolDefBuilder {
+    name: String,
+    description: String,
+    input_schema: Value,
+    cache_control: Option<CacheControl>,
+}
+
+impl Default for ToolDefBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ToolDefBuilder {
+    /// Start with an empty name and description and an object schema with no
+    /// properties
+    pub fn new() -> Self {
+        Self {
+            name: String::new(),
+            description: String::new(),
+            input_schema: json!({"type": "object", "properties": {}}),
+            cache_control: None,
+        }
+    }
+
+    /// Set the name Claude calls the tool by
+    pub fn name(mut self, name: impl Into<String>) -> Self {
+        self.name = name.into();
+        self
+    }
+
+    /// Set the description of what the tool does
+    pub fn description(mut self, description: impl Into<String>) -> Self {
+        self.description = description.into();
+        self
+    }
+
+    /// Replace the whole input schema
+    pub fn input_schema(mut self, input_schema: Value) -> Self {
+        self.input_schema = input_schema;
+        self
+    }
+
+    /// Add an optional input property with its own schema
+    pub fn property(mut self, name: impl Into<String>, schema: Value) -> Self {
+        if !self.input_schema["properties"].is_object() {
+            self.input_schema["properties"] = json!({});
+        }
+        self.input_schema["properties"][name.into()] = schema;
+        self
+    }
+
+    /// Add an input property Claude must always supply
+    pub fn required_property(mut self, name: impl Into<String>, schema: Value) -> Self {
+        let name = name.into();
+        self = self.property(name.clone(), schema);
+        match self.input_schema["required"].as_array_mut() {
+            Some(required) => required.push(json!(name)),
+            None => self.input_schema["required"] = json!([name]),
+        }
+        self
+    }
+
+    /// Mark a prompt caching breakpoint after this tool definition
+    pub fn cache_control(mut self, cache_control: CacheControl) -> Self {
+        self.cache_control = Some(cache_control);
+        self
+    }
+
+    /// Finish the tool definition
+    pub fn build(self) -> ToolDef {
+        ToolDef {
+            name: self.name,
+            description: self.description,
+            input_schema: self.input_schema,
+            cache_control: self.cache_control,
+        }
+    }
+}
+
+/// Prompt caching marker for Anthropic's prompt caching API
+///
+/// Attaching a `CacheControl` to a tool definition, system block, or tool
+/// result marks a cache breakpoint: everything in the prompt up to and including that block is cached
+/// and re-used by subsequent requests with the same prefix.
+///
+/// # Example
+///
+/// ```rust
+/// use claude::CacheControl;
+///
+/// let value = serde_json::to_value(CacheControl::Ephemeral).unwrap();
+/// assert_eq!(value, serde_json::json!({"type": "ephemeral"}));
+/// ```
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum CacheControl {
+    /// Short-lived cache entry (currently five minutes)
+    Ephemeral,
+}
+
+/// System prompt sent with a request
+///
+/// The API accepts either a plain string or a list of text blocks. Use the block
+/// form to attach [`CacheControl`] markers to parts of the system prompt.
+///
+/// # Example
+///
+/// ```rust
+/// use claude::{SystemPrompt, SystemBlock, CacheControl};
+/// use serde_json::json;
+///
+/// let plain: SystemPrompt = "You are a helpful assistant.".into();
+/// assert_eq!(serde_json::to_value(&plain).unwrap(), json!("You are a helpful assistant."));
+///
+/// let cached = SystemPrompt::Blocks(vec![SystemBlock::Text {
+///     text: "You are a helpful assistant.".to_string(),
+///     cache_control: Some(CacheControl::Ephemeral),
+/// }]);
+/// assert_eq!(
+///     serde_json::to_value(&cached).unwrap(),
+///     json!([{
+///         "type": "text",
+///         "text": "You are a helpful assistant.",
+///         "cache_control": {"type": "ephemeral"}
+///     }])
+/// );
+/// ```
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(untagged)]
+pub enum SystemPrompt {
+    /// A plain text system prompt
+    Text(String),
+    /// A structured system prompt made of text blocks
+    Blocks(Vec<SystemBlock>),
+}
+
+impl SystemPrompt {
+    /// Check if any block of this system prompt carries a cache marker
+    pub fn has_cache_control(&self) -> bool {
+        match self {
+            SystemPrompt::Text(_) => false,
+            SystemPrompt::Blocks(blocks) => blocks.iter().any(|block| match block {
+                SystemBlock::Text { cache_control, .. } => cache_control.is_some(),
+            }),
+        }
+    }
+}
+
+impl From<String> for SystemPrompt {
+    fn from(text: String) -> Self {
+        SystemPrompt::Text(text)
+    }
+}
+
+impl From<&str> for SystemPrompt {
+    fn from(text: &str) -> Self {
+        SystemPrompt::Text(text.to_string())
+    }
+}
+
+/// A block within a structured [`SystemPrompt`]
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum SystemBlock {
+    /// Text content of the system prompt
+    Text {
+        /// The text content
+        text: String,
+        /// Optional prompt caching breakpoint after this block
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        cache_control: Option<CacheControl>,
+    },
 }
 
 /// Request structure for the Claude Messages API
@@ -52,8 +308,10 @@ pub struct ToolDef {
 ///
 /// # Optional Fields
 ///
-/// - `system`: System prompt to guide behavior
+/// - `system`: System prompt to guide behavior (plain or with cache markers)
 /// - `temperature`: Controls randomness (0.0-1.0)
+/// - `tool_choice`: Whether and which tools Claude must use
+/// - `thinking`: Extended thinking budget
 ///
 /// # Example
 ///
@@ -69,8 +327,10 @@ pub struct ToolDef {
 ///     ],
 ///     tools: vec![],
 ///     max_tokens: 1024,
-///     system: Some("You are a helpful assistant.".to_string()),
+///     system: Some("You are a helpful assistant.".into()),
 ///     temperature: Some(0.7),
+///     tool_choice: None,
+///     thinking: None,
 /// };
 /// ```
 #[derive(Debug, Serialize, Deserialize)]
@@ -84,10 +344,691 @@ pub struct MessageRequest {
     /// Maximum number of tokens Claude should generate in its response
     pub max_tokens: u32,
     /// Optional system prompt to guide Claude's behavior
-    pub system: Option<String>,
+    pub system: Option<SystemPrompt>,
     /// Optional temperature setting (0.0-1.0) to control randomness
     #[serde(skip_serializing_if = "Option::is_none")]
     pub temperature: Option<f32>,
+    /// Optional control over whether and which tools Claude must use
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tool_choice: Option<ToolChoice>,
+    /// Optional extended thinking configuration
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub thinking: Option<ThinkingConfig>,
+}
+
+/// Fluent builder for [`MessageRequest`]
+///
+/// Only the model is required. Unset fields default to no messages, no tools,
+/// `max_tokens` of [`DEFAULT_MAX_TOKENS`](Self::DEFAULT_MAX_TOKENS), and no
+/// system prompt, temperature, tool choice, or thinking, so code using the
+/// builder keeps compiling when optional fields are added.
+///
+/// # Example
+///
+/// ```rust
+/// use claude::{Message, MessageRequest, ToolChoice, ToolDef};
+/// use serde_json::json;
+///
+/// let weather = ToolDef {
+///     name: "weather".to_string(),
+///     description: "Current weather in a city".to_string(),
+///     input_schema: json!({"type": "object", "properties": {"city": {"type": "string"}}}),
+///     cache_control: None,
+/// };
+///
+/// let built = MessageRequest::builder("claude-3-5-haiku-20241022")
+///     .message(Message::user(vec!["Weather in Paris?".to_string().into()]))
+///     .tool(weather.clone())
+///     .system("Be brief.")
+///     .temperature(0.2)
+///     .tool_choice(ToolChoice::Auto)
+///     .build();
+///
+/// let by_hand = MessageRequest {
+///     model: "claude-3-5-haiku-20241022".to_string(),
+///     messages: vec![Message::user(vec!["Weather in Paris?".to_string().into()])],
+///     tools: vec![weather],
+///     max_tokens: 1024,
+///     system: Some("Be brief.".into()),
+///     temperature: Some(0.2),
+///     tool_choice: Some(ToolChoice::Auto),
+///     thinking: None,
+/// };
+/// assert_eq!(serde_json::to_value(&built)?, serde_json::to_value(&by_hand)?);
+///
+/// // The minimal request matches too, and survives a round trip unchanged
+/// let minimal = MessageRequest::builder("claude-3-5-haiku-20241022").build();
+/// let json = serde_json::to_value(&minimal)?;
+/// assert_eq!(json, json!({
+///     "model": "claude-3-5-haiku-20241022",
+///     "messages": [],
+///     "tools": [],
+///     "max_tokens": 1024,
+///     "system": null
+/// }));
+/// for request in [&built, &minimal] {
+///     let json = serde_json::to_value(request)?;
+///     let reparsed: MessageRequest = serde_json::from_value(json.clone())?;
+///     assert_eq!(serde_json::to_value(&reparsed)?, json);
+/// }
+/// # Ok::<(), serde_json::Error>(())
+/// ```
+#[derive(Debug, Clone)]
+#[must_use]
+pub struct MessageRequestBuilder {
+    model: String,
+    messages: Vec<Message>,
+    tools: Vec<ToolDef>,
+    max_tokens: u32,
+    system: Option<SystemPrompt>,
+    temperature: Option<f32>,
+    tool_choice: Option<ToolChoice>,
+    thinking: Option<ThinkingConfig>,
+}
+
+impl MessageRequestBuilder {
+    /// `max_tokens` used unless [`max_tokens`](Self::max_tokens) is called
+    pub const DEFAULT_MAX_TOKENS: u32 = 1024;
+
+    /// Start building a request for `model`
+    pub fn new(model: impl Into<String>) -> Self {
+        Self {
+            model: model.into(),
+            messages: Vec::new(),
+            tools: Vec::new(),
+            max_tokens: Self::DEFAULT_MAX_TOKENS,
+            system: None,
+            temperature: None,
+            tool_choice: None,
+            thinking: None,
+        }
+    }
+
+    /// Append a message to the conversation
+    pub fn message(mut self, message: Message) -> Self {
+        self.messages.push(message);
+        self
+    }
+
+    /// Append several messages to the conversation
+    pub fn messages(mut self, messages: impl IntoIterator<Item = Message>) -> Self {
+        self.messages.extend(messages);
+        self
+    }
+
+    /// Offer a tool to Claude
+    pub fn tool(mut self, tool: ToolDef) -> Self {
+        self.tools.push(tool);
+        self
+    }
+
+    /// Offer several tools to Claude
+    pub fn tools(mut self, tools: impl IntoIterator<Item = ToolDef>) -> Self {
+        self.tools.extend(tools);
+        self
+    }
+
+    /// Set the maximum number of tokens to generate
+    pub fn max_tokens(mut self, max_tokens: u32) -> Self {
+        self.max_tokens = max_tokens;
+        self
+    }
+
+    /// Set the system prompt
+    pub fn system(mut self, system: impl Into<SystemPrompt>) -> Self {
+        self.system = Some(system.into());
+        self
+    }
+
+    /// Set the sampling temperature
+    pub fn temperature(mut self, temperature: f32) -> Self {
+        self.temperature = Some(temperature);
+        self
+    }
+
+    /// Control whether and which tools Claude must use
+    pub fn tool_choice(mut self, tool_choice: ToolChoice) -> Self {
+        self.tool_choice = Some(tool_choice);
+        self
+    }
+
+    /// Enable extended thinking
+    pub fn thinking(mut self, thinking: ThinkingConfig) -> Self {
+        self.thinking = Some(thinking);
+        self
+    }
+
+    /// Finish the request
+    pub fn build(self) -> MessageRequest {
+        MessageRequest {
+            model: self.model,
+            messages: self.messages,
+            tools: self.tools,
+            max_tokens: self.max_tokens,
+            system: self.system,
+            temperature: self.temperature,
+            tool_choice: self.tool_choice,
+            thinking: self.thinking,
+        }
+    }
+}
+
+/// Controls how Claude chooses between tools
+///
+/// Serializes to the `tool_choice` object of the Messages API.
+///
+/// # Example
+///
+/// ```rust
+/// use claude::{MessageRequest, ToolChoice};
+/// use serde_json::json;
+///
+/// assert_eq!(serde_json::to_value(ToolChoice::Auto).unwrap(), json!({"type": "auto"}));
+/// assert_eq!(serde_json::to_value(ToolChoice::Any).unwrap(), json!({"type": "any"}));
+/// assert_eq!(serde_json::to_value(ToolChoice::None).unwrap(), json!({"type": "none"}));
+/// assert_eq!(
+///     serde_json::to_value(ToolChoice::Tool { name: "z3_solver".to_string() }).unwrap(),
+///     json!({"type": "tool", "name": "z3_solver"})
+/// );
+///
+/// // Omitted from the request entirely when unset
+/// let request = MessageRequest {
+///     model: "claude-3-haiku-20240307".to_string(),
+///     messages: vec![],
+///     tools: vec![],
+///     max_tokens: 1024,
+///     system: None,
+///     temperature: None,
+///     tool_choice: None,
+///     thinking: None,
+/// };
+/// assert!(serde_json::to_value(&request).unwrap().get("tool_choice").is_none());
+/// ```
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ToolChoice {
+    /// Claude decides whether to use tools (the API default)
+    Auto,
+    /// Claude must use one of the available tools
+    Any,
+    /// Claude must use the named tool
+    Tool {
+        /// Name of the tool to use
+        name: String,
+    },
+    /// Claude must not use any tools
+    None,
+}
+
+/// Extended thinking configuration for a request
+///
+/// With thinking enabled Claude reasons in [`ContentBlock::Thinking`] blocks
+/// before answering. `budget_tokens` counts towards `max_tokens`, must be at
+/// least 1024, and must be smaller than `max_tokens`.
+///
+/// # Example
+///
+/// ```rust
+/// use claude::{MessageRequest, ThinkingConfig};
+/// use serde_json::json;
+///
+/// let thinking = ThinkingConfig::Enabled { budget_tokens: 8192 };
+/// assert_eq!(
+///     serde_json::to_value(&thinking).unwrap(),
+///     json!({"type": "enabled", "budget_tokens": 8192})
+/// );
+/// assert_eq!(thinking.budget_tokens(), Some(8192));
+///
+/// let request = MessageRequest {
+///     model: "claude-sonnet-4-20250514".to_string(),
+///     messages: vec![],
+///     tools: vec![],
+///     max_tokens: 4096 + 8192,
+///     system: None,
+///     temperature: None,
+///     tool_choice: None,
+///     thinking: Some(thinking),
+/// };
+/// let value = serde_json::to_value(&request).unwrap();
+/// assert_eq!(value["thinking"]["budget_tokens"], 8192);
+/// ```
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ThinkingConfig {
+    /// Let Claude think for up to `budget_tokens` tokens before answering
+    Enabled {
+        /// Maximum number of tokens to spend on thinking
+        budget_tokens: u32,
+    },
+    /// No extended thinking (the API default)
+    Disabled,
+}
+
+impl ThinkingConfig {
+    /// The thinking budget, if thinking is enabled
+    pub fn budget_tokens(&self) -> Option<u32> {
+        match self {
+            ThinkingConfig::Enabled { budget_tokens } => Some(*budget_tokens),
+            ThinkingConfig::Disabled => None,
+        }
+    }
+}
+
+/// How a request with unmatched `tool_use`/`tool_result` blocks is handled
+///
+/// The API rejects a conversation in which a `tool_result` isn't answering a
+/// `tool_use` in the message right before it, or a `tool_use` isn't answered
+/// in the message right after it. This happens when a history is edited or
+/// truncated by hand.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ToolPairing {
+    /// Send the request as is (the default)
+    #[default]
+    Unchecked,
+    /// Fail before sending, describing every unmatched block
+    Strict,
+    /// Drop unmatched blocks, and messages left empty by that, then send
+    Repair,
+}
+
+/// A `tool_use` or `tool_result` block without its counterpart
+struct OrphanedBlock {
+    message: usize,
+    block: usize,
+    description: String,
+}
+
+/// Find every tool block whose counterpart isn't in the adjacent message
+fn find_orphans(messages: &[Message]) -> Vec<OrphanedBlock> {
+    let ids = |message: Option<&Message>, role: &str, results: bool| -> Vec<String> {
+        message
+            .filter(|m| m.role == role)
+            .map(|m| {
+                m.content
+                    .iter()
+                    .filter_map(|block| match block {
+                        ContentBlock::ToolUse { id, .. } if !results => Some(id.clone()),
+                        ContentBlock::ToolResult { tool_use_id, .. } if results => {
+                            Some(tool_use_id.clone())
+                        }
+                        _ => None,
+                    })
+                    .collect()
+            })
+            .unwrap_or_default()
+    };
+
+    let mut orphans = Vec::new();
+    for (i, message) in messages.iter().enumerate() {
+        for (j, block) in message.content.iter().enumerate() {
+            match block {
+                ContentBlock::ToolUse { id, name, .. } => {
+                    let answered = message.role == "assistant"
+                        && ids(messages.get(i + 1), "user", true).contains(id);
+                    if !answered {
+                        orphans.push(OrphanedBlock {
+                            message: i,
+                            block: j,
+                            description: format!(
+                                "tool_use '{}' ({}) in message {} has no tool_result in the following user message",
+                                id, name, i
+                            ),
+                        });
+                    }
+                }
+                ContentBlock::ToolResult { tool_use_id, .. } => {
+                    let previous = i.checked_sub(1).and_then(|p| messages.get(p));
+                    let requested = message.role == "user"
+                        && ids(previous, "assistant", false).contains(tool_use_id);
+                    if !requested {
+                        orphans.push(OrphanedBlock {
+                            message: i,
+                            block: j,
+                            description: format!(
+                                "tool_result '{}' in message {} has no matching tool_use in the preceding assistant message",
+                                tool_use_id, i
+                            ),
+                        });
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+    orphans
+}
+
+impl MessageRequest {
+    /// Replace the system prompt with a single cached text block
+    ///
+    /// The whole system prompt (and the tool definitions preceding it) becomes a
+    /// prompt caching breakpoint, so repeated turns are billed at the cache-read rate.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use claude::MessageRequest;
+    ///
+    /// let request = MessageRequest {
+    ///     model: "claude-3-haiku-20240307".to_string(),
+    ///     messages: vec![],
+    ///     tools: vec![],
+    ///     max_tokens: 1024,
+    ///     system: None,
+    ///     temperature: None,
+    ///     tool_choice: None,
+    ///     thinking: None,
+    /// };
+    /// assert!(!request.uses_prompt_caching());
+    ///
+    /// let request = request.with_cached_system("You are a helpful assistant.");
+    /// assert!(request.uses_prompt_caching());
+    /// ```
+    pub fn with_cached_system(mut self, text: impl Into<String>) -> Self {
+        self.system = Some(SystemPrompt::Blocks(vec![SystemBlock::Text {
+            text: text.into(),
+            cache_control: Some(CacheControl::Ephemeral),
+        }]));
+        self
+    }
+
+    /// Start building a request for `model`
+    ///
+    /// See [`MessageRequestBuilder`].
+    pub fn builder(model: impl Into<String>) -> MessageRequestBuilder {
+        MessageRequestBuilder::new(model)
+    }
+
+    /// Check if any part of this request carries a prompt caching marker
+    ///
+    /// When this returns true the client sends the prompt caching beta header.
+    ///
+    /// # Example
+    ///
+    /// A cached tool result, and the cache read it leads to on the next request:
+    ///
+    /// ```rust
+    /// use claude::test_util::MockTransport;
+    /// use claude::{CacheControl, Claude, ContentBlock, Message, MessageRequest, PROMPT_CACHING_BETA};
+    /// use serde_json::json;
+    /// use std::sync::Arc;
+    ///
+    /// let request = MessageRequest::builder("claude-3-5-haiku-20241022")
+    ///     .message(Message::user(vec!["Summarize the report".to_string().into()]))
+    ///     .message(Message::assistant(vec![ContentBlock::ToolUse {
+    ///         id: "toolu_1".to_string(),
+    ///         name: "http_fetch".to_string(),
+    ///         input: json!({"url": "https://example.com/report"}),
+    ///     }]))
+    ///     .message(Message::user(vec![ContentBlock::ToolResult {
+    ///         content: vec!["<a long report>".into()],
+    ///         tool_use_id: "toolu_1".to_string(),
+    ///         is_error: None,
+    ///         cache_control: Some(CacheControl::Ephemeral),
+    ///     }]))
+    ///     .build();
+    /// assert!(request.uses_prompt_caching());
+    ///
+    /// let transport = Arc::new(MockTransport::new());
+    /// transport.push_json(json!({
+    ///     "id": "msg_1",
+    ///     "type": "message",
+    ///     "role": "assistant",
+    ///     "model": "claude-3-5-haiku-20241022",
+    ///     "content": [{"type": "text", "text": "The report says..."}],
+    ///     "stop_reason": "end_turn",
+    ///     "stop_sequence": null,
+    ///     "usage": {"input_tokens": 12, "output_tokens": 40, "cache_read_input_tokens": 1800}
+    /// }));
+    /// let client = Claude::new("test-key".to_string(), "claude-3-5-haiku-20241022".to_string())
+    ///     .with_transport(transport.clone());
+    ///
+    /// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+    /// let response = client.next_message(request).await?;
+    /// let usage = response.usage.unwrap();
+    /// assert_eq!(usage.cache_read_input_tokens, Some(1800));
+    /// assert_eq!(usage.total_tokens(), 1852);
+    ///
+    /// let sent = &transport.requests()[0];
+    /// assert_eq!(sent.headers["anthropic-beta"], PROMPT_CACHING_BETA);
+    /// let body: serde_json::Value = serde_json::from_str(&sent.body_text()).unwrap();
+    /// assert_eq!(body["messages"][2]["content"][0]["cache_control"], json!({"type": "ephemeral"}));
+    /// # Ok::<(), claude::Error>(())
+    /// # })?;
+    /// # Ok::<(), claude::Error>(())
+    /// ```
+    ///
+    /// The header goes only with requests that cache something, whether in the
+    /// system prompt, a tool definition, or a tool result, and is merged with
+    /// any betas configured on the client:
+    ///
+    /// ```rust
+    /// use claude::test_util::MockTransport;
+    /// use claude::{CacheControl, Claude, Message, MessageRequest, ToolDef, PROMPT_CACHING_BETA};
+    /// use serde_json::json;
+    /// use std::sync::Arc;
+    ///
+    /// let transport = Arc::new(MockTransport::new());
+    /// for _ in 0..5 {
+    ///     transport.push_message(json!([{"type": "text", "text": "Hi!"}]), "end_turn");
+    /// }
+    /// let client = Claude::new("test-key".to_string(), "claude-3-5-haiku-20241022".to_string())
+    ///     .with_transport(transport.clone());
+    /// let beta = |i: usize| {
+    ///     transport.requests()[i]
+    ///         .headers
+    ///         .get("anthropic-beta")
+    ///         .map(|value| value.to_str().unwrap().to_string())
+    /// };
+    /// let request = || {
+    ///     MessageRequest::builder("claude-3-5-haiku-20241022")
+    ///         .system("You are terse.")
+    ///         .tool(ToolDef::new("search", "Search the web", json!({"type": "object"})))
+    ///         .message(Message::user(vec!["Hello".to_string().into()]))
+    /// };
+    ///
+    /// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+    /// // Nothing cached, no header
+    /// let plain = request().build();
+    /// assert!(!plain.uses_prompt_caching());
+    /// client.next_message(plain).await?;
+    /// assert_eq!(beta(0), None);
+    ///
+    /// // A cached system prompt
+    /// client.next_message(request().build().with_cached_system("You are terse.")).await?;
+    /// assert_eq!(beta(1).as_deref(), Some(PROMPT_CACHING_BETA));
+    ///
+    /// // A cached tool definition
+    /// let mut cached_tool = request().build();
+    /// cached_tool.tools[0].cache_control = Some(CacheControl::Ephemeral);
+    /// client.next_message(cached_tool).await?;
+    /// assert_eq!(beta(2).as_deref(), Some(PROMPT_CACHING_BETA));
+    ///
+    /// // Configured betas come first, and the caching beta isn't repeated
+    /// let client = client.with_beta_headers(vec!["pdfs-2024-09-25".to_string()]);
+    /// client.next_message(request().build().with_cached_system("You are terse.")).await?;
+    /// assert_eq!(beta(3).as_deref(), Some("pdfs-2024-09-25,prompt-caching-2024-07-31"));
+    /// let client = client.with_beta_headers(vec![PROMPT_CACHING_BETA.to_string()]);
+    /// client.next_message(request().build().with_cached_system("You are terse.")).await?;
+    /// assert_eq!(beta(4).as_deref(), Some(PROMPT_CACHING_BETA));
+    /// # Ok::<(), claude::Error>(())
+    /// # })?;
+    /// # Ok::<(), claude::Error>(())
+    /// ```
+    pub fn uses_prompt_caching(&self) -> bool {
+        self.tools.iter().any(|tool| tool.cache_control.is_some())
+            || self
+                .system
+                .as_ref()
+                .is_some_and(|system| system.has_cache_control())
+            || self
+                .messages
+                .iter()
+                .flat_map(|message| &message.content)
+                .any(|block| {
+                    matches!(
+                        block,
+                        ContentBlock::ToolResult {
+                            cache_control: Some(_),
+                            ..
+                        }
+                    )
+                })
+    }
+
+    /// Check if any message carries a [`ContentBlock::Document`]
+    ///
+    /// When this returns true the client sends the PDF support beta header.
+    pub fn uses_documents(&self) -> bool {
+        self.messages
+            .iter()
+            .flat_map(|message| &message.content)
+            .any(|block| matches!(block, ContentBlock::Document { .. }))
+    }
+
+    /// Check that every `tool_use` and `tool_result` block has its counterpart
+    ///
+    /// A `tool_result` must answer a `tool_use` of the assistant message right
+    /// before it, and each `tool_use` must be answered in the user message
+    /// right after it. Message indices in the error count from 0.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use claude::{ContentBlock, Message, MessageRequest};
+    /// use serde_json::json;
+    ///
+    /// let tool_use = |id: &str| ContentBlock::ToolUse {
+    ///     name: "calculator".to_string(),
+    ///     input: json!({"expression": "2+2"}),
+    ///     id: id.to_string(),
+    /// };
+    /// let tool_result = |id: &str| ContentBlock::ToolResult {
+    ///     content: vec!["4".into()],
+    ///     tool_use_id: id.to_string(),
+    ///     is_error: None,
+    ///     cache_control: None,
+    /// };
+    /// let request = |messages: Vec<Message>| MessageRequest {
+    ///     model: "claude-3-haiku-20240307".to_string(),
+    ///     messages,
+    ///     tools: vec![],
+    ///     max_tokens: 1024,
+    ///     system: None,
+    ///     temperature: None,
+    ///     tool_choice: None,
+    ///     thinking: None,
+    /// };
+    ///
+    /// let question = Message::user(vec!["What's 2+2?".to_string().into()]);
+    /// let paired = request(vec![
+    ///     question.clone(),
+    ///     Message::assistant(vec![tool_use("toolu_1")]),
+    ///     Message::user(vec![tool_result("toolu_1")]),
+    /// ]);
+    /// assert!(paired.validate().is_ok());
+    ///
+    /// // A result whose tool_use was truncated away
+    /// let orphaned_result = request(vec![
+    ///     Message::user(vec![tool_result("toolu_1"), "Thanks!".to_string().into()]),
+    /// ]);
+    /// let error = orphaned_result.validate().unwrap_err().to_string();
+    /// assert!(error.contains("tool_result 'toolu_1' in message 0"), "{}", error);
+    ///
+    /// // A tool_use that was never answered
+    /// let orphaned_use = request(vec![
+    ///     question,
+    ///     Message::assistant(vec!["Let me check.".to_string().into(), tool_use("toolu_2")]),
+    ///     Message::user(vec!["Never mind".to_string().into()]),
+    /// ]);
+    /// let error = orphaned_use.validate().unwrap_err().to_string();
+    /// assert!(error.contains("tool_use 'toolu_2' (calculator) in message 1"), "{}", error);
+    /// ```
+    pub fn validate(&self) -> Result<()> {
+        let orphans = find_orphans(&self.messages);
+        if orphans.is_empty() {
+            return Ok(());
+        }
+        let problems: Vec<String> = orphans.into_iter().map(|o| o.description).collect();
+        Err(Error::Other(format!(
+            "Request has unmatched tool blocks: {}",
+            problems.join("; ")
+        )))
+    }
+
+    /// Drop unmatched `tool_use` and `tool_result` blocks
+    ///
+    /// Messages left without content are removed too. Returns the number of
+    /// blocks dropped; afterwards [`validate`](Self::validate) succeeds.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use claude::{ContentBlock, Message, MessageRequest};
+    /// use serde_json::json;
+    ///
+    /// let mut request = MessageRequest {
+    ///     model: "claude-3-haiku-20240307".to_string(),
+    ///     messages: vec![
+    ///         // Orphaned result: its tool_use was truncated away
+    ///         Message::user(vec![ContentBlock::ToolResult {
+    ///             content: vec!["4".into()],
+    ///             tool_use_id: "toolu_1".to_string(),
+    ///             is_error: None,
+    ///             cache_control: None,
+    ///         }]),
+    ///         Message::assistant(vec![
+    ///             "Let me check.".to_string().into(),
+    ///             // Orphaned use: never answered
+    ///             ContentBlock::ToolUse {
+    ///                 name: "calculator".to_string(),
+    ///                 input: json!({"expression": "2+2"}),
+    ///                 id: "toolu_2".to_string(),
+    ///             },
+    ///         ]),
+    ///         Message::user(vec!["What's 3+3?".to_string().into()]),
+    ///     ],
+    ///     tools: vec![],
+    ///     max_tokens: 1024,
+    ///     system: None,
+    ///     temperature: None,
+    ///     tool_choice: None,
+    ///     thinking: None,
+    /// };
+    ///
+    /// assert_eq!(request.repair_tool_pairing(), 2);
+    /// assert!(request.validate().is_ok());
+    /// assert_eq!(request.messages.len(), 2);
+    /// assert_eq!(request.messages[0].visible_text(), "Let me check.");
+    /// ```
+    pub fn repair_tool_pairing(&mut self) -> usize {
+        let mut dropped = 0;
+        // Removing an emptied message changes which messages are adjacent, so
+        // repeat until nothing is left to drop
+        loop {
+            let orphans = find_orphans(&self.messages);
+            if orphans.is_empty() {
+                return dropped;
+            }
+            dropped += orphans.len();
+            for orphan in orphans.iter().rev() {
+                self.messages[orphan.message].content.remove(orphan.block);
+            }
+            self.messages.retain(|message| !message.content.is_empty());
+        }
+    }
+
+    /// Handle unmatched tool blocks according to `mode`
+    ///
+    /// Returns the number of blocks dropped, which is only non-zero for
+    /// [`ToolPairing::Repair`].
+    pub fn enforce_tool_pairing(&mut self, mode: ToolPairing) -> Result<usize> {
+        match mode {
+            ToolPairing::Unchecked => Ok(0),
+            ToolPairing::Strict => self.validate().map(|()| 0),
+            ToolPairing::Repair => Ok(self.repair_tool_pairing()),
+        }
+    }
 }
 
 /// Response from the Claude Messages API
@@ -148,6 +1089,137 @@ pub struct MessageResponse {
     pub usage: Option<Usage>,
 }
 
+impl MessageResponse {
+    /// All text blocks of the response, joined by newlines
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use claude::{MessageResponse, ContentBlock};
+    /// let response = MessageResponse {
+    ///     id: "msg_123".to_string(),
+    ///     model: "claude-3-haiku-20240307".to_string(),
+    ///     role: "assistant".to_string(),
+    ///     content: vec![
+    ///         ContentBlock::Text { text: "Let me check both cities.".to_string() },
+    ///         ContentBlock::ToolUse {
+    ///             name: "weather".to_string(),
+    ///             input: serde_json::json!({"city": "London"}),
+    ///             id: "tool_1".to_string(),
+    ///         },
+    ///         ContentBlock::Text { text: "And then Paris.".to_string() },
+    ///         ContentBlock::ToolUse {
+    ///             name: "weather".to_string(),
+    ///             input: serde_json::json!({"city": "Paris"}),
+    ///             id: "tool_2".to_string(),
+    ///         },
+    ///     ],
+    ///     stop_reason: "tool_use".to_string(),
+    ///     stop_sequence: None,
+    ///     usage: None,
+    /// };
+    /// assert_eq!(response.text(), "Let me check both cities.\nAnd then Paris.");
+    ///
+    /// let tool_uses = response.tool_uses();
+    /// assert_eq!(tool_uses.len(), 2);
+    /// assert_eq!(tool_uses[1].name, "weather");
+    /// assert_eq!(tool_uses[1].input["city"], "Paris");
+    /// assert_eq!(tool_uses[1].id, "tool_2");
+    /// assert!(response.is_tool_use());
+    /// ```
+    pub fn text(&self) -> String {
+        self.content
+            .iter()
+            .filter_map(|block| match block {
+                ContentBlock::Text { text } => Some(text.as_str()),
+                _ => None,
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    /// Tool use requests in the response, in order
+    pub fn tool_uses(&self) -> Vec<ToolUse> {
+        self.content
+            .iter()
+            .filter_map(|block| block.try_into().ok())
+            .collect()
+    }
+
+    /// Whether generation stopped because Claude wants to use a tool
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use claude::MessageResponse;
+    /// let response = MessageResponse {
+    ///     id: "msg_123".to_string(),
+    ///     model: "claude-3-haiku-20240307".to_string(),
+    ///     role: "assistant".to_string(),
+    ///     content: vec!["Hello!".to_string().into()],
+    ///     stop_reason: "end_turn".to_string(),
+    ///     stop_sequence: None,
+    ///     usage: None,
+    /// };
+    /// assert!(!response.is_tool_use());
+    /// assert!(response.tool_uses().is_empty());
+    /// assert_eq!(response.text(), "Hello!");
+    /// ```
+    pub fn is_tool_use(&self) -> bool {
+        self.stop_reason == "tool_use"
+    }
+
+    /// Name of a tool whose `tool_use` block was cut off by `max_tokens`
+    ///
+    /// When generation stops at `max_tokens` while Claude is still producing a
+    /// tool call, the call's input is incomplete and must not be executed.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use claude::{MessageResponse, ContentBlock};
+    /// let mut response = MessageResponse {
+    ///     id: "msg_123".to_string(),
+    ///     model: "claude-3-haiku-20240307".to_string(),
+    ///     role: "assistant".to_string(),
+    ///     content: vec![
+    ///         ContentBlock::Text { text: "Let me check.".to_string() },
+    ///         ContentBlock::ToolUse {
+    ///             name: "weather".to_string(),
+    ///             input: serde_json::json!({}),
+    ///             id: "tool_123".to_string(),
+    ///         },
+    ///     ],
+    ///     stop_reason: "max_tokens".to_string(),
+    ///     stop_sequence: None,
+    ///     usage: None,
+    /// };
+    /// assert_eq!(response.truncated_tool_use(), Some("weather"));
+    ///
+    /// // A truncated text response is not a truncated tool call
+    /// response.content.pop();
+    /// assert_eq!(response.truncated_tool_use(), None);
+    ///
+    /// // A complete tool call is not truncated
+    /// response.content.push(ContentBlock::ToolUse {
+    ///     name: "weather".to_string(),
+    ///     input: serde_json::json!({"city": "London"}),
+    ///     id: "tool_123".to_string(),
+    /// });
+    /// response.stop_reason = "tool_use".to_string();
+    /// assert_eq!(response.truncated_tool_use(), None);
+    /// ```
+    pub fn truncated_tool_use(&self) -> Option<&str> {
+        if self.stop_reason != "max_tokens" {
+            return None;
+        }
+        match self.content.last() {
+            Some(ContentBlock::ToolUse { name, .. }) => Some(name),
+            _ => None,
+        }
+    }
+}
+
 impl Into<Message> for &MessageResponse {
     /// Convert a MessageResponse into a Message for conversation history
     ///
@@ -196,23 +1268,104 @@ impl Into<Message> for &MessageResponse {
 /// #     output_tokens: 100,
 /// #     cache_creation_input_tokens: None,
 /// #     cache_read_input_tokens: None,
+/// #     service_tier: None,
 /// # };
 /// println!("Total tokens used: {}", usage.input_tokens + usage.output_tokens);
 ///
 /// if let Some(cached) = usage.cache_read_input_tokens {
 ///     println!("Tokens read from cache: {}", cached);
 /// }
+///
+/// // Usage from several requests can be summed
+/// let total = Usage::default() + usage.clone() + usage;
+/// assert_eq!(total.input_tokens, 100);
+/// assert_eq!(total.output_tokens, 200);
+///
+/// // Cache fields are populated when prompt caching is in use
+/// let cached: Usage = serde_json::from_str(r#"{
+///     "input_tokens": 20,
+///     "output_tokens": 10,
+///     "cache_creation_input_tokens": 1500,
+///     "cache_read_input_tokens": 0,
+///     "service_tier": "standard"
+/// }"#).unwrap();
+/// assert_eq!(cached.cache_creation_input_tokens, Some(1500));
+/// assert_eq!(cached.cache_read_input_tokens, Some(0));
+/// assert_eq!(cached.service_tier.as_deref(), Some("standard"));
+/// assert_eq!(cached.total_tokens(), 1530);
+///
+/// // and are absent otherwise
+/// let plain: Usage = serde_json::from_str(r#"{"input_tokens": 5, "output_tokens": 7}"#).unwrap();
+/// assert_eq!(plain.cache_creation_input_tokens, None);
+/// assert_eq!(plain.service_tier, None);
+///
+/// // Accumulating keeps cache counts once any request reports them
+/// let mut turn = plain;
+/// turn += cached;
+/// assert_eq!(turn.input_tokens, 25);
+/// assert_eq!(turn.output_tokens, 17);
+/// assert_eq!(turn.cache_creation_input_tokens, Some(1500));
+/// assert_eq!(turn.cache_read_input_tokens, Some(0));
+/// assert_eq!(turn.service_tier.as_deref(), Some("standard"));
+/// assert_eq!(turn.total_tokens(), 1542);
 /// ```
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
 pub struct Usage {
     /// Number of input tokens processed
     pub input_tokens: u32,
     /// Number of output tokens generated
     pub output_tokens: u32,
     /// Tokens used for cache creation (if applicable)
-    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub cache_creation_input_tokens: Option<u32>,
     /// Tokens read from cache (if applicable)
-    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub cache_read_input_tokens: Option<u32>,
+    /// Service tier the request was processed in, e.g. "standard" (if reported)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub service_tier: Option<String>,
+}
+
+impl Usage {
+    /// All tokens processed: input, output, and cache creation and reads
+    pub fn total_tokens(&self) -> u64 {
+        self.input_tokens as u64
+            + self.output_tokens as u64
+            + self.cache_creation_input_tokens.unwrap_or(0) as u64
+            + self.cache_read_input_tokens.unwrap_or(0) as u64
+    }
+}
+
+impl Add for Usage {
+    type Output = Usage;
+
+    /// Sum two usage records; cache counts stay `None` only if both are `None`
+    fn add(self, other: Usage) -> Usage {
+        fn add_optional(a: Option<u32>, b: Option<u32>) -> Option<u32> {
+            match (a, b) {
+                (None, None) => None,
+                (a, b) => Some(a.unwrap_or(0) + b.unwrap_or(0)),
+            }
+        }
+
+        Usage {
+            input_tokens: self.input_tokens + other.input_tokens,
+            output_tokens: self.output_tokens + other.output_tokens,
+            cache_creation_input_tokens: add_optional(
+                self.cache_creation_input_tokens,
+                other.cache_creation_input_tokens,
+            ),
+            cache_read_input_tokens: add_optional(
+                self.cache_read_input_tokens,
+                other.cache_read_input_tokens,
+            ),
+            service_tier: self.service_tier.or(other.service_tier),
+        }
+    }
+}
+
+impl AddAssign for Usage {
+    fn add_assign(&mut self, other: Usage) {
+        *self = std::mem::take(self) + other;
+    }
 }
@@ -0,0 +1,64 @@
+use std::time::Duration;
+
+/// How [`Claude`](crate::Claude) retries requests that fail with a retryable
+/// error
+///
+/// A request is retried when [`Error::is_retryable`](crate::Error::is_retryable)
+/// says it might succeed, up to `max_retries` times, waiting an exponentially
+/// growing backoff in between. A `retry-after` header on the failed response
+/// takes the place of the backoff. No wait is longer than `max_backoff`.
+///
+/// Every attempt of a POST carries the same
+/// [`IDEMPOTENCY_KEY_HEADER`](crate::IDEMPOTENCY_KEY_HEADER), so a retry of a
+/// request the API did receive isn't mistaken for a new one.
+///
+/// # Example
+///
+/// ```rust
+/// use claude::RetryPolicy;
+/// use std::time::Duration;
+///
+/// let policy = RetryPolicy::default();
+/// assert_eq!(policy.max_retries, 2);
+/// assert_eq!(policy.backoff(0), Duration::from_millis(500));
+/// assert_eq!(policy.backoff(1), Duration::from_secs(1));
+/// assert_eq!(policy.backoff(10), policy.max_backoff);
+///
+/// assert_eq!(RetryPolicy::none().max_retries, 0);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetryPolicy {
+    /// Retries after the first attempt; zero turns retrying off
+    pub max_retries: u32,
+    /// Wait before the first retry, doubled for each one after
+    pub initial_backoff: Duration,
+    /// Longest wait between attempts
+    pub max_backoff: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_retries: 2,
+            initial_backoff: Duration::from_millis(500),
+            max_backoff: Duration::from_secs(8),
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// A policy that never retries, the one a new client starts with
+    pub fn none() -> Self {
+        Self {
+            max_retries: 0,
+            ..Self::default()
+        }
+    }
+
+    /// How long to wait before retry number `retry`, counting from zero
+    pub fn backoff(&self, retry: u32) -> Duration {
+        self.initial_backoff
+            .saturating_mul(2u32.saturating_pow(retry))
+            .min(self.max_backoff)
+    }
+}
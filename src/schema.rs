@@ -0,0 +1,337 @@
+use serde_json::Value;
+
+/// Validate a JSON value against a JSON Schema
+///
+/// Supports the subset of JSON Schema used by tool input schemas: `type`, `enum`,
+/// `properties`, `required`, `additionalProperties`, `items`, `minimum`,
+/// `maximum`, `minLength`, `maxLength`, `minItems`, and `maxItems`. Unknown
+/// keywords are ignored.
+///
+/// Returns a list of human-readable violations, each prefixed with the path of
+/// the offending value (`$` is the root). An empty list means the value is valid.
+///
+/// # Example
+///
+/// ```rust
+/// use claude::schema::validate;
+/// use serde_json::json;
+///
+/// let schema = json!({
+///     "type": "object",
+///     "properties": {
+///         "city": {"type": "string"},
+///         "units": {"type": "string", "enum": ["metric", "imperial"]}
+///     },
+///     "required": ["city"],
+///     "additionalProperties": false
+/// });
+///
+/// assert!(validate(&schema, &json!({"city": "London"})).is_empty());
+///
+/// let errors = validate(&schema, &json!({"city": 42, "units": "kelvin", "extra": true}));
+/// assert_eq!(errors, vec![
+///     "$.city: expected string, got number".to_string(),
+///     "$.extra: unexpected property".to_string(),
+///     "$.units: value \"kelvin\" is not one of [\"metric\",\"imperial\"]".to_string(),
+/// ]);
+/// ```
+pub fn validate(schema: &Value, instance: &Value) -> Vec<String> {
+    let mut errors = Vec::new();
+    validate_at(schema, instance, "$", &mut errors);
+    errors
+}
+
+/// Check that a tool input schema is well formed
+///
+/// The root must be an object schema (`"type": "object"`), since that's all
+/// the API accepts for tool inputs. Throughout the schema, `type` must name a
+/// JSON type, `properties` must map names to schemas, `items` must be a
+/// schema, `enum` must be an array, and every `required` entry must be a
+/// declared property.
+///
+/// Returns a list of problems, each prefixed with the path of the offending
+/// schema (`$` is the root). An empty list means the schema is usable.
+///
+/// # Example
+///
+/// ```rust
+/// use claude::schema::check_schema;
+/// use serde_json::json;
+///
+/// let good = json!({
+///     "type": "object",
+///     "properties": {
+///         "city": {"type": "string"},
+///         "days": {"type": "array", "items": {"type": "integer"}}
+///     },
+///     "required": ["city"]
+/// });
+/// assert!(check_schema(&good).is_empty());
+///
+/// let untyped = json!({"properties": {"city": {"type": "string"}}});
+/// assert_eq!(check_schema(&untyped), vec![
+///     "$: expected \"type\": \"object\"".to_string(),
+/// ]);
+///
+/// let broken = json!({
+///     "type": "object",
+///     "properties": {"city": {"type": "text"}},
+///     "required": ["city", "country"]
+/// });
+/// assert_eq!(check_schema(&broken), vec![
+///     "$.properties.city: unknown type \"text\"".to_string(),
+///     "$: required property 'country' is not in properties".to_string(),
+/// ]);
+/// ```
+pub fn check_schema(schema: &Value) -> Vec<String> {
+    let mut problems = Vec::new();
+    if schema.get("type").and_then(|t| t.as_str()) != Some("object") {
+        problems.push("$: expected \"type\": \"object\"".to_string());
+    }
+    check_schema_at(schema, "$", &mut problems);
+    problems
+}
+
+const JSON_TYPES: &[&str] = &[
+    "object", "array", "string", "number", "integer", "boolean", "null",
+];
+
+fn check_schema_at(schema: &Value, path: &str, problems: &mut Vec<String>) {
+    let Some(schema) = schema.as_object() else {
+        problems.push(format!("{}: schema must be an object", path));
+        return;
+    };
+
+    if let Some(expected) = schema.get("type") {
+        let types: Vec<&Value> = match expected {
+            Value::Array(ts) => ts.iter().collect(),
+            t => vec![t],
+        };
+        for t in types {
+            match t.as_str() {
+                Some(name) if JSON_TYPES.contains(&name) => {}
+                Some(name) => problems.push(format!("{}: unknown type \"{}\"", path, name)),
+                None => problems.push(format!("{}: type must be a string, got {}", path, t)),
+            }
+        }
+    }
+
+    if let Some(allowed) = schema.get("enum") {
+        if !allowed.is_array() {
+            problems.push(format!("{}: enum must be an array", path));
+        }
+    }
+
+    let properties = match schema.get("properties") {
+        Some(Value::Object(properties)) => {
+            let mut names: Vec<&String> = properties.keys().collect();
+            names.sort();
+            for name in names {
+                let child_path = format!("{}.properties.{}", path, name);
+                check_schema_at(&properties[name], &child_path, problems);
+            }
+            Some(properties)
+        }
+        Some(_) => {
+            problems.push(format!("{}: properties must be an object", path));
+            None
+        }
+        None => None,
+    };
+
+    match schema.get("required") {
+        Some(Value::Array(required)) => {
+            for entry in required {
+                match entry.as_str() {
+                    Some(name) if properties.is_some_and(|p| p.contains_key(name)) => {}
+                    Some(name) => problems.push(format!(
+                        "{}: required property '{}' is not in properties",
+                        path, name
+                    )),
+                    None => problems.push(format!(
+                        "{}: required entries must be strings, got {}",
+                        path, entry
+                    )),
+                }
+            }
+        }
+        Some(_) => problems.push(format!("{}: required must be an array", path)),
+        None => {}
+    }
+
+    if let Some(items) = schema.get("items") {
+        check_schema_at(items, &format!("{}.items", path), problems);
+    }
+
+    match schema.get("additionalProperties") {
+        Some(extra @ Value::Object(_)) => {
+            check_schema_at(extra, &format!("{}.additionalProperties", path), problems)
+        }
+        Some(Value::Bool(_)) | None => {}
+        Some(_) => problems.push(format!(
+            "{}: additionalProperties must be a boolean or a schema",
+            path
+        )),
+    }
+}
+
+fn validate_at(schema: &Value, instance: &Value, path: &str, errors: &mut Vec<String>) {
+    let Some(schema) = schema.as_object() else {
+        return;
+    };
+
+    if let Some(expected) = schema.get("type") {
+        let types: Vec<&str> = match expected {
+            Value::String(t) => vec![t.as_str()],
+            Value::Array(ts) => ts.iter().filter_map(|t| t.as_str()).collect(),
+            _ => vec![],
+        };
+        if !types.is_empty() && !types.iter().any(|t| matches_type(t, instance)) {
+            errors.push(format!(
+                "{}: expected {}, got {}",
+                path,
+                types.join(" or "),
+                type_name(instance)
+            ));
+            // Further checks would only repeat the type mismatch
+            return;
+        }
+    }
+
+    if let Some(Value::Array(allowed)) = schema.get("enum") {
+        if !allowed.contains(instance) {
+            errors.push(format!(
+                "{}: value {} is not one of {}",
+                path,
+                instance,
+                Value::Array(allowed.clone())
+            ));
+        }
+    }
+
+    match instance {
+        Value::Object(map) => {
+            if let Some(Value::Array(required)) = schema.get("required") {
+                for name in required.iter().filter_map(|r| r.as_str()) {
+                    if !map.contains_key(name) {
+                        errors.push(format!("{}: missing required property '{}'", path, name));
+                    }
+                }
+            }
+
+            let properties = schema.get("properties").and_then(|p| p.as_object());
+            let mut keys: Vec<&String> = map.keys().collect();
+            keys.sort();
+            for key in keys {
+                let child_path = format!("{}.{}", path, key);
+                match properties.and_then(|p| p.get(key)) {
+                    Some(property_schema) => {
+                        validate_at(property_schema, &map[key], &child_path, errors)
+                    }
+                    None => match schema.get("additionalProperties") {
+                        Some(Value::Bool(false)) => {
+                            errors.push(format!("{}: unexpected property", child_path))
+                        }
+                        Some(extra_schema @ Value::Object(_)) => {
+                            validate_at(extra_schema, &map[key], &child_path, errors)
+                        }
+                        _ => {}
+                    },
+                }
+            }
+        }
+        Value::Array(items) => {
+            check_bound(
+                schema,
+                "minItems",
+                items.len() as f64,
+                path,
+                "items",
+                errors,
+            );
+            check_bound(
+                schema,
+                "maxItems",
+                items.len() as f64,
+                path,
+                "items",
+                errors,
+            );
+            if let Some(item_schema) = schema.get("items") {
+                for (index, item) in items.iter().enumerate() {
+                    validate_at(item_schema, item, &format!("{}[{}]", path, index), errors);
+                }
+            }
+        }
+        Value::String(text) => {
+            let length = text.chars().count() as f64;
+            check_bound(schema, "minLength", length, path, "characters", errors);
+            check_bound(schema, "maxLength", length, path, "characters", errors);
+        }
+        Value::Number(number) => {
+            if let Some(value) = number.as_f64() {
+                check_bound(schema, "minimum", value, path, "", errors);
+                check_bound(schema, "maximum", value, path, "", errors);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Check a `min*`/`max*` keyword against a measured value
+fn check_bound(
+    schema: &serde_json::Map<String, Value>,
+    keyword: &str,
+    actual: f64,
+    path: &str,
+    unit: &str,
+    errors: &mut Vec<String>,
+) {
+    let Some(limit) = schema.get(keyword).and_then(|l| l.as_f64()) else {
+        return;
+    };
+    let (violated, relation) = if keyword.starts_with("min") {
+        (actual < limit, "at least")
+    } else {
+        (actual > limit, "at most")
+    };
+    if violated {
+        let unit = if unit.is_empty() {
+            String::new()
+        } else {
+            format!(" {}", unit)
+        };
+        errors.push(format!(
+            "{}: expected {} {}{}, got {}",
+            path, relation, limit, unit, actual
+        ));
+    }
+}
+
+fn matches_type(expected: &str, instance: &Value) -> bool {
+    match expected {
+        "object" => instance.is_object(),
+        "array" => instance.is_array(),
+        "string" => instance.is_string(),
+        "boolean" => instance.is_boolean(),
+        "null" => instance.is_null(),
+        "number" => instance.is_number(),
+        "integer" => {
+            instance.is_i64()
+                || instance.is_u64()
+                || instance.as_f64().is_some_and(|n| n.fract() == 0.0)
+        }
+        _ => true,
+    }
+}
+
+fn type_name(instance: &Value) -> &'static str {
+    match instance {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
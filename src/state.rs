@@ -1,7 +1,54 @@
-use crate::Message;
+use crate::export;
+use crate::pricing::estimate_cost;
+use crate::{Error, Message, Result, ToolExecution, Transcript, Usage};
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 
+/// Everything the chatbot saves and restores between sessions
+///
+/// Usage statistics were added after the first saved-state format, so they
+/// default to empty when an older file is loaded.
+///
+/// # Example
+///
+/// ```rust
+/// use claude::{ChatbotState, Usage};
+///
+/// // A file saved before usage statistics were tracked
+/// let old = r#"{
+///     "conversation_history": [
+///         {"role": "user", "content": [{"type": "text", "text": "Hi"}]},
+///         {"role": "assistant", "content": [{"type": "text", "text": "Hello!"}]}
+///     ],
+///     "model": "claude-sonnet-4-20250514",
+///     "always_allow_tools": ["calculator"],
+///     "always_deny_tools": [],
+///     "system_prompt": null,
+///     "max_result_length": 200
+/// }"#;
+/// let mut state: ChatbotState = serde_json::from_str(old).unwrap();
+/// assert_eq!(state.conversation_history.len(), 2);
+/// assert_eq!(state.total_usage, Usage::default());
+/// assert!(state.tool_call_counts.is_empty());
+/// assert!(state.tool_history.is_empty());
+///
+/// state.record_tool_call("calculator");
+/// state.record_usage(&Usage {
+///     input_tokens: 1200,
+///     output_tokens: 300,
+///     cache_creation_input_tokens: None,
+///     cache_read_input_tokens: None,
+///     service_tier: None,
+/// });
+///
+/// // The new fields survive a save and reload
+/// let saved = serde_json::to_string(&state).unwrap();
+/// let restored: ChatbotState = serde_json::from_str(&saved).unwrap();
+/// assert_eq!(restored.total_usage.input_tokens, 1200);
+/// assert_eq!(restored.tool_call_counts["calculator"], 1);
+/// assert_eq!(restored.started_at, state.started_at);
+/// ```
 #[derive(Debug, Serialize, Deserialize)]
 pub struct ChatbotState {
     pub conversation_history: Vec<Message>,
@@ -10,18 +57,59 @@ pub struct ChatbotState {
     pub always_deny_tools: HashSet<String>,
     pub system_prompt: Option<String>,
     pub max_result_length: usize,
+    /// Token usage summed over every request of the session
+    #[serde(default)]
+    pub total_usage: Usage,
+    /// Number of calls made to each tool
+    #[serde(default)]
+    pub tool_call_counts: HashMap<String, usize>,
+    /// When the session started (load time for files saved without it)
+    #[serde(default = "Utc::now")]
+    pub started_at: DateTime<Utc>,
+    /// Saved points in the conversation that can be restored
+    #[serde(default)]
+    pub checkpoints: Vec<Checkpoint>,
+    /// The tool registry's execution history, for
+    /// [`ToolRegistry::load_history`](crate::ToolRegistry::load_history)
+    #[serde(default)]
+    pub tool_history: Vec<ToolExecution>,
+}
+
+/// Identifier of a [`Checkpoint`], unique within one [`ChatbotState`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct CheckpointId(pub usize);
+
+impl std::fmt::Display for CheckpointId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+/// A named snapshot of the conversation history
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Checkpoint {
+    pub id: CheckpointId,
+    pub label: String,
+    pub created_at: DateTime<Utc>,
+    /// The conversation history when the checkpoint was taken
+    pub messages: Vec<Message>,
+}
+
+/// Format a token count compactly, e.g. `950`, `12.3k` or `1.2M`
+fn format_tokens(tokens: u64) -> String {
+    if tokens >= 1_000_000 {
+        format!("{:.1}M", tokens as f64 / 1_000_000.0)
+    } else if tokens >= 1_000 {
+        format!("{:.1}k", tokens as f64 / 1_000.0)
+    } else {
+        tokens.to_string()
+    }
 }
 
 impl ChatbotState {
     pub fn new(model: String) -> Self {
-        Self {
-            conversation_history: Vec::new(),
-            model,
-            always_allow_tools: HashSet::new(),
-            always_deny_tools: HashSet::new(),
-            system_prompt: None,
-            max_result_length: 200,
-        }
+        Self::from_conversation(Vec::new(), model)
     }
 
     pub fn from_conversation(conversation: Vec<Message>, model: String) -> Self {
@@ -32,6 +120,256 @@ impl ChatbotState {
             always_deny_tools: HashSet::new(),
             system_prompt: None,
             max_result_length: 200,
+            total_usage: Usage::default(),
+            tool_call_counts: HashMap::new(),
+            started_at: Utc::now(),
+            checkpoints: Vec::new(),
+            tool_history: Vec::new(),
         }
     }
+
+    /// Save the current conversation history under `label`
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use claude::{ChatbotState, Message};
+    ///
+    /// let say = |text: &str| Message::user(vec![text.to_string().into()]);
+    /// let mut state = ChatbotState::new("claude-sonnet-4-20250514".to_string());
+    /// state.conversation_history.push(say("Plan a trip"));
+    /// state.conversation_history.push(say("Somewhere warm"));
+    /// let warm = state.checkpoint("before choosing a destination");
+    ///
+    /// // Explore one path...
+    /// state.conversation_history.push(say("Let's go to Lisbon"));
+    /// state.conversation_history.push(say("Book flights"));
+    ///
+    /// // ...rewind, keeping the abandoned branch as its own checkpoint
+    /// let lisbon = state.restore(warm).unwrap().expect("branch saved");
+    /// assert_eq!(state.conversation_history.len(), 2);
+    /// state.conversation_history.push(say("Let's go to Seville"));
+    ///
+    /// assert_eq!(state.checkpoints.len(), 2);
+    /// state.restore(lisbon).unwrap();
+    /// assert_eq!(state.conversation_history.len(), 4);
+    /// assert_eq!(state.checkpoints.len(), 3);
+    ///
+    /// // Checkpoints are saved with the rest of the state
+    /// let saved = serde_json::to_string(&state).unwrap();
+    /// let mut loaded: ChatbotState = serde_json::from_str(&saved).unwrap();
+    /// assert_eq!(loaded.checkpoints.len(), 3);
+    /// loaded.restore(warm).unwrap();
+    /// assert_eq!(loaded.conversation_history.len(), 2);
+    ///
+    /// assert!(loaded.restore(claude::CheckpointId(99)).is_err());
+    /// ```
+    pub fn checkpoint(&mut self, label: impl Into<String>) -> CheckpointId {
+        let id = CheckpointId(
+            self.checkpoints
+                .iter()
+                .map(|checkpoint| checkpoint.id.0 + 1)
+                .max()
+                .unwrap_or(0),
+        );
+        self.checkpoints.push(Checkpoint {
+            id,
+            label: label.into(),
+            created_at: Utc::now(),
+            messages: self.conversation_history.clone(),
+        });
+        id
+    }
+
+    /// Replace the conversation history with that of checkpoint `id`
+    ///
+    /// Unless the current history is already saved in a checkpoint, it is first
+    /// kept as a new checkpoint whose id is returned, so no branch is lost.
+    pub fn restore(&mut self, id: CheckpointId) -> Result<Option<CheckpointId>> {
+        let target = self
+            .checkpoints
+            .iter()
+            .find(|checkpoint| checkpoint.id == id)
+            .ok_or_else(|| Error::Other(format!("No checkpoint with id {}", id)))?;
+        let label = target.label.clone();
+        let messages = target.messages.clone();
+
+        let current = serde_json::to_value(&self.conversation_history)?;
+        let already_saved = self.checkpoints.iter().any(|checkpoint| {
+            serde_json::to_value(&checkpoint.messages).is_ok_and(|saved| saved == current)
+        });
+        let branch =
+            (!already_saved).then(|| self.checkpoint(format!("before restoring '{}'", label)));
+
+        self.conversation_history = messages;
+        Ok(branch)
+    }
+
+    /// Render the conversation as Markdown for sharing
+    ///
+    /// Each message gets a role heading. Tool calls become fenced code blocks
+    /// with the tool's name and JSON input, except `patch_file` calls, which
+    /// show their diff. Tool results are folded into `<details>` blocks and
+    /// truncated to [`export::MAX_RESULT_CHARS`] characters.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use claude::{ChatbotState, ContentBlock, Message};
+    /// use serde_json::json;
+    ///
+    /// let mut state = ChatbotState::new("claude-sonnet-4-20250514".to_string());
+    /// state.conversation_history = vec![
+    ///     Message::user(vec!["What's 6 * 7?".to_string().into()]),
+    ///     Message::assistant(vec![ContentBlock::ToolUse {
+    ///         name: "calculator".to_string(),
+    ///         input: json!({"expression": "6 * 7"}),
+    ///         id: "toolu_1".to_string(),
+    ///     }]),
+    ///     Message::user(vec![ContentBlock::ToolResult {
+    ///         content: vec!["42".repeat(2000).into()],
+    ///         tool_use_id: "toolu_1".to_string(),
+    ///         is_error: None,
+    ///         cache_control: None,
+    ///     }]),
+    ///     Message::assistant(vec!["It's 42.".to_string().into()]),
+    /// ];
+    ///
+    /// let markdown = state.to_markdown();
+    /// assert!(markdown.contains("## User\n\nWhat's 6 * 7?"));
+    /// assert!(markdown.contains("## Assistant\n\nIt's 42."));
+    /// assert!(markdown.contains("**Tool call:** `calculator`"));
+    /// assert!(markdown.contains("```json\n{\n  \"expression\": \"6 * 7\"\n}\n```"));
+    /// assert!(markdown.contains("## Tool results"));
+    /// assert!(markdown.contains("<summary>calculator result</summary>"));
+    /// assert!(markdown.contains("(2000 more characters)"));
+    ///
+    /// // patch_file calls show their diff, coloured in the HTML version
+    /// state.conversation_history.push(Message::assistant(vec![ContentBlock::ToolUse {
+    ///     name: "patch_file".to_string(),
+    ///     input: json!({"path": "notes.txt", "diff": "@@ -1 +1 @@\n-old\n+new <b>"}),
+    ///     id: "toolu_2".to_string(),
+    /// }]));
+    /// assert!(state.to_markdown().contains("```diff\n@@ -1 +1 @@\n-old\n+new <b>\n```"));
+    /// let html = state.to_html();
+    /// assert!(html.contains("<span class=\"add\">+new &lt;b&gt;</span>"));
+    /// assert!(html.contains("<span class=\"del\">-old</span>"));
+    /// ```
+    pub fn to_markdown(&self) -> String {
+        export::to_markdown(&self.conversation_history, &self.export_title())
+    }
+
+    /// Render the conversation as a standalone HTML page
+    ///
+    /// Laid out like [`to_markdown`](Self::to_markdown), with `patch_file`
+    /// diffs coloured the way the permission prompt shows them.
+    pub fn to_html(&self) -> String {
+        export::to_html(&self.conversation_history, &self.export_title())
+    }
+
+    /// Read the conversation as a [`Transcript`] of text, tool calls, and results
+    pub fn transcript(&self) -> Transcript<'_> {
+        Transcript::new(&self.conversation_history)
+    }
+
+    fn export_title(&self) -> String {
+        format!(
+            "Conversation with {} ({})",
+            self.model,
+            self.started_at.format("%Y-%m-%d %H:%M UTC")
+        )
+    }
+
+    /// Add the usage of one API request to the session total
+    pub fn record_usage(&mut self, usage: &Usage) {
+        self.total_usage += usage.clone();
+    }
+
+    /// Count one call of `tool_name`
+    pub fn record_tool_call(&mut self, tool_name: &str) {
+        *self
+            .tool_call_counts
+            .entry(tool_name.to_string())
+            .or_default() += 1;
+    }
+
+    /// Total number of tool calls this session
+    pub fn total_tool_calls(&self) -> usize {
+        self.tool_call_counts.values().sum()
+    }
+
+    /// A short report of the session so far
+    ///
+    /// The first line reads like "17 messages, 4 tool calls, 12.3k tokens,
+    /// $0.03"; the cost is left out for models without known pricing. Later
+    /// lines give the token split, per-tool counts, and session length.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use claude::{ChatbotState, Message, Usage};
+    ///
+    /// let mut state = ChatbotState::new("claude-sonnet-4-20250514".to_string());
+    /// state.conversation_history.push(Message::user(vec!["What's 2+2?".to_string().into()]));
+    /// state.conversation_history.push(Message::assistant(vec!["4".to_string().into()]));
+    /// state.record_tool_call("calculator");
+    /// state.record_tool_call("calculator");
+    /// state.record_tool_call("bash");
+    /// state.record_usage(&Usage {
+    ///     input_tokens: 10_000,
+    ///     output_tokens: 2_300,
+    ///     cache_creation_input_tokens: None,
+    ///     cache_read_input_tokens: None,
+    ///     service_tier: None,
+    /// });
+    ///
+    /// let summary = state.summary();
+    /// let first_line = summary.lines().next().unwrap();
+    /// assert_eq!(first_line, "2 messages, 3 tool calls, 12.3k tokens, $0.0645");
+    /// assert!(summary.contains("calculator ×2, bash ×1"), "{}", summary);
+    /// ```
+    pub fn summary(&self) -> String {
+        let usage = &self.total_usage;
+        let total_tokens = usage.total_tokens();
+
+        let mut headline = format!(
+            "{} messages, {} tool calls, {} tokens",
+            self.conversation_history.len(),
+            self.total_tool_calls(),
+            format_tokens(total_tokens)
+        );
+        if let Some(cost) = estimate_cost(&self.model, usage) {
+            let digits = if cost.total_cost < 1.0 { 4 } else { 2 };
+            headline.push_str(&format!(", ${:.*}", digits, cost.total_cost));
+        }
+
+        let mut lines = vec![headline];
+        lines.push(format!(
+            "Tokens: {} input, {} output, {} cache write, {} cache read",
+            format_tokens(usage.input_tokens as u64),
+            format_tokens(usage.output_tokens as u64),
+            format_tokens(usage.cache_creation_input_tokens.unwrap_or(0) as u64),
+            format_tokens(usage.cache_read_input_tokens.unwrap_or(0) as u64)
+        ));
+
+        if !self.tool_call_counts.is_empty() {
+            let mut counts: Vec<(&String, &usize)> = self.tool_call_counts.iter().collect();
+            counts.sort_by(|a, b| b.1.cmp(a.1).then_with(|| a.0.cmp(b.0)));
+            let tools: Vec<String> = counts
+                .iter()
+                .map(|(name, count)| format!("{} ×{}", name, count))
+                .collect();
+            lines.push(format!("Tools: {}", tools.join(", ")));
+        }
+
+        let elapsed = Utc::now().signed_duration_since(self.started_at);
+        lines.push(format!(
+            "Session: {}, started {} ({}m ago)",
+            self.model,
+            self.started_at.format("%Y-%m-%d %H:%M UTC"),
+            elapsed.num_minutes().max(0)
+        ));
+
+        lines.join("\n")
+    }
 }
@@ -0,0 +1,671 @@
+use crate::error::{Error, Result};
+use crate::transport::{RawRequest, RawResponse, Transport};
+use async_trait::async_trait;
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::{BTreeMap, VecDeque};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+/// [`Transport`] that replays canned responses and errors in order
+///
+/// Every request is recorded, so a test can check what the client sent. Once
+/// the queue is empty, further requests fail with [`Error::Other`].
+///
+/// # Example
+///
+/// Drive a two-round tool conversation without a network:
+///
+/// ```rust
+/// use async_trait::async_trait;
+/// use claude::test_util::MockTransport;
+/// use claude::{Claude, Tool, ToolRegistry};
+/// use serde_json::{json, Value};
+/// use std::sync::Arc;
+///
+/// struct Weather;
+///
+/// #[async_trait]
+/// impl Tool for Weather {
+///     fn name(&self) -> &str { "weather" }
+///     fn description(&self) -> &str { "Current weather in a city" }
+///     fn input_schema(&self) -> Value {
+///         json!({"type": "object", "properties": {"city": {"type": "string"}}, "required": ["city"]})
+///     }
+///     async fn execute(&self, input: Value) -> Result<String, claude::Error> {
+///         Ok(format!("Sunny in {}", input["city"].as_str().unwrap_or("?")))
+///     }
+/// }
+///
+/// let transport = Arc::new(MockTransport::new());
+/// transport.push_message(
+///     json!([{"type": "tool_use", "id": "toolu_1", "name": "weather", "input": {"city": "Paris"}}]),
+///     "tool_use",
+/// );
+/// transport.push_message(json!([{"type": "text", "text": "It's sunny in Paris."}]), "end_turn");
+///
+/// let client = Claude::new("test-key".to_string(), "claude-3-5-haiku-20241022".to_string())
+///     .with_transport(transport.clone());
+/// let mut registry = ToolRegistry::new();
+/// registry.register(Arc::new(Weather))?;
+///
+/// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+/// let text = client
+///     .run_conversation_turn("Weather in Paris?", &mut registry, None, None, None)
+///     .await?;
+/// assert_eq!(text, "It's sunny in Paris.");
+///
+/// let requests = transport.requests();
+/// assert_eq!(requests.len(), 2);
+/// assert!(requests[0].url.ends_with("/v1/messages"));
+/// let second: Value = serde_json::from_str(&requests[1].body_text()).unwrap();
+/// let result = &second["messages"][2]["content"][0];
+/// assert_eq!(result["tool_use_id"], "toolu_1");
+/// assert_eq!(result["content"], "Sunny in Paris");
+/// # Ok::<(), claude::Error>(())
+/// # })?;
+/// # Ok::<(), claude::Error>(())
+/// ```
+///
+/// Errors come back the way the API would send them:
+///
+/// ```rust
+/// use claude::test_util::MockTransport;
+/// use claude::transport::RawResponse;
+/// use claude::{Claude, Error, ToolRegistry};
+/// use std::sync::Arc;
+///
+/// let transport = Arc::new(MockTransport::new());
+/// transport.push_response(RawResponse::new(
+///     529,
+///     r#"{"type": "error", "error": {"type": "overloaded_error", "message": "Overloaded"}}"#,
+/// ));
+/// transport.push_error(Error::Other("connection reset".to_string()));
+///
+/// let client = Claude::new("test-key".to_string(), "claude-3-5-haiku-20241022".to_string())
+///     .with_transport(transport);
+/// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+/// let err = client
+///     .run_conversation_turn("Hi", &mut ToolRegistry::new(), None, None, None)
+///     .await
+///     .unwrap_err();
+/// assert!(matches!(err, Error::Api { status: 529, .. }));
+///
+/// let err = client
+///     .run_conversation_turn("Hi", &mut ToolRegistry::new(), None, None, None)
+///     .await
+///     .unwrap_err();
+/// assert_eq!(err.to_string(), "connection reset");
+///
+/// // Nothing left to replay
+/// assert!(client
+///     .run_conversation_turn("Hi", &mut ToolRegistry::new(), None, None, None)
+///     .await
+///     .is_err());
+/// # });
+/// ```
+#[derive(Debug, Default)]
+pub struct MockTransport {
+    responses: Mutex<VecDeque<Result<RawResponse>>>,
+    requests: Mutex<Vec<RawRequest>>,
+}
+
+impl MockTransport {
+    /// Create a transport with nothing queued
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Queue a response
+    pub fn push_response(&self, response: RawResponse) {
+        self.responses.lock().unwrap().push_back(Ok(response));
+    }
+
+    /// Queue a 200 response with a JSON body
+    pub fn push_json(&self, body: Value) {
+        self.push_response(RawResponse::new(200, body.to_string()));
+    }
+
+    /// Queue a Messages API response with the given content blocks and stop reason
+    pub fn push_message(&self, content: Value, stop_reason: &str) {
+        let id = format!("msg_{}", self.responses.lock().unwrap().len() + 1);
+        self.push_json(serde_json::json!({
+            "id": id,
+            "type": "message",
+            "role": "assistant",
+            "model": "claude-3-5-haiku-20241022",
+            "content": content,
+            "stop_reason": stop_reason,
+            "stop_sequence": null,
+            "usage": {"input_tokens": 10, "output_tokens": 5}
+        }));
+    }
+
+    /// Queue an error, as if the request never got a response
+    pub fn push_error(&self, error: Error) {
+        self.responses.lock().unwrap().push_back(Err(error));
+    }
+
+    /// Every request sent so far, oldest first
+    pub fn requests(&self) -> Vec<RawRequest> {
+        self.requests.lock().unwrap().clone()
+    }
+
+    /// Number of queued responses not yet sent
+    pub fn remaining(&self) -> usize {
+        self.responses.lock().unwrap().len()
+    }
+}
+
+#[async_trait]
+impl Transport for MockTransport {
+    async fn send(&self, request: RawRequest) -> Result<RawResponse> {
+        let description = format!("{} {}", request.method, request.url);
+        self.requests.lock().unwrap().push(request);
+        self.responses
+            .lock()
+            .unwrap()
+            .pop_front()
+            .unwrap_or_else(|| {
+                Err(Error::Other(format!(
+                    "MockTransport has no response queued for {}",
+                    description
+                )))
+            })
+    }
+}
+
+/// A request as stored in a [`Cassette`]
+///
+/// Headers are left out so the API key never ends up in a recording.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RecordedRequest {
+    /// HTTP method, e.g. `POST`
+    pub method: String,
+    /// Full URL, including any query string
+    pub url: String,
+    /// Request body, if any
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub body: Option<String>,
+}
+
+impl RecordedRequest {
+    fn from_raw(request: &RawRequest) -> Self {
+        Self {
+            method: request.method.to_string(),
+            url: request.url.clone(),
+            body: request.body.as_ref().map(|_| request.body_text()),
+        }
+    }
+
+    /// Whether `request` is the one recorded here
+    ///
+    /// JSON bodies are compared as values, so key order and whitespace don't
+    /// matter.
+    pub fn matches(&self, request: &RawRequest) -> bool {
+        let incoming = Self::from_raw(request);
+        if self.method != incoming.method || self.url != incoming.url {
+            return false;
+        }
+        match (&self.body, &incoming.body) {
+            (Some(recorded), Some(incoming)) => {
+                match (
+                    serde_json::from_str::<Value>(recorded),
+                    serde_json::from_str::<Value>(incoming),
+                ) {
+                    (Ok(recorded), Ok(incoming)) => recorded == incoming,
+                    _ => recorded == incoming,
+                }
+            }
+            (recorded, incoming) => recorded == incoming,
+        }
+    }
+}
+
+/// A response as stored in a [`Cassette`]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RecordedResponse {
+    /// HTTP status code
+    pub status: u16,
+    /// Response headers that are valid UTF-8
+    #[serde(default)]
+    pub headers: BTreeMap<String, String>,
+    /// Response body
+    pub body: String,
+}
+
+impl RecordedResponse {
+    fn from_raw(response: &RawResponse) -> Self {
+        Self {
+            status: response.status,
+            headers: response
+                .headers
+                .iter()
+                .filter_map(|(name, value)| {
+                    Some((name.to_string(), value.to_str().ok()?.to_string()))
+                })
+                .collect(),
+            body: response.body.clone(),
+        }
+    }
+
+    fn to_raw(&self) -> RawResponse {
+        let mut headers = HeaderMap::new();
+        for (name, value) in &self.headers {
+            if let (Ok(name), Ok(value)) = (
+                HeaderName::from_bytes(name.as_bytes()),
+                HeaderValue::from_str(value),
+            ) {
+                headers.insert(name, value);
+            }
+        }
+        RawResponse {
+            status: self.status,
+            headers,
+            body: self.body.clone(),
+        }
+    }
+}
+
+/// One request and the response it got
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Interaction {
+    /// What was sent
+    pub request: RecordedRequest,
+    /// What came back
+    pub response: RecordedResponse,
+}
+
+/// Recorded HTTP interactions, saved as JSON
+///
+/// Written by [`RecordingTransport`] and played back by [`ReplayTransport`].
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Cassette {
+    /// Interactions in the order they happened
+    pub interactions: Vec<Interaction>,
+}
+
+impl Cassette {
+    /// Read a cassette from a JSON file
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let data = fs::read_to_string(path).map_err(|e| {
+            Error::Other(format!("Failed to read cassette {}: {}", path.display(), e))
+        })?;
+        serde_json::from_str(&data).map_err(|e| {
+            Error::Other(format!(
+                "Failed to parse cassette {}: {}",
+                path.display(),
+                e
+            ))
+        })
+    }
+
+    /// Write the cassette to a JSON file, replacing it if it exists
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<()> {
+        let path = path.as_ref();
+        let data = serde_json::to_string_pretty(self)?;
+        fs::write(path, data).map_err(|e| {
+            Error::Other(format!(
+                "Failed to write cassette {}: {}",
+                path.display(),
+                e
+            ))
+        })
+    }
+}
+
+/// [`Transport`] that passes requests through and records them to a cassette
+///
+/// Every successful round trip, whatever its status, is appended to the
+/// cassette file as it happens, so a recording survives a test that fails
+/// halfway. Transport errors are passed on and not recorded.
+///
+/// # Example
+///
+/// Record a conversation once, then replay it without the original transport:
+///
+/// ```rust
+/// use claude::test_util::{Cassette, MockTransport, RecordingTransport, ReplayTransport};
+/// use claude::{Claude, Message, MessageRequest};
+/// use serde_json::json;
+/// use std::sync::Arc;
+///
+/// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+/// let dir = tempfile::tempdir().unwrap();
+/// let cassette = dir.path().join("greeting.json");
+/// let request = |text: &str| {
+///     MessageRequest::builder("claude-3-5-haiku-20241022")
+///         .message(Message::user(vec![text.to_string().into()]))
+///         .build()
+/// };
+///
+/// // Stands in for the real API while recording
+/// let mock = Arc::new(MockTransport::new());
+/// mock.push_message(json!([{"type": "text", "text": "Hello!"}]), "end_turn");
+/// mock.push_message(json!([{"type": "text", "text": "Goodbye!"}]), "end_turn");
+///
+/// let recorder = Arc::new(RecordingTransport::new(mock, &cassette));
+/// let client = Claude::new("sk-ant-secret".to_string(), "claude-3-5-haiku-20241022".to_string())
+///     .with_transport(recorder.clone());
+/// let recorded = vec![
+///     client.next_message(request("Hi")).await?,
+///     client.next_message(request("Bye")).await?,
+/// ];
+///
+/// let saved = Cassette::load(&cassette)?;
+/// assert_eq!(saved.interactions.len(), 2);
+/// assert_eq!(saved, recorder.cassette());
+/// assert!(!std::fs::read_to_string(&cassette).unwrap().contains("sk-ant-secret"));
+///
+/// // Replaying needs no API key and gives the same responses
+/// let replay = Arc::new(ReplayTransport::from_file(&cassette)?);
+/// let client = Claude::new(String::new(), "claude-3-5-haiku-20241022".to_string())
+///     .with_transport(replay.clone());
+/// let replayed = vec![
+///     client.next_message(request("Hi")).await?,
+///     client.next_message(request("Bye")).await?,
+/// ];
+/// assert_eq!(
+///     serde_json::to_value(&replayed).unwrap(),
+///     serde_json::to_value(&recorded).unwrap()
+/// );
+/// assert_eq!(replay.remaining(), 0);
+///
+/// // A request that wasn't recorded fails instead of reaching the network
+/// let err = client.next_message(request("Something new")).await.unwrap_err();
+/// assert!(err.to_string().contains("no recorded response"), "{}", err);
+/// # Ok::<(), claude::Error>(())
+/// # }).unwrap();
+/// ```
+pub struct RecordingTransport {
+    inner: Arc<dyn Transport>,
+    path: PathBuf,
+    cassette: Mutex<Cassette>,
+}
+
+impl RecordingTransport {
+    /// Record requests sent through `inner` to a new cassette at `path`
+    pub fn new(inner: Arc<dyn Transport>, path: impl Into<PathBuf>) -> Self {
+        Self {
+            inner,
+            path: path.into(),
+            cassette: Mutex::new(Cassette::default()),
+        }
+    }
+
+    /// Everything recorded so far
+    pub fn cassette(&self) -> Cassette {
+        self.cassette.lock().unwrap().clone()
+    }
+
+    /// The file the cassette is written to
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+#[async_trait]
+impl Transport for RecordingTransport {
+    async fn send(&self, request: RawRequest) -> Result<RawResponse> {
+        let recorded = RecordedRequest::from_raw(&request);
+        let response = self.inner.send(request).await?;
+
+        let mut cassette = self.cassette.lock().unwrap();
+        cassette.interactions.push(Interaction {
+            request: recorded,
+            response: RecordedResponse::from_raw(&response),
+        });
+        cassette.save(&self.path)?;
+        Ok(response)
+    }
+}
+
+/// [`Transport`] that answers requests from a [`Cassette`]
+///
+/// Each request is matched against the recorded requests by method, URL,
+/// and body, and gets the response of the first match not yet used, so a
+/// request made twice replays both of its recorded responses in order. A
+/// request with no match fails with [`Error::Other`]. See
+/// [`RecordingTransport`] for an example.
+#[derive(Debug)]
+pub struct ReplayTransport {
+    interactions: Mutex<Vec<Option<Interaction>>>,
+}
+
+impl ReplayTransport {
+    /// Replay the interactions of `cassette`
+    pub fn new(cassette: Cassette) -> Self {
+        Self {
+            interactions: Mutex::new(cassette.interactions.into_iter().map(Some).collect()),
+        }
+    }
+
+    /// Replay the cassette saved at `path`
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self> {
+        Ok(Self::new(Cassette::load(path)?))
+    }
+
+    /// Number of recorded interactions not yet replayed
+    pub fn remaining(&self) -> usize {
+        self.interactions
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|interaction| interaction.is_some())
+            .count()
+    }
+}
+
+#[async_trait]
+impl Transport for ReplayTransport {
+    async fn send(&self, request: RawRequest) -> Result<RawResponse> {
+        let mut interactions = self.interactions.lock().unwrap();
+        let slot = interactions.iter_mut().find(|slot| {
+            slot.as_ref()
+                .is_some_and(|interaction| interaction.request.matches(&request))
+        });
+        match slot.and_then(Option::take) {
+            Some(interaction) => Ok(interaction.response.to_raw()),
+            None => Err(Error::Other(format!(
+                "ReplayTransport has no recorded response for {} {}",
+                request.method, request.url
+            ))),
+        }
+    }
+}
+
+/// HTTP server on a local port that answers every request with a closure
+///
+/// For code that makes its own HTTP requests rather than going through a
+/// [`Transport`], like the Firecrawl tools. Each request is recorded as a
+/// [`RawRequest`] whose URL starts with [`url`](Self::url), and gets whatever
+/// [`RawResponse`] `respond` returns for it, with a JSON content type unless
+/// the response sets one. Requests are served one at a time over
+/// `Connection: close`, and request bodies must come with a `Content-Length`.
+/// The server stops when it is dropped.
+///
+/// # Example
+///
+/// ```rust
+/// use claude::test_util::MockServer;
+/// use claude::transport::RawResponse;
+/// use serde_json::json;
+///
+/// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+/// let server = MockServer::start(|request| match request.url.ends_with("/ping") {
+///     true => RawResponse::new(200, json!({"pong": true}).to_string()),
+///     false => RawResponse::new(404, "{}"),
+/// })
+/// .await?;
+///
+/// let client = reqwest::Client::new();
+/// let reply = client
+///     .post(format!("{}/ping", server.url()))
+///     .body("hello")
+///     .send()
+///     .await
+///     .unwrap();
+/// assert_eq!(reply.status(), 200);
+/// assert_eq!(reply.text().await.unwrap(), r#"{"pong":true}"#);
+///
+/// let missing = client.get(format!("{}/nope", server.url())).send().await.unwrap();
+/// assert_eq!(missing.status(), 404);
+///
+/// let requests = server.requests();
+/// assert_eq!(requests.len(), 2);
+/// assert_eq!(requests[0].method, "POST");
+/// assert_eq!(requests[0].url, format!("{}/ping", server.url()));
+/// assert_eq!(requests[0].body_text(), "hello");
+/// # Ok::<(), claude::Error>(())
+/// # }).unwrap();
+/// ```
+pub struct MockServer {
+    url: String,
+    requests: Arc<Mutex<Vec<RawRequest>>>,
+    task: tokio::task::JoinHandle<()>,
+}
+
+impl MockServer {
+    /// Start serving on a free local port
+    ///
+    /// Must be called from within a Tokio runtime.
+    pub async fn start<F>(mut respond: F) -> Result<Self>
+    where
+        F: FnMut(&RawRequest) -> RawResponse + Send + 'static,
+    {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0")
+            .await
+            .map_err(|e| Error::Other(format!("Failed to start mock server: {}", e)))?;
+        let url = format!(
+            "http://{}",
+            listener
+                .local_addr()
+                .map_err(|e| Error::Other(format!("Failed to start mock server: {}", e)))?
+        );
+        let requests = Arc::new(Mutex::new(Vec::new()));
+
+        let server_url = url.clone();
+        let server_requests = requests.clone();
+        let task = tokio::spawn(async move {
+            while let Ok((mut socket, _)) = listener.accept().await {
+                let Some(request) = read_request(&mut socket, &server_url).await else {
+                    continue;
+                };
+                let response = respond(&request);
+                server_requests.lock().unwrap().push(request);
+                let _ = write_response(&mut socket, &response).await;
+            }
+        });
+
+        Ok(Self {
+            url,
+            requests,
+            task,
+        })
+    }
+
+    /// Base URL of the server, e.g. `http://127.0.0.1:4321`
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    /// Every request served so far, oldest first
+    pub fn requests(&self) -> Vec<RawRequest> {
+        self.requests.lock().unwrap().clone()
+    }
+}
+
+impl Drop for MockServer {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+/// Read one HTTP/1.1 request, or `None` if the connection closes or it's malformed
+async fn read_request(socket: &mut tokio::net::TcpStream, base_url: &str) -> Option<RawRequest> {
+    use tokio::io::AsyncReadExt;
+
+    let mut data = Vec::new();
+    let mut buf = [0u8; 4096];
+    let header_end = loop {
+        if let Some(end) = data.windows(4).position(|w| w == b"\r\n\r\n") {
+            break end;
+        }
+        let n = socket.read(&mut buf).await.ok()?;
+        if n == 0 {
+            return None;
+        }
+        data.extend_from_slice(&buf[..n]);
+    };
+
+    let head = String::from_utf8_lossy(&data[..header_end]).to_string();
+    let mut lines = head.split("\r\n");
+    let mut request_line = lines.next()?.split(' ');
+    let method = request_line.next()?.parse().ok()?;
+    let path = request_line.next()?;
+
+    let mut headers = HeaderMap::new();
+    for line in lines {
+        let (name, value) = line.split_once(':')?;
+        if let (Ok(name), Ok(value)) = (
+            HeaderName::from_bytes(name.trim().as_bytes()),
+            HeaderValue::from_str(value.trim()),
+        ) {
+            headers.append(name, value);
+        }
+    }
+    let length = headers
+        .get(reqwest::header::CONTENT_LENGTH)
+        .and_then(|v| v.to_str().ok()?.parse::<usize>().ok())
+        .unwrap_or(0);
+
+    let mut body = data.split_off(header_end + 4);
+    while body.len() < length {
+        let n = socket.read(&mut buf).await.ok()?;
+        if n == 0 {
+            return None;
+        }
+        body.extend_from_slice(&buf[..n]);
+    }
+
+    Some(RawRequest {
+        method,
+        url: format!("{}{}", base_url, path),
+        headers,
+        body: (length > 0).then_some(body),
+    })
+}
+
+/// Write `response` with a `Content-Length` and `Connection: close`
+async fn write_response(
+    socket: &mut tokio::net::TcpStream,
+    response: &RawResponse,
+) -> std::io::Result<()> {
+    use tokio::io::AsyncWriteExt;
+
+    let reason = reqwest::StatusCode::from_u16(response.status)
+        .ok()
+        .and_then(|status| status.canonical_reason())
+        .unwrap_or("");
+    let mut head = format!("HTTP/1.1 {} {}\r\n", response.status, reason);
+    if !response.headers.contains_key(reqwest::header::CONTENT_TYPE) {
+        head.push_str("Content-Type: application/json\r\n");
+    }
+    for (name, value) in &response.headers {
+        if name == reqwest::header::CONTENT_LENGTH || name == reqwest::header::CONNECTION {
+            continue;
+        }
+        head.push_str(&format!("{}: {}\r\n", name, value.to_str().unwrap_or("")));
+    }
+    head.push_str(&format!(
+        "Content-Length: {}\r\nConnection: close\r\n\r\n",
+        response.body.len()
+    ));
+
+    socket.write_all(head.as_bytes()).await?;
+    socket.write_all(response.body.as_bytes()).await?;
+    socket.shutdown().await
+}
@@ -1,14 +1,195 @@
 use crate::error::{Error, Result};
 use crate::execution::{ExecutionState, ToolExecution};
-use crate::message::ContentBlock;
+use crate::message::{ContentBlock, ImageSource, ToolResultContent};
 use crate::permissions::{
     AlwaysAllowPermissions, PermissionDecision, ToolExecutionRequest, ToolPermissionHandler,
 };
 use crate::request::ToolDef;
+use crate::schema;
 use async_trait::async_trait;
+use futures::future::{join_all, BoxFuture};
 use serde_json::Value;
 use std::collections::HashMap;
+use std::future::Future;
+use std::path::Path;
 use std::sync::Arc;
+use std::time::Duration;
+use tokio_util::sync::CancellationToken;
+
+/// Typed output produced by a tool
+///
+/// Returned by [`Tool::execute_typed`] so that structured data reaches Claude as
+/// JSON rather than a JSON document encoded inside a string.
+///
+/// # Example
+///
+/// ```rust
+/// use claude::ToolOutput;
+/// use serde_json::json;
+///
+/// let text = ToolOutput::Text("done".to_string());
+/// assert_eq!(text.into_text(), "done");
+///
+/// let data = ToolOutput::Json(json!({"answer": 42}));
+/// let rendered = data.into_text();
+/// assert_eq!(serde_json::from_str::<serde_json::Value>(&rendered).unwrap(), json!({"answer": 42}));
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub enum ToolOutput {
+    /// Plain text output
+    Text(String),
+    /// Structured JSON output
+    Json(Value),
+    /// Base64-encoded image output
+    Image {
+        /// MIME type of the image (e.g., "image/png")
+        media_type: String,
+        /// Base64-encoded image bytes
+        data: String,
+    },
+}
+
+impl ToolOutput {
+    /// Render this output as text
+    ///
+    /// JSON is pretty-printed, and images are replaced by a short placeholder
+    /// describing them.
+    pub fn into_text(self) -> String {
+        match self {
+            ToolOutput::Text(text) => text,
+            ToolOutput::Json(value) => {
+                serde_json::to_string_pretty(&value).unwrap_or_else(|_| value.to_string())
+            }
+            ToolOutput::Image { media_type, data } => {
+                format!("[{} image, {} bytes base64]", media_type, data.len())
+            }
+        }
+    }
+    /// Convert this output into the content of a `ToolResult` block
+    ///
+    /// Text and JSON become a single text item, rendered as by
+    /// [`into_text`](Self::into_text); images are passed on as images.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use claude::{ImageSource, ToolOutput, ToolResultContent};
+    ///
+    /// let screenshot = ToolOutput::Image {
+    ///     media_type: "image/png".to_string(),
+    ///     data: "iVBORw0KGgo=".to_string(),
+    /// };
+    /// match screenshot.into_result_content().as_slice() {
+    ///     [ToolResultContent::Image { source: ImageSource::Base64 { media_type, .. } }] => {
+    ///         assert_eq!(media_type, "image/png");
+    ///     }
+    ///     other => panic!("expected one image, got {:?}", other),
+    /// }
+    /// ```
+    pub fn into_result_content(self) -> Vec<ToolResultContent> {
+        match self {
+            ToolOutput::Image { media_type, data } => vec![ToolResultContent::Image {
+                source: ImageSource::Base64 { media_type, data },
+            }],
+            output => vec![output.into_text().into()],
+        }
+    }
+
+    /// Cap the rendered text of this output at `max_bytes`
+    ///
+    /// Output that fits is returned unchanged. Otherwise the result is text
+    /// ending in a `[truncated N bytes]` marker, which doesn't count towards
+    /// the cap. A JSON array is cut between items, keeping as many leading
+    /// items as fit, so what remains is still valid JSON; other output is cut
+    /// at a character boundary. Images are never truncated.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use claude::ToolOutput;
+    /// use serde_json::json;
+    ///
+    /// let page = ToolOutput::Text("x".repeat(1000));
+    /// assert_eq!(
+    ///     page.truncate(10).into_text(),
+    ///     format!("{}\n[truncated 990 bytes]", "x".repeat(10))
+    /// );
+    ///
+    /// let rows = ToolOutput::Json(json!([{"id": 1}, {"id": 2}, {"id": 3}]));
+    /// let text = rows.clone().truncate(40).into_text();
+    /// let (kept, marker) = text.split_once("\n[truncated").unwrap();
+    /// assert_eq!(kept, serde_json::to_string_pretty(&json!([{"id": 1}])).unwrap());
+    /// let full = rows.into_text();
+    /// assert_eq!(marker, format!(" {} bytes]", full.len() - kept.len()));
+    ///
+    /// let short = ToolOutput::Text("ok".to_string());
+    /// assert_eq!(short.clone().truncate(10), short);
+    /// ```
+    pub fn truncate(self, max_bytes: usize) -> ToolOutput {
+        fn cut(text: &str, max_bytes: usize) -> String {
+            let mut end = max_bytes;
+            while !text.is_char_boundary(end) {
+                end -= 1;
+            }
+            text[..end].to_string()
+        }
+
+        let (full_len, kept) = match self {
+            ToolOutput::Image { .. } => return self,
+            ToolOutput::Json(Value::Array(items)) => {
+                // Pretty-printed as "[\n  item,\n  item\n]", so an item takes its
+                // own length, re-indented, plus four bytes, and the brackets two
+                let rendered: Vec<String> = items
+                    .iter()
+                    .map(|item| {
+                        serde_json::to_string_pretty(item)
+                            .unwrap_or_else(|_| item.to_string())
+                            .replace('\n', "\n  ")
+                    })
+                    .collect();
+                let full_len = 2 + rendered.iter().map(|item| item.len() + 4).sum::<usize>();
+                if full_len <= max_bytes {
+                    return ToolOutput::Json(Value::Array(items));
+                }
+
+                let mut kept_len = 2;
+                let mut count = 0;
+                for item in &rendered {
+                    kept_len += item.len() + 4;
+                    if kept_len > max_bytes {
+                        break;
+                    }
+                    count += 1;
+                }
+                let kept = if count == 0 {
+                    "[]".to_string()
+                } else {
+                    format!("[\n  {}\n]", rendered[..count].join(",\n  "))
+                };
+                (full_len, kept)
+            }
+            ToolOutput::Json(value) => {
+                let full =
+                    serde_json::to_string_pretty(&value).unwrap_or_else(|_| value.to_string());
+                if full.len() <= max_bytes {
+                    return ToolOutput::Json(value);
+                }
+                (full.len(), cut(&full, max_bytes))
+            }
+            ToolOutput::Text(text) => {
+                if text.len() <= max_bytes {
+                    return ToolOutput::Text(text);
+                }
+                (text.len(), cut(&text, max_bytes))
+            }
+        };
+        ToolOutput::Text(format!(
+            "{}\n[truncated {} bytes]",
+            kept,
+            full_len - kept.len()
+        ))
+    }
+}
 
 /// Trait defining a tool that Claude can use during conversations
 ///
@@ -71,6 +252,59 @@ pub trait Tool: Send + Sync {
     /// Get the JSON schema defining the expected input format
     fn input_schema(&self) -> Value;
 
+    /// Get the version of this tool's behavior
+    ///
+    /// Recorded in each [`ToolExecution`] so that results in the history can
+    /// be traced back to the tool version that produced them. Bump it when a
+    /// change alters what the tool does. Defaults to `"1"`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use claude::{Tool, ToolRegistry};
+    /// # use std::sync::Arc;
+    /// # use async_trait::async_trait;
+    /// # use serde_json::{json, Value};
+    /// struct Search;
+    ///
+    /// #[async_trait]
+    /// impl Tool for Search {
+    ///     fn name(&self) -> &str { "search" }
+    ///     fn description(&self) -> &str { "Searches the web" }
+    ///     fn input_schema(&self) -> Value { json!({"type": "object"}) }
+    ///     fn version(&self) -> &str { "2.1" }
+    ///     async fn execute(&self, _input: Value) -> Result<String, claude::Error> {
+    ///         Ok("No results".to_string())
+    ///     }
+    /// }
+    ///
+    /// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+    /// let mut registry = ToolRegistry::new();
+    /// registry.register(Arc::new(Search))?;
+    /// registry
+    ///     .execute_tool("search", json!({}), "tool_1".to_string(), None)
+    ///     .await?;
+    ///
+    /// assert_eq!(registry.execution_history()[0].tool_version, "2.1");
+    /// assert_eq!(registry.execution_history_json()?[0]["tool_version"], "2.1");
+    /// # Ok::<(), claude::Error>(())
+    /// # }).unwrap();
+    /// ```
+    fn version(&self) -> &str {
+        "1"
+    }
+
+    /// Get example inputs showing how this tool is meant to be called
+    ///
+    /// Each example is a complete input matching [`input_schema`](Self::input_schema).
+    /// Tools with an easy-to-get-wrong input format, such as an expression
+    /// syntax, can use these to show Claude concrete calls; see
+    /// [`ToolRegistry::tool_usage_guide`] and
+    /// [`ToolRegistry::set_examples_in_descriptions`]. Defaults to none.
+    fn examples(&self) -> Vec<Value> {
+        Vec::new()
+    }
+
     /// Execute the tool with the given input parameters
     ///
     /// # Arguments
@@ -82,16 +316,178 @@ pub trait Tool: Send + Sync {
     /// Returns a Result containing either the tool's output as a string or an error
     async fn execute(&self, input: Value) -> Result<String>;
 
+    /// Execute the tool and return typed output
+    ///
+    /// The default implementation wraps [`execute`](Self::execute) in
+    /// [`ToolOutput::Text`]. Tools that produce structured data should override
+    /// this to return [`ToolOutput::Json`].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use claude::{ContentBlock, Tool, ToolOutput, ToolRegistry};
+    /// # use std::sync::Arc;
+    /// # use async_trait::async_trait;
+    /// # use serde_json::{json, Value};
+    /// struct Stats;
+    ///
+    /// #[async_trait]
+    /// impl Tool for Stats {
+    ///     fn name(&self) -> &str { "stats" }
+    ///     fn description(&self) -> &str { "Reports statistics" }
+    ///     fn input_schema(&self) -> Value { json!({"type": "object"}) }
+    ///     async fn execute(&self, input: Value) -> Result<String, claude::Error> {
+    ///         Ok(self.execute_typed(input).await?.into_text())
+    ///     }
+    ///     async fn execute_typed(&self, _input: Value) -> Result<ToolOutput, claude::Error> {
+    ///         Ok(ToolOutput::Json(json!({"count": 3, "mean": 1.5})))
+    ///     }
+    /// }
+    ///
+    /// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+    /// let mut registry = ToolRegistry::new();
+    /// registry.register(Arc::new(Stats))?;
+    ///
+    /// let result = registry
+    ///     .execute_tool("stats", json!({}), "tool_1".to_string(), None)
+    ///     .await?;
+    /// match result {
+    ///     ContentBlock::ToolResult { content, is_error, .. } => {
+    ///         assert_eq!(is_error, None);
+    ///         let text = claude::ToolResultContent::text_of(&content);
+    ///         let parsed: Value = serde_json::from_str(&text).unwrap();
+    ///         assert_eq!(parsed, json!({"count": 3, "mean": 1.5}));
+    ///     }
+    ///     _ => panic!("Expected tool result"),
+    /// }
+    /// # Ok::<(), claude::Error>(())
+    /// # }).unwrap();
+    /// ```
+    async fn execute_typed(&self, input: Value) -> Result<ToolOutput> {
+        Ok(ToolOutput::Text(self.execute(input).await?))
+    }
+
+    /// Execute the tool, stopping early if `cancel` is triggered
+    ///
+    /// This is the entry point used by [`ToolRegistry`], which gets the token
+    /// from [`TurnOptions::cancel`](crate::TurnOptions::cancel) during a turn.
+    /// The default implementation ignores the token and calls
+    /// [`execute_typed`](Self::execute_typed). Long-running tools should
+    /// override this to watch the token (for example with `tokio::select!` on
+    /// [`CancellationToken::cancelled`]) and return promptly once it fires, as
+    /// [`BashTool`](crate::tools::BashTool) and
+    /// [`HttpFetchTool`](crate::tools::HttpFetchTool) do.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use claude::{CancellationToken, ContentBlock, Tool, ToolOutput, ToolRegistry};
+    /// # use std::sync::Arc;
+    /// # use std::time::{Duration, Instant};
+    /// # use async_trait::async_trait;
+    /// # use serde_json::{json, Value};
+    /// struct Countdown;
+    ///
+    /// #[async_trait]
+    /// impl Tool for Countdown {
+    ///     fn name(&self) -> &str { "countdown" }
+    ///     fn description(&self) -> &str { "Counts down slowly" }
+    ///     fn input_schema(&self) -> Value { json!({"type": "object"}) }
+    ///     async fn execute(&self, input: Value) -> Result<String, claude::Error> {
+    ///         let output = self.execute_with_cancel(input, CancellationToken::new()).await?;
+    ///         Ok(output.into_text())
+    ///     }
+    ///     async fn execute_with_cancel(
+    ///         &self,
+    ///         _input: Value,
+    ///         cancel: CancellationToken,
+    ///     ) -> Result<ToolOutput, claude::Error> {
+    ///         tokio::select! {
+    ///             _ = tokio::time::sleep(Duration::from_secs(60)) => {
+    ///                 Ok(ToolOutput::Text("liftoff".to_string()))
+    ///             }
+    ///             _ = cancel.cancelled() => Err(claude::Error::Other("cancelled".to_string())),
+    ///         }
+    ///     }
+    /// }
+    ///
+    /// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+    /// let mut registry = ToolRegistry::new();
+    /// registry.register(Arc::new(Countdown))?;
+    ///
+    /// let cancel = CancellationToken::new();
+    /// let trigger = cancel.clone();
+    /// tokio::spawn(async move {
+    ///     tokio::time::sleep(Duration::from_millis(100)).await;
+    ///     trigger.cancel();
+    /// });
+    ///
+    /// let started = Instant::now();
+    /// let result = registry
+    ///     .execute_tool("countdown", json!({}), "tool_1".to_string(), Some(cancel))
+    ///     .await?;
+    ///
+    /// assert!(started.elapsed() < Duration::from_secs(5));
+    /// match result {
+    ///     ContentBlock::ToolResult { content, is_error, .. } => {
+    ///         assert_eq!(is_error, Some(true));
+    ///         assert!(claude::ToolResultContent::text_of(&content).contains("cancelled"));
+    ///     }
+    ///     _ => panic!("Expected tool result"),
+    /// }
+    /// # Ok::<(), claude::Error>(())
+    /// # }).unwrap();
+    /// ```
+    async fn execute_with_cancel(
+        &self,
+        input: Value,
+        _cancel: CancellationToken,
+    ) -> Result<ToolOutput> {
+        self.execute_typed(input).await
+    }
+
     /// Convert this tool to a ToolDef for use with the Claude API
     fn to_tool_def(&self) -> ToolDef {
         ToolDef {
             name: self.name().to_string(),
             description: self.description().to_string(),
             input_schema: self.input_schema(),
+            cache_control: None,
         }
     }
 }
 
+/// A tool advertised by its [`ToolDef`] and run by a closure
+///
+/// See [`ToolRegistry::register_external`].
+struct ExternalTool {
+    def: ToolDef,
+    handler: Box<dyn Fn(Value) -> BoxFuture<'static, Result<String>> + Send + Sync>,
+}
+
+#[async_trait]
+impl Tool for ExternalTool {
+    fn name(&self) -> &str {
+        &self.def.name
+    }
+
+    fn description(&self) -> &str {
+        &self.def.description
+    }
+
+    fn input_schema(&self) -> Value {
+        self.def.input_schema.clone()
+    }
+
+    async fn execute(&self, input: Value) -> Result<String> {
+        (self.handler)(input).await
+    }
+
+    fn to_tool_def(&self) -> ToolDef {
+        self.def.clone()
+    }
+}
+
 /// Registry for managing available tools
 ///
 /// The `ToolRegistry` maintains a collection of tools that Claude can use,
@@ -109,7 +505,7 @@ pub trait Tool: Send + Sync {
 /// # impl Tool for MyTool {
 /// #     fn name(&self) -> &str { "my_tool" }
 /// #     fn description(&self) -> &str { "A custom tool" }
-/// #     fn input_schema(&self) -> Value { serde_json::json!({}) }
+/// #     fn input_schema(&self) -> Value { serde_json::json!({"type": "object"}) }
 /// #     async fn execute(&self, input: Value) -> Result<String, claude::Error> { Ok("Done".to_string()) }
 /// # }
 ///
@@ -132,6 +528,17 @@ pub struct ToolRegistry {
     tools: HashMap<String, Arc<dyn Tool>>,
     executions: Vec<ToolExecution>,
     permission_handler: Box<dyn ToolPermissionHandler>,
+    validate_inputs: bool,
+    deduplicate_calls: bool,
+    max_tool_result_bytes: Option<usize>,
+    keep_full_results: bool,
+    examples_in_descriptions: bool,
+    default_timeout: Option<Duration>,
+    tool_timeouts: HashMap<String, Duration>,
+    turn_index: Option<usize>,
+    turn_user_text: Option<String>,
+    /// Length of the execution history when the current turn began
+    turn_start: usize,
 }
 
 impl ToolRegistry {
@@ -141,9 +548,58 @@ impl ToolRegistry {
             tools: HashMap::new(),
             executions: Vec::new(),
             permission_handler: Box::new(AlwaysAllowPermissions),
+            validate_inputs: true,
+            deduplicate_calls: false,
+            max_tool_result_bytes: None,
+            keep_full_results: true,
+            examples_in_descriptions: false,
+            default_timeout: None,
+            tool_timeouts: HashMap::new(),
+            turn_index: None,
+            turn_user_text: None,
+            turn_start: 0,
         }
     }
 
+    /// Create a registry holding the standard tool suite
+    ///
+    /// See [`register_default_tools`](Self::register_default_tools).
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use claude::ToolRegistry;
+    ///
+    /// let registry = ToolRegistry::with_default_tools()?;
+    /// for name in [
+    ///     "patch_file",
+    ///     "read_file",
+    ///     "write_file",
+    ///     "git",
+    ///     "bash",
+    ///     "system_info",
+    ///     "calculator",
+    ///     "weather",
+    ///     "http_fetch",
+    ///     "enhanced_memory",
+    ///     "think",
+    ///     "wikipedia",
+    ///     "todo",
+    /// ] {
+    ///     assert!(registry.has_tool(name), "missing {}", name);
+    /// }
+    /// assert_eq!(registry.has_tool("list_directory"), cfg!(feature = "search"));
+    /// assert_eq!(registry.has_tool("search_files"), cfg!(feature = "search"));
+    /// assert_eq!(registry.has_tool("z3_solver"), cfg!(feature = "z3"));
+    /// assert_eq!(registry.has_tool("firecrawl_search"), cfg!(feature = "firecrawl"));
+    /// # Ok::<(), claude::Error>(())
+    /// ```
+    pub fn with_default_tools() -> Result<Self> {
+        let mut registry = Self::new();
+        registry.register_default_tools()?;
+        Ok(registry)
+    }
+
     /// Create a new tool registry with a custom permission handler
     ///
     /// # Example
@@ -157,9 +613,8 @@ impl ToolRegistry {
     /// ```
     pub fn with_permission_handler(handler: Box<dyn ToolPermissionHandler>) -> Self {
         Self {
-            tools: HashMap::new(),
-            executions: Vec::new(),
             permission_handler: handler,
+            ..Self::new()
         }
     }
 
@@ -182,11 +637,426 @@ impl ToolRegistry {
         self.permission_handler = handler;
     }
 
+    /// Mark the start of a conversation turn prompted by `user_text`
+    ///
+    /// Permission handlers then see the turn's index, the user's message, and
+    /// how many tools already ran during the turn in each
+    /// [`ToolExecutionRequest`]. The `run_conversation_turn*` methods of
+    /// [`Claude`](crate::Claude) call this for you.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use claude::tools::CalculatorTool;
+    /// use claude::{AsyncInteractivePermissions, ToolExecutionRequest, ToolRegistry};
+    /// use serde_json::json;
+    /// use std::sync::{Arc, Mutex};
+    ///
+    /// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+    /// let seen = Arc::new(Mutex::new(Vec::<ToolExecutionRequest>::new()));
+    /// let log = seen.clone();
+    /// let mut registry = ToolRegistry::with_permission_handler(Box::new(
+    ///     AsyncInteractivePermissions::new(move |request: ToolExecutionRequest| {
+    ///         log.lock().unwrap().push(request);
+    ///         async { true }
+    ///     }),
+    /// ));
+    /// registry.register(Arc::new(CalculatorTool))?;
+    /// let input = json!({"expression": "1 + 1"});
+    ///
+    /// registry.begin_turn("What is 1 + 1, twice?");
+    /// registry.execute_tool("calculator", input.clone(), "toolu_1".to_string(), None).await?;
+    /// registry.execute_tool("calculator", input.clone(), "toolu_2".to_string(), None).await?;
+    /// registry.begin_turn("And once more?");
+    /// registry.execute_tool("calculator", input.clone(), "toolu_3".to_string(), None).await?;
+    ///
+    /// let seen = seen.lock().unwrap();
+    /// let context: Vec<_> = seen
+    ///     .iter()
+    ///     .map(|r| (r.turn_index, r.recent_user_text.as_deref(), r.prior_executions_this_turn))
+    ///     .collect();
+    /// assert_eq!(
+    ///     context,
+    ///     [
+    ///         (Some(0), Some("What is 1 + 1, twice?"), 0),
+    ///         (Some(0), Some("What is 1 + 1, twice?"), 1),
+    ///         (Some(1), Some("And once more?"), 0),
+    ///     ]
+    /// );
+    /// # Ok::<(), claude::Error>(())
+    /// # }).unwrap();
+    /// ```
+    pub fn begin_turn(&mut self, user_text: impl Into<String>) {
+        self.turn_index = Some(self.turn_index.map_or(0, |index| index + 1));
+        self.turn_user_text = Some(user_text.into());
+        self.turn_start = self.executions.len();
+    }
+
+    /// Number of tool executions recorded since the current turn began
+    fn executions_this_turn(&self) -> usize {
+        self.executions.len().saturating_sub(self.turn_start)
+    }
+
+    /// Enable or disable input validation against each tool's schema
+    ///
+    /// When enabled (the default), [`execute_tool`](Self::execute_tool) checks the
+    /// input against [`Tool::input_schema`] before asking for permission. Invalid
+    /// input is not executed; instead an error result listing the schema
+    /// violations is returned so that Claude can correct its call.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use claude::{ContentBlock, ToolRegistry, Tool};
+    /// # use std::sync::Arc;
+    /// # use async_trait::async_trait;
+    /// # use serde_json::{json, Value};
+    /// struct Echo;
+    ///
+    /// #[async_trait]
+    /// impl Tool for Echo {
+    ///     fn name(&self) -> &str { "echo" }
+    ///     fn description(&self) -> &str { "Echoes a message" }
+    ///     fn input_schema(&self) -> Value {
+    ///         json!({
+    ///             "type": "object",
+    ///             "properties": {"message": {"type": "string"}},
+    ///             "required": ["message"]
+    ///         })
+    ///     }
+    ///     async fn execute(&self, input: Value) -> Result<String, claude::Error> {
+    ///         Ok(input["message"].as_str().unwrap().to_string())
+    ///     }
+    /// }
+    ///
+    /// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+    /// let mut registry = ToolRegistry::new();
+    /// registry.register(Arc::new(Echo))?;
+    ///
+    /// let result = registry
+    ///     .execute_tool("echo", json!({"message": 42}), "tool_1".to_string(), None)
+    ///     .await?;
+    /// match result {
+    ///     ContentBlock::ToolResult { content, is_error, .. } => {
+    ///         assert_eq!(is_error, Some(true));
+    ///         assert!(claude::ToolResultContent::text_of(&content).contains("$.message: expected string, got number"));
+    ///     }
+    ///     _ => panic!("Expected tool result"),
+    /// }
+    ///
+    /// // With validation disabled the tool receives the input as-is
+    /// registry.set_input_validation(false);
+    /// assert!(!registry.input_validation());
+    /// # Ok::<(), claude::Error>(())
+    /// # }).unwrap();
+    /// ```
+    pub fn set_input_validation(&mut self, enabled: bool) {
+        self.validate_inputs = enabled;
+    }
+
+    /// Check whether inputs are validated against tool schemas before execution
+    pub fn input_validation(&self) -> bool {
+        self.validate_inputs
+    }
+
+    /// Enable or disable deduplication of identical calls in one batch
+    ///
+    /// When enabled, a call with the same tool name and input as an earlier
+    /// call in the same batch (one [`execute_tools_parallel`](Self::execute_tools_parallel)
+    /// call, or one response during a conversation turn) is not executed
+    /// again. It gets a copy of the first call's result under its own
+    /// `tool_use_id`, and a copy of the first call's record in the history.
+    /// Disabled by default.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use claude::{ToolRegistry, Tool, ContentBlock, ToolResultContent};
+    /// # use std::sync::Arc;
+    /// # use std::sync::atomic::{AtomicUsize, Ordering};
+    /// # use async_trait::async_trait;
+    /// # use serde_json::{json, Value};
+    /// struct Search(Arc<AtomicUsize>);
+    ///
+    /// #[async_trait]
+    /// impl Tool for Search {
+    ///     fn name(&self) -> &str { "search" }
+    ///     fn description(&self) -> &str { "A slow web search" }
+    ///     fn input_schema(&self) -> Value { json!({"type": "object"}) }
+    ///     async fn execute(&self, input: Value) -> Result<String, claude::Error> {
+    ///         self.0.fetch_add(1, Ordering::SeqCst);
+    ///         Ok(format!("results for {}", input["query"]))
+    ///     }
+    /// }
+    ///
+    /// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+    /// let runs = Arc::new(AtomicUsize::new(0));
+    /// let mut registry = ToolRegistry::new();
+    /// registry.register(Arc::new(Search(runs.clone())))?;
+    /// registry.set_deduplicate_calls(true);
+    ///
+    /// // Key order doesn't make two inputs different
+    /// let results = registry.execute_tools_parallel(vec![
+    ///     ("search".to_string(), json!({"query": "rust", "limit": 5}), "tool_1".to_string()),
+    ///     ("search".to_string(), json!({"limit": 5, "query": "rust"}), "tool_2".to_string()),
+    /// ], None).await?;
+    ///
+    /// assert_eq!(runs.load(Ordering::SeqCst), 1);
+    /// let ids: Vec<&str> = results.iter().map(|r| match r {
+    ///     ContentBlock::ToolResult { tool_use_id, content, .. } => {
+    ///         assert_eq!(ToolResultContent::text_of(content), "results for \"rust\"");
+    ///         tool_use_id.as_str()
+    ///     }
+    ///     _ => panic!("Expected tool result"),
+    /// }).collect();
+    /// assert_eq!(ids, vec!["tool_1", "tool_2"]);
+    /// assert_eq!(registry.execution_history().len(), 2);
+    ///
+    /// // Without deduplication both calls run
+    /// registry.set_deduplicate_calls(false);
+    /// registry.execute_tools_parallel(vec![
+    ///     ("search".to_string(), json!({"query": "rust"}), "tool_3".to_string()),
+    ///     ("search".to_string(), json!({"query": "rust"}), "tool_4".to_string()),
+    /// ], None).await?;
+    /// assert_eq!(runs.load(Ordering::SeqCst), 3);
+    /// # Ok::<(), claude::Error>(())
+    /// # }).unwrap();
+    /// ```
+    pub fn set_deduplicate_calls(&mut self, enabled: bool) {
+        self.deduplicate_calls = enabled;
+    }
+
+    /// Check whether identical calls in one batch are executed only once
+    pub fn deduplicate_calls(&self) -> bool {
+        self.deduplicate_calls
+    }
+
+    /// Cap the size of tool results sent back to Claude
+    ///
+    /// Successful output longer than `max_bytes` is cut down with
+    /// [`ToolOutput::truncate`] before it goes into the `tool_result` block,
+    /// so one huge page or listing doesn't use up the context window. The
+    /// execution history keeps the full output unless
+    /// [`set_keep_full_results`](Self::set_keep_full_results) turns that off.
+    /// `None` (the default) sends results whole.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use claude::{ContentBlock, ExecutionState, ToolRegistry, Tool, ToolResultContent};
+    /// # use std::sync::Arc;
+    /// # use async_trait::async_trait;
+    /// # use serde_json::{json, Value};
+    /// struct Fetch;
+    ///
+    /// #[async_trait]
+    /// impl Tool for Fetch {
+    ///     fn name(&self) -> &str { "fetch" }
+    ///     fn description(&self) -> &str { "Downloads a very large page" }
+    ///     fn input_schema(&self) -> Value { json!({"type": "object"}) }
+    ///     async fn execute(&self, _input: Value) -> Result<String, claude::Error> {
+    ///         Ok("<p>".repeat(100_000))
+    ///     }
+    /// }
+    ///
+    /// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+    /// let mut registry = ToolRegistry::new();
+    /// registry.register(Arc::new(Fetch))?;
+    /// registry.set_max_tool_result_bytes(Some(1000));
+    ///
+    /// let result = registry
+    ///     .execute_tool("fetch", json!({}), "tool_1".to_string(), None)
+    ///     .await?;
+    /// match result {
+    ///     ContentBlock::ToolResult { content, .. } => {
+    ///         let text = ToolResultContent::text_of(&content);
+    ///         assert!(text.len() < 1100);
+    ///         assert!(text.ends_with("[truncated 299000 bytes]"));
+    ///     }
+    ///     _ => panic!("Expected tool result"),
+    /// }
+    ///
+    /// // The history still has all of it
+    /// match &registry.execution_history()[0].state {
+    ///     ExecutionState::Completed { result } => assert_eq!(result.len(), 300_000),
+    ///     other => panic!("Expected a completed execution, got {:?}", other),
+    /// }
+    /// # Ok::<(), claude::Error>(())
+    /// # }).unwrap();
+    /// ```
+    pub fn set_max_tool_result_bytes(&mut self, max_bytes: Option<usize>) {
+        self.max_tool_result_bytes = max_bytes;
+    }
+
+    /// Get the cap on tool result size, if any
+    pub fn max_tool_result_bytes(&self) -> Option<usize> {
+        self.max_tool_result_bytes
+    }
+
+    /// Choose whether the execution history keeps untruncated output
+    ///
+    /// Only matters when [`set_max_tool_result_bytes`](Self::set_max_tool_result_bytes)
+    /// is set. When disabled, the history records exactly what Claude was
+    /// sent. Enabled by default.
+    pub fn set_keep_full_results(&mut self, enabled: bool) {
+        self.keep_full_results = enabled;
+    }
+
+    /// Check whether the execution history keeps untruncated output
+    pub fn keep_full_results(&self) -> bool {
+        self.keep_full_results
+    }
+
+    /// Record a duplicate of an already executed call and return its result
+    ///
+    /// Copies the result and history record of the call that produced `first`,
+    /// relabelled with `tool_use_id`.
+    pub(crate) fn reuse_result(
+        &mut self,
+        first: &ContentBlock,
+        tool_use_id: String,
+    ) -> ContentBlock {
+        let mut result = first.clone();
+        if let ContentBlock::ToolResult {
+            tool_use_id: first_id,
+            ..
+        } = &mut result
+        {
+            if let Some(execution) = self.executions.iter().rev().find(|e| e.id == *first_id) {
+                let mut execution = execution.clone();
+                execution.id = tool_use_id.clone();
+                self.executions.push(execution);
+            }
+            *first_id = tool_use_id;
+        }
+        result
+    }
+
+    /// Set the time limit applied to every tool execution
+    ///
+    /// A tool that runs longer than this is abandoned, its execution is recorded as
+    /// failed, and an error result is returned to Claude. `None` (the default)
+    /// means tools may run indefinitely. Per-tool limits set with
+    /// [`set_tool_timeout`](Self::set_tool_timeout) take precedence.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use claude::{ContentBlock, ExecutionState, ToolRegistry, Tool};
+    /// # use std::sync::Arc;
+    /// # use std::time::Duration;
+    /// # use async_trait::async_trait;
+    /// # use serde_json::{json, Value};
+    /// struct Slow;
+    ///
+    /// #[async_trait]
+    /// impl Tool for Slow {
+    ///     fn name(&self) -> &str { "slow" }
+    ///     fn description(&self) -> &str { "Takes far too long" }
+    ///     fn input_schema(&self) -> Value { json!({"type": "object"}) }
+    ///     async fn execute(&self, _input: Value) -> Result<String, claude::Error> {
+    ///         tokio::time::sleep(Duration::from_secs(60)).await;
+    ///         Ok("finally".to_string())
+    ///     }
+    /// }
+    ///
+    /// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+    /// let mut registry = ToolRegistry::new();
+    /// registry.register(Arc::new(Slow))?;
+    /// registry.set_default_timeout(Some(Duration::from_millis(100)));
+    ///
+    /// let result = registry
+    ///     .execute_tool("slow", json!({}), "tool_1".to_string(), None)
+    ///     .await?;
+    /// match result {
+    ///     ContentBlock::ToolResult { content, is_error, .. } => {
+    ///         assert_eq!(is_error, Some(true));
+    ///         assert!(claude::ToolResultContent::text_of(&content).contains("timed out after 100ms"));
+    ///     }
+    ///     _ => panic!("Expected tool result"),
+    /// }
+    /// assert!(matches!(
+    ///     registry.execution_history()[0].state,
+    ///     ExecutionState::Failed { .. }
+    /// ));
+    /// # Ok::<(), claude::Error>(())
+    /// # }).unwrap();
+    /// ```
+    pub fn set_default_timeout(&mut self, timeout: Option<Duration>) {
+        self.default_timeout = timeout;
+    }
+
+    /// Override the time limit for a single tool
+    ///
+    /// Passing `None` removes the override so the default timeout applies again.
+    pub fn set_tool_timeout(&mut self, tool_name: &str, timeout: Option<Duration>) {
+        match timeout {
+            Some(limit) => {
+                self.tool_timeouts.insert(tool_name.to_string(), limit);
+            }
+            None => {
+                self.tool_timeouts.remove(tool_name);
+            }
+        }
+    }
+
+    /// Get the time limit that applies to a tool, if any
+    pub fn timeout_for(&self, tool_name: &str) -> Option<Duration> {
+        self.tool_timeouts
+            .get(tool_name)
+            .copied()
+            .or(self.default_timeout)
+    }
+
+    /// Register the standard tool suite, the one the `generalist` CLI uses
+    ///
+    /// Tools behind a disabled Cargo feature are left out. The think tool gets
+    /// its own [`ThinkingBudget`](crate::tools::ThinkingBudget); use
+    /// [`replace`](Self::replace) to swap in one sharing a budget with
+    /// [`TurnOptions`](crate::TurnOptions).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if one of the tools is already registered, or if the
+    /// memory tool can't load its storage.
+    pub fn register_default_tools(&mut self) -> Result<()> {
+        use crate::tools::*;
+
+        self.register(Arc::new(PatchFileTool::new()))?;
+        self.register(Arc::new(ReadFileTool))?;
+        self.register(Arc::new(WriteFileTool::new()))?;
+        #[cfg(feature = "search")]
+        self.register(Arc::new(ListDirectoryTool))?;
+        #[cfg(feature = "search")]
+        self.register(Arc::new(SearchFilesTool))?;
+        self.register(Arc::new(GitTool::new()))?;
+        self.register(Arc::new(BashTool::new()))?;
+        self.register(Arc::new(SystemInfoTool))?;
+        self.register(Arc::new(CalculatorTool))?;
+        self.register(Arc::new(WeatherTool::new()))?;
+        self.register(Arc::new(HttpFetchTool::new()))?;
+        self.register(Arc::new(EnhancedMemoryTool::new()?))?;
+        self.register(Arc::new(ThinkTool::new()))?;
+        self.register(Arc::new(WikipediaTool))?;
+        #[cfg(feature = "z3")]
+        self.register(Arc::new(Z3SolverTool))?;
+        self.register(Arc::new(TodoTool::new()))?;
+        #[cfg(feature = "firecrawl")]
+        {
+            self.register(Arc::new(FirecrawlCrawlTool))?;
+            self.register(Arc::new(FirecrawlSearchTool))?;
+            self.register(Arc::new(FirecrawlMapTool))?;
+            self.register(Arc::new(FirecrawlExtractTool))?;
+        }
+        Ok(())
+    }
+
     /// Register a new tool in the registry
     ///
     /// # Errors
     ///
-    /// Returns an error if a tool with the same name is already registered
+    /// Returns an error if a tool with the same name is already registered, or
+    /// if the tool's input schema fails [`check_schema`](crate::schema::check_schema)
     ///
     /// # Example
     ///
@@ -194,17 +1064,36 @@ impl ToolRegistry {
     /// # use claude::{ToolRegistry, Tool};
     /// # use std::sync::Arc;
     /// # use async_trait::async_trait;
-    /// # use serde_json::Value;
-    /// # struct MyTool;
+    /// # use serde_json::{json, Value};
+    /// # struct MyTool(Value);
     /// # #[async_trait]
     /// # impl Tool for MyTool {
     /// #     fn name(&self) -> &str { "my_tool" }
     /// #     fn description(&self) -> &str { "A custom tool" }
-    /// #     fn input_schema(&self) -> Value { serde_json::json!({}) }
+    /// #     fn input_schema(&self) -> Value { self.0.clone() }
     /// #     async fn execute(&self, input: Value) -> Result<String, claude::Error> { Ok("Done".to_string()) }
     /// # }
     /// let mut registry = ToolRegistry::new();
-    /// registry.register(Arc::new(MyTool))?;
+    /// registry.register(Arc::new(MyTool(json!({
+    ///     "type": "object",
+    ///     "properties": {"path": {"type": "string"}},
+    ///     "required": ["path"]
+    /// }))))?;
+    ///
+    /// // A schema whose `required` names an undeclared property is rejected
+    /// let mut registry = ToolRegistry::new();
+    /// let err = registry
+    ///     .register(Arc::new(MyTool(json!({
+    ///         "type": "object",
+    ///         "properties": {"path": {"type": "string"}},
+    ///         "required": ["file"]
+    ///     }))))
+    ///     .unwrap_err();
+    /// assert!(err.to_string().contains("required property 'file' is not in properties"));
+    ///
+    /// // So is a schema without a type
+    /// assert!(registry.register(Arc::new(MyTool(json!({})))).is_err());
+    /// assert!(registry.tool_names().is_empty());
     /// # Ok::<(), claude::Error>(())
     /// ```
     pub fn register(&mut self, tool: Arc<dyn Tool>) -> Result<()> {
@@ -212,15 +1101,285 @@ impl ToolRegistry {
         if self.tools.contains_key(&name) {
             return Err(Error::Other(format!("Tool '{}' already registered", name)));
         }
+        check_tool_schema(tool.as_ref())?;
         self.tools.insert(name, tool);
         Ok(())
     }
 
+    /// Register a tool described by `def` whose calls are handled by `handler`
+    ///
+    /// For tools implemented outside this process, or anywhere implementing
+    /// [`Tool`] would be overkill. The tool goes through the same permission
+    /// checks, input validation and history as any other, and `def` is sent to
+    /// Claude as is, `cache_control` included.
+    ///
+    /// # Errors
+    ///
+    /// Fails like [`register`](Self::register) for a duplicate name or an
+    /// invalid input schema.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use claude::{ContentBlock, ToolDef, ToolRegistry, ToolResultContent};
+    /// use serde_json::json;
+    /// use std::collections::HashMap;
+    /// use std::sync::Arc;
+    ///
+    /// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+    /// let orders = Arc::new(HashMap::from([("A1", "shipped"), ("B2", "pending")]));
+    /// let def = ToolDef::builder()
+    ///     .name("order_status")
+    ///     .description("Look up the status of an order")
+    ///     .required_property("id", json!({"type": "string"}))
+    ///     .build();
+    ///
+    /// let mut registry = ToolRegistry::new();
+    /// registry.register_external(def, move |input| {
+    ///     let orders = orders.clone();
+    ///     async move {
+    ///         let id = input["id"].as_str().unwrap_or_default();
+    ///         orders
+    ///             .get(id)
+    ///             .map(|status| status.to_string())
+    ///             .ok_or_else(|| claude::Error::Other(format!("No order {}", id)))
+    ///     }
+    /// })?;
+    /// assert_eq!(registry.get_tool_defs()[0].name, "order_status");
+    ///
+    /// let result = registry
+    ///     .execute_tool("order_status", json!({"id": "A1"}), "tool_1".to_string(), None)
+    ///     .await?;
+    /// let ContentBlock::ToolResult { content, is_error, .. } = result else { unreachable!() };
+    /// assert_eq!(ToolResultContent::text_of(&content), "shipped");
+    /// assert_eq!(is_error, None);
+    ///
+    /// // Errors from the closure become error results, like any tool's
+    /// let result = registry
+    ///     .execute_tool("order_status", json!({"id": "Z9"}), "tool_2".to_string(), None)
+    ///     .await?;
+    /// let ContentBlock::ToolResult { content, is_error, .. } = result else { unreachable!() };
+    /// assert!(ToolResultContent::text_of(&content).contains("No order Z9"));
+    /// assert_eq!(is_error, Some(true));
+    /// assert_eq!(registry.execution_stats()["total"], 2);
+    ///
+    /// // Names are still unique
+    /// let again = ToolDef::new("order_status", "Duplicate", json!({"type": "object"}));
+    /// assert!(registry.register_external(again, |_| async { Ok(String::new()) }).is_err());
+    /// # Ok::<(), claude::Error>(())
+    /// # }).unwrap();
+    /// ```
+    pub fn register_external<F, Fut>(&mut self, def: ToolDef, handler: F) -> Result<()>
+    where
+        F: Fn(Value) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<String>> + Send + 'static,
+    {
+        self.register(Arc::new(ExternalTool {
+            def,
+            handler: Box::new(move |input| Box::pin(handler(input))),
+        }))
+    }
+
+    /// Remove a tool from the registry
+    ///
+    /// Returns the removed tool, or `None` if no tool with that name was registered.
+    /// Execution history for the tool is kept.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use claude::{ToolRegistry, Tool};
+    /// # use std::sync::Arc;
+    /// # use async_trait::async_trait;
+    /// # use serde_json::Value;
+    /// # struct MyTool;
+    /// # #[async_trait]
+    /// # impl Tool for MyTool {
+    /// #     fn name(&self) -> &str { "my_tool" }
+    /// #     fn description(&self) -> &str { "A custom tool" }
+    /// #     fn input_schema(&self) -> Value { serde_json::json!({"type": "object"}) }
+    /// #     async fn execute(&self, input: Value) -> Result<String, claude::Error> { Ok("Done".to_string()) }
+    /// # }
+    /// let mut registry = ToolRegistry::new();
+    /// registry.register(Arc::new(MyTool))?;
+    ///
+    /// assert!(registry.unregister("my_tool").is_some());
+    /// assert!(!registry.has_tool("my_tool"));
+    /// assert!(registry.get_tool_defs().is_empty());
+    /// assert!(registry.tool_names().is_empty());
+    ///
+    /// assert!(registry.unregister("my_tool").is_none());
+    /// # Ok::<(), claude::Error>(())
+    /// ```
+    pub fn unregister(&mut self, name: &str) -> Option<Arc<dyn Tool>> {
+        self.tools.remove(name)
+    }
+
+    /// Register a tool, overwriting any tool already registered under its name
+    ///
+    /// Returns the previously registered tool, if any.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error, leaving the registry unchanged, if the tool's input
+    /// schema fails [`check_schema`](crate::schema::check_schema)
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use claude::{ToolRegistry, Tool};
+    /// # use std::sync::Arc;
+    /// # use async_trait::async_trait;
+    /// # use serde_json::{json, Value};
+    /// struct Weather(&'static str, Value);
+    ///
+    /// #[async_trait]
+    /// impl Tool for Weather {
+    ///     fn name(&self) -> &str { "weather" }
+    ///     fn description(&self) -> &str { self.0 }
+    ///     fn input_schema(&self) -> Value { self.1.clone() }
+    ///     async fn execute(&self, _input: Value) -> Result<String, claude::Error> {
+    ///         Ok("Sunny".to_string())
+    ///     }
+    /// }
+    ///
+    /// let schema = json!({"type": "object"});
+    /// let mut registry = ToolRegistry::new();
+    /// assert!(registry.replace(Arc::new(Weather("Real weather", schema.clone())))?.is_none());
+    ///
+    /// let previous = registry.replace(Arc::new(Weather("Mock weather", schema)))?.unwrap();
+    /// assert_eq!(previous.description(), "Real weather");
+    /// assert!(registry.has_tool("weather"));
+    /// assert_eq!(registry.tool_names(), vec!["weather".to_string()]);
+    /// assert_eq!(registry.get_tool_defs()[0].description, "Mock weather");
+    ///
+    /// // A replacement with a bad schema is rejected and the old tool kept
+    /// let Err(err) = registry.replace(Arc::new(Weather("Broken weather", json!({"required": ["city"]})))) else {
+    ///     panic!("expected the schema to be rejected")
+    /// };
+    /// assert!(err.to_string().contains("invalid input schema"), "{}", err);
+    /// assert_eq!(registry.get_tool_defs()[0].description, "Mock weather");
+    /// # Ok::<(), claude::Error>(())
+    /// ```
+    pub fn replace(&mut self, tool: Arc<dyn Tool>) -> Result<Option<Arc<dyn Tool>>> {
+        check_tool_schema(tool.as_ref())?;
+        Ok(self.tools.insert(tool.name().to_string(), tool))
+    }
+
     /// Get tool definitions for all registered tools
     ///
     /// Returns a vector of ToolDef structs that can be sent to the Claude API
     pub fn get_tool_defs(&self) -> Vec<ToolDef> {
-        self.tools.values().map(|tool| tool.to_tool_def()).collect()
+        self.tools
+            .values()
+            .map(|tool| {
+                let mut def = tool.to_tool_def();
+                let examples = tool.examples();
+                if self.examples_in_descriptions && !examples.is_empty() {
+                    def.description.push_str("\n\nExample inputs:");
+                    for example in examples {
+                        def.description.push_str(&format!("\n{}", example));
+                    }
+                }
+                def
+            })
+            .collect()
+    }
+
+    /// Append each tool's [`examples`](Tool::examples) to its description
+    ///
+    /// When enabled, [`get_tool_defs`](Self::get_tool_defs) lists the example
+    /// inputs, one JSON object per line, at the end of the descriptions sent
+    /// to the API. Disabled by default; use
+    /// [`tool_usage_guide`](Self::tool_usage_guide) to put them in the system
+    /// prompt instead.
+    pub fn set_examples_in_descriptions(&mut self, enabled: bool) {
+        self.examples_in_descriptions = enabled;
+    }
+
+    /// Check whether tool descriptions include example inputs
+    pub fn examples_in_descriptions(&self) -> bool {
+        self.examples_in_descriptions
+    }
+
+    /// Describe the example inputs of every tool that has some, as Markdown
+    ///
+    /// Tools are listed by name, each with its examples as JSON, ready to be
+    /// appended to a system prompt. Returns an empty string if no registered
+    /// tool has examples.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use claude::{Tool, ToolRegistry};
+    /// # use std::sync::Arc;
+    /// # use async_trait::async_trait;
+    /// # use serde_json::{json, Value};
+    /// struct Solver;
+    ///
+    /// #[async_trait]
+    /// impl Tool for Solver {
+    ///     fn name(&self) -> &str { "solver" }
+    ///     fn description(&self) -> &str { "Solves SMT-LIB constraints" }
+    ///     fn input_schema(&self) -> Value { json!({"type": "object"}) }
+    ///     fn examples(&self) -> Vec<Value> {
+    ///         vec![json!({"constraints": ["(> x 0)"], "variables": {"x": "Int"}})]
+    ///     }
+    ///     async fn execute(&self, _input: Value) -> Result<String, claude::Error> {
+    ///         Ok("sat".to_string())
+    ///     }
+    /// }
+    ///
+    /// struct Clock;
+    ///
+    /// #[async_trait]
+    /// impl Tool for Clock {
+    ///     fn name(&self) -> &str { "clock" }
+    ///     fn description(&self) -> &str { "Tells the time" }
+    ///     fn input_schema(&self) -> Value { json!({"type": "object"}) }
+    ///     async fn execute(&self, _input: Value) -> Result<String, claude::Error> {
+    ///         Ok("noon".to_string())
+    ///     }
+    /// }
+    ///
+    /// let mut registry = ToolRegistry::new();
+    /// registry.register(Arc::new(Clock))?;
+    /// assert_eq!(registry.tool_usage_guide(), "");
+    ///
+    /// registry.register(Arc::new(Solver))?;
+    /// let guide = registry.tool_usage_guide();
+    /// assert!(guide.contains("## solver"));
+    /// assert!(guide.contains(r#"{"constraints":["(> x 0)"],"variables":{"x":"Int"}}"#));
+    /// assert!(!guide.contains("clock"));
+    ///
+    /// // The examples can go into the tool descriptions instead
+    /// registry.set_examples_in_descriptions(true);
+    /// let defs = registry.get_tool_defs();
+    /// let solver = defs.iter().find(|d| d.name == "solver").unwrap();
+    /// assert!(solver.description.ends_with("Example inputs:\n{\"constraints\":[\"(> x 0)\"],\"variables\":{\"x\":\"Int\"}}"));
+    /// let clock = defs.iter().find(|d| d.name == "clock").unwrap();
+    /// assert_eq!(clock.description, "Tells the time");
+    /// # Ok::<(), claude::Error>(())
+    /// ```
+    pub fn tool_usage_guide(&self) -> String {
+        let mut names = self.tool_names();
+        names.sort();
+
+        let mut guide = String::new();
+        for name in names {
+            let examples = self.tools[&name].examples();
+            if examples.is_empty() {
+                continue;
+            }
+            if guide.is_empty() {
+                guide.push_str("# Tool usage examples\n");
+            }
+            guide.push_str(&format!("\n## {}\n\n", name));
+            for example in examples {
+                guide.push_str(&format!("```json\n{}\n```\n", example));
+            }
+        }
+        guide
     }
 
     /// Check if a tool with the given name is registered
@@ -245,6 +1404,8 @@ impl ToolRegistry {
     /// * `tool_name` - Name of the tool to execute
     /// * `input` - Input parameters for the tool
     /// * `tool_use_id` - Unique identifier for this tool execution
+    /// * `cancel` - Optional token passed to [`Tool::execute_with_cancel`] so the
+    ///   tool can stop early
     ///
     /// # Returns
     ///
@@ -254,7 +1415,175 @@ impl ToolRegistry {
         tool_name: &str,
         input: Value,
         tool_use_id: String,
+        cancel: Option<CancellationToken>,
     ) -> Result<ContentBlock> {
+        let prior = self.executions_this_turn();
+        let (result, execution) = self
+            .run_tool(tool_name, input, tool_use_id, cancel, prior)
+            .await?;
+        self.executions.push(execution);
+        Ok(result)
+    }
+
+    /// Execute several tools concurrently with permission checking
+    ///
+    /// Each call is a `(tool_name, input, tool_use_id)` tuple, as returned by
+    /// [`Message::get_tool_uses`](crate::Message::get_tool_uses). Permission checks
+    /// and executions run concurrently, and the returned `ToolResult` blocks are in
+    /// the same order as `calls`. Execution records are added to the history once
+    /// all calls have finished. `cancel` is passed to every call, as in
+    /// [`execute_tool`](Self::execute_tool).
+    ///
+    /// # Errors
+    ///
+    /// Returns the first error encountered (e.g. an unknown tool name). Records for
+    /// the calls that did run are still added to the history.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use claude::{CancellationToken, ToolOutput, ToolRegistry, Tool};
+    /// # use std::sync::Arc;
+    /// # use std::time::{Duration, Instant};
+    /// # use async_trait::async_trait;
+    /// # use serde_json::{json, Value};
+    /// struct Sleepy(&'static str);
+    ///
+    /// #[async_trait]
+    /// impl Tool for Sleepy {
+    ///     fn name(&self) -> &str { self.0 }
+    ///     fn description(&self) -> &str { "Sleeps for a while" }
+    ///     fn input_schema(&self) -> Value { json!({"type": "object"}) }
+    ///     async fn execute(&self, input: Value) -> Result<String, claude::Error> {
+    ///         let output = self.execute_with_cancel(input, CancellationToken::new()).await?;
+    ///         Ok(output.into_text())
+    ///     }
+    ///     async fn execute_with_cancel(
+    ///         &self,
+    ///         _input: Value,
+    ///         cancel: CancellationToken,
+    ///     ) -> Result<ToolOutput, claude::Error> {
+    ///         tokio::select! {
+    ///             _ = tokio::time::sleep(Duration::from_millis(200)) => {
+    ///                 Ok(ToolOutput::Text(format!("{} done", self.0)))
+    ///             }
+    ///             _ = cancel.cancelled() => Err(claude::Error::Other("cancelled".to_string())),
+    ///         }
+    ///     }
+    /// }
+    ///
+    /// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+    /// let mut registry = ToolRegistry::new();
+    /// registry.register(Arc::new(Sleepy("first")))?;
+    /// registry.register(Arc::new(Sleepy("second")))?;
+    ///
+    /// let started = Instant::now();
+    /// let results = registry.execute_tools_parallel(vec![
+    ///     ("first".to_string(), json!({}), "tool_1".to_string()),
+    ///     ("second".to_string(), json!({}), "tool_2".to_string()),
+    /// ], None).await?;
+    ///
+    /// // Both tools slept concurrently rather than back to back
+    /// assert!(started.elapsed() < Duration::from_millis(350));
+    /// assert_eq!(results.len(), 2);
+    /// assert!(matches!(&results[0],
+    ///     claude::ContentBlock::ToolResult { tool_use_id, .. } if tool_use_id == "tool_1"));
+    /// assert!(matches!(&results[1],
+    ///     claude::ContentBlock::ToolResult { tool_use_id, .. } if tool_use_id == "tool_2"));
+    /// assert_eq!(registry.execution_history().len(), 2);
+    ///
+    /// // A cancelled token reaches every call in the batch
+    /// let cancel = CancellationToken::new();
+    /// cancel.cancel();
+    /// let results = registry.execute_tools_parallel(vec![
+    ///     ("first".to_string(), json!({}), "tool_3".to_string()),
+    ///     ("second".to_string(), json!({}), "tool_4".to_string()),
+    /// ], Some(cancel)).await?;
+    /// assert!(results.iter().all(|result| matches!(result,
+    ///     claude::ContentBlock::ToolResult { is_error: Some(true), .. })));
+    /// # Ok::<(), claude::Error>(())
+    /// # }).unwrap();
+    /// ```
+    pub async fn execute_tools_parallel(
+        &mut self,
+        calls: Vec<(String, Value, String)>,
+        cancel: Option<CancellationToken>,
+    ) -> Result<Vec<ContentBlock>> {
+        // For each call, the index of the earlier identical call it repeats
+        let mut seen = HashMap::new();
+        let mut duplicates = Vec::with_capacity(calls.len());
+        let mut unique = Vec::new();
+        for (i, (tool_name, input, tool_use_id)) in calls.into_iter().enumerate() {
+            if self.deduplicate_calls {
+                if let Some(&first) = seen.get(&call_key(&tool_name, &input)) {
+                    duplicates.push(Some((first, tool_use_id)));
+                    continue;
+                }
+                seen.insert(call_key(&tool_name, &input), i);
+            }
+            duplicates.push(None);
+            unique.push((i, tool_name, input, tool_use_id));
+        }
+
+        let outcomes = {
+            let this = &*self;
+            let prior = self.executions_this_turn();
+            join_all(unique.into_iter().enumerate().map(
+                |(n, (i, tool_name, input, tool_use_id))| {
+                    let cancel = cancel.clone();
+                    async move {
+                        let outcome = this
+                            .run_tool(&tool_name, input, tool_use_id, cancel, prior + n)
+                            .await;
+                        (i, outcome)
+                    }
+                },
+            ))
+            .await
+        };
+
+        let mut results: Vec<Option<ContentBlock>> = vec![None; duplicates.len()];
+        let mut first_error = None;
+        for (i, outcome) in outcomes {
+            match outcome {
+                Ok((result, execution)) => {
+                    self.executions.push(execution);
+                    results[i] = Some(result);
+                }
+                Err(e) => {
+                    first_error.get_or_insert(e);
+                }
+            }
+        }
+        for (i, duplicate) in duplicates.into_iter().enumerate() {
+            if let Some((first, tool_use_id)) = duplicate {
+                if let Some(first_result) = results[first].clone() {
+                    results[i] = Some(self.reuse_result(&first_result, tool_use_id));
+                }
+            }
+        }
+        let results: Vec<ContentBlock> = results.into_iter().flatten().collect();
+
+        match first_error {
+            Some(e) => Err(e),
+            None => Ok(results),
+        }
+    }
+
+    /// Check permissions and run a tool, returning its result and execution record
+    ///
+    /// Takes `&self` so that several tools can run concurrently; callers are
+    /// responsible for adding the returned record to the history and for
+    /// counting the calls made before this one in the turn.
+    #[tracing::instrument(name = "tool", skip_all, fields(tool = %tool_name, tool_use_id = %tool_use_id))]
+    async fn run_tool(
+        &self,
+        tool_name: &str,
+        input: Value,
+        tool_use_id: String,
+        cancel: Option<CancellationToken>,
+        prior_executions_this_turn: usize,
+    ) -> Result<(ContentBlock, ToolExecution)> {
         // Find the tool
         let tool = self
             .tools
@@ -265,6 +1594,34 @@ impl ToolRegistry {
         // Create execution record
         let mut execution =
             ToolExecution::new(tool_use_id.clone(), tool_name.to_string(), input.clone());
+        execution.tool_version = tool.version().to_string();
+
+        // Reject input that doesn't match the tool's schema
+        if self.validate_inputs {
+            let violations = schema::validate(&tool.input_schema(), &input);
+            if !violations.is_empty() {
+                let error_msg = format!(
+                    "Invalid input for tool '{}':\n- {}",
+                    tool_name,
+                    violations.join("\n- ")
+                );
+                execution.complete(Err(error_msg.clone()));
+                tracing::warn!(
+                    violations = violations.len(),
+                    "tool input rejected by schema"
+                );
+
+                return Ok((
+                    ContentBlock::ToolResult {
+                        content: vec![error_msg.into()],
+                        tool_use_id,
+                        is_error: Some(true),
+                        cache_control: None,
+                    },
+                    execution,
+                ));
+            }
+        }
 
         // Check permissions
         let request = ToolExecutionRequest {
@@ -272,66 +1629,107 @@ impl ToolRegistry {
             tool_name: tool_name.to_string(),
             input: input.clone(),
             tool_description: tool.description().to_string(),
+            turn_index: self.turn_index,
+            recent_user_text: self.turn_user_text.clone(),
+            prior_executions_this_turn,
         };
 
         let decision = self.permission_handler.check_permission(&request).await;
+        tracing::debug!(?decision, "permission decided");
 
         match decision {
             PermissionDecision::Allow => {
-                execution.state = ExecutionState::Executing;
-                self.executions.push(execution.clone());
+                execution.start();
+
+                // Execute the tool, giving up once its time limit has passed
+                let cancel = cancel.unwrap_or_default();
+                let run = tool.execute_with_cancel(input, cancel);
+                let outcome = match self.timeout_for(tool_name) {
+                    Some(limit) => match tokio::time::timeout(limit, run).await {
+                        Ok(outcome) => outcome,
+                        Err(_) => Err(Error::Other(format!("timed out after {:?}", limit))),
+                    },
+                    None => run.await,
+                };
 
-                // Execute the tool
-                match tool.execute(input).await {
+                match outcome {
                     Ok(output) => {
-                        // Update execution record
-                        if let Some(exec) = self.executions.iter_mut().find(|e| e.id == tool_use_id)
-                        {
-                            exec.complete(Ok(output.clone()));
-                        }
-
-                        Ok(ContentBlock::ToolResult {
-                            content: output,
-                            tool_use_id,
-                            is_error: None,
-                        })
+                        let (content, full) = match self.max_tool_result_bytes {
+                            Some(max_bytes) => {
+                                let full = self.keep_full_results.then(|| {
+                                    ToolResultContent::text_of(
+                                        &output.clone().into_result_content(),
+                                    )
+                                });
+                                (output.truncate(max_bytes).into_result_content(), full)
+                            }
+                            None => (output.into_result_content(), None),
+                        };
+                        execution.complete(Ok(
+                            full.unwrap_or_else(|| ToolResultContent::text_of(&content))
+                        ));
+                        tracing::info!(duration_ms = execution.duration_ms, "tool completed");
+
+                        Ok((
+                            ContentBlock::ToolResult {
+                                content,
+                                tool_use_id,
+                                is_error: None,
+                                cache_control: None,
+                            },
+                            execution,
+                        ))
                     }
                     Err(e) => {
                         let error_msg = e.to_string();
+                        execution.complete(Err(error_msg.clone()));
+                        tracing::warn!(
+                            duration_ms = execution.duration_ms,
+                            error = %error_msg,
+                            "tool failed"
+                        );
 
-                        // Update execution record
-                        if let Some(exec) = self.executions.iter_mut().find(|e| e.id == tool_use_id)
-                        {
-                            exec.complete(Err(error_msg.clone()));
-                        }
-
-                        Ok(ContentBlock::ToolResult {
-                            content: format!("Tool execution failed: {}", error_msg),
-                            tool_use_id,
-                            is_error: Some(true),
-                        })
+                        Ok((
+                            ContentBlock::ToolResult {
+                                content: vec![
+                                    format!("Tool execution failed: {}", error_msg).into()
+                                ],
+                                tool_use_id,
+                                is_error: Some(true),
+                                cache_control: None,
+                            },
+                            execution,
+                        ))
                     }
                 }
             }
             PermissionDecision::Deny => {
                 execution.deny("Permission denied");
-                self.executions.push(execution);
+                tracing::info!("tool denied");
 
-                Ok(ContentBlock::ToolResult {
-                    content: "Tool execution denied".to_string(),
-                    tool_use_id,
-                    is_error: Some(true),
-                })
+                Ok((
+                    ContentBlock::ToolResult {
+                        content: vec!["Tool execution denied".into()],
+                        tool_use_id,
+                        is_error: Some(true),
+                        cache_control: None,
+                    },
+                    execution,
+                ))
             }
             PermissionDecision::DenyWithReason(reason) => {
                 execution.deny(&reason);
-                self.executions.push(execution);
+                tracing::info!(%reason, "tool denied");
 
-                Ok(ContentBlock::ToolResult {
-                    content: format!("Tool execution denied: {}", reason),
-                    tool_use_id,
-                    is_error: Some(true),
-                })
+                Ok((
+                    ContentBlock::ToolResult {
+                        content: vec![format!("Tool execution denied: {}", reason).into()],
+                        tool_use_id,
+                        is_error: Some(true),
+                        cache_control: None,
+                    },
+                    execution,
+                ))
             }
         }
     }
@@ -341,9 +1739,148 @@ impl ToolRegistry {
         &self.executions
     }
 
+    /// Export the execution history as a JSON array
+    ///
+    /// Each element records the tool name, input, state, timestamps, and duration
+    /// of one execution, in the order the executions were recorded.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use claude::{PolicyPermissions, Tool, ToolRegistry};
+    /// # use std::sync::Arc;
+    /// # use async_trait::async_trait;
+    /// # use serde_json::{json, Value};
+    /// struct Echo(&'static str);
+    ///
+    /// #[async_trait]
+    /// impl Tool for Echo {
+    ///     fn name(&self) -> &str { self.0 }
+    ///     fn description(&self) -> &str { "Echoes its input" }
+    ///     fn input_schema(&self) -> Value { json!({"type": "object"}) }
+    ///     async fn execute(&self, input: Value) -> Result<String, claude::Error> {
+    ///         Ok(input.to_string())
+    ///     }
+    /// }
+    ///
+    /// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+    /// let policy = PolicyPermissions::new(vec!["echo".to_string()], false);
+    /// let mut registry = ToolRegistry::with_permission_handler(Box::new(policy));
+    /// registry.register(Arc::new(Echo("echo")))?;
+    /// registry.register(Arc::new(Echo("forbidden")))?;
+    ///
+    /// registry
+    ///     .execute_tool("echo", json!({"n": 1}), "tool_1".to_string(), None)
+    ///     .await?;
+    /// registry
+    ///     .execute_tool("forbidden", json!({}), "tool_2".to_string(), None)
+    ///     .await?;
+    ///
+    /// let history = registry.execution_history_json()?;
+    /// let entries = history.as_array().unwrap();
+    /// assert_eq!(entries.len(), 2);
+    ///
+    /// assert_eq!(entries[0]["id"], "tool_1");
+    /// assert_eq!(entries[0]["tool_name"], "echo");
+    /// assert_eq!(entries[0]["tool_version"], "1");
+    /// assert_eq!(entries[0]["input"], json!({"n": 1}));
+    /// assert_eq!(entries[0]["state"]["status"], "completed");
+    /// assert_eq!(entries[0]["state"]["result"], r#"{"n":1}"#);
+    /// assert!(entries[0]["started_at"].is_string());
+    /// assert!(entries[0]["completed_at"].is_string());
+    /// assert!(entries[0]["duration_ms"].is_u64());
+    ///
+    /// assert_eq!(entries[1]["tool_name"], "forbidden");
+    /// assert_eq!(entries[1]["state"]["status"], "denied");
+    /// assert!(entries[1]["state"]["reason"].is_string());
+    /// # Ok::<(), claude::Error>(())
+    /// # }).unwrap();
+    /// ```
+    pub fn execution_history_json(&self) -> Result<Value> {
+        Ok(serde_json::to_value(&self.executions)?)
+    }
+
+    /// Write the execution history to a file as pretty-printed JSON
+    ///
+    /// The file contains the same array as
+    /// [`execution_history_json`](Self::execution_history_json) and is
+    /// overwritten if it already exists.
+    pub fn export_history_to_file(&self, path: impl AsRef<Path>) -> Result<()> {
+        let path = path.as_ref();
+        let json = serde_json::to_string_pretty(&self.executions)?;
+        std::fs::write(path, json).map_err(|e| {
+            Error::Other(format!(
+                "Failed to write execution history to {}: {}",
+                path.display(),
+                e
+            ))
+        })
+    }
+
+    /// Replace the execution history with `history`, e.g. one saved by a
+    /// previous session
+    ///
+    /// Executions run afterwards are appended to it, and
+    /// [`execution_stats`](Self::execution_stats) counts both. The loaded
+    /// executions don't count towards the current turn.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use claude::{ChatbotState, Tool, ToolRegistry};
+    /// # use std::sync::Arc;
+    /// # use async_trait::async_trait;
+    /// # use serde_json::{json, Value};
+    /// struct Echo;
+    ///
+    /// #[async_trait]
+    /// impl Tool for Echo {
+    ///     fn name(&self) -> &str { "echo" }
+    ///     fn description(&self) -> &str { "Echoes its input" }
+    ///     fn input_schema(&self) -> Value { json!({"type": "object"}) }
+    ///     async fn execute(&self, input: Value) -> Result<String, claude::Error> {
+    ///         Ok(input.to_string())
+    ///     }
+    /// }
+    ///
+    /// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+    /// let mut registry = ToolRegistry::new();
+    /// registry.register(Arc::new(Echo))?;
+    /// registry.execute_tool("echo", json!({"n": 1}), "tool_1".to_string(), None).await?;
+    /// registry.execute_tool("missing", json!({}), "tool_2".to_string(), None).await.ok();
+    /// registry.execute_tool("echo", json!({"n": 2}), "tool_3".to_string(), None).await?;
+    ///
+    /// // Save the history with the rest of the session, and end it
+    /// let mut state = ChatbotState::new("claude-sonnet-4-20250514".to_string());
+    /// state.tool_history = registry.execution_history().to_vec();
+    /// let saved = serde_json::to_string(&state)?;
+    /// let stats = registry.execution_stats();
+    /// drop(registry);
+    ///
+    /// // A new session picks up where it left off
+    /// let restored: ChatbotState = serde_json::from_str(&saved)?;
+    /// let mut registry = ToolRegistry::new();
+    /// registry.register(Arc::new(Echo))?;
+    /// registry.load_history(restored.tool_history);
+    /// assert_eq!(registry.execution_stats(), stats);
+    /// let ids: Vec<&str> = registry.execution_history().iter().map(|e| e.id.as_str()).collect();
+    /// assert_eq!(ids, ["tool_1", "tool_3"]);
+    /// assert_eq!(registry.execution_history()[1].input, json!({"n": 2}));
+    ///
+    /// registry.execute_tool("echo", json!({"n": 3}), "tool_4".to_string(), None).await?;
+    /// assert_eq!(registry.execution_stats()["total"], 3);
+    /// # Ok::<(), claude::Error>(())
+    /// # }).unwrap();
+    /// ```
+    pub fn load_history(&mut self, history: Vec<ToolExecution>) {
+        self.executions = history;
+        self.turn_start = self.executions.len();
+    }
+
     /// Clear the execution history
     pub fn clear_history(&mut self) {
         self.executions.clear();
+        self.turn_start = 0;
     }
 
     /// Get execution statistics
@@ -376,3 +1913,37 @@ impl ToolRegistry {
         stats
     }
 }
+
+/// Reject `tool` if its input schema fails [`schema::check_schema`]
+fn check_tool_schema(tool: &dyn Tool) -> Result<()> {
+    let problems = schema::check_schema(&tool.input_schema());
+    if problems.is_empty() {
+        return Ok(());
+    }
+    Err(Error::Other(format!(
+        "Tool '{}' has an invalid input schema: {}",
+        tool.name(),
+        problems.join("; ")
+    )))
+}
+
+/// Key identifying a call by tool name and input, ignoring object key order
+pub(crate) fn call_key(tool_name: &str, input: &Value) -> String {
+    fn canonical(value: &Value) -> Value {
+        match value {
+            Value::Object(map) => {
+                let mut entries: Vec<(&String, &Value)> = map.iter().collect();
+                entries.sort_by_key(|(key, _)| *key);
+                Value::Object(
+                    entries
+                        .into_iter()
+                        .map(|(key, value)| (key.clone(), canonical(value)))
+                        .collect(),
+                )
+            }
+            Value::Array(items) => Value::Array(items.iter().map(canonical).collect()),
+            other => other.clone(),
+        }
+    }
+    format!("{}\u{0}{}", tool_name, canonical(input))
+}
@@ -1,9 +1,312 @@
-use crate::{Error, Result, Tool};
+use crate::{Error, Result, Tool, ToolOutput};
 use async_trait::async_trait;
+use regex::Regex;
+use serde::Deserialize;
 use serde_json::{json, Value};
-use std::process::Command;
+use std::collections::HashMap;
+use std::process::Stdio;
+use std::time::{Duration, Instant};
+use tokio::io::{AsyncRead, AsyncReadExt};
+use tokio::process::{Child, Command};
+use tokio_util::sync::CancellationToken;
 
-pub struct BashTool;
+/// Regexes for commands [`BashTool::new`] refuses to run
+///
+/// Covers deleting the root or home directory, formatting or overwriting a
+/// disk, and the classic fork bomb.
+pub const DEFAULT_BLOCKLIST: &[&str] = &[
+    r"\brm\s+(?:-\S+\s+)*(?:/|/\*|~/?|\$HOME/?)(?:\s|[;&|]|$)",
+    r"\bmkfs(?:\.\w+)?\b",
+    r"\bdd\b.*\bof=/dev/(?:sd|hd|vd|xvd|nvme|mmcblk|disk)",
+    r">\s*/dev/(?:sd|hd|vd|xvd|nvme|mmcblk|disk)",
+    r":\(\)\s*\{\s*:\s*\|\s*:\s*&\s*\}\s*;\s*:",
+];
+
+/// Default cap on each of stdout and stderr, in bytes
+///
+/// Both streams together stay under the CLI's cap on a whole tool result.
+pub const DEFAULT_MAX_STREAM_BYTES: usize = 40_000;
+
+/// How long output is still read after the shell exits, for background
+/// processes that inherited its stdout or stderr
+const STREAM_GRACE: Duration = Duration::from_secs(1);
+
+/// Runs commands with `bash -c`
+///
+/// Each command runs in its own process group so a timeout, or cancelling the
+/// token passed to [`execute_with_cancel`](Tool::execute_with_cancel), can kill
+/// everything it started, not just the top-level shell. Background processes
+/// that still hold stdout or stderr a second after the shell exits are killed
+/// too, so that reading the output never outlasts the command.
+///
+/// The result is an object with `stdout`, `stderr`, `exit_code` (`null` if
+/// the command was killed), `duration_ms`, `timed_out`, and a one-line
+/// `summary` for display. Each stream is capped separately, keeping its
+/// start and end around a `[... N bytes truncated ...]` marker, so a noisy
+/// stdout can't crowd out the error on stderr.
+///
+/// Commands matching the blocklist are refused before anything is spawned,
+/// whatever the permission handler decided. This is a guard against obvious
+/// accidents, not a sandbox: a determined command can always be rewritten to
+/// avoid a pattern.
+///
+/// # Example
+///
+/// ```rust
+/// use claude::tools::BashTool;
+/// use claude::{CancellationToken, Tool, ToolOutput};
+/// use serde_json::json;
+/// use std::time::{Duration, Instant};
+///
+/// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+/// let dir = tempfile::tempdir().unwrap();
+/// let output = BashTool::new()
+///     .execute_typed(json!({
+///         "command": "echo \"$GREETING from $(basename \"$PWD\")\"; echo oops >&2; exit 3",
+///         "cwd": dir.path(),
+///         "env": {"GREETING": "hello"}
+///     }))
+///     .await?;
+/// let ToolOutput::Json(result) = output else { panic!("expected JSON output") };
+/// let dir_name = dir.path().file_name().unwrap().to_str().unwrap();
+/// assert_eq!(result["stdout"], format!("hello from {}\n", dir_name));
+/// assert_eq!(result["stderr"], "oops\n");
+/// assert_eq!(result["exit_code"], 3);
+/// assert_eq!(result["timed_out"], false);
+/// assert!(result["duration_ms"].is_u64());
+/// assert!(result["summary"].as_str().unwrap().starts_with("Exited with code 3"));
+///
+/// // A hanging command is killed, along with the children it spawned, and
+/// // whatever it printed first is kept
+/// let started = Instant::now();
+/// let output = BashTool::new()
+///     .execute_typed(json!({"command": "echo started; sleep 10 & sleep 10", "timeout_secs": 1}))
+///     .await?;
+/// let ToolOutput::Json(result) = output else { panic!("expected JSON output") };
+/// assert_eq!(result["timed_out"], true);
+/// assert_eq!(result["exit_code"], json!(null));
+/// assert_eq!(result["stdout"], "started\n");
+/// assert!(result["summary"].as_str().unwrap().starts_with("Timed out after 1s"));
+/// assert!(started.elapsed() < Duration::from_secs(5));
+///
+/// // A background child left holding the output doesn't keep the call waiting
+/// let started = Instant::now();
+/// let output = BashTool::new()
+///     .execute_typed(json!({"command": "echo done; sleep 30 &", "timeout_secs": 60}))
+///     .await?;
+/// let ToolOutput::Json(result) = output else { panic!("expected JSON output") };
+/// assert_eq!(result["exit_code"], 0);
+/// assert_eq!(result["stdout"], "done\n");
+/// assert!(result["summary"].as_str().unwrap().contains("background processes"));
+/// assert!(started.elapsed() < Duration::from_secs(5));
+///
+/// // Cancelling kills it the same way, and fails the call
+/// let cancel = CancellationToken::new();
+/// let trigger = cancel.clone();
+/// tokio::spawn(async move {
+///     tokio::time::sleep(Duration::from_millis(200)).await;
+///     trigger.cancel();
+/// });
+/// let started = Instant::now();
+/// let err = BashTool::new()
+///     .execute_with_cancel(json!({"command": "sleep 10 & sleep 10"}), cancel)
+///     .await
+///     .unwrap_err();
+/// assert!(err.to_string().contains("cancelled"), "{}", err);
+/// assert!(started.elapsed() < Duration::from_secs(5));
+/// # Ok::<(), claude::Error>(())
+/// # }).unwrap();
+/// ```
+#[derive(Debug, Clone)]
+pub struct BashTool {
+    blocklist: Vec<Regex>,
+    max_stream_bytes: usize,
+}
+
+impl BashTool {
+    /// Create a bash tool that refuses the commands in [`DEFAULT_BLOCKLIST`]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Refuse commands matching any of `patterns` instead of the default blocklist
+    ///
+    /// Patterns are regexes searched for anywhere in the command. An empty
+    /// list turns the filter off.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a pattern isn't a valid regex.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use claude::tools::BashTool;
+    /// use claude::Tool;
+    /// use serde_json::json;
+    ///
+    /// // Stock patterns are checked without running anything
+    /// let bash = BashTool::new();
+    /// assert!(bash.blocked_by("rm -rf /").is_some());
+    /// assert!(bash.blocked_by("sudo rm -rf --no-preserve-root / ").is_some());
+    /// assert!(bash.blocked_by("mkfs.ext4 /dev/sda1").is_some());
+    /// assert!(bash.blocked_by(":(){ :|:& };:").is_some());
+    /// assert!(bash.blocked_by("dd if=/dev/zero of=/dev/sda bs=1M").is_some());
+    /// assert!(bash.blocked_by("rm -rf ./build /tmp/scratch").is_none());
+    /// assert!(bash.blocked_by("ls -la /").is_none());
+    ///
+    /// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+    /// let dir = tempfile::tempdir().unwrap();
+    /// let marker = dir.path().join("marker");
+    /// let bash = BashTool::new().with_blocklist([r"\btouch\b"])?;
+    ///
+    /// // A blocked command is refused before it is spawned
+    /// let err = bash
+    ///     .execute(json!({"command": format!("touch {}", marker.display())}))
+    ///     .await
+    ///     .unwrap_err();
+    /// assert!(err.to_string().contains("blocklist"), "{}", err);
+    /// assert!(!marker.exists());
+    ///
+    /// // Anything else runs as usual
+    /// let output = bash.execute(json!({"command": "echo fine"})).await?;
+    /// assert!(output.contains("fine"));
+    ///
+    /// assert!(BashTool::new().with_blocklist(["("]).is_err());
+    /// # Ok::<(), claude::Error>(())
+    /// # }).unwrap();
+    /// ```
+    pub fn with_blocklist<I, S>(mut self, patterns: I) -> Result<Self>
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        self.blocklist = patterns
+            .into_iter()
+            .map(|pattern| {
+                let pattern = pattern.as_ref();
+                Regex::new(pattern).map_err(|e| {
+                    Error::Other(format!("Invalid blocklist pattern '{}': {}", pattern, e))
+                })
+            })
+            .collect::<Result<_>>()?;
+        Ok(self)
+    }
+
+    /// Cap each of stdout and stderr at `max_bytes` instead of
+    /// [`DEFAULT_MAX_STREAM_BYTES`]
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use claude::tools::BashTool;
+    /// use claude::{Tool, ToolOutput};
+    /// use serde_json::json;
+    ///
+    /// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+    /// let bash = BashTool::new().with_max_stream_bytes(20);
+    /// let output = bash
+    ///     .execute_typed(json!({"command": "seq 1000; echo short >&2"}))
+    ///     .await?;
+    /// let ToolOutput::Json(result) = output else { panic!("expected JSON output") };
+    ///
+    /// // Only the long stream is cut, keeping its start and end
+    /// let stdout = result["stdout"].as_str().unwrap();
+    /// assert!(stdout.starts_with("1\n2\n3\n"), "{}", stdout);
+    /// assert!(stdout.contains("bytes truncated ..."), "{}", stdout);
+    /// assert!(stdout.ends_with("999\n1000\n"), "{}", stdout);
+    /// assert_eq!(result["stderr"], "short\n");
+    /// # Ok::<(), claude::Error>(())
+    /// # }).unwrap();
+    /// ```
+    pub fn with_max_stream_bytes(mut self, max_bytes: usize) -> Self {
+        self.max_stream_bytes = max_bytes;
+        self
+    }
+
+    /// The first blocklist pattern `command` matches, if any
+    pub fn blocked_by(&self, command: &str) -> Option<&str> {
+        self.blocklist
+            .iter()
+            .find(|pattern| pattern.is_match(command))
+            .map(Regex::as_str)
+    }
+}
+
+impl Default for BashTool {
+    fn default() -> Self {
+        Self {
+            blocklist: DEFAULT_BLOCKLIST
+                .iter()
+                .map(|pattern| Regex::new(pattern).expect("default blocklist pattern is valid"))
+                .collect(),
+            max_stream_bytes: DEFAULT_MAX_STREAM_BYTES,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct BashInput {
+    command: String,
+    cwd: Option<String>,
+    #[serde(default)]
+    env: HashMap<String, String>,
+    timeout_secs: Option<u64>,
+}
+
+/// Read a child's output stream to the end in the background
+fn capture(
+    stream: Option<impl AsyncRead + Unpin + Send + 'static>,
+) -> tokio::task::JoinHandle<Vec<u8>> {
+    tokio::spawn(async move {
+        let mut buf = Vec::new();
+        if let Some(mut stream) = stream {
+            let _ = stream.read_to_end(&mut buf).await;
+        }
+        buf
+    })
+}
+
+/// Decode a captured stream, keeping its start and end if it's over `max_bytes`
+fn clip_stream(bytes: &[u8], max_bytes: usize) -> String {
+    let text = String::from_utf8_lossy(bytes);
+    if text.len() <= max_bytes {
+        return text.into_owned();
+    }
+
+    let mut head_end = max_bytes / 2;
+    while !text.is_char_boundary(head_end) {
+        head_end -= 1;
+    }
+    let mut tail_start = text.len() - (max_bytes - max_bytes / 2);
+    while !text.is_char_boundary(tail_start) {
+        tail_start += 1;
+    }
+    format!(
+        "{}\n[... {} bytes truncated ...]\n{}",
+        &text[..head_end],
+        tail_start - head_end,
+        &text[tail_start..]
+    )
+}
+
+/// Kill the child, whose pid is `pid`, and every process in its process group
+#[cfg(unix)]
+fn kill_process_group(pid: Option<u32>, child: &mut Child) {
+    if let Some(pid) = pid {
+        // The child leads its own group (see `process_group(0)`), so its pid
+        // is also the group id, which lives on with any process left in it
+        unsafe {
+            libc::killpg(pid as libc::pid_t, libc::SIGKILL);
+        }
+    }
+    let _ = child.start_kill();
+}
+
+#[cfg(not(unix))]
+fn kill_process_group(_pid: Option<u32>, child: &mut Child) {
+    let _ = child.start_kill();
+}
 
 #[async_trait]
 impl Tool for BashTool {
@@ -12,7 +315,7 @@ impl Tool for BashTool {
     }
 
     fn description(&self) -> &str {
-        "Execute bash commands or scripts"
+        "Execute bash commands or scripts. Optionally set the working directory, extra environment variables, and a timeout. Returns stdout and stderr (each truncated separately if very long), the exit code, the duration, and whether the command timed out."
     }
 
     fn input_schema(&self) -> Value {
@@ -22,6 +325,21 @@ impl Tool for BashTool {
                 "command": {
                     "type": "string",
                     "description": "The bash command or script to execute"
+                },
+                "cwd": {
+                    "type": "string",
+                    "description": "Directory to run the command in (default: the current directory)"
+                },
+                "env": {
+                    "type": "object",
+                    "description": "Extra environment variables as key-value pairs",
+                    "additionalProperties": {
+                        "type": "string"
+                    }
+                },
+                "timeout_secs": {
+                    "type": "integer",
+                    "description": "Kill the command and everything it started after this many seconds"
                 }
             },
             "required": ["command"],
@@ -30,33 +348,139 @@ impl Tool for BashTool {
     }
 
     async fn execute(&self, input: Value) -> Result<String> {
-        let command = input
-            .get("command")
-            .and_then(|v| v.as_str())
-            .ok_or_else(|| {
-                Error::Other(
-                    "Missing 'command' field. Example: {\"command\": \"ls -la\"}".to_string(),
-                )
-            })?;
-
-        let output = Command::new("bash")
+        Ok(self.execute_typed(input).await?.into_text())
+    }
+
+    async fn execute_typed(&self, input: Value) -> Result<ToolOutput> {
+        self.execute_with_cancel(input, CancellationToken::new())
+            .await
+    }
+
+    async fn execute_with_cancel(
+        &self,
+        input: Value,
+        cancel: CancellationToken,
+    ) -> Result<ToolOutput> {
+        let params: BashInput = serde_json::from_value(input).map_err(|e| {
+            Error::Other(format!(
+                "Invalid input parameters: {}. Example: {{\"command\": \"ls -la\"}}",
+                e
+            ))
+        })?;
+
+        if let Some(pattern) = self.blocked_by(&params.command) {
+            return Err(Error::Other(format!(
+                "Command refused: it matches the bash tool's blocklist pattern '{}'",
+                pattern
+            )));
+        }
+
+        let mut command = Command::new("bash");
+        command
             .arg("-c")
-            .arg(command)
-            .output()
+            .arg(&params.command)
+            .envs(&params.env)
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true);
+        if let Some(cwd) = &params.cwd {
+            command.current_dir(cwd);
+        }
+        #[cfg(unix)]
+        command.process_group(0);
+
+        let mut child = command
+            .spawn()
             .map_err(|e| Error::Other(format!("Failed to execute bash command: {}", e)))?;
 
-        let stdout = String::from_utf8_lossy(&output.stdout);
-        let stderr = String::from_utf8_lossy(&output.stderr);
-
-        if output.status.success() {
-            Ok(stdout.to_string())
-        } else {
-            Ok(format!(
-                "Exit code: {}\nStdout:\n{}\nStderr:\n{}",
-                output.status.code().unwrap_or(-1),
-                stdout,
-                stderr
-            ))
+        let started = Instant::now();
+        // Kept because `child.id()` is gone once the shell has been waited on
+        let pid = child.id();
+        let stdout = capture(child.stdout.take());
+        let stderr = capture(child.stderr.take());
+
+        let timeout = async {
+            match params.timeout_secs {
+                Some(secs) => tokio::time::sleep(Duration::from_secs(secs)).await,
+                None => std::future::pending().await,
+            }
+        };
+        let status = tokio::select! {
+            status = child.wait() => Some(status),
+            _ = timeout => None,
+            _ = cancel.cancelled() => None,
+        };
+        if status.is_none() {
+            kill_process_group(pid, &mut child);
+            let _ = child.wait().await;
+        }
+        let duration_ms = started.elapsed().as_millis() as u64;
+        if cancel.is_cancelled() && status.is_none() {
+            return Err(Error::Other(format!(
+                "Command cancelled after {} ms; it and everything it started were killed",
+                duration_ms
+            )));
         }
+        let status = status
+            .transpose()
+            .map_err(|e| Error::Other(format!("Failed to wait for bash command: {}", e)))?;
+
+        // Background children can hold the pipes open long after the shell has
+        // exited. Give them until the deadline, at most STREAM_GRACE, then kill
+        // the group so the reads finish. Whatever was printed before a timeout
+        // helps explain the hang, so it is kept either way.
+        let grace = match params.timeout_secs {
+            Some(secs) => Duration::from_secs(secs)
+                .saturating_sub(started.elapsed())
+                .min(STREAM_GRACE),
+            None => STREAM_GRACE,
+        };
+        let mut streams = Box::pin(async {
+            (
+                stdout.await.unwrap_or_default(),
+                stderr.await.unwrap_or_default(),
+            )
+        });
+        let mut lingering = false;
+        let (stdout, stderr) = match tokio::time::timeout(grace, &mut streams).await {
+            Ok(streams) => streams,
+            Err(_) => {
+                lingering = status.is_some();
+                kill_process_group(pid, &mut child);
+                streams.await
+            }
+        };
+
+        let exit_code = status.and_then(|status| status.code());
+        let summary = match (status, exit_code) {
+            (None, _) => format!(
+                "Timed out after {}s and was killed",
+                params.timeout_secs.unwrap_or_default()
+            ),
+            (Some(_), Some(code)) => format!("Exited with code {} in {} ms", code, duration_ms),
+            (Some(_), None) => format!("Killed by a signal after {} ms", duration_ms),
+        };
+        let summary = format!(
+            "{} ({} bytes on stdout, {} bytes on stderr){}",
+            summary,
+            stdout.len(),
+            stderr.len(),
+            if lingering {
+                "; background processes still holding its output were killed"
+            } else {
+                ""
+            }
+        );
+
+        Ok(ToolOutput::Json(json!({
+            "exit_code": exit_code,
+            "success": status.is_some_and(|status| status.success()),
+            "timed_out": status.is_none(),
+            "duration_ms": duration_ms,
+            "stdout": clip_stream(&stdout, self.max_stream_bytes),
+            "stderr": clip_stream(&stderr, self.max_stream_bytes),
+            "summary": summary,
+        })))
     }
 }
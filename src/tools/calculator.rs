@@ -20,7 +20,7 @@ impl Tool for CalculatorTool {
             "properties": {
                 "expression": {
                     "type": "string",
-                    "description": "Mathematical expression to evaluate (e.g., '2 + 2', 'sin(45) * pi', 'sqrt(16)')"
+                    "description": "Mathematical expression to evaluate (e.g., '2 + 2', 'sin(pi / 4) * 2', 'sqrt(16)', '7 % 3', '2 ^ 10'). Trigonometric functions use radians; log is base 10 and ln is natural."
                 }
             },
             "required": ["expression"],
@@ -38,13 +38,328 @@ impl Tool for CalculatorTool {
                 )
             })?;
 
-        // Use exmex crate for safe expression evaluation
-        match exmex::eval_str::<f64>(expression) {
-            Ok(result) => Ok(format!("{} = {}", expression, result)),
-            Err(e) => Err(Error::Other(format!(
-                "Failed to evaluate expression: {}",
-                e
+        let result = evaluate(expression)
+            .map_err(|e| Error::Other(format!("Failed to evaluate expression: {}", e)))?;
+        Ok(format!("{} = {}", expression, result))
+    }
+}
+
+/// Evaluate an arithmetic expression
+///
+/// Supports `+ - * / % ^`, parentheses, unary minus, the constants `pi` and
+/// `e`, and the functions `sqrt`, `abs`, `exp`, `ln`, `log` (base 10),
+/// `log2`, `sin`, `cos`, `tan`, `asin`, `acos`, `atan`, `floor`, `ceil` and
+/// `round`. `^` is right-associative and binds tighter than unary minus, so
+/// `-2 ^ 2` is `-4`. Trigonometric functions work in radians.
+///
+/// # Errors
+///
+/// Returns an error for malformed expressions, unknown names, division or
+/// remainder by zero, and results that are not finite real numbers.
+///
+/// # Example
+///
+/// ```rust
+/// use claude::tools::calculator::evaluate;
+///
+/// // Operator precedence
+/// assert_eq!(evaluate("2 + 3 * 4").unwrap(), 14.0);
+/// assert_eq!(evaluate("2 ^ 3 ^ 2").unwrap(), 512.0);
+/// assert_eq!(evaluate("-2 ^ 2").unwrap(), -4.0);
+/// assert_eq!(evaluate("10 - 4 - 3").unwrap(), 3.0);
+/// assert_eq!(evaluate("7 % 3 * 2").unwrap(), 2.0);
+///
+/// // Nested parentheses and unary minus
+/// assert_eq!(evaluate("((1 + 2) * (3 - -1)) / 2").unwrap(), 6.0);
+/// assert_eq!(evaluate("-(-(4))").unwrap(), 4.0);
+///
+/// // Functions and constants
+/// assert_eq!(evaluate("sqrt(16) + abs(-3)").unwrap(), 7.0);
+/// assert!((evaluate("log(1000)").unwrap() - 3.0).abs() < 1e-12);
+/// assert!((evaluate("sin(pi / 2)").unwrap() - 1.0).abs() < 1e-12);
+/// assert!((evaluate("ln(e ^ 2)").unwrap() - 2.0).abs() < 1e-12);
+/// assert_eq!(evaluate("1.5e2").unwrap(), 150.0);
+///
+/// // Error cases
+/// assert!(evaluate("1 / 0").unwrap_err().to_string().contains("Division by zero"));
+/// assert!(evaluate("5 % 0").unwrap_err().to_string().contains("Division by zero"));
+/// assert!(evaluate("2 +").is_err());
+/// assert!(evaluate("(1 + 2").unwrap_err().to_string().contains("')'"));
+/// assert!(evaluate("1 2").is_err());
+/// assert!(evaluate("foo(1)").unwrap_err().to_string().contains("Unknown function 'foo'"));
+/// assert!(evaluate("sqrt(-1)").is_err());
+/// assert!(evaluate("").is_err());
+/// ```
+pub fn evaluate(expression: &str) -> Result<f64> {
+    let tokens = tokenize(expression)?;
+    let mut parser = Parser { tokens, pos: 0 };
+    let value = parser.expression()?;
+
+    if let Some((token, position)) = parser.tokens.get(parser.pos) {
+        return Err(Error::Other(format!(
+            "Unexpected {} at position {}",
+            token.describe(),
+            position
+        )));
+    }
+
+    if !value.is_finite() {
+        return Err(Error::Other(format!(
+            "Result is not a finite number ({})",
+            value
+        )));
+    }
+
+    Ok(value)
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Number(f64),
+    Ident(String),
+    Op(char),
+    LParen,
+    RParen,
+}
+
+impl Token {
+    fn describe(&self) -> String {
+        match self {
+            Token::Number(n) => format!("number {}", n),
+            Token::Ident(name) => format!("name '{}'", name),
+            Token::Op(op) => format!("operator '{}'", op),
+            Token::LParen => "'('".to_string(),
+            Token::RParen => "')'".to_string(),
+        }
+    }
+}
+
+/// Split an expression into tokens paired with their character positions
+fn tokenize(expression: &str) -> Result<Vec<(Token, usize)>> {
+    let chars: Vec<char> = expression.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+        let start = i;
+
+        if c.is_whitespace() {
+            i += 1;
+            continue;
+        }
+
+        if c.is_ascii_digit() || c == '.' {
+            while i < chars.len() && (chars[i].is_ascii_digit() || chars[i] == '.') {
+                i += 1;
+            }
+            // Scientific notation, only when an exponent actually follows
+            if i < chars.len() && (chars[i] == 'e' || chars[i] == 'E') {
+                let mut j = i + 1;
+                if j < chars.len() && (chars[j] == '+' || chars[j] == '-') {
+                    j += 1;
+                }
+                if j < chars.len() && chars[j].is_ascii_digit() {
+                    i = j;
+                    while i < chars.len() && chars[i].is_ascii_digit() {
+                        i += 1;
+                    }
+                }
+            }
+            let text: String = chars[start..i].iter().collect();
+            let number = text.parse::<f64>().map_err(|_| {
+                Error::Other(format!("Invalid number '{}' at position {}", text, start))
+            })?;
+            tokens.push((Token::Number(number), start));
+            continue;
+        }
+
+        if c.is_alphabetic() || c == '_' {
+            while i < chars.len() && (chars[i].is_alphanumeric() || chars[i] == '_') {
+                i += 1;
+            }
+            let name: String = chars[start..i].iter().collect();
+            tokens.push((Token::Ident(name.to_lowercase()), start));
+            continue;
+        }
+
+        let token = match c {
+            '+' | '-' | '*' | '/' | '%' | '^' => Token::Op(c),
+            '(' => Token::LParen,
+            ')' => Token::RParen,
+            _ => {
+                return Err(Error::Other(format!(
+                    "Unexpected character '{}' at position {}",
+                    c, start
+                )))
+            }
+        };
+        tokens.push((token, start));
+        i += 1;
+    }
+
+    Ok(tokens)
+}
+
+/// Recursive-descent parser that evaluates as it goes
+struct Parser {
+    tokens: Vec<(Token, usize)>,
+    pos: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos).map(|(token, _)| token)
+    }
+
+    fn advance(&mut self) -> Option<(Token, usize)> {
+        let token = self.tokens.get(self.pos).cloned();
+        if token.is_some() {
+            self.pos += 1;
+        }
+        token
+    }
+
+    fn expect(&mut self, expected: Token) -> Result<()> {
+        match self.advance() {
+            Some((token, _)) if token == expected => Ok(()),
+            Some((token, position)) => Err(Error::Other(format!(
+                "Expected {} but found {} at position {}",
+                expected.describe(),
+                token.describe(),
+                position
+            ))),
+            None => Err(Error::Other(format!(
+                "Expected {} but reached the end of the expression",
+                expected.describe()
+            ))),
+        }
+    }
+
+    /// expression := term (('+' | '-') term)*
+    fn expression(&mut self) -> Result<f64> {
+        let mut value = self.term()?;
+        while let Some(Token::Op(op @ ('+' | '-'))) = self.peek().cloned() {
+            self.pos += 1;
+            let rhs = self.term()?;
+            value = if op == '+' { value + rhs } else { value - rhs };
+        }
+        Ok(value)
+    }
+
+    /// term := unary (('*' | '/' | '%') unary)*
+    fn term(&mut self) -> Result<f64> {
+        let mut value = self.unary()?;
+        while let Some(Token::Op(op @ ('*' | '/' | '%'))) = self.peek().cloned() {
+            self.pos += 1;
+            let rhs = self.unary()?;
+            value = match op {
+                '*' => value * rhs,
+                _ if rhs == 0.0 => return Err(Error::Other("Division by zero".to_string())),
+                '/' => value / rhs,
+                _ => value % rhs,
+            };
+        }
+        Ok(value)
+    }
+
+    /// unary := ('-' | '+') unary | power
+    fn unary(&mut self) -> Result<f64> {
+        match self.peek() {
+            Some(Token::Op('-')) => {
+                self.pos += 1;
+                Ok(-self.unary()?)
+            }
+            Some(Token::Op('+')) => {
+                self.pos += 1;
+                self.unary()
+            }
+            _ => self.power(),
+        }
+    }
+
+    /// power := primary ('^' unary)?
+    fn power(&mut self) -> Result<f64> {
+        let base = self.primary()?;
+        if let Some(Token::Op('^')) = self.peek() {
+            self.pos += 1;
+            let exponent = self.unary()?;
+            return Ok(base.powf(exponent));
+        }
+        Ok(base)
+    }
+
+    /// primary := number | constant | function '(' expression ')' | '(' expression ')'
+    fn primary(&mut self) -> Result<f64> {
+        match self.advance() {
+            Some((Token::Number(n), _)) => Ok(n),
+            Some((Token::LParen, _)) => {
+                let value = self.expression()?;
+                self.expect(Token::RParen)?;
+                Ok(value)
+            }
+            Some((Token::Ident(name), position)) => {
+                if self.peek() == Some(&Token::LParen) {
+                    self.pos += 1;
+                    let argument = self.expression()?;
+                    self.expect(Token::RParen)?;
+                    apply_function(&name, argument, position)
+                } else {
+                    constant(&name, position)
+                }
+            }
+            Some((token, position)) => Err(Error::Other(format!(
+                "Unexpected {} at position {}",
+                token.describe(),
+                position
             ))),
+            None => Err(Error::Other("Unexpected end of expression".to_string())),
         }
     }
 }
+
+fn constant(name: &str, position: usize) -> Result<f64> {
+    match name {
+        "pi" | "π" => Ok(std::f64::consts::PI),
+        "e" => Ok(std::f64::consts::E),
+        "tau" => Ok(std::f64::consts::TAU),
+        _ => Err(Error::Other(format!(
+            "Unknown constant '{}' at position {}",
+            name, position
+        ))),
+    }
+}
+
+fn apply_function(name: &str, x: f64, position: usize) -> Result<f64> {
+    let value = match name {
+        "sqrt" => x.sqrt(),
+        "abs" => x.abs(),
+        "exp" => x.exp(),
+        "ln" => x.ln(),
+        "log" => x.log10(),
+        "log2" => x.log2(),
+        "sin" => x.sin(),
+        "cos" => x.cos(),
+        "tan" => x.tan(),
+        "asin" => x.asin(),
+        "acos" => x.acos(),
+        "atan" => x.atan(),
+        "floor" => x.floor(),
+        "ceil" => x.ceil(),
+        "round" => x.round(),
+        _ => {
+            return Err(Error::Other(format!(
+                "Unknown function '{}' at position {}",
+                name, position
+            )))
+        }
+    };
+
+    if value.is_nan() {
+        return Err(Error::Other(format!(
+            "{}({}) is not a real number",
+            name, x
+        )));
+    }
+    Ok(value)
+}
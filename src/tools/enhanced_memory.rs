@@ -1,18 +1,208 @@
-use crate::{Error, Result, Tool};
+use crate::{paths, Error, Result, Tool};
 use async_trait::async_trait;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
 use std::collections::HashMap;
-use std::fs;
-use std::path::PathBuf;
+use std::fs::{self, File, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use tokio::sync::RwLock;
 use uuid::Uuid;
 
 /// Enhanced memory system with persistence, search, and tagging
+///
+/// Entries are saved as JSON, by default in `~/.claude_memory.json`. Writes go
+/// to a temporary file that is renamed into place, so an interrupted save
+/// never leaves a half-written file, and an advisory lock on a `.lock` file
+/// next to it keeps concurrent instances from interleaving reads and writes.
+/// A file that cannot be parsed is moved aside to `.bak` and a fresh store is
+/// started.
+///
+/// # Example
+///
+/// ```rust
+/// use claude::tools::EnhancedMemoryTool;
+/// use claude::Tool;
+/// use serde_json::json;
+///
+/// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+/// let dir = tempfile::tempdir().unwrap();
+/// let path = dir.path().join("memory.json");
+/// std::fs::write(&path, "{\"entries\": {\"trunc").unwrap();
+///
+/// // The corrupt file is backed up rather than failing every future run
+/// let memory = EnhancedMemoryTool::with_path(&path)?;
+/// let backup = std::fs::read_to_string(path.with_extension("json.bak")).unwrap();
+/// assert_eq!(backup, "{\"entries\": {\"trunc");
+///
+/// memory
+///     .execute(json!({"action": "store", "content": "Recovered"}))
+///     .await?;
+/// let saved: serde_json::Value =
+///     serde_json::from_str(&std::fs::read_to_string(&path).unwrap())?;
+/// assert_eq!(saved["entries"].as_object().unwrap().len(), 1);
+/// # Ok::<(), claude::Error>(())
+/// # }).unwrap();
+/// ```
+///
+/// Entries returned by `search` can be fetched in full with `get`, which also
+/// counts how often each entry is accessed:
+///
+/// ```rust
+/// # use claude::tools::EnhancedMemoryTool;
+/// # use claude::Tool;
+/// # use serde_json::{json, Value};
+/// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+/// # let dir = tempfile::tempdir().unwrap();
+/// let memory = EnhancedMemoryTool::with_path(dir.path().join("memory.json"))?;
+/// let stored: Value = serde_json::from_str(
+///     &memory
+///         .execute(json!({"action": "store", "content": "Deploy on Fridays", "tags": ["ops"]}))
+///         .await?,
+/// )?;
+/// let id = stored["id"].as_str().unwrap();
+///
+/// memory.execute(json!({"action": "get", "id": id})).await?;
+/// let fetched: Value =
+///     serde_json::from_str(&memory.execute(json!({"action": "get", "id": id})).await?)?;
+/// assert_eq!(fetched["entry"]["content"], "Deploy on Fridays");
+/// assert_eq!(fetched["entry"]["tags"], json!(["ops"]));
+/// assert_eq!(fetched["entry"]["access_count"], 2);
+///
+/// let missing = memory.execute(json!({"action": "get", "id": "no-such-id"})).await;
+/// assert!(missing.unwrap_err().to_string().contains("not found"));
+/// # Ok::<(), claude::Error>(())
+/// # }).unwrap();
+/// ```
+///
+/// Entries stored with `ttl_seconds` expire. Expired entries are skipped and
+/// deleted by `search` and `get`, and `prune_expired` deletes them all:
+///
+/// ```rust
+/// # use claude::tools::EnhancedMemoryTool;
+/// # use claude::Tool;
+/// # use serde_json::{json, Value};
+/// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+/// # let dir = tempfile::tempdir().unwrap();
+/// let path = dir.path().join("memory.json");
+/// let memory = EnhancedMemoryTool::with_path(&path)?;
+/// let stored: Value = serde_json::from_str(
+///     &memory
+///         .execute(json!({"action": "store", "content": "Debugging the parser", "ttl_seconds": 3600}))
+///         .await?,
+/// )?;
+/// assert!(stored["expires_at"].is_string());
+///
+/// // Move the expiry into the past, as if an hour had gone by
+/// let mut saved: Value = serde_json::from_str(&std::fs::read_to_string(&path).unwrap())?;
+/// let id = stored["id"].as_str().unwrap();
+/// saved["entries"][id]["expires_at"] = json!("2020-01-01T00:00:00Z");
+/// std::fs::write(&path, saved.to_string()).unwrap();
+///
+/// let memory = EnhancedMemoryTool::with_path(&path)?;
+/// memory
+///     .execute(json!({"action": "store", "content": "Parser lives in src/parse.rs"}))
+///     .await?;
+/// let found: Value = serde_json::from_str(
+///     &memory.execute(json!({"action": "search", "query": "parser"})).await?,
+/// )?;
+/// assert_eq!(found["count"], 1);
+/// assert_eq!(found["results"][0]["content"], "Parser lives in src/parse.rs");
+///
+/// let get = memory.execute(json!({"action": "get", "id": id})).await;
+/// assert!(get.unwrap_err().to_string().contains("not found"));
+/// # Ok::<(), claude::Error>(())
+/// # }).unwrap();
+/// ```
+///
+/// ```rust
+/// # use claude::tools::EnhancedMemoryTool;
+/// # use claude::Tool;
+/// # use serde_json::{json, Value};
+/// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+/// # let dir = tempfile::tempdir().unwrap();
+/// let path = dir.path().join("memory.json");
+/// std::fs::write(&path, json!({
+///     "entries": {
+///         "old": {
+///             "id": "old", "content": "Currently on call", "tags": ["status"], "metadata": {},
+///             "created_at": "2019-12-31T00:00:00Z", "updated_at": "2019-12-31T00:00:00Z",
+///             "expires_at": "2020-01-01T00:00:00Z"
+///         }
+///     },
+///     "tag_index": {"status": ["old"]}
+/// }).to_string()).unwrap();
+///
+/// let memory = EnhancedMemoryTool::with_path(&path)?;
+/// let pruned: Value =
+///     serde_json::from_str(&memory.execute(json!({"action": "prune_expired"})).await?)?;
+/// assert_eq!(pruned["pruned"], 1);
+/// assert_eq!(pruned["ids"], json!(["old"]));
+///
+/// let saved: Value = serde_json::from_str(&std::fs::read_to_string(&path).unwrap())?;
+/// assert!(saved["entries"].as_object().unwrap().is_empty());
+/// assert_eq!(saved["tag_index"]["status"], json!([]));
+/// # Ok::<(), claude::Error>(())
+/// # }).unwrap();
+/// ```
+///
+/// `search` ranks entries by BM25. A term found in few entries counts for more
+/// than one found in most, and repeating a term has diminishing returns:
+///
+/// ```rust
+/// # use claude::tools::EnhancedMemoryTool;
+/// # use claude::Tool;
+/// # use serde_json::{json, Value};
+/// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+/// # let dir = tempfile::tempdir().unwrap();
+/// async fn search(memory: &EnhancedMemoryTool, query: &str) -> claude::Result<Vec<(String, f64)>> {
+///     let found: Value =
+///         serde_json::from_str(&memory.execute(json!({"action": "search", "query": query})).await?)?;
+///     Ok(found["results"]
+///         .as_array()
+///         .unwrap()
+///         .iter()
+///         .map(|r| (r["content"].as_str().unwrap().to_string(), r["score"].as_f64().unwrap()))
+///         .collect())
+/// }
+///
+/// let memory = EnhancedMemoryTool::with_path(dir.path().join("notes.json"))?;
+/// for content in [
+///     "rust build failed on ci",
+///     "rust docs updated today",
+///     "rust release notes drafted",
+///     "flaky ci job retried",
+/// ] {
+///     memory.execute(json!({"action": "store", "content": content})).await?;
+/// }
+/// // "rust" is in three entries and "flaky" in one, so the one match on
+/// // "flaky" beats every match on "rust"
+/// let ranked = search(&memory, "rust flaky").await?;
+/// assert_eq!(ranked.len(), 4);
+/// assert_eq!(ranked[0].0, "flaky ci job retried");
+/// assert!(ranked[0].1 > ranked[1].1);
+///
+/// let memory = EnhancedMemoryTool::with_path(dir.path().join("deploys.json"))?;
+/// for content in [
+///     "deploy notes for the alpha beta gamma release",
+///     "deploy deploy deploy deploy deploy deploy deploy deploy",
+/// ] {
+///     memory.execute(json!({"action": "store", "content": content})).await?;
+/// }
+/// // Eight mentions rank higher than one, but score well under eight times as
+/// // much: term frequency saturates below K1 + 1 = 2.2 times a single mention
+/// let ranked = search(&memory, "deploy").await?;
+/// assert!(ranked[0].0.starts_with("deploy deploy"));
+/// let ratio = ranked[0].1 / ranked[1].1;
+/// assert!(ratio > 1.0 && ratio < 2.2, "{}", ratio);
+/// # Ok::<(), claude::Error>(())
+/// # }).unwrap();
+/// ```
 pub struct EnhancedMemoryTool {
     storage: Arc<RwLock<MemoryStorage>>,
+    path: PathBuf,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -23,6 +213,27 @@ struct MemoryEntry {
     created_at: DateTime<Utc>,
     updated_at: DateTime<Utc>,
     metadata: HashMap<String, String>,
+    /// Number of times the entry has been fetched with `get`
+    #[serde(default)]
+    access_count: u64,
+    /// When the entry stops being returned, if it was stored with a TTL
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    expires_at: Option<DateTime<Utc>>,
+}
+
+impl MemoryEntry {
+    fn is_expired(&self, now: DateTime<Utc>) -> bool {
+        self.expires_at.is_some_and(|expires_at| expires_at <= now)
+    }
+}
+
+/// A search hit with its relevance score, serialized as the entry plus `score`
+#[derive(Debug, Clone, Serialize)]
+struct ScoredEntry {
+    #[serde(flatten)]
+    entry: MemoryEntry,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    score: Option<f64>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -104,39 +315,78 @@ impl MemoryStorage {
         query: Option<&str>,
         tags: Option<&[String]>,
         limit: Option<usize>,
-    ) -> Vec<MemoryEntry> {
-        let mut results: Vec<&MemoryEntry> = self.entries.values().collect();
+    ) -> Vec<ScoredEntry> {
+        let mut candidates: Vec<&MemoryEntry> = self.entries.values().collect();
 
         // Filter by tags if provided
         if let Some(search_tags) = tags {
-            results.retain(|entry| search_tags.iter().any(|tag| entry.tags.contains(tag)));
+            candidates.retain(|entry| search_tags.iter().any(|tag| entry.tags.contains(tag)));
         }
 
-        // Filter by query if provided
-        if let Some(q) = query {
-            let q_lower = q.to_lowercase();
-            results.retain(|entry| {
-                entry.content.to_lowercase().contains(&q_lower)
-                    || entry
-                        .tags
-                        .iter()
-                        .any(|tag| tag.to_lowercase().contains(&q_lower))
-                    || entry
-                        .metadata
-                        .values()
-                        .any(|v| v.to_lowercase().contains(&q_lower))
-            });
-        }
+        let mut results: Vec<ScoredEntry> = match query {
+            Some(q) => {
+                let scores = bm25_scores(&candidates, q);
+                let q_lower = q.to_lowercase();
+                candidates
+                    .into_iter()
+                    .zip(scores)
+                    // Keep substring matches (e.g. partial words or metadata) that
+                    // BM25 doesn't score, ranked after every scored entry
+                    .filter(|(entry, score)| *score > 0.0 || Self::contains_text(entry, &q_lower))
+                    .map(|(entry, score)| ScoredEntry {
+                        entry: entry.clone(),
+                        score: Some(score),
+                    })
+                    .collect()
+            }
+            None => candidates
+                .into_iter()
+                .map(|entry| ScoredEntry {
+                    entry: entry.clone(),
+                    score: None,
+                })
+                .collect(),
+        };
 
-        // Sort by updated_at (most recent first)
-        results.sort_by(|a, b| b.updated_at.cmp(&a.updated_at));
+        // Sort by score, then by updated_at (most recent first)
+        results.sort_by(|a, b| {
+            b.score
+                .unwrap_or(0.0)
+                .total_cmp(&a.score.unwrap_or(0.0))
+                .then_with(|| b.entry.updated_at.cmp(&a.entry.updated_at))
+        });
 
         // Apply limit
         if let Some(limit) = limit {
             results.truncate(limit);
         }
 
-        results.into_iter().cloned().collect()
+        results
+    }
+
+    fn contains_text(entry: &MemoryEntry, q_lower: &str) -> bool {
+        entry.content.to_lowercase().contains(q_lower)
+            || entry
+                .tags
+                .iter()
+                .any(|tag| tag.to_lowercase().contains(q_lower))
+            || entry
+                .metadata
+                .values()
+                .any(|v| v.to_lowercase().contains(q_lower))
+    }
+
+    fn get(&mut self, id: &str) -> Result<MemoryEntry> {
+        let entry = self.entries.get_mut(id).ok_or_else(|| {
+            Error::Other(format!(
+                "Memory entry '{}' not found. Use 'search' to find available entries",
+                id
+            ))
+        })?;
+
+        entry.access_count += 1;
+
+        Ok(entry.clone())
     }
 
     fn delete(&mut self, id: &str) -> Result<()> {
@@ -156,41 +406,313 @@ impl MemoryStorage {
 
         Ok(())
     }
+
+    /// Delete every entry that has expired by `now`, returning their ids
+    fn prune_expired(&mut self, now: DateTime<Utc>) -> Vec<String> {
+        let mut expired: Vec<String> = self
+            .entries
+            .values()
+            .filter(|entry| entry.is_expired(now))
+            .map(|entry| entry.id.clone())
+            .collect();
+        expired.sort();
+
+        for id in &expired {
+            // The ids were just taken from the map, so this can't fail
+            let _ = self.delete(id);
+        }
+        expired
+    }
+}
+
+/// Split text into lowercase alphanumeric terms
+fn tokenize(text: &str) -> Vec<String> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|term| !term.is_empty())
+        .map(str::to_lowercase)
+        .collect()
+}
+
+/// Score each entry's content and tags against `query` with Okapi BM25
+///
+/// Short entries that repeat a query term score higher than long entries that
+/// mention it once, and rare terms count for more than common ones.
+fn bm25_scores(entries: &[&MemoryEntry], query: &str) -> Vec<f64> {
+    const K1: f64 = 1.2;
+    const B: f64 = 0.75;
+
+    let mut query_terms = tokenize(query);
+    query_terms.sort();
+    query_terms.dedup();
+
+    let documents: Vec<Vec<String>> = entries
+        .iter()
+        .map(|entry| {
+            let mut terms = tokenize(&entry.content);
+            for tag in &entry.tags {
+                terms.extend(tokenize(tag));
+            }
+            terms
+        })
+        .collect();
+
+    if query_terms.is_empty() || documents.is_empty() {
+        return vec![0.0; entries.len()];
+    }
+
+    let doc_count = documents.len() as f64;
+    let avg_len = documents.iter().map(Vec::len).sum::<usize>() as f64 / doc_count;
+
+    let idf: HashMap<&str, f64> = query_terms
+        .iter()
+        .map(|term| {
+            let df = documents.iter().filter(|doc| doc.contains(term)).count() as f64;
+            let idf = ((doc_count - df + 0.5) / (df + 0.5) + 1.0).ln();
+            (term.as_str(), idf)
+        })
+        .collect();
+
+    documents
+        .iter()
+        .map(|doc| {
+            let len = doc.len() as f64;
+            let norm = if avg_len > 0.0 { len / avg_len } else { 0.0 };
+            query_terms
+                .iter()
+                .map(|term| {
+                    let tf = doc.iter().filter(|t| *t == term).count() as f64;
+                    if tf == 0.0 {
+                        return 0.0;
+                    }
+                    idf[term.as_str()] * tf * (K1 + 1.0) / (tf + K1 * (1.0 - B + B * norm))
+                })
+                .sum()
+        })
+        .collect()
 }
 
 impl EnhancedMemoryTool {
+    /// Create a memory tool backed by `~/.claude_memory.json`
+    ///
+    /// On first use, memories saved by older versions in
+    /// `~/.chatbot_memory/memories.json` are imported and the legacy file is
+    /// renamed to `memories.json.migrated`.
     pub fn new() -> Result<Self> {
-        let storage = Arc::new(RwLock::new(Self::load_storage()?));
-        Ok(Self { storage })
+        let path = Self::default_path();
+        let mut storage = Self::load_storage(&path)?;
+
+        if let Some(legacy_path) = Self::legacy_storage_path().filter(|p| p.exists()) {
+            let _lock = Self::lock_storage(&path)?;
+            storage = Self::read_storage(&path)?;
+            if Self::import_legacy(&mut storage, &legacy_path)? > 0 {
+                Self::write_storage(&path, &storage)?;
+            }
+            fs::rename(&legacy_path, legacy_path.with_extension("json.migrated"))
+                .map_err(|e| Error::Other(format!("Failed to retire legacy memory file: {}", e)))?;
+        }
+
+        Ok(Self {
+            storage: Arc::new(RwLock::new(storage)),
+            path,
+        })
+    }
+
+    /// Create a memory tool that persists to `path` instead of the default location
+    ///
+    /// Several tools, in one process or many, can share a file: every action
+    /// re-reads it under a lock before changing it, so none of them loses
+    /// another's updates.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use claude::tools::EnhancedMemoryTool;
+    /// use claude::Tool;
+    /// use serde_json::{json, Value};
+    ///
+    /// async fn store(tool: &EnhancedMemoryTool, n: usize) -> claude::Result<String> {
+    ///     tool.execute(json!({"action": "store", "content": format!("note {}", n), "tags": ["shared"]}))
+    ///         .await
+    /// }
+    ///
+    /// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+    /// let dir = tempfile::tempdir().unwrap();
+    /// let path = dir.path().join("memory.json");
+    /// let first = EnhancedMemoryTool::with_path(&path)?;
+    /// let second = EnhancedMemoryTool::with_path(&path)?;
+    ///
+    /// let (a, b, c, d) = tokio::join!(store(&first, 1), store(&second, 2), store(&first, 3), store(&second, 4));
+    /// for result in [a, b, c, d] {
+    ///     result?;
+    /// }
+    ///
+    /// // Each tool sees what the other stored
+    /// let found: Value =
+    ///     serde_json::from_str(&first.execute(json!({"action": "search", "tags": ["shared"]})).await?)?;
+    /// assert_eq!(found["count"], 4);
+    ///
+    /// let saved: Value = serde_json::from_str(&std::fs::read_to_string(&path).unwrap())?;
+    /// assert_eq!(saved["entries"].as_object().unwrap().len(), 4);
+    /// # Ok::<(), claude::Error>(())
+    /// # }).unwrap();
+    /// ```
+    pub fn with_path(path: impl Into<PathBuf>) -> Result<Self> {
+        let path = path.into();
+        let storage = Arc::new(RwLock::new(Self::load_storage(&path)?));
+        Ok(Self { storage, path })
+    }
+
+    /// The file [`new`](Self::new) persists to, `~/.claude_memory.json`
+    pub fn default_path() -> PathBuf {
+        paths::in_home(".claude_memory.json")
+    }
+
+    fn legacy_storage_path() -> Option<PathBuf> {
+        paths::home_dir().map(|home| home.join(".chatbot_memory").join("memories.json"))
+    }
+
+    /// Add entries from a legacy `memories.json`, skipping ids already present
+    fn import_legacy(storage: &mut MemoryStorage, legacy_path: &Path) -> Result<usize> {
+        #[derive(Deserialize)]
+        struct LegacyMemory {
+            id: String,
+            content: String,
+            #[serde(default)]
+            tags: Vec<String>,
+            created_at: DateTime<Utc>,
+            accessed_at: DateTime<Utc>,
+            #[serde(default)]
+            access_count: u64,
+            #[serde(default)]
+            metadata: HashMap<String, Value>,
+        }
+
+        let data = fs::read_to_string(legacy_path)
+            .map_err(|e| Error::Other(format!("Failed to read legacy memories: {}", e)))?;
+        let legacy: Vec<LegacyMemory> = serde_json::from_str(&data)
+            .map_err(|e| Error::Other(format!("Failed to parse legacy memories: {}", e)))?;
+
+        let mut imported = 0;
+        for memory in legacy {
+            if storage.entries.contains_key(&memory.id) {
+                continue;
+            }
+            let metadata = memory
+                .metadata
+                .into_iter()
+                .map(|(key, value)| match value {
+                    Value::String(s) => (key, s),
+                    other => (key, other.to_string()),
+                })
+                .collect();
+            storage.add_entry(MemoryEntry {
+                id: memory.id,
+                content: memory.content,
+                tags: memory.tags,
+                created_at: memory.created_at,
+                updated_at: memory.accessed_at,
+                metadata,
+                access_count: memory.access_count,
+                expires_at: None,
+            });
+            imported += 1;
+        }
+
+        Ok(imported)
     }
 
-    fn get_storage_path() -> PathBuf {
-        let home_dir = std::env::home_dir().unwrap_or_else(|| PathBuf::from("."));
-        home_dir.join(".claude_memory.json")
+    /// Take an advisory lock on the `.lock` file beside `path`, released on drop
+    fn lock_storage(path: &Path) -> Result<File> {
+        let lock_file = OpenOptions::new()
+            .create(true)
+            .truncate(false)
+            .write(true)
+            .open(path.with_extension("json.lock"))
+            .map_err(|e| Error::Other(format!("Failed to open memory lock file: {}", e)))?;
+        lock_file
+            .lock()
+            .map_err(|e| Error::Other(format!("Failed to lock memory file: {}", e)))?;
+        Ok(lock_file)
     }
 
-    fn load_storage() -> Result<MemoryStorage> {
-        let path = Self::get_storage_path();
+    /// Read the stored memories under the file lock
+    fn load_storage(path: &Path) -> Result<MemoryStorage> {
+        if !path.exists() {
+            return Ok(MemoryStorage::new());
+        }
+        let _lock = Self::lock_storage(path)?;
+        Self::read_storage(path)
+    }
 
-        if path.exists() {
-            let data = fs::read_to_string(&path)
-                .map_err(|e| Error::Other(format!("Failed to read memory file: {}", e)))?;
+    /// Read the stored memories; the caller must hold the file lock
+    fn read_storage(path: &Path) -> Result<MemoryStorage> {
+        if !path.exists() {
+            return Ok(MemoryStorage::new());
+        }
 
-            serde_json::from_str(&data)
-                .map_err(|e| Error::Other(format!("Failed to parse memory file: {}", e)))
-        } else {
-            Ok(MemoryStorage::new())
+        let data = fs::read_to_string(path)
+            .map_err(|e| Error::Other(format!("Failed to read memory file: {}", e)))?;
+
+        match serde_json::from_str(&data) {
+            Ok(storage) => Ok(storage),
+            Err(e) => {
+                // Keep the damaged file for inspection and start over
+                let backup = path.with_extension("json.bak");
+                fs::rename(path, &backup).map_err(|rename_err| {
+                    Error::Other(format!(
+                        "Failed to parse memory file ({}) and could not back it up: {}",
+                        e, rename_err
+                    ))
+                })?;
+                tracing::warn!(
+                    path = %path.display(),
+                    backup = %backup.display(),
+                    error = %e,
+                    "memory file was corrupt; moved it aside and started fresh"
+                );
+                Ok(MemoryStorage::new())
+            }
         }
     }
 
-    async fn save_storage(&self) -> Result<()> {
-        let path = Self::get_storage_path();
-        let storage = self.storage.read().await;
+    /// Apply `change` to the stored memories while holding the file lock
+    ///
+    /// The file is re-read under the lock first, so updates made by other
+    /// instances or processes are kept rather than overwritten, and written
+    /// back only if `change` reports that it modified something.
+    async fn transact<T>(
+        &self,
+        change: impl FnOnce(&mut MemoryStorage) -> Result<(T, bool)>,
+    ) -> Result<T> {
+        let mut storage = self.storage.write().await;
+        let _lock = Self::lock_storage(&self.path)?;
+        *storage = Self::read_storage(&self.path)?;
+        let (value, changed) = change(&mut storage)?;
+        if changed {
+            Self::write_storage(&self.path, &storage)?;
+        }
+        Ok(value)
+    }
 
-        let data = serde_json::to_string_pretty(&*storage)
+    /// Write the stored memories; the caller must hold the file lock
+    fn write_storage(path: &Path, storage: &MemoryStorage) -> Result<()> {
+        let data = serde_json::to_string_pretty(storage)
             .map_err(|e| Error::Other(format!("Failed to serialize memory: {}", e)))?;
 
-        fs::write(&path, data)
+        // Write to a temporary file in the same directory, then rename it over the
+        // old file so readers only ever see a complete file
+        let dir = match path.parent() {
+            Some(dir) if !dir.as_os_str().is_empty() => dir,
+            _ => Path::new("."),
+        };
+        let mut temp_file = tempfile::NamedTempFile::new_in(dir)
+            .map_err(|e| Error::Other(format!("Failed to create temporary memory file: {}", e)))?;
+        temp_file
+            .write_all(data.as_bytes())
+            .and_then(|_| temp_file.as_file().sync_all())
+            .map_err(|e| Error::Other(format!("Failed to write memory file: {}", e)))?;
+        temp_file
+            .persist(path)
             .map_err(|e| Error::Other(format!("Failed to write memory file: {}", e)))?;
 
         Ok(())
@@ -205,6 +727,7 @@ enum MemoryAction {
         content: String,
         tags: Option<Vec<String>>,
         metadata: Option<HashMap<String, String>>,
+        ttl_seconds: Option<u64>,
     },
     #[serde(rename = "search")]
     Search {
@@ -219,10 +742,14 @@ enum MemoryAction {
         tags: Option<Vec<String>>,
         metadata: Option<HashMap<String, String>>,
     },
+    #[serde(rename = "get")]
+    Get { id: String },
     #[serde(rename = "delete")]
     Delete { id: String },
     #[serde(rename = "list_tags")]
     ListTags,
+    #[serde(rename = "prune_expired")]
+    PruneExpired,
 }
 
 #[async_trait]
@@ -241,7 +768,7 @@ impl Tool for EnhancedMemoryTool {
             "properties": {
                 "action": {
                     "type": "string",
-                    "enum": ["store", "search", "update", "delete", "list_tags"],
+                    "enum": ["store", "search", "get", "update", "delete", "list_tags", "prune_expired"],
                     "description": "The memory operation to perform"
                 },
                 "content": {
@@ -250,7 +777,7 @@ impl Tool for EnhancedMemoryTool {
                 },
                 "id": {
                     "type": "string",
-                    "description": "Memory entry ID (for update/delete actions)"
+                    "description": "Memory entry ID (for get/update/delete actions)"
                 },
                 "tags": {
                     "type": "array",
@@ -268,11 +795,16 @@ impl Tool for EnhancedMemoryTool {
                 },
                 "query": {
                     "type": "string",
-                    "description": "Search query to filter memories"
+                    "description": "Search query; results are ranked by relevance (BM25 over content and tags) and include a 'score'"
                 },
                 "limit": {
                     "type": "integer",
                     "description": "Maximum number of results to return"
+                },
+                "ttl_seconds": {
+                    "type": "integer",
+                    "minimum": 1,
+                    "description": "For 'store': seconds until the memory expires and is no longer returned, for short-lived facts (default: never expires)"
                 }
             },
             "required": ["action"],
@@ -291,8 +823,21 @@ impl Tool for EnhancedMemoryTool {
                 content,
                 tags,
                 metadata,
+                ttl_seconds,
             } => {
                 let id = Uuid::new_v4().to_string();
+                let expires_at = match ttl_seconds {
+                    Some(ttl) => Some(
+                        i64::try_from(ttl)
+                            .ok()
+                            .and_then(chrono::TimeDelta::try_seconds)
+                            .and_then(|ttl| Utc::now().checked_add_signed(ttl))
+                            .ok_or_else(|| {
+                                Error::Other(format!("ttl_seconds {} is too large", ttl))
+                            })?,
+                    ),
+                    None => None,
+                };
                 let entry = MemoryEntry {
                     id: id.clone(),
                     content,
@@ -300,25 +845,36 @@ impl Tool for EnhancedMemoryTool {
                     created_at: Utc::now(),
                     updated_at: Utc::now(),
                     metadata: metadata.unwrap_or_default(),
+                    access_count: 0,
+                    expires_at,
                 };
 
-                let mut storage = self.storage.write().await;
-                storage.add_entry(entry);
-                drop(storage);
-
-                self.save_storage().await?;
+                self.transact(|storage| {
+                    storage.add_entry(entry);
+                    Ok(((), true))
+                })
+                .await?;
 
-                Ok(json!({
+                let mut response = json!({
                     "success": true,
                     "id": id,
                     "message": "Memory stored successfully"
-                })
-                .to_string())
+                });
+                if let Some(expires_at) = expires_at {
+                    response["expires_at"] = json!(expires_at);
+                }
+                Ok(response.to_string())
             }
 
             MemoryAction::Search { query, tags, limit } => {
-                let storage = self.storage.read().await;
-                let results = storage.search(query.as_deref(), tags.as_deref(), limit.or(Some(10)));
+                let results = self
+                    .transact(|storage| {
+                        let pruned = storage.prune_expired(Utc::now());
+                        let results =
+                            storage.search(query.as_deref(), tags.as_deref(), limit.or(Some(10)));
+                        Ok((results, !pruned.is_empty()))
+                    })
+                    .await?;
 
                 Ok(json!({
                     "success": true,
@@ -334,11 +890,11 @@ impl Tool for EnhancedMemoryTool {
                 tags,
                 metadata,
             } => {
-                let mut storage = self.storage.write().await;
-                storage.update_entry(&id, content, tags, metadata)?;
-                drop(storage);
-
-                self.save_storage().await?;
+                self.transact(|storage| {
+                    storage.update_entry(&id, content, tags, metadata)?;
+                    Ok(((), true))
+                })
+                .await?;
 
                 Ok(json!({
                     "success": true,
@@ -347,12 +903,29 @@ impl Tool for EnhancedMemoryTool {
                 .to_string())
             }
 
-            MemoryAction::Delete { id } => {
-                let mut storage = self.storage.write().await;
-                storage.delete(&id)?;
-                drop(storage);
+            MemoryAction::Get { id } => {
+                let entry = self
+                    .transact(|storage| {
+                        let pruned = storage.prune_expired(Utc::now());
+                        let entry = storage.get(&id);
+                        let changed = entry.is_ok() || !pruned.is_empty();
+                        Ok((entry, changed))
+                    })
+                    .await??;
 
-                self.save_storage().await?;
+                Ok(json!({
+                    "success": true,
+                    "entry": entry
+                })
+                .to_string())
+            }
+
+            MemoryAction::Delete { id } => {
+                self.transact(|storage| {
+                    storage.delete(&id)?;
+                    Ok(((), true))
+                })
+                .await?;
 
                 Ok(json!({
                     "success": true,
@@ -362,12 +935,16 @@ impl Tool for EnhancedMemoryTool {
             }
 
             MemoryAction::ListTags => {
-                let storage = self.storage.read().await;
-                let mut tags: Vec<(String, usize)> = storage
-                    .tag_index
-                    .iter()
-                    .map(|(tag, ids)| (tag.clone(), ids.len()))
-                    .collect();
+                let mut tags: Vec<(String, usize)> = self
+                    .transact(|storage| {
+                        let tags = storage
+                            .tag_index
+                            .iter()
+                            .map(|(tag, ids)| (tag.clone(), ids.len()))
+                            .collect();
+                        Ok((tags, false))
+                    })
+                    .await?;
 
                 // Sort by count (descending)
                 tags.sort_by(|a, b| b.1.cmp(&a.1));
@@ -383,6 +960,23 @@ impl Tool for EnhancedMemoryTool {
                 })
                 .to_string())
             }
+
+            MemoryAction::PruneExpired => {
+                let pruned = self
+                    .transact(|storage| {
+                        let pruned = storage.prune_expired(Utc::now());
+                        let changed = !pruned.is_empty();
+                        Ok((pruned, changed))
+                    })
+                    .await?;
+
+                Ok(json!({
+                    "success": true,
+                    "pruned": pruned.len(),
+                    "ids": pruned
+                })
+                .to_string())
+            }
         }
     }
 }
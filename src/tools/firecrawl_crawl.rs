@@ -1,10 +1,96 @@
+use crate::tools::firecrawl_error::{
+    describe_error, missing_api_key, FirecrawlErrorCode, FirecrawlFailure,
+};
 use crate::{Error, Result, Tool};
 use async_trait::async_trait;
-use firecrawl::crawl::{CrawlOptions, CrawlScrapeOptions};
+use firecrawl::crawl::{CrawlOptions, CrawlScrapeOptions, CrawlStatus, CrawlStatusTypes};
 use firecrawl::FirecrawlApp;
 use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
+use std::time::{Duration, Instant};
 
+const DEFAULT_POLL_INTERVAL_MS: u64 = 2000;
+const DEFAULT_CRAWL_TIMEOUT_SECS: u64 = 300;
+
+/// Firecrawl Crawl Tool - Follows links from a starting URL and scrapes every page
+///
+/// Crawls run asynchronously on Firecrawl's side: the tool starts a crawl job,
+/// then polls its status until it completes, fails, or `crawl_timeout` elapses.
+/// Set `FIRECRAWL_API_URL` to use a self-hosted Firecrawl instance instead of
+/// the cloud API.
+///
+/// # Example
+///
+/// Crawling against a mock Firecrawl server that reports the job as still
+/// running on the first poll:
+///
+/// ```rust
+/// use claude::test_util::MockServer;
+/// use claude::transport::RawResponse;
+/// use claude::tools::FirecrawlCrawlTool;
+/// use claude::Tool;
+/// use serde_json::{json, Value};
+///
+/// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+/// let page = |url: &str, title: &str| json!({
+///     "markdown": format!("# {}", title),
+///     "metadata": {"sourceURL": url, "statusCode": 200, "title": title}
+/// });
+/// let mut polls = 0;
+/// let server = MockServer::start(move |request| {
+///     let reply = if request.url.ends_with("/v1/crawl") {
+///         json!({"success": true, "id": "job-1", "url": "http://localhost/v1/crawl/job-1"})
+///     } else if request.url.ends_with("/v1/crawl/job-1") {
+///         polls += 1;
+///         if polls == 1 {
+///             json!({"success": true, "status": "scraping", "total": 2, "completed": 1,
+///                    "creditsUsed": 1, "expiresAt": "2030-01-01T00:00:00Z", "data": []})
+///         } else {
+///             json!({"success": true, "status": "completed", "total": 2, "completed": 2,
+///                    "creditsUsed": 2, "expiresAt": "2030-01-01T00:00:00Z",
+///                    "data": [page("https://example.com/docs", "Docs"),
+///                             page("https://example.com/docs/intro", "Intro")]})
+///         }
+///     } else {
+///         json!({"success": false, "error": "unexpected request"})
+///     };
+///     RawResponse::new(200, reply.to_string())
+/// })
+/// .await?;
+///
+/// std::env::set_var("FIRECRAWL_API_URL", server.url());
+/// std::env::set_var("FIRECRAWL_API_KEY", "test-key");
+///
+/// let output: Value = serde_json::from_str(
+///     &FirecrawlCrawlTool
+///         .execute(json!({
+///             "url": "https://example.com/docs",
+///             "max_depth": 1,
+///             "limit": 5,
+///             "include_paths": ["/docs/**"],
+///             "exclude_paths": ["/docs/*.pdf"],
+///             "poll_interval": 10
+///         }))
+///         .await?,
+/// )?;
+///
+/// assert_eq!(output["success"], true);
+/// assert_eq!(output["status"], "completed");
+/// assert_eq!(output["crawl_id"], "job-1");
+/// assert_eq!(output["completed_pages"], 2);
+/// assert_eq!(output["pages"][1]["url"], "https://example.com/docs/intro");
+/// assert_eq!(output["pages"][1]["title"], "Intro");
+/// assert_eq!(output["pages"][1]["markdown"], "# Intro");
+///
+/// // Globs are sent to Firecrawl as anchored regular expressions
+/// let start: Value = serde_json::from_str(&server.requests()[0].body_text())?;
+/// assert_eq!(start["maxDepth"], 1);
+/// assert_eq!(start["limit"], 5);
+/// assert_eq!(start["includePaths"], json!(["^/docs/.*$"]));
+/// assert_eq!(start["excludePaths"], json!(["^/docs/[^/]*\\.pdf$"]));
+/// # Ok::<(), claude::Error>(())
+/// # }).unwrap();
+/// ```
 pub struct FirecrawlCrawlTool;
 
 #[derive(Debug, Deserialize)]
@@ -12,22 +98,30 @@ pub struct FirecrawlCrawlInput {
     url: String,
     max_depth: Option<u32>,
     limit: Option<u32>,
-    exclude_patterns: Option<Vec<String>>,
-    include_patterns: Option<Vec<String>>,
+    #[serde(alias = "exclude_patterns")]
+    exclude_paths: Option<Vec<String>>,
+    #[serde(alias = "include_patterns")]
+    include_paths: Option<Vec<String>>,
     allow_backward_links: Option<bool>,
     allow_external_links: Option<bool>,
     headers: Option<std::collections::HashMap<String, String>>,
     wait_for: Option<u32>,
     timeout: Option<u32>,
+    poll_interval: Option<u64>,
+    crawl_timeout: Option<u64>,
 }
 
 #[derive(Debug, Serialize)]
 pub struct FirecrawlCrawlResponse {
     success: bool,
+    crawl_id: Option<String>,
+    status: String,
     total_pages: usize,
     completed_pages: usize,
     pages: Vec<CrawledPage>,
     error: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error_code: Option<FirecrawlErrorCode>,
 }
 
 #[derive(Debug, Serialize)]
@@ -41,6 +135,71 @@ pub struct CrawledPage {
     metadata: Option<Value>,
 }
 
+/// Translate a URL path glob into the anchored regex Firecrawl expects
+///
+/// `**` matches across path segments, `*` and `?` stay within one segment,
+/// and every other character matches literally.
+///
+/// # Example
+///
+/// ```rust
+/// use claude::tools::firecrawl_crawl::glob_to_regex;
+///
+/// assert_eq!(glob_to_regex("/blog/*"), "^/blog/[^/]*$");
+/// assert_eq!(glob_to_regex("/docs/**"), "^/docs/.*$");
+/// assert_eq!(glob_to_regex("/v?/api.json"), "^/v[^/]/api\\.json$");
+/// ```
+pub fn glob_to_regex(glob: &str) -> String {
+    let mut regex = String::from("^");
+    let mut chars = glob.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '*' if chars.peek() == Some(&'*') => {
+                chars.next();
+                regex.push_str(".*");
+            }
+            '*' => regex.push_str("[^/]*"),
+            '?' => regex.push_str("[^/]"),
+            '.' | '+' | '(' | ')' | '|' | '^' | '$' | '[' | ']' | '{' | '}' | '\\' => {
+                regex.push('\\');
+                regex.push(c);
+            }
+            _ => regex.push(c),
+        }
+    }
+    regex.push('$');
+    regex
+}
+
+/// Build a client for the cloud API, or for `FIRECRAWL_API_URL` when set
+fn firecrawl_app() -> Result<FirecrawlApp> {
+    let api_key = std::env::var("FIRECRAWL_API_KEY").ok();
+    let app = match std::env::var("FIRECRAWL_API_URL") {
+        Ok(api_url) if !api_url.is_empty() => FirecrawlApp::new_selfhosted(api_url, api_key),
+        _ => {
+            let api_key = api_key.ok_or_else(missing_api_key)?;
+            FirecrawlApp::new(api_key)
+        }
+    };
+    app.map_err(|e| Error::Other(format!("Failed to initialize Firecrawl: {:?}", e)))
+}
+
+fn crawled_pages(status: CrawlStatus) -> Vec<CrawledPage> {
+    status
+        .data
+        .into_iter()
+        .map(|doc| CrawledPage {
+            url: doc.metadata.source_url.clone(),
+            title: doc.metadata.title.clone(),
+            content: doc.markdown.clone(),
+            markdown: doc.markdown,
+            html: doc.html,
+            links: doc.links,
+            metadata: Some(serde_json::to_value(&doc.metadata).unwrap_or(Value::Null)),
+        })
+        .collect()
+}
+
 #[async_trait]
 impl Tool for FirecrawlCrawlTool {
     fn name(&self) -> &str {
@@ -48,7 +207,7 @@ impl Tool for FirecrawlCrawlTool {
     }
 
     fn description(&self) -> &str {
-        "Crawl websites using Firecrawl API - a powerful web scraping service that handles JavaScript rendering, anti-bot measures, and content extraction. Can crawl entire websites or specific sections based on URL patterns."
+        "Crawl websites using Firecrawl API - a powerful web scraping service that handles JavaScript rendering, anti-bot measures, and content extraction. Follows links from the starting URL up to max_depth/limit and returns each page's markdown and metadata. Use include_paths/exclude_paths globs to restrict which sections are crawled."
     }
 
     fn input_schema(&self) -> Value {
@@ -65,17 +224,17 @@ impl Tool for FirecrawlCrawlTool {
                 },
                 "limit": {
                     "type": "integer",
-                    "description": "Maximum number of pages to crawl"
+                    "description": "Maximum number of pages to crawl (default: 10)"
                 },
-                "exclude_patterns": {
+                "exclude_paths": {
                     "type": "array",
                     "items": {"type": "string"},
-                    "description": "URL patterns to exclude from crawling"
+                    "description": "URL path globs to skip, e.g. '/blog/*' or '/archive/**'"
                 },
-                "include_patterns": {
+                "include_paths": {
                     "type": "array",
                     "items": {"type": "string"},
-                    "description": "URL patterns to include in crawling"
+                    "description": "URL path globs to crawl exclusively, e.g. '/docs/**'"
                 },
                 "allow_backward_links": {
                     "type": "boolean",
@@ -95,7 +254,15 @@ impl Tool for FirecrawlCrawlTool {
                 },
                 "timeout": {
                     "type": "integer",
-                    "description": "Request timeout (milliseconds)"
+                    "description": "Per-page request timeout (milliseconds)"
+                },
+                "poll_interval": {
+                    "type": "integer",
+                    "description": "How often to check the crawl job's status (milliseconds, default: 2000)"
+                },
+                "crawl_timeout": {
+                    "type": "integer",
+                    "description": "Maximum time to wait for the whole crawl to finish (seconds, default: 300)"
                 }
             },
             "required": ["url"],
@@ -107,94 +274,126 @@ impl Tool for FirecrawlCrawlTool {
         let params: FirecrawlCrawlInput = serde_json::from_value(input)
             .map_err(|e| Error::Other(format!("Invalid input parameters: {}", e)))?;
 
-        let api_key = std::env::var("FIRECRAWL_API_KEY").map_err(|_| {
-            Error::Other("FIRECRAWL_API_KEY environment variable not set".to_string())
-        })?;
+        let firecrawl = firecrawl_app()?;
 
-        let firecrawl = FirecrawlApp::new(&api_key)
-            .map_err(|e| Error::Other(format!("Failed to initialize Firecrawl: {:?}", e)))?;
+        let scrape_options = CrawlScrapeOptions {
+            headers: params.headers,
+            wait_for: params.wait_for,
+            timeout: params.timeout,
+            ..Default::default()
+        };
 
-        let mut scrape_options = CrawlScrapeOptions::default();
+        let crawl_options = CrawlOptions {
+            scrape_options: Some(scrape_options),
+            max_depth: params.max_depth,
+            limit: params.limit,
+            exclude_paths: params.exclude_paths.map(|globs| {
+                globs
+                    .iter()
+                    .map(String::as_str)
+                    .map(glob_to_regex)
+                    .collect()
+            }),
+            include_paths: params.include_paths.map(|globs| {
+                globs
+                    .iter()
+                    .map(String::as_str)
+                    .map(glob_to_regex)
+                    .collect()
+            }),
+            allow_backward_links: params.allow_backward_links,
+            allow_external_links: params.allow_external_links,
+            ..Default::default()
+        };
 
-        if let Some(headers) = params.headers {
-            scrape_options.headers = Some(headers);
-        }
+        let poll_interval =
+            Duration::from_millis(params.poll_interval.unwrap_or(DEFAULT_POLL_INTERVAL_MS));
+        let crawl_timeout =
+            Duration::from_secs(params.crawl_timeout.unwrap_or(DEFAULT_CRAWL_TIMEOUT_SECS));
 
-        if let Some(wait_for) = params.wait_for {
-            scrape_options.wait_for = Some(wait_for);
-        }
+        let failure = |crawl_id: Option<String>, failure: FirecrawlFailure| {
+            let response = FirecrawlCrawlResponse {
+                success: false,
+                crawl_id,
+                status: "failed".to_string(),
+                total_pages: 0,
+                completed_pages: 0,
+                pages: vec![],
+                error: Some(failure.message),
+                error_code: Some(failure.code),
+            };
+            serde_json::to_string_pretty(&response)
+                .map_err(|e| Error::Other(format!("Failed to serialize error response: {}", e)))
+        };
 
-        if let Some(timeout) = params.timeout {
-            scrape_options.timeout = Some(timeout);
-        }
+        let job = match firecrawl
+            .crawl_url_async(&params.url, Some(crawl_options))
+            .await
+        {
+            Ok(job) => job,
+            Err(e) => return failure(None, describe_error("Crawl", &e)),
+        };
 
-        let mut crawl_options = CrawlOptions::default();
-        crawl_options.scrape_options = Some(scrape_options);
-
-        if let Some(max_depth) = params.max_depth {
-            crawl_options.max_depth = Some(max_depth);
-        }
-
-        if let Some(limit) = params.limit {
-            crawl_options.limit = Some(limit);
-        }
-
-        if let Some(exclude) = params.exclude_patterns {
-            crawl_options.exclude_paths = Some(exclude);
-        }
+        let deadline = Instant::now() + crawl_timeout;
+        loop {
+            let status = match firecrawl.check_crawl_status(&job.id).await {
+                Ok(status) => status,
+                Err(e) => return failure(Some(job.id), describe_error("Crawl status check", &e)),
+            };
 
-        if let Some(include) = params.include_patterns {
-            crawl_options.include_paths = Some(include);
-        }
-
-        if let Some(allow_backward) = params.allow_backward_links {
-            crawl_options.allow_backward_links = Some(allow_backward);
-        }
+            let (success, label, error) = match status.status {
+                CrawlStatusTypes::Completed => (true, "completed", None),
+                CrawlStatusTypes::Failed => (
+                    false,
+                    "failed",
+                    Some(FirecrawlFailure {
+                        code: FirecrawlErrorCode::JobFailed,
+                        message: "Crawl job failed".to_string(),
+                    }),
+                ),
+                CrawlStatusTypes::Cancelled => (
+                    false,
+                    "cancelled",
+                    Some(FirecrawlFailure {
+                        code: FirecrawlErrorCode::JobFailed,
+                        message: "Crawl job was cancelled".to_string(),
+                    }),
+                ),
+                CrawlStatusTypes::Scraping if Instant::now() + poll_interval > deadline => {
+                    // Stop the job so it doesn't keep spending credits; any
+                    // pages scraped so far are still returned below
+                    let _ = firecrawl.cancel_crawl(&job.id).await;
+                    (
+                        false,
+                        "timeout",
+                        Some(FirecrawlFailure {
+                            code: FirecrawlErrorCode::Timeout,
+                            message: format!(
+                                "Crawl did not finish within {:?} ({} of {} pages scraped)",
+                                crawl_timeout, status.completed, status.total
+                            ),
+                        }),
+                    )
+                }
+                CrawlStatusTypes::Scraping => {
+                    tokio::time::sleep(poll_interval).await;
+                    continue;
+                }
+            };
 
-        if let Some(allow_external) = params.allow_external_links {
-            crawl_options.allow_external_links = Some(allow_external);
-        }
+            let response = FirecrawlCrawlResponse {
+                success,
+                crawl_id: Some(job.id),
+                status: label.to_string(),
+                total_pages: status.total as usize,
+                completed_pages: status.completed as usize,
+                pages: crawled_pages(status),
+                error: error.as_ref().map(|failure| failure.message.clone()),
+                error_code: error.map(|failure| failure.code),
+            };
 
-        match firecrawl.crawl_url(&params.url, Some(crawl_options)).await {
-            Ok(crawl_result) => {
-                let pages: Vec<CrawledPage> = crawl_result
-                    .data
-                    .into_iter()
-                    .enumerate()
-                    .map(|(i, doc)| CrawledPage {
-                        url: format!("page_{}", i), // Documents don't have URLs in crawl results
-                        title: doc.metadata.title.clone(),
-                        content: doc.markdown.clone(),
-                        markdown: doc.markdown,
-                        html: doc.html,
-                        links: doc.links,
-                        metadata: Some(serde_json::to_value(&doc.metadata).unwrap_or(Value::Null)),
-                    })
-                    .collect();
-
-                let response = FirecrawlCrawlResponse {
-                    success: true,
-                    total_pages: crawl_result.total as usize,
-                    completed_pages: crawl_result.completed as usize,
-                    pages,
-                    error: None,
-                };
-
-                serde_json::to_string_pretty(&response)
-                    .map_err(|e| Error::Other(format!("Failed to serialize response: {}", e)))
-            }
-            Err(e) => {
-                let response = FirecrawlCrawlResponse {
-                    success: false,
-                    total_pages: 0,
-                    completed_pages: 0,
-                    pages: vec![],
-                    error: Some(format!("Crawl failed: {:?}", e)),
-                };
-
-                serde_json::to_string_pretty(&response)
-                    .map_err(|e| Error::Other(format!("Failed to serialize error response: {}", e)))
-            }
+            return serde_json::to_string_pretty(&response)
+                .map_err(|e| Error::Other(format!("Failed to serialize response: {}", e)));
         }
     }
 }
@@ -0,0 +1,224 @@
+use crate::Error;
+use firecrawl::FirecrawlError;
+use serde::Serialize;
+
+/// Machine-readable kind of a Firecrawl failure
+///
+/// Sent as `error_code` in the responses of the Firecrawl tools.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FirecrawlErrorCode {
+    /// The API key was missing, invalid, or revoked
+    AuthFailed,
+    /// The account has run out of credits
+    InsufficientCredits,
+    /// Too many requests in a short time
+    RateLimited,
+    /// Firecrawl or the target site took too long
+    Timeout,
+    /// The target URL couldn't be resolved or connected to
+    UrlUnreachable,
+    /// The target site blocked scraping, e.g. with a paywall or bot check
+    Blocked,
+    /// Firecrawl itself couldn't be reached
+    ServiceUnreachable,
+    /// Firecrawl rejected the request's parameters
+    InvalidRequest,
+    /// Firecrawl had an internal error
+    ServerError,
+    /// A crawl job ended in failure
+    JobFailed,
+    /// Firecrawl's response couldn't be read
+    BadResponse,
+    /// Anything not covered above
+    Unknown,
+}
+
+/// A Firecrawl failure described for whoever has to act on it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FirecrawlFailure {
+    /// Kind of failure
+    pub code: FirecrawlErrorCode,
+    /// What went wrong and, where possible, what to do about it
+    pub message: String,
+}
+
+/// The error returned when no Firecrawl API key is configured
+pub fn missing_api_key() -> Error {
+    Error::Other(
+        "FIRECRAWL_API_KEY is not set. Get a key at https://firecrawl.dev, then export \
+         FIRECRAWL_API_KEY=fc-... or add that line to ~/.generalist.env"
+            .to_string(),
+    )
+}
+
+/// Failure kind for an HTTP status returned by Firecrawl
+fn code_for_status(status: u16) -> FirecrawlErrorCode {
+    match status {
+        401 | 403 => FirecrawlErrorCode::AuthFailed,
+        402 => FirecrawlErrorCode::InsufficientCredits,
+        408 | 504 => FirecrawlErrorCode::Timeout,
+        429 => FirecrawlErrorCode::RateLimited,
+        400 | 404 | 422 => FirecrawlErrorCode::InvalidRequest,
+        500..=599 => FirecrawlErrorCode::ServerError,
+        _ => FirecrawlErrorCode::Unknown,
+    }
+}
+
+/// Failure kind for an error message from the Firecrawl API
+fn code_for_message(message: &str) -> FirecrawlErrorCode {
+    let message = message.to_lowercase();
+    let mentions = |needles: &[&str]| needles.iter().any(|needle| message.contains(needle));
+
+    if mentions(&["unauthorized", "invalid token", "api key", "authentication"]) {
+        FirecrawlErrorCode::AuthFailed
+    } else if mentions(&[
+        "insufficient credits",
+        "payment required",
+        "upgrade your plan",
+    ]) {
+        FirecrawlErrorCode::InsufficientCredits
+    } else if mentions(&["rate limit", "too many requests"]) {
+        FirecrawlErrorCode::RateLimited
+    } else if mentions(&["timeout", "timed out"]) {
+        FirecrawlErrorCode::Timeout
+    } else if mentions(&[
+        "dns",
+        "could not resolve",
+        "name_not_resolved",
+        "connection refused",
+        "unreachable",
+        "failed to fetch",
+    ]) {
+        FirecrawlErrorCode::UrlUnreachable
+    } else if mentions(&[
+        "paywall",
+        "blocked",
+        "forbidden",
+        "captcha",
+        "not supported",
+    ]) {
+        FirecrawlErrorCode::Blocked
+    } else if mentions(&["invalid", "bad request", "validation"]) {
+        FirecrawlErrorCode::InvalidRequest
+    } else {
+        FirecrawlErrorCode::Unknown
+    }
+}
+
+/// What to tell the user for each kind of failure
+fn advice(code: FirecrawlErrorCode) -> &'static str {
+    match code {
+        FirecrawlErrorCode::AuthFailed => "Firecrawl rejected the API key; check FIRECRAWL_API_KEY",
+        FirecrawlErrorCode::InsufficientCredits => {
+            "The Firecrawl account is out of credits; top up or upgrade the plan"
+        }
+        FirecrawlErrorCode::RateLimited => {
+            "Firecrawl's rate limit was hit; wait a minute before trying again"
+        }
+        FirecrawlErrorCode::Timeout => {
+            "The request timed out; try again, or a smaller page or crawl"
+        }
+        FirecrawlErrorCode::UrlUnreachable => {
+            "The URL couldn't be reached; check that it is spelled correctly and the site is up"
+        }
+        FirecrawlErrorCode::Blocked => {
+            "The site blocked scraping (paywall, login, or bot protection); try another source"
+        }
+        FirecrawlErrorCode::ServiceUnreachable => {
+            "Firecrawl couldn't be reached; check the network connection or FIRECRAWL_API_URL"
+        }
+        FirecrawlErrorCode::InvalidRequest => "Firecrawl rejected the request's parameters",
+        FirecrawlErrorCode::ServerError => "Firecrawl had an internal error; try again later",
+        FirecrawlErrorCode::JobFailed => "The Firecrawl job failed",
+        FirecrawlErrorCode::BadResponse => "Firecrawl sent a response that couldn't be read",
+        FirecrawlErrorCode::Unknown => "Firecrawl request failed",
+    }
+}
+
+/// Turn a Firecrawl error into a readable message and an error code
+///
+/// `action` names what was being attempted, e.g. `"Search"`, and starts the
+/// message.
+///
+/// # Example
+///
+/// ```rust
+/// use claude::tools::firecrawl_error::{describe_error, FirecrawlErrorCode};
+/// use firecrawl::FirecrawlError;
+/// use serde_json::json;
+///
+/// // The API error type isn't exported, but it can be deserialized
+/// let api_error = |message: &str| {
+///     FirecrawlError::APIError(
+///         "scrape".to_string(),
+///         serde_json::from_value(json!({"success": false, "error": message})).unwrap(),
+///     )
+/// };
+///
+/// let cases = [
+///     (FirecrawlError::HttpRequestFailed("search".into(), 401, "Unauthorized".into()), FirecrawlErrorCode::AuthFailed),
+///     (FirecrawlError::HttpRequestFailed("search".into(), 402, "Payment Required".into()), FirecrawlErrorCode::InsufficientCredits),
+///     (FirecrawlError::HttpRequestFailed("search".into(), 429, "Too Many Requests".into()), FirecrawlErrorCode::RateLimited),
+///     (FirecrawlError::HttpRequestFailed("search".into(), 504, "Gateway Timeout".into()), FirecrawlErrorCode::Timeout),
+///     (FirecrawlError::HttpRequestFailed("search".into(), 502, "Bad Gateway".into()), FirecrawlErrorCode::ServerError),
+///     (api_error("Unauthorized: Invalid token"), FirecrawlErrorCode::AuthFailed),
+///     (api_error("Rate limit exceeded. Consumed (req/min): 11"), FirecrawlErrorCode::RateLimited),
+///     (api_error("Request timed out"), FirecrawlErrorCode::Timeout),
+///     (api_error("Specified URL is unreachable: ERR_NAME_NOT_RESOLVED"), FirecrawlErrorCode::UrlUnreachable),
+///     (api_error("This website is behind a paywall"), FirecrawlErrorCode::Blocked),
+///     (api_error("Something odd happened"), FirecrawlErrorCode::Unknown),
+///     (FirecrawlError::Missuse("Poll interval must be positive".into()), FirecrawlErrorCode::InvalidRequest),
+///     (
+///         FirecrawlError::ResponseParseError(serde_json::from_str::<u8>("{").unwrap_err()),
+///         FirecrawlErrorCode::BadResponse,
+///     ),
+/// ];
+/// for (error, code) in cases {
+///     let failure = describe_error("Search", &error);
+///     assert_eq!(failure.code, code, "{}", error);
+///     assert!(failure.message.starts_with("Search failed: "), "{}", failure.message);
+/// }
+///
+/// // The API's own message is kept for context, without Debug noise
+/// let failure = describe_error("Extract", &api_error("This website is behind a paywall"));
+/// assert_eq!(
+///     failure.message,
+///     "Extract failed: The site blocked scraping (paywall, login, or bot protection); \
+///      try another source (This website is behind a paywall)"
+/// );
+/// ```
+pub fn describe_error(action: &str, error: &FirecrawlError) -> FirecrawlFailure {
+    let (code, detail) = match error {
+        FirecrawlError::HttpRequestFailed(_, status, reason) => (
+            code_for_status(*status),
+            format!("HTTP {} {}", status, reason),
+        ),
+        FirecrawlError::HttpError(_, e) if e.is_timeout() => {
+            (FirecrawlErrorCode::Timeout, e.to_string())
+        }
+        FirecrawlError::HttpError(_, e) => {
+            let code = match e.status() {
+                Some(status) => code_for_status(status.as_u16()),
+                None => FirecrawlErrorCode::ServiceUnreachable,
+            };
+            (code, e.to_string())
+        }
+        FirecrawlError::APIError(_, api_error) => {
+            (code_for_message(&api_error.error), api_error.to_string())
+        }
+        FirecrawlError::ResponseParseError(e) => (FirecrawlErrorCode::BadResponse, e.to_string()),
+        FirecrawlError::ResponseParseErrorText(e) => {
+            (FirecrawlErrorCode::BadResponse, e.to_string())
+        }
+        FirecrawlError::CrawlJobFailed(message, _) => {
+            (FirecrawlErrorCode::JobFailed, message.clone())
+        }
+        FirecrawlError::Missuse(message) => (FirecrawlErrorCode::InvalidRequest, message.clone()),
+    };
+
+    FirecrawlFailure {
+        code,
+        message: format!("{} failed: {} ({})", action, advice(code), detail),
+    }
+}
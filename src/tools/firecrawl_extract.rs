@@ -1,3 +1,4 @@
+use crate::tools::firecrawl_error::{describe_error, missing_api_key, FirecrawlErrorCode};
 use crate::{Error, Result, Tool};
 use async_trait::async_trait;
 use firecrawl::scrape::{JsonOptions, ScrapeFormats, ScrapeOptions};
@@ -58,6 +59,8 @@ pub struct FirecrawlExtractResponse {
     images: Option<Vec<String>>,
     metadata: Option<PageMetadata>,
     error: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error_code: Option<FirecrawlErrorCode>,
 }
 
 #[derive(Debug, Serialize)]
@@ -136,9 +139,7 @@ impl Tool for FirecrawlExtractTool {
         let params: FirecrawlExtractInput = serde_json::from_value(input)
             .map_err(|e| Error::Other(format!("Invalid input parameters: {}", e)))?;
 
-        let api_key = std::env::var("FIRECRAWL_API_KEY").map_err(|_| {
-            Error::Other("FIRECRAWL_API_KEY environment variable not set".to_string())
-        })?;
+        let api_key = std::env::var("FIRECRAWL_API_KEY").map_err(|_| missing_api_key())?;
 
         let firecrawl = FirecrawlApp::new(&api_key)
             .map_err(|e| Error::Other(format!("Failed to initialize Firecrawl: {:?}", e)))?;
@@ -242,12 +243,14 @@ impl Tool for FirecrawlExtractTool {
                     images,
                     metadata,
                     error: None,
+                    error_code: None,
                 };
 
                 serde_json::to_string_pretty(&response)
                     .map_err(|e| Error::Other(format!("Failed to serialize response: {}", e)))
             }
             Err(e) => {
+                let failure = describe_error("Extract", &e);
                 let response = FirecrawlExtractResponse {
                     success: false,
                     url: params.url,
@@ -259,7 +262,8 @@ impl Tool for FirecrawlExtractTool {
                     links: None,
                     images: None,
                     metadata: None,
-                    error: Some(format!("Extract failed: {:?}", e)),
+                    error: Some(failure.message),
+                    error_code: Some(failure.code),
                 };
 
                 serde_json::to_string_pretty(&response)
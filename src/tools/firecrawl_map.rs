@@ -1,3 +1,4 @@
+use crate::tools::firecrawl_error::{describe_error, missing_api_key, FirecrawlErrorCode};
 use crate::{Error, Result, Tool};
 use async_trait::async_trait;
 use firecrawl::map::MapOptions;
@@ -25,6 +26,8 @@ pub struct FirecrawlMapResponse {
     sitemap: Vec<SitemapEntry>,
     link_graph: HashMap<String, Vec<String>>,
     error: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error_code: Option<FirecrawlErrorCode>,
 }
 
 #[derive(Debug, Serialize)]
@@ -81,9 +84,7 @@ impl Tool for FirecrawlMapTool {
         let params: FirecrawlMapInput = serde_json::from_value(input)
             .map_err(|e| Error::Other(format!("Invalid input parameters: {}", e)))?;
 
-        let api_key = std::env::var("FIRECRAWL_API_KEY").map_err(|_| {
-            Error::Other("FIRECRAWL_API_KEY environment variable not set".to_string())
-        })?;
+        let api_key = std::env::var("FIRECRAWL_API_KEY").map_err(|_| missing_api_key())?;
 
         let firecrawl = FirecrawlApp::new(&api_key)
             .map_err(|e| Error::Other(format!("Failed to initialize Firecrawl: {:?}", e)))?;
@@ -147,19 +148,22 @@ impl Tool for FirecrawlMapTool {
                     sitemap,
                     link_graph,
                     error: None,
+                    error_code: None,
                 };
 
                 serde_json::to_string_pretty(&response)
                     .map_err(|e| Error::Other(format!("Failed to serialize response: {}", e)))
             }
             Err(e) => {
+                let failure = describe_error("Map", &e);
                 let response = FirecrawlMapResponse {
                     success: false,
                     url: params.url,
                     total_links: 0,
                     sitemap: vec![],
                     link_graph: HashMap::new(),
-                    error: Some(format!("Map failed: {:?}", e)),
+                    error: Some(failure.message),
+                    error_code: Some(failure.code),
                 };
 
                 serde_json::to_string_pretty(&response)
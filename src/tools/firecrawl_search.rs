@@ -1,3 +1,4 @@
+use crate::tools::firecrawl_error::{describe_error, missing_api_key, FirecrawlErrorCode};
 use crate::{Error, Result, Tool};
 use async_trait::async_trait;
 use firecrawl::search::SearchParams;
@@ -25,6 +26,8 @@ pub struct FirecrawlSearchResponse {
     total_results: usize,
     results: Vec<SearchResult>,
     error: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error_code: Option<FirecrawlErrorCode>,
 }
 
 #[derive(Debug, Serialize)]
@@ -86,9 +89,7 @@ impl Tool for FirecrawlSearchTool {
         let params: FirecrawlSearchInput = serde_json::from_value(input)
             .map_err(|e| Error::Other(format!("Invalid input parameters: {}", e)))?;
 
-        let api_key = std::env::var("FIRECRAWL_API_KEY").map_err(|_| {
-            Error::Other("FIRECRAWL_API_KEY environment variable not set".to_string())
-        })?;
+        let api_key = std::env::var("FIRECRAWL_API_KEY").map_err(|_| missing_api_key())?;
 
         let firecrawl = FirecrawlApp::new(&api_key)
             .map_err(|e| Error::Other(format!("Failed to initialize Firecrawl: {:?}", e)))?;
@@ -124,18 +125,21 @@ impl Tool for FirecrawlSearchTool {
                     total_results: results.len(),
                     results,
                     error: None,
+                    error_code: None,
                 };
 
                 serde_json::to_string_pretty(&response)
                     .map_err(|e| Error::Other(format!("Failed to serialize response: {}", e)))
             }
             Err(e) => {
+                let failure = describe_error("Search", &e);
                 let response = FirecrawlSearchResponse {
                     success: false,
                     query: params.query,
                     total_results: 0,
                     results: vec![],
-                    error: Some(format!("Search failed: {:?}", e)),
+                    error: Some(failure.message),
+                    error_code: Some(failure.code),
                 };
 
                 serde_json::to_string_pretty(&response)
@@ -0,0 +1,535 @@
+use crate::{Error, Result, Tool, ToolOutput};
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::path::Path;
+use std::process::Stdio;
+use tokio::process::Command;
+
+const DEFAULT_LOG_ENTRIES: usize = 10;
+const MAX_LOG_ENTRIES: usize = 100;
+
+/// Longest diff or `show` output returned, in characters
+const MAX_OUTPUT_CHARS: usize = 50_000;
+
+/// Actions that only read the repository
+const READ_ACTIONS: &[&str] = &["status", "diff", "log", "show"];
+
+/// Actions that change the index or history, off unless enabled
+const MUTATING_ACTIONS: &[&str] = &["add", "commit"];
+
+/// Inspects a git repository without going through `bash`
+///
+/// Read-only actions: `status` (branch and changed files, parsed), `diff`
+/// (working tree or `staged`, optionally limited to a `path`), `log` (the last
+/// `n` commits, parsed) and `show` (a commit or other ref). The `add` and
+/// `commit` actions are refused unless the tool was built with
+/// [`with_mutating_actions`](Self::with_mutating_actions).
+///
+/// # Example
+///
+/// ```rust
+/// use claude::tools::GitTool;
+/// use claude::{Tool, ToolOutput};
+/// use serde_json::{json, Value};
+/// use std::process::Command;
+///
+/// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+/// let dir = tempfile::tempdir().unwrap();
+/// let repo = dir.path();
+/// let git = |args: &[&str]| {
+///     let status = Command::new("git").arg("-C").arg(repo).args(args).status().unwrap();
+///     assert!(status.success(), "git {:?}", args);
+/// };
+/// git(&["init", "-q", "-b", "main"]);
+/// git(&["config", "user.name", "Ada"]);
+/// git(&["config", "user.email", "ada@example.com"]);
+/// std::fs::write(repo.join("README.md"), "hello\n").unwrap();
+/// git(&["add", "README.md"]);
+/// git(&["commit", "-q", "-m", "Add readme"]);
+/// std::fs::write(repo.join("main.rs"), "fn main() {}\n").unwrap();
+/// git(&["add", "main.rs"]);
+/// git(&["commit", "-q", "-m", "Add main"]);
+///
+/// let tool = GitTool::new();
+/// let run = |input: Value| {
+///     let tool = tool.clone();
+///     async move {
+///         let ToolOutput::Json(output) = tool.execute_typed(input).await? else {
+///             panic!("expected JSON output")
+///         };
+///         Ok::<_, claude::Error>(output)
+///     }
+/// };
+///
+/// // Status is parsed into staged, unstaged and untracked files
+/// let status = run(json!({"action": "status", "repo": repo})).await?;
+/// assert_eq!(status["branch"], "main");
+/// assert_eq!(status["clean"], true);
+///
+/// std::fs::write(repo.join("README.md"), "hello, world\n").unwrap();
+/// std::fs::write(repo.join("notes.txt"), "todo\n").unwrap();
+/// std::fs::write(repo.join("lib.rs"), "\n").unwrap();
+/// git(&["add", "lib.rs"]);
+/// let status = run(json!({"action": "status", "repo": repo})).await?;
+/// assert_eq!(status["clean"], false);
+/// assert_eq!(status["staged"], json!([{"path": "lib.rs", "status": "added"}]));
+/// assert_eq!(status["unstaged"], json!([{"path": "README.md", "status": "modified"}]));
+/// assert_eq!(status["untracked"], json!(["notes.txt"]));
+///
+/// // Log returns the newest commits first
+/// let log = run(json!({"action": "log", "repo": repo, "n": 5})).await?;
+/// let subjects: Vec<&str> = log["commits"]
+///     .as_array()
+///     .unwrap()
+///     .iter()
+///     .map(|c| c["subject"].as_str().unwrap())
+///     .collect();
+/// assert_eq!(subjects, ["Add main", "Add readme"]);
+/// assert_eq!(log["commits"][0]["author"], "Ada");
+/// assert_eq!(log["commits"][0]["hash"].as_str().unwrap().len(), 40);
+///
+/// let log = run(json!({"action": "log", "repo": repo, "n": 1})).await?;
+/// assert_eq!(log["commits"].as_array().unwrap().len(), 1);
+///
+/// // Diffs and show come back as text
+/// let diff = tool.execute(json!({"action": "diff", "repo": repo})).await?;
+/// assert!(diff.contains("+hello, world"), "{}", diff);
+/// let staged = tool.execute(json!({"action": "diff", "repo": repo, "staged": true})).await?;
+/// assert!(staged.contains("lib.rs") && !staged.contains("README.md"), "{}", staged);
+/// let shown = tool.execute(json!({"action": "show", "repo": repo, "ref": "HEAD~1"})).await?;
+/// assert!(shown.contains("Add readme"), "{}", shown);
+///
+/// // Commits are refused unless enabled
+/// let err = tool
+///     .execute(json!({"action": "commit", "repo": repo, "message": "Sneaky"}))
+///     .await
+///     .unwrap_err();
+/// assert!(err.to_string().contains("disabled"), "{}", err);
+/// # Ok::<(), claude::Error>(())
+/// # }).unwrap();
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct GitTool {
+    /// Whether `add` and `commit` are allowed
+    mutating_actions: bool,
+}
+
+impl GitTool {
+    /// Create a tool that only reads repositories
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Allow the `add` and `commit` actions, which change the index and history
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use claude::tools::GitTool;
+    /// use claude::{Tool, ToolOutput};
+    /// use serde_json::json;
+    /// use std::process::Command;
+    ///
+    /// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+    /// let dir = tempfile::tempdir().unwrap();
+    /// let repo = dir.path();
+    /// let git = |args: &[&str]| {
+    ///     let status = Command::new("git").arg("-C").arg(repo).args(args).status().unwrap();
+    ///     assert!(status.success(), "git {:?}", args);
+    /// };
+    /// git(&["init", "-q", "-b", "main"]);
+    /// git(&["config", "user.name", "Ada"]);
+    /// git(&["config", "user.email", "ada@example.com"]);
+    /// std::fs::write(repo.join("notes.txt"), "todo\n").unwrap();
+    ///
+    /// let tool = GitTool::new().with_mutating_actions(true);
+    /// assert!(tool.mutating_actions());
+    /// tool.execute(json!({"action": "add", "repo": repo, "paths": ["notes.txt"]})).await?;
+    /// let ToolOutput::Json(commit) = tool
+    ///     .execute_typed(json!({"action": "commit", "repo": repo, "message": "Add notes"}))
+    ///     .await?
+    /// else {
+    ///     panic!("expected JSON output")
+    /// };
+    /// assert_eq!(commit["subject"], "Add notes");
+    ///
+    /// let log = tool.execute(json!({"action": "log", "repo": repo})).await?;
+    /// assert!(log.contains(commit["hash"].as_str().unwrap()), "{}", log);
+    /// # Ok::<(), claude::Error>(())
+    /// # }).unwrap();
+    /// ```
+    pub fn with_mutating_actions(mut self, enabled: bool) -> Self {
+        self.mutating_actions = enabled;
+        self
+    }
+
+    /// Whether `add` and `commit` are allowed
+    pub fn mutating_actions(&self) -> bool {
+        self.mutating_actions
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct GitInput {
+    action: String,
+    repo: Option<String>,
+    #[serde(default)]
+    staged: bool,
+    path: Option<String>,
+    n: Option<usize>,
+    #[serde(rename = "ref")]
+    reference: Option<String>,
+    paths: Option<Vec<String>>,
+    message: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+struct FileStatus {
+    path: String,
+    status: &'static str,
+}
+
+#[derive(Debug, Serialize)]
+struct Commit {
+    hash: String,
+    author: String,
+    email: String,
+    date: String,
+    subject: String,
+}
+
+/// Run `git -C repo args...` and return its stdout
+async fn run_git(repo: &str, args: &[&str]) -> Result<String> {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(repo)
+        .args(["-c", "core.quotepath=off"])
+        .args(args)
+        .env("GIT_TERMINAL_PROMPT", "0")
+        .env("GIT_OPTIONAL_LOCKS", "0")
+        .stdin(Stdio::null())
+        .kill_on_drop(true)
+        .output()
+        .await
+        .map_err(|e| Error::Other(format!("Failed to run git: {}", e)))?;
+
+    if !output.status.success() {
+        return Err(Error::Other(format!(
+            "git {} failed: {}",
+            args.first().copied().unwrap_or_default(),
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// A status letter from `git status --porcelain` as a word
+fn describe_status(code: char) -> &'static str {
+    match code {
+        'M' => "modified",
+        'T' => "type_changed",
+        'A' => "added",
+        'D' => "deleted",
+        'R' => "renamed",
+        'C' => "copied",
+        'U' => "unmerged",
+        _ => "changed",
+    }
+}
+
+/// Parse `git status --porcelain=v1 --branch -z` output
+fn parse_status(output: &str) -> Value {
+    let mut branch = None;
+    let mut upstream = None;
+    let mut ahead = 0;
+    let mut behind = 0;
+    let mut staged = Vec::new();
+    let mut unstaged = Vec::new();
+    let mut untracked = Vec::new();
+
+    let mut fields = output.split('\0').filter(|field| !field.is_empty());
+    while let Some(entry) = fields.next() {
+        if let Some(header) = entry.strip_prefix("## ") {
+            // "main...origin/main [ahead 1, behind 2]" or "No commits yet on main"
+            let (names, counts) = header.split_once(" [").unwrap_or((header, ""));
+            let names = names.strip_prefix("No commits yet on ").unwrap_or(names);
+            let (local, remote) = match names.split_once("...") {
+                Some((local, remote)) => (local, Some(remote)),
+                None => (names, None),
+            };
+            branch = (local != "HEAD (no branch)").then(|| local.to_string());
+            upstream = remote.map(str::to_string);
+            for count in counts.trim_end_matches(']').split(", ") {
+                if let Some(n) = count.strip_prefix("ahead ") {
+                    ahead = n.parse().unwrap_or(0);
+                } else if let Some(n) = count.strip_prefix("behind ") {
+                    behind = n.parse().unwrap_or(0);
+                }
+            }
+            continue;
+        }
+
+        let mut codes = entry.chars();
+        let (Some(index), Some(worktree)) = (codes.next(), codes.next()) else {
+            continue;
+        };
+        let path = entry.get(3..).unwrap_or_default().to_string();
+        // Renames and copies are followed by the original path
+        if index == 'R' || index == 'C' {
+            fields.next();
+        }
+
+        if index == '?' {
+            untracked.push(path);
+            continue;
+        }
+        if index != ' ' {
+            staged.push(FileStatus {
+                path: path.clone(),
+                status: describe_status(index),
+            });
+        }
+        if worktree != ' ' {
+            unstaged.push(FileStatus {
+                path,
+                status: describe_status(worktree),
+            });
+        }
+    }
+
+    json!({
+        "branch": branch,
+        "upstream": upstream,
+        "ahead": ahead,
+        "behind": behind,
+        "clean": staged.is_empty() && unstaged.is_empty() && untracked.is_empty(),
+        "staged": staged,
+        "unstaged": unstaged,
+        "untracked": untracked,
+    })
+}
+
+/// Parse `git log` output written with [`LOG_FORMAT`]
+fn parse_log(output: &str) -> Vec<Commit> {
+    output
+        .split('\x1e')
+        .map(|record| record.trim_matches('\n'))
+        .filter(|record| !record.is_empty())
+        .filter_map(|record| {
+            let mut fields = record.split('\x1f').map(str::to_string);
+            Some(Commit {
+                hash: fields.next()?,
+                author: fields.next()?,
+                email: fields.next()?,
+                date: fields.next()?,
+                subject: fields.next()?,
+            })
+        })
+        .collect()
+}
+
+/// Hash, author, email, ISO date and subject, unit-separated, one record each
+const LOG_FORMAT: &str = "--format=%H%x1f%an%x1f%ae%x1f%aI%x1f%s%x1e";
+
+/// `text` cut to [`MAX_OUTPUT_CHARS`] characters, with a note if it was cut
+fn clip_output(text: String) -> String {
+    match text.char_indices().nth(MAX_OUTPUT_CHARS) {
+        Some((end, _)) => format!(
+            "{}\n[output truncated after {} characters]",
+            &text[..end],
+            MAX_OUTPUT_CHARS
+        ),
+        None => text,
+    }
+}
+
+/// Refuse values git would take as an option
+fn check_not_option<'a>(name: &str, value: &'a str) -> Result<&'a str> {
+    if value.starts_with('-') {
+        return Err(Error::Other(format!(
+            "Invalid {} '{}': it must not start with '-'",
+            name, value
+        )));
+    }
+    Ok(value)
+}
+
+#[async_trait]
+impl Tool for GitTool {
+    fn name(&self) -> &str {
+        "git"
+    }
+
+    fn description(&self) -> &str {
+        if self.mutating_actions {
+            "Inspect or update a git repository: status (branch and changed files), diff (unstaged, or staged, optionally for one path), log (recent commits), show (a commit or ref), add (stage paths) and commit (record staged changes)."
+        } else {
+            "Inspect a git repository without changing it: status (branch and changed files), diff (unstaged, or staged, optionally for one path), log (recent commits) and show (a commit or ref)."
+        }
+    }
+
+    fn input_schema(&self) -> Value {
+        let mut actions = READ_ACTIONS.to_vec();
+        if self.mutating_actions {
+            actions.extend_from_slice(MUTATING_ACTIONS);
+        }
+        let mut properties = json!({
+            "action": {
+                "type": "string",
+                "enum": actions,
+                "description": "What to do"
+            },
+            "repo": {
+                "type": "string",
+                "description": "Path to the repository or any directory inside it (default: current directory)"
+            },
+            "staged": {
+                "type": "boolean",
+                "description": "For diff: show staged changes instead of unstaged ones (default: false)"
+            },
+            "path": {
+                "type": "string",
+                "description": "For diff and log: only include changes to this file or directory"
+            },
+            "n": {
+                "type": "integer",
+                "minimum": 1,
+                "description": "For log: number of commits to return (default: 10, max: 100)"
+            },
+            "ref": {
+                "type": "string",
+                "description": "For show: the commit, tag or branch to show (default: HEAD)"
+            }
+        });
+        if self.mutating_actions {
+            properties["paths"] = json!({
+                "type": "array",
+                "items": {"type": "string"},
+                "description": "For add: the paths to stage"
+            });
+            properties["message"] = json!({
+                "type": "string",
+                "description": "For commit: the commit message"
+            });
+        }
+        json!({
+            "type": "object",
+            "properties": properties,
+            "required": ["action"],
+            "additionalProperties": false
+        })
+    }
+
+    async fn execute(&self, input: Value) -> Result<String> {
+        Ok(self.execute_typed(input).await?.into_text())
+    }
+
+    async fn execute_typed(&self, input: Value) -> Result<ToolOutput> {
+        let params: GitInput = serde_json::from_value(input).map_err(|e| {
+            Error::Other(format!(
+                "Invalid input parameters: {}. Example: {{\"action\": \"log\", \"repo\": \".\", \"n\": 5}}",
+                e
+            ))
+        })?;
+
+        let repo = params.repo.as_deref().unwrap_or(".");
+        if !Path::new(repo).is_dir() {
+            return Err(Error::Other(format!("Repository not found: {}", repo)));
+        }
+        let action = params.action.as_str();
+        if MUTATING_ACTIONS.contains(&action) && !self.mutating_actions {
+            return Err(Error::Other(format!(
+                "The '{}' action is disabled; this git tool is read-only. Allowed actions: {}",
+                action,
+                READ_ACTIONS.join(", ")
+            )));
+        }
+
+        match action {
+            "status" => {
+                let output = run_git(repo, &["status", "--porcelain=v1", "--branch", "-z"]).await?;
+                Ok(ToolOutput::Json(parse_status(&output)))
+            }
+            "diff" => {
+                let mut args = vec!["diff", "--no-color", "--no-ext-diff"];
+                if params.staged {
+                    args.push("--cached");
+                }
+                if let Some(path) = &params.path {
+                    args.extend(["--", path]);
+                }
+                let diff = run_git(repo, &args).await?;
+                if diff.is_empty() {
+                    let which = if params.staged { "staged" } else { "unstaged" };
+                    return Ok(ToolOutput::Text(format!("No {} changes", which)));
+                }
+                Ok(ToolOutput::Text(clip_output(diff)))
+            }
+            "log" => {
+                let n = params
+                    .n
+                    .unwrap_or(DEFAULT_LOG_ENTRIES)
+                    .clamp(1, MAX_LOG_ENTRIES)
+                    .to_string();
+                let mut args = vec!["log", "-n", &n, LOG_FORMAT];
+                if let Some(path) = &params.path {
+                    args.extend(["--", path]);
+                }
+                let commits = parse_log(&run_git(repo, &args).await?);
+                Ok(ToolOutput::Json(json!({
+                    "count": commits.len(),
+                    "commits": commits,
+                })))
+            }
+            "show" => {
+                let reference =
+                    check_not_option("ref", params.reference.as_deref().unwrap_or("HEAD"))?;
+                let output = run_git(
+                    repo,
+                    &[
+                        "show",
+                        "--no-color",
+                        "--no-ext-diff",
+                        "--stat",
+                        "--patch",
+                        reference,
+                        "--",
+                    ],
+                )
+                .await?;
+                Ok(ToolOutput::Text(clip_output(output)))
+            }
+            "add" => {
+                let paths = params.paths.unwrap_or_default();
+                if paths.is_empty() {
+                    return Err(Error::Other("The add action needs 'paths'".to_string()));
+                }
+                let mut args = vec!["add", "--"];
+                args.extend(paths.iter().map(String::as_str));
+                run_git(repo, &args).await?;
+                Ok(ToolOutput::Json(json!({ "staged": paths })))
+            }
+            "commit" => {
+                let message = params
+                    .message
+                    .filter(|message| !message.trim().is_empty())
+                    .ok_or_else(|| {
+                        Error::Other("The commit action needs a 'message'".to_string())
+                    })?;
+                run_git(repo, &["commit", "-q", "-m", &message]).await?;
+                let commits = parse_log(&run_git(repo, &["log", "-n", "1", LOG_FORMAT]).await?);
+                Ok(ToolOutput::Json(json!(commits.first())))
+            }
+            other => Err(Error::Other(format!(
+                "Unknown action '{}'. Expected one of: {}",
+                other,
+                if self.mutating_actions {
+                    [READ_ACTIONS, MUTATING_ACTIONS].concat().join(", ")
+                } else {
+                    READ_ACTIONS.join(", ")
+                }
+            ))),
+        }
+    }
+}
@@ -1,24 +1,354 @@
-use crate::{Error, Result, Tool};
+use crate::{Error, Result, Tool, ToolOutput};
 use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
 use std::collections::HashMap;
+use std::net::{IpAddr, SocketAddr};
 use std::time::Duration;
+use tokio_util::sync::CancellationToken;
+use url::{Host, Url};
+
+const DEFAULT_MAX_BODY_BYTES: usize = 10 * 1024 * 1024;
+const MAX_REDIRECTS: usize = 10;
 
 /// HTTP Fetch tool for making HTTP requests
-pub struct HttpFetchTool;
+///
+/// Every request, including each redirect hop, is checked before it is sent:
+/// only `http` and `https` URLs are allowed, and hosts that resolve to
+/// loopback, private, link-local or otherwise non-public addresses are
+/// rejected unless the input sets `allow_private: true`. The connection is
+/// pinned to the addresses that passed the check, so a second DNS lookup
+/// cannot swap in an internal address. A redirect to another scheme, host or
+/// port drops headers carrying credentials, such as `Authorization`, `Cookie`
+/// and API keys, and a redirect that turns the request into a GET drops the
+/// body along with its `Content-*` headers.
+///
+/// A request in flight is dropped as soon as the token passed to
+/// [`execute_with_cancel`](Tool::execute_with_cancel) is cancelled.
+///
+/// # Example
+///
+/// ```rust
+/// use claude::tools::HttpFetchTool;
+/// use claude::Tool;
+/// use serde_json::json;
+///
+/// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+/// let fetch = HttpFetchTool::new();
+/// for url in [
+///     "http://127.0.0.1:8080/",
+///     "http://localhost/admin",
+///     "http://10.0.0.5/",
+///     "http://169.254.169.254/latest/meta-data/",
+///     "http://[::1]/",
+/// ] {
+///     let err = fetch.execute(json!({"url": url})).await.unwrap_err();
+///     assert!(err.to_string().contains("non-public address"), "{}: {}", url, err);
+/// }
+///
+/// let err = fetch
+///     .execute(json!({"url": "file:///etc/passwd"}))
+///     .await
+///     .unwrap_err();
+/// assert!(err.to_string().contains("http:// or https://"));
+/// # });
+/// ```
+///
+/// Posting JSON to a local mock API, which needs `allow_private`:
+///
+/// ```rust
+/// use claude::test_util::MockServer;
+/// use claude::tools::HttpFetchTool;
+/// use claude::transport::RawResponse;
+/// use claude::Tool;
+/// use reqwest::header::HeaderValue;
+/// use serde_json::{json, Value};
+///
+/// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+/// let server = MockServer::start(|_| {
+///     let mut response = RawResponse::new(201, json!({"id": 7}).to_string());
+///     response.headers.insert("x-request-id", HeaderValue::from_static("42"));
+///     response
+/// })
+/// .await?;
+/// let url = format!("{}/items", server.url());
+///
+/// let output: Value = serde_json::from_str(
+///     &HttpFetchTool::new()
+///         .execute(json!({
+///             "url": url,
+///             "method": "POST",
+///             "headers": {"Authorization": "Bearer secret-token", "Accept": "application/json"},
+///             "body": {"name": "widget", "count": 3},
+///             "allow_private": true
+///         }))
+///         .await?,
+/// )?;
+///
+/// assert_eq!(output["status"], 201);
+/// assert_eq!(output["headers"]["x-request-id"], "42");
+/// assert_eq!(output["body"], r#"{"id":7}"#);
+///
+/// let received = &server.requests()[0];
+/// assert_eq!(received.method, "POST");
+/// assert_eq!(received.url, url);
+/// assert_eq!(received.headers["content-type"], "application/json");
+/// assert_eq!(received.headers["authorization"], "Bearer secret-token");
+/// let body: Value = serde_json::from_str(&received.body_text())?;
+/// assert_eq!(body, json!({"name": "widget", "count": 3}));
+///
+/// // The request summary never repeats credentials
+/// assert_eq!(output["request"]["method"], "POST");
+/// assert_eq!(output["request"]["headers"]["Authorization"], "[REDACTED]");
+/// assert_eq!(output["request"]["headers"]["Accept"], "application/json");
+/// assert!(!output.to_string().contains("secret-token"));
+/// # Ok::<(), claude::Error>(())
+/// # }).unwrap();
+/// ```
+///
+/// Credentials follow a redirect within the same origin, but not to another:
+///
+/// ```rust
+/// use claude::test_util::MockServer;
+/// use claude::tools::HttpFetchTool;
+/// use claude::transport::RawResponse;
+/// use claude::Tool;
+/// use reqwest::header::{HeaderValue, LOCATION};
+/// use serde_json::json;
+///
+/// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+/// let redirect = |status: u16, to: String| {
+///     let mut response = RawResponse::new(status, "");
+///     response.headers.insert(LOCATION, HeaderValue::from_str(&to).unwrap());
+///     response
+/// };
+/// let other = MockServer::start(|_| RawResponse::new(200, "landed")).await?;
+/// let other_url = other.url().to_string();
+/// let origin = MockServer::start(move |request| {
+///     if request.url.ends_with("/start") {
+///         redirect(307, "/again".to_string())
+///     } else {
+///         redirect(303, format!("{}/landing", other_url))
+///     }
+/// })
+/// .await?;
+///
+/// HttpFetchTool::new()
+///     .execute(json!({
+///         "url": format!("{}/start", origin.url()),
+///         "method": "POST",
+///         "headers": {"Authorization": "Bearer secret", "X-Api-Key": "key", "Accept": "text/plain"},
+///         "body": {"name": "widget"},
+///         "allow_private": true
+///     }))
+///     .await?;
+///
+/// // The same-origin hop keeps the method, body and credentials
+/// let again = &origin.requests()[1];
+/// assert_eq!(again.method, "POST");
+/// assert_eq!(again.headers["authorization"], "Bearer secret");
+/// assert_eq!(again.body_text(), r#"{"name":"widget"}"#);
+///
+/// // The 303 to another port arrives as a bare GET without them
+/// let landing = &other.requests()[0];
+/// assert_eq!(landing.method, "GET");
+/// assert!(landing.headers.get("authorization").is_none());
+/// assert!(landing.headers.get("x-api-key").is_none());
+/// assert!(landing.headers.get("content-type").is_none());
+/// assert_eq!(landing.headers["accept"], "text/plain");
+/// assert!(landing.body.is_none());
+/// # Ok::<(), claude::Error>(())
+/// # }).unwrap();
+/// ```
+///
+/// Cancelling a request to a server that never answers:
+///
+/// ```rust
+/// use claude::tools::HttpFetchTool;
+/// use claude::{CancellationToken, Tool};
+/// use serde_json::json;
+/// use std::time::{Duration, Instant};
+///
+/// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+/// // Connections are queued but never accepted, so no response comes
+/// let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+/// let url = format!("http://{}/slow", listener.local_addr().unwrap());
+///
+/// let cancel = CancellationToken::new();
+/// let trigger = cancel.clone();
+/// tokio::spawn(async move {
+///     tokio::time::sleep(Duration::from_millis(200)).await;
+///     trigger.cancel();
+/// });
+///
+/// let started = Instant::now();
+/// let err = HttpFetchTool::new()
+///     .execute_with_cancel(json!({"url": url, "allow_private": true}), cancel)
+///     .await
+///     .unwrap_err();
+/// assert!(err.to_string().contains("cancelled"), "{}", err);
+/// assert!(started.elapsed() < Duration::from_secs(10));
+/// # });
+/// ```
+pub struct HttpFetchTool {
+    max_body_bytes: usize,
+}
+
+impl Default for HttpFetchTool {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl HttpFetchTool {
+    /// Create a fetch tool that reads at most 10MB of each response body
+    pub fn new() -> Self {
+        Self {
+            max_body_bytes: DEFAULT_MAX_BODY_BYTES,
+        }
+    }
+
+    /// Set the largest response body, in bytes, that will be read
+    pub fn with_max_body_bytes(mut self, max_body_bytes: usize) -> Self {
+        self.max_body_bytes = max_body_bytes;
+        self
+    }
+}
+
+/// Whether `ip` is a publicly routable address
+///
+/// Loopback, private, carrier-grade NAT, link-local (including the cloud
+/// metadata endpoint), multicast, documentation and reserved ranges are all
+/// treated as non-public, as are IPv6 addresses that embed one of them.
+///
+/// # Example
+///
+/// ```rust
+/// use claude::tools::http_fetch::is_public_ip;
+///
+/// assert!(is_public_ip("93.184.216.34".parse().unwrap()));
+/// assert!(is_public_ip("2606:4700::1111".parse().unwrap()));
+///
+/// for blocked in ["127.0.0.1", "10.1.2.3", "172.16.0.1", "192.168.1.1", "169.254.169.254",
+///                 "100.64.0.1", "0.0.0.0", "255.255.255.255", "::1", "fe80::1", "fd00::1",
+///                 "::ffff:127.0.0.1"] {
+///     assert!(!is_public_ip(blocked.parse().unwrap()), "{}", blocked);
+/// }
+/// ```
+pub fn is_public_ip(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(v4) => {
+            let [a, b, c, _] = v4.octets();
+            !(v4.is_loopback()
+                || v4.is_private()
+                || v4.is_link_local()
+                || v4.is_unspecified()
+                || v4.is_broadcast()
+                || v4.is_multicast()
+                || v4.is_documentation()
+                // "This network", shared address space (RFC 6598), IETF
+                // protocol assignments, benchmarking, and the reserved 240/4
+                || a == 0
+                || (a == 100 && (64..128).contains(&b))
+                || (a == 192 && b == 0 && c == 0)
+                || (a == 198 && (b == 18 || b == 19))
+                || a >= 240)
+        }
+        IpAddr::V6(v6) => {
+            if let Some(v4) = v6.to_ipv4_mapped() {
+                return is_public_ip(IpAddr::V4(v4));
+            }
+            let segments = v6.segments();
+            !(v6.is_loopback()
+                || v6.is_unspecified()
+                || v6.is_multicast()
+                || v6.is_unique_local()
+                || v6.is_unicast_link_local()
+                // Documentation (2001:db8::/32) and IPv4-compatible (::/96)
+                || (segments[0] == 0x2001 && segments[1] == 0x0db8)
+                || segments[..6].iter().all(|&s| s == 0))
+        }
+    }
+}
+
+/// Whether a header carries credentials and must not be echoed back
+fn is_sensitive_header(name: &str) -> bool {
+    let name = name.to_lowercase();
+    matches!(
+        name.as_str(),
+        "authorization" | "proxy-authorization" | "cookie"
+    ) || ["auth", "token", "secret", "api-key", "apikey", "password"]
+        .iter()
+        .any(|marker| name.contains(marker))
+}
+
+/// Whether a header describes the request body and must go when the body does
+fn is_body_header(name: &str) -> bool {
+    let name = name.to_lowercase();
+    name.starts_with("content-") || name == "transfer-encoding"
+}
+
+/// Resolve `url`'s host, rejecting non-http(s) schemes and, unless
+/// `allow_private` is set, any host with a non-public address
+async fn resolve_target(url: &Url, allow_private: bool) -> Result<Vec<SocketAddr>> {
+    if url.scheme() != "http" && url.scheme() != "https" {
+        return Err(Error::Other(
+            "URL must start with http:// or https://. Example: {\"url\": \"https://api.example.com/data\"}".to_string()
+        ));
+    }
+
+    let port = url
+        .port_or_known_default()
+        .ok_or_else(|| Error::Other(format!("Cannot determine port for {}", url)))?;
+    let addrs: Vec<SocketAddr> = match url.host() {
+        Some(Host::Ipv4(ip)) => vec![SocketAddr::new(IpAddr::V4(ip), port)],
+        Some(Host::Ipv6(ip)) => vec![SocketAddr::new(IpAddr::V6(ip), port)],
+        Some(Host::Domain(domain)) => tokio::net::lookup_host((domain, port))
+            .await
+            .map_err(|e| Error::Other(format!("Failed to resolve {}: {}", domain, e)))?
+            .collect(),
+        None => return Err(Error::Other(format!("URL has no host: {}", url))),
+    };
+
+    if addrs.is_empty() {
+        return Err(Error::Other(format!("No addresses found for {}", url)));
+    }
+
+    if !allow_private {
+        if let Some(blocked) = addrs.iter().find(|addr| !is_public_ip(addr.ip())) {
+            return Err(Error::Other(format!(
+                "Refusing to fetch {}: host resolves to non-public address {}. Pass \"allow_private\": true only if you are sure this internal address is intended",
+                url,
+                blocked.ip()
+            )));
+        }
+    }
+
+    Ok(addrs)
+}
 
 #[derive(Debug, Deserialize)]
 struct HttpFetchInput {
     url: String,
     method: Option<String>,
     headers: Option<HashMap<String, String>>,
-    body: Option<String>,
+    body: Option<Value>,
     timeout_seconds: Option<u64>,
+    #[serde(default)]
+    allow_private: bool,
+}
+
+/// What was sent, echoed back with credentials redacted
+#[derive(Debug, Serialize)]
+struct RequestSummary {
+    method: String,
+    url: String,
+    headers: HashMap<String, String>,
 }
 
 #[derive(Debug, Serialize)]
 struct HttpFetchResponse {
+    request: RequestSummary,
     status: u16,
     headers: HashMap<String, String>,
     body: String,
@@ -33,7 +363,7 @@ impl Tool for HttpFetchTool {
     }
 
     fn description(&self) -> &str {
-        "Make HTTP requests to fetch data from URLs. Supports GET, POST, PUT, DELETE methods with custom headers and body."
+        "Make HTTP requests to fetch data from URLs or call APIs. Supports GET, POST, PUT, PATCH, DELETE and HEAD with custom headers and a string or JSON body. Returns the response status, headers and body."
     }
 
     fn input_schema(&self) -> Value {
@@ -57,12 +387,16 @@ impl Tool for HttpFetchTool {
                     }
                 },
                 "body": {
-                    "type": "string",
-                    "description": "Optional request body (for POST, PUT, PATCH)"
+                    "type": ["string", "object", "array", "number", "boolean"],
+                    "description": "Optional request body (for POST, PUT, PATCH). Strings are sent as-is; any other JSON value is serialized and sent with Content-Type: application/json unless a Content-Type header is given"
                 },
                 "timeout_seconds": {
                     "type": "integer",
                     "description": "Request timeout in seconds (default: 30, max: 300)"
+                },
+                "allow_private": {
+                    "type": "boolean",
+                    "description": "Allow requests to loopback, private and link-local addresses (default: false)"
                 }
             },
             "required": ["url"],
@@ -76,88 +410,139 @@ impl Tool for HttpFetchTool {
                 "Invalid input parameters: {}. Example: {{\"url\": \"https://api.example.com/data\", \"method\": \"GET\"}}", e
             )))?;
 
-        // Validate URL
-        if !params.url.starts_with("http://") && !params.url.starts_with("https://") {
-            return Err(Error::Other(
-                "URL must start with http:// or https://. Example: {\"url\": \"https://api.example.com/data\"}".to_string()
-            ));
-        }
-
         // Validate URL format
-        let url = reqwest::Url::parse(&params.url).map_err(|e| {
+        let mut url = Url::parse(&params.url).map_err(|e| {
             Error::Other(format!(
                 "Invalid URL: {}. Example: {{\"url\": \"https://api.example.com/data\"}}",
                 e
             ))
         })?;
 
-        // Security: Block local addresses
-        if let Some(host) = url.host_str() {
-            if host == "localhost"
-                || host == "127.0.0.1"
-                || host.starts_with("192.168.")
-                || host.starts_with("10.")
-                || host.starts_with("172.")
-            {
-                return Err(Error::Other(
-                    "Access to local addresses is not allowed. Use external URLs like https://api.example.com".to_string()
-                ));
-            }
-        }
-
         // Determine timeout (max 5 minutes)
         let timeout = params
             .timeout_seconds
             .map(|s| Duration::from_secs(s.min(300)))
             .unwrap_or(Duration::from_secs(30));
 
-        // Build HTTP client
-        let client = reqwest::Client::builder()
-            .timeout(timeout)
-            .user_agent("Claude-RS-Bot/1.0")
-            .build()
-            .map_err(|e| Error::Other(format!("Failed to create HTTP client: {}", e)))?;
-
         // Determine method
-        let method = params.method.as_deref().unwrap_or("GET").to_uppercase();
-
-        // Build request
-        let mut request = match method.as_str() {
-            "GET" => client.get(&params.url),
-            "POST" => client.post(&params.url),
-            "PUT" => client.put(&params.url),
-            "DELETE" => client.delete(&params.url),
-            "HEAD" => client.head(&params.url),
-            "PATCH" => client.patch(&params.url),
-            _ => return Err(Error::Other(format!(
+        let mut method = params.method.as_deref().unwrap_or("GET").to_uppercase();
+        if !matches!(
+            method.as_str(),
+            "GET" | "POST" | "PUT" | "DELETE" | "HEAD" | "PATCH"
+        ) {
+            return Err(Error::Other(format!(
                 "Unsupported HTTP method: {}. Supported methods: GET, POST, PUT, DELETE, HEAD, PATCH", method
-            ))),
-        };
+            )));
+        }
 
-        // Add headers
-        if let Some(headers) = params.headers {
-            for (key, value) in headers {
-                // Skip potentially dangerous headers
+        // Headers to send, minus ones reqwest must control itself
+        let mut request_headers: HashMap<String, String> = params
+            .headers
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|(key, _)| {
                 let key_lower = key.to_lowercase();
-                if key_lower == "host" || key_lower == "content-length" {
-                    continue;
+                key_lower != "host" && key_lower != "content-length"
+            })
+            .collect();
+
+        // Non-string bodies are sent as JSON
+        let mut body = match params.body {
+            None | Some(Value::Null) => None,
+            Some(Value::String(text)) => Some(text),
+            Some(json_body) => {
+                if !request_headers
+                    .keys()
+                    .any(|k| k.eq_ignore_ascii_case("content-type"))
+                {
+                    request_headers
+                        .insert("Content-Type".to_string(), "application/json".to_string());
                 }
-                request = request.header(&key, &value);
+                Some(json_body.to_string())
             }
-        }
+        };
+
+        // Redirects are followed by hand so every hop gets the same address check
+        let mut redirects = 0;
+        let mut response = loop {
+            let addrs = resolve_target(&url, params.allow_private).await?;
 
-        // Add body for appropriate methods
-        if let Some(body) = params.body {
-            if matches!(method.as_str(), "POST" | "PUT" | "PATCH") {
-                request = request.body(body);
+            // Build HTTP client pinned to the checked addresses
+            let mut builder = reqwest::Client::builder()
+                .timeout(timeout)
+                .user_agent("Claude-RS-Bot/1.0")
+                .redirect(reqwest::redirect::Policy::none());
+            if let Some(Host::Domain(domain)) = url.host() {
+                builder = builder.resolve_to_addrs(domain, &addrs);
             }
-        }
+            let client = builder
+                .build()
+                .map_err(|e| Error::Other(format!("Failed to create HTTP client: {}", e)))?;
 
-        // Execute request
-        let response = request
-            .send()
-            .await
-            .map_err(|e| Error::Other(format!("Request failed: {}", e)))?;
+            // Build request
+            let mut request = match method.as_str() {
+                "GET" => client.get(url.clone()),
+                "POST" => client.post(url.clone()),
+                "PUT" => client.put(url.clone()),
+                "DELETE" => client.delete(url.clone()),
+                "HEAD" => client.head(url.clone()),
+                _ => client.patch(url.clone()),
+            };
+
+            // Add headers
+            for (key, value) in &request_headers {
+                request = request.header(key, value);
+            }
+
+            // Add body for appropriate methods
+            if let Some(body) = &body {
+                if matches!(method.as_str(), "POST" | "PUT" | "PATCH") {
+                    request = request.body(body.clone());
+                }
+            }
+
+            // Execute request
+            let response = request
+                .send()
+                .await
+                .map_err(|e| Error::Other(format!("Request failed: {}", e)))?;
+
+            let location = response
+                .headers()
+                .get("location")
+                .and_then(|v| v.to_str().ok())
+                .filter(|_| response.status().is_redirection());
+            let Some(location) = location else {
+                break response;
+            };
+
+            redirects += 1;
+            if redirects > MAX_REDIRECTS {
+                return Err(Error::Other(format!(
+                    "Too many redirects (more than {})",
+                    MAX_REDIRECTS
+                )));
+            }
+            let next = url
+                .join(location)
+                .map_err(|e| Error::Other(format!("Invalid redirect location: {}", e)))?;
+
+            // Credentials are only for the origin they were given for
+            if next.origin() != url.origin() {
+                request_headers.retain(|key, _| !is_sensitive_header(key));
+            }
+            url = next;
+
+            // 303, and 301/302 after a POST, continue as a GET without the body
+            let status = response.status().as_u16();
+            if status == 303 || (matches!(status, 301 | 302) && method == "POST") {
+                if method != "HEAD" {
+                    method = "GET".to_string();
+                }
+                body = None;
+                request_headers.retain(|key, _| !is_body_header(key));
+            }
+        };
 
         // Extract response details
         let status = response.status().as_u16();
@@ -175,21 +560,53 @@ impl Tool for HttpFetchTool {
             }
         }
 
-        // Read body with size limit (10MB)
-        let body_bytes = response
-            .bytes()
-            .await
-            .map_err(|e| Error::Other(format!("Failed to read response body: {}", e)))?;
+        // Read body with size limit, stopping as soon as it is exceeded
+        let too_large = || {
+            Error::Other(format!(
+                "Response body too large (>{} bytes)",
+                self.max_body_bytes
+            ))
+        };
+        if response
+            .content_length()
+            .is_some_and(|len| len > self.max_body_bytes as u64)
+        {
+            return Err(too_large());
+        }
 
-        if body_bytes.len() > 10 * 1024 * 1024 {
-            return Err(Error::Other("Response body too large (>10MB)".to_string()));
+        let mut body_bytes = Vec::new();
+        while let Some(chunk) = response
+            .chunk()
+            .await
+            .map_err(|e| Error::Other(format!("Failed to read response body: {}", e)))?
+        {
+            if body_bytes.len() + chunk.len() > self.max_body_bytes {
+                return Err(too_large());
+            }
+            body_bytes.extend_from_slice(&chunk);
         }
 
         let body = String::from_utf8_lossy(&body_bytes).to_string();
         let content_length = body_bytes.len();
 
+        let request = RequestSummary {
+            method,
+            url: url.to_string(),
+            headers: request_headers
+                .into_iter()
+                .map(|(key, value)| {
+                    if is_sensitive_header(&key) {
+                        (key, "[REDACTED]".to_string())
+                    } else {
+                        (key, value)
+                    }
+                })
+                .collect(),
+        };
+
         // Create response
         let fetch_response = HttpFetchResponse {
+            request,
             status,
             headers,
             body,
@@ -201,4 +618,16 @@ impl Tool for HttpFetchTool {
         serde_json::to_string_pretty(&fetch_response)
             .map_err(|e| Error::Other(format!("Failed to serialize response: {}", e)))
     }
+
+    async fn execute_with_cancel(
+        &self,
+        input: Value,
+        cancel: CancellationToken,
+    ) -> Result<ToolOutput> {
+        // Dropping the request future closes its connection
+        tokio::select! {
+            result = self.execute(input) => result.map(ToolOutput::Text),
+            _ = cancel.cancelled() => Err(Error::Other("Request cancelled".to_string())),
+        }
+    }
 }
@@ -1,9 +1,108 @@
-use crate::{Error, Result, Tool};
+use crate::{Error, Result, Tool, ToolOutput};
 use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use ignore::WalkBuilder;
+use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
+use std::path::Path;
 
+const DEFAULT_MAX_ENTRIES: usize = 1000;
+const MAX_ENTRIES_LIMIT: usize = 10_000;
+
+/// Lists a directory, optionally recursively
+///
+/// Recursive listings skip `.git`, `target` and anything matched by
+/// `.gitignore`/`.ignore` files unless `respect_gitignore` is `false`, and
+/// stop after `max_entries` entries with `truncated` set.
+///
+/// # Example
+///
+/// ```rust
+/// use claude::tools::ListDirectoryTool;
+/// use claude::{Tool, ToolOutput};
+/// use serde_json::{json, Value};
+///
+/// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+/// let dir = tempfile::tempdir().unwrap();
+/// let root = dir.path();
+/// std::fs::create_dir_all(root.join("src/nested/deeper")).unwrap();
+/// std::fs::create_dir_all(root.join("target/debug")).unwrap();
+/// std::fs::write(root.join("Cargo.toml"), "[package]").unwrap();
+/// std::fs::write(root.join("src/lib.rs"), "").unwrap();
+/// std::fs::write(root.join("src/nested/mod.rs"), "").unwrap();
+/// std::fs::write(root.join("src/nested/deeper/leaf.rs"), "").unwrap();
+/// std::fs::write(root.join("src/nested/notes.md"), "").unwrap();
+/// std::fs::write(root.join("target/debug/app"), "").unwrap();
+/// std::fs::write(root.join("debug.log"), "").unwrap();
+/// std::fs::write(root.join(".gitignore"), "*.log\n").unwrap();
+///
+/// let list = |input: Value| async move {
+///     let ToolOutput::Json(output) = ListDirectoryTool.execute_typed(input).await? else {
+///         panic!("expected JSON output")
+///     };
+///     let paths: Vec<String> = output["entries"]
+///         .as_array()
+///         .unwrap()
+///         .iter()
+///         .map(|e| e["path"].as_str().unwrap().to_string())
+///         .collect();
+///     Ok::<_, claude::Error>((paths, output))
+/// };
+///
+/// // One level by default; .gitignore'd files still show without recursion
+/// let (paths, _) = list(json!({"path": root})).await?;
+/// assert_eq!(paths, [".gitignore", "Cargo.toml", "debug.log", "src", "target"]);
+///
+/// // Depth-limited recursion skips target/ and ignored files
+/// let (paths, output) = list(json!({"path": root, "recursive": true, "max_depth": 2})).await?;
+/// assert_eq!(
+///     paths,
+///     [".gitignore", "Cargo.toml", "src", "src/lib.rs", "src/nested"]
+/// );
+/// assert_eq!(output["entries"][3]["type"], "file");
+/// assert_eq!(output["entries"][4]["type"], "dir");
+/// assert_eq!(output["truncated"], false);
+///
+/// // Glob filtering applies to file names, or to paths when it has a '/'
+/// let (paths, _) = list(json!({"path": root, "recursive": true, "pattern": "*.rs"})).await?;
+/// assert_eq!(paths, ["src/lib.rs", "src/nested/deeper/leaf.rs", "src/nested/mod.rs"]);
+/// let (paths, _) = list(json!({"path": root, "recursive": true, "pattern": "src/*/*.md"})).await?;
+/// assert_eq!(paths, ["src/nested/notes.md"]);
+///
+/// // Everything, but capped
+/// let (paths, output) = list(json!({
+///     "path": root, "recursive": true, "respect_gitignore": false, "max_entries": 3
+/// }))
+/// .await?;
+/// assert_eq!(paths.len(), 3);
+/// assert_eq!(output["truncated"], true);
+/// # Ok::<(), claude::Error>(())
+/// # }).unwrap();
+/// ```
 pub struct ListDirectoryTool;
 
+#[derive(Debug, Deserialize)]
+struct ListDirectoryInput {
+    path: String,
+    #[serde(default)]
+    recursive: bool,
+    max_depth: Option<usize>,
+    pattern: Option<String>,
+    respect_gitignore: Option<bool>,
+    max_entries: Option<usize>,
+}
+
+#[derive(Debug, Serialize)]
+struct DirectoryEntry {
+    path: String,
+    #[serde(rename = "type")]
+    kind: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    size: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    modified: Option<DateTime<Utc>>,
+}
+
 #[async_trait]
 impl Tool for ListDirectoryTool {
     fn name(&self) -> &str {
@@ -11,7 +110,7 @@ impl Tool for ListDirectoryTool {
     }
 
     fn description(&self) -> &str {
-        "List files and directories in a given path"
+        "List files and directories in a given path, optionally recursively with a depth limit and glob filter. Returns each entry's type (file/dir/symlink), size and modification time."
     }
 
     fn input_schema(&self) -> Value {
@@ -21,6 +120,28 @@ impl Tool for ListDirectoryTool {
                 "path": {
                     "type": "string",
                     "description": "The directory path to list"
+                },
+                "recursive": {
+                    "type": "boolean",
+                    "description": "List subdirectories too (default: false)"
+                },
+                "max_depth": {
+                    "type": "integer",
+                    "minimum": 1,
+                    "description": "How many levels to descend when recursive; 1 lists only the directory itself (default: unlimited)"
+                },
+                "pattern": {
+                    "type": "string",
+                    "description": "Only list entries matching this glob, e.g. '*.rs'. Matched against the file name, or against the relative path if it contains '/'"
+                },
+                "respect_gitignore": {
+                    "type": "boolean",
+                    "description": "Skip .git, target and gitignored paths when recursive (default: true)"
+                },
+                "max_entries": {
+                    "type": "integer",
+                    "minimum": 1,
+                    "description": "Maximum number of entries to return (default: 1000, max: 10000)"
                 }
             },
             "required": ["path"],
@@ -29,37 +150,123 @@ impl Tool for ListDirectoryTool {
     }
 
     async fn execute(&self, input: Value) -> Result<String> {
-        let path = input.get("path").and_then(|v| v.as_str()).ok_or_else(|| {
-            Error::Other(
-                "Missing 'path' field. Example: {\"path\": \"/home/user/documents\"}".to_string(),
-            )
+        Ok(self.execute_typed(input).await?.into_text())
+    }
+
+    async fn execute_typed(&self, input: Value) -> Result<ToolOutput> {
+        let params: ListDirectoryInput = serde_json::from_value(input).map_err(|e| {
+            Error::Other(format!(
+                "Invalid input parameters: {}. Example: {{\"path\": \"/home/user/documents\"}}",
+                e
+            ))
         })?;
 
-        use std::fs;
-
-        let entries = fs::read_dir(path)
-            .map_err(|e| Error::Other(format!("Failed to read directory: {}", e)))?;
-
-        let mut results = Vec::new();
-        for entry in entries {
-            if let Ok(entry) = entry {
-                let metadata = entry.metadata();
-                let file_type = if let Ok(meta) = metadata {
-                    if meta.is_dir() {
-                        "[DIR]"
-                    } else {
-                        "[FILE]"
-                    }
+        let root = Path::new(&params.path);
+        if !root.is_dir() {
+            return Err(Error::Other(format!(
+                "Failed to read directory: {} is not a directory",
+                params.path
+            )));
+        }
+
+        let pattern = params
+            .pattern
+            .as_deref()
+            .map(glob::Pattern::new)
+            .transpose()
+            .map_err(|e| Error::Other(format!("Invalid pattern: {}", e)))?;
+        let max_entries = params
+            .max_entries
+            .unwrap_or(DEFAULT_MAX_ENTRIES)
+            .clamp(1, MAX_ENTRIES_LIMIT);
+        let max_depth = if params.recursive {
+            params.max_depth
+        } else {
+            Some(1)
+        };
+        let respect_gitignore = params.recursive && params.respect_gitignore.unwrap_or(true);
+
+        let mut walker = WalkBuilder::new(root);
+        walker
+            .max_depth(max_depth)
+            .hidden(false)
+            .follow_links(false)
+            .require_git(false)
+            .git_ignore(respect_gitignore)
+            .git_global(respect_gitignore)
+            .git_exclude(respect_gitignore)
+            .ignore(respect_gitignore)
+            .parents(respect_gitignore)
+            .sort_by_file_path(|a, b| a.cmp(b));
+        if respect_gitignore {
+            walker.filter_entry(|entry| {
+                let name = entry.file_name();
+                !(entry.depth() > 0
+                    && entry.file_type().is_some_and(|t| t.is_dir())
+                    && (name == ".git" || name == "target"))
+            });
+        }
+
+        let mut entries = Vec::new();
+        let mut truncated = false;
+        for entry in walker.build() {
+            let entry = match entry {
+                Ok(entry) => entry,
+                // Unreadable subdirectories shouldn't sink the whole listing
+                Err(_) => continue,
+            };
+            if entry.depth() == 0 {
+                continue;
+            }
+
+            let relative = entry.path().strip_prefix(root).unwrap_or(entry.path());
+            if let Some(pattern) = &pattern {
+                let matched = if pattern.as_str().contains('/') {
+                    let options = glob::MatchOptions {
+                        require_literal_separator: true,
+                        ..Default::default()
+                    };
+                    pattern.matches_path_with(relative, options)
                 } else {
-                    "[?]"
+                    pattern.matches(&entry.file_name().to_string_lossy())
                 };
-
-                if let Some(name) = entry.file_name().to_str() {
-                    results.push(format!("{} {}", file_type, name));
+                if !matched {
+                    continue;
                 }
             }
+
+            if entries.len() == max_entries {
+                truncated = true;
+                break;
+            }
+
+            let file_type = entry.file_type();
+            let kind = match file_type {
+                Some(t) if t.is_symlink() => "symlink",
+                Some(t) if t.is_dir() => "dir",
+                Some(_) => "file",
+                None => "unknown",
+            };
+            let metadata = entry.metadata().ok();
+            entries.push(DirectoryEntry {
+                path: relative.to_string_lossy().replace('\\', "/"),
+                kind,
+                size: metadata.as_ref().filter(|m| m.is_file()).map(|m| m.len()),
+                modified: metadata
+                    .and_then(|m| m.modified().ok())
+                    .map(DateTime::<Utc>::from),
+            });
         }
 
-        Ok(results.join("\n"))
+        Ok(ToolOutput::Json(json!({
+            "path": params.path,
+            "count": entries.len(),
+            "truncated": truncated,
+            "note": truncated.then(|| format!(
+                "Listing stopped after {} entries; narrow it with max_depth or pattern, or raise max_entries",
+                max_entries
+            )),
+            "entries": entries,
+        })))
     }
 }
@@ -1,35 +1,81 @@
-use crate::{Tool, Result, Error};
+//! Legacy `memory_save`/`memory_recall`/`memory_delete` tools
+//!
+//! These keep the original tool names and input formats but store everything
+//! through an [`EnhancedMemoryTool`], so memories saved here are visible to the
+//! `enhanced_memory` tool and vice versa.
+
+use crate::tools::EnhancedMemoryTool;
+use crate::{Error, Result, Tool};
 use async_trait::async_trait;
 use serde_json::{json, Value};
-use std::collections::HashMap;
-use std::fs;
-use std::path::PathBuf;
-use chrono::{DateTime, Utc};
-use serde::{Serialize, Deserialize};
-
-#[derive(Debug, Serialize, Deserialize, Clone)]
-struct Memory {
-    id: String,
-    content: String,
-    tags: Vec<String>,
-    created_at: DateTime<Utc>,
-    accessed_at: DateTime<Utc>,
-    access_count: u32,
-    metadata: HashMap<String, Value>,
+use std::sync::Arc;
+
+/// Parse the JSON string returned by [`EnhancedMemoryTool::execute`]
+async fn run(memory: &EnhancedMemoryTool, action: Value) -> Result<Value> {
+    let output = memory.execute(action).await?;
+    serde_json::from_str(&output)
+        .map_err(|e| Error::Other(format!("Unexpected memory tool output: {}", e)))
+}
+
+fn string_list(input: &Value, field: &str) -> Option<Vec<String>> {
+    input.get(field).and_then(|v| v.as_array()).map(|arr| {
+        arr.iter()
+            .filter_map(|v| v.as_str().map(String::from))
+            .collect()
+    })
+}
+
+/// Saves memories through a shared [`EnhancedMemoryTool`]
+///
+/// # Example
+///
+/// ```rust
+/// use claude::tools::memory::MemorySaveTool;
+/// use claude::tools::EnhancedMemoryTool;
+/// use claude::Tool;
+/// use serde_json::{json, Value};
+/// use std::sync::Arc;
+///
+/// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+/// # let dir = tempfile::tempdir().unwrap();
+/// let memory = Arc::new(EnhancedMemoryTool::with_path(dir.path().join("memory.json"))?);
+/// let save = MemorySaveTool::new(memory.clone());
+///
+/// save.execute(json!({"content": "The cat is called Miso", "tags": ["pets"]}))
+///     .await?;
+///
+/// // The memory is visible through the enhanced tool
+/// let found: Value = serde_json::from_str(
+///     &memory
+///         .execute(json!({"action": "search", "query": "Miso"}))
+///         .await?,
+/// )?;
+/// assert_eq!(found["count"], 1);
+/// assert_eq!(found["results"][0]["content"], "The cat is called Miso");
+/// assert_eq!(found["results"][0]["tags"], json!(["pets"]));
+/// # Ok::<(), claude::Error>(())
+/// # }).unwrap();
+/// ```
+pub struct MemorySaveTool {
+    memory: Arc<EnhancedMemoryTool>,
 }
 
-pub struct MemorySaveTool;
+impl MemorySaveTool {
+    pub fn new(memory: Arc<EnhancedMemoryTool>) -> Self {
+        Self { memory }
+    }
+}
 
 #[async_trait]
 impl Tool for MemorySaveTool {
     fn name(&self) -> &str {
         "memory_save"
     }
-    
+
     fn description(&self) -> &str {
         "Save information to long-term memory with tags and metadata"
     }
-    
+
     fn input_schema(&self) -> Value {
         json!({
             "type": "object",
@@ -52,7 +98,7 @@ impl Tool for MemorySaveTool {
             "additionalProperties": false
         })
     }
-    
+
     async fn execute(&self, input: Value) -> Result<String> {
         let content = input
             .get("content")
@@ -60,71 +106,62 @@ impl Tool for MemorySaveTool {
             .ok_or_else(|| Error::Other(
                 "Missing 'content' field. Example: {\"content\": \"Important information to remember\", \"tags\": [\"info\", \"important\"]}".to_string()
             ))?;
-        
-        let tags = input
-            .get("tags")
-            .and_then(|v| v.as_array())
-            .map(|arr| arr.iter().filter_map(|v| v.as_str().map(String::from)).collect())
-            .unwrap_or_else(Vec::new);
-        
-        let metadata = input
+
+        let tags = string_list(&input, "tags").unwrap_or_default();
+
+        // The shared store keeps metadata values as strings
+        let metadata: serde_json::Map<String, Value> = input
             .get("metadata")
             .and_then(|v| v.as_object())
-            .map(|obj| obj.iter().map(|(k, v)| (k.clone(), v.clone())).collect())
-            .unwrap_or_else(HashMap::new);
-        
-        let memory_dir = get_memory_dir();
-        fs::create_dir_all(&memory_dir)
-            .map_err(|e| Error::Other(format!("Failed to create memory directory: {}", e)))?;
-        
-        // Load existing memories
-        let memories_file = memory_dir.join("memories.json");
-        let mut memories: Vec<Memory> = if memories_file.exists() {
-            let data = fs::read_to_string(&memories_file)
-                .map_err(|e| Error::Other(format!("Failed to read memories: {}", e)))?;
-            serde_json::from_str(&data)
-                .unwrap_or_else(|_| Vec::new())
-        } else {
-            Vec::new()
-        };
-        
-        // Create new memory
-        let id = format!("mem_{}", uuid::Uuid::new_v4());
-        let now = Utc::now();
-        let memory = Memory {
-            id: id.clone(),
-            content: content.to_string(),
-            tags,
-            created_at: now,
-            accessed_at: now,
-            access_count: 0,
-            metadata,
-        };
-        
-        memories.push(memory);
-        
-        // Save memories
-        let json_data = serde_json::to_string_pretty(&memories)
-            .map_err(|e| Error::Other(format!("Failed to serialize memories: {}", e)))?;
-        fs::write(&memories_file, json_data)
-            .map_err(|e| Error::Other(format!("Failed to write memories: {}", e)))?;
-        
-        Ok(format!("Memory saved with ID: {}", id))
+            .map(|obj| {
+                obj.iter()
+                    .map(|(k, v)| match v {
+                        Value::String(_) => (k.clone(), v.clone()),
+                        other => (k.clone(), Value::String(other.to_string())),
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        let stored = run(
+            &self.memory,
+            json!({
+                "action": "store",
+                "content": content,
+                "tags": tags,
+                "metadata": metadata
+            }),
+        )
+        .await?;
+
+        Ok(format!(
+            "Memory saved with ID: {}",
+            stored["id"].as_str().unwrap_or_default()
+        ))
     }
 }
 
-pub struct MemoryRecallTool;
+/// Recalls memories through a shared [`EnhancedMemoryTool`]
+pub struct MemoryRecallTool {
+    memory: Arc<EnhancedMemoryTool>,
+}
+
+impl MemoryRecallTool {
+    pub fn new(memory: Arc<EnhancedMemoryTool>) -> Self {
+        Self { memory }
+    }
+}
 
 #[async_trait]
 impl Tool for MemoryRecallTool {
     fn name(&self) -> &str {
         "memory_recall"
     }
-    
+
     fn description(&self) -> &str {
         "Recall memories by searching content, tags, or metadata"
     }
-    
+
     fn input_schema(&self) -> Value {
         json!({
             "type": "object",
@@ -146,113 +183,96 @@ impl Tool for MemoryRecallTool {
             "additionalProperties": false
         })
     }
-    
+
     async fn execute(&self, input: Value) -> Result<String> {
         let query = input.get("query").and_then(|v| v.as_str());
-        let filter_tags: Vec<String> = input
-            .get("tags")
-            .and_then(|v| v.as_array())
-            .map(|arr| arr.iter().filter_map(|v| v.as_str().map(String::from)).collect())
-            .unwrap_or_else(Vec::new);
-        let limit = input
-            .get("limit")
-            .and_then(|v| v.as_i64())
-            .unwrap_or(5) as usize;
-        
-        let memory_dir = get_memory_dir();
-        let memories_file = memory_dir.join("memories.json");
-        
-        if !memories_file.exists() {
-            return Ok("No memories found.".to_string());
+        let filter_tags = string_list(&input, "tags").unwrap_or_default();
+        let limit = input.get("limit").and_then(|v| v.as_u64()).unwrap_or(5) as usize;
+
+        let mut search = json!({"action": "search", "limit": usize::MAX});
+        if let Some(q) = query {
+            search["query"] = json!(q);
         }
-        
-        let data = fs::read_to_string(&memories_file)
-            .map_err(|e| Error::Other(format!("Failed to read memories: {}", e)))?;
-        let mut memories: Vec<Memory> = serde_json::from_str(&data)
-            .map_err(|e| Error::Other(format!("Failed to parse memories: {}", e)))?;
-        
-        // Filter memories
-        let mut filtered: Vec<&mut Memory> = memories.iter_mut()
-            .filter(|memory| {
-                // Check tags
-                if !filter_tags.is_empty() {
-                    if !filter_tags.iter().all(|tag| memory.tags.contains(tag)) {
-                        return false;
-                    }
-                }
-                
-                // Check query
-                if let Some(q) = query {
-                    let q_lower = q.to_lowercase();
-                    if !memory.content.to_lowercase().contains(&q_lower) &&
-                       !memory.tags.iter().any(|tag| tag.to_lowercase().contains(&q_lower)) {
-                        return false;
-                    }
-                }
-                
-                true
+        if !filter_tags.is_empty() {
+            search["tags"] = json!(filter_tags);
+        }
+        let found = run(&self.memory, search).await?;
+
+        // The shared search matches any tag; this tool requires all of them
+        let ids: Vec<String> = found["results"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter(|entry| {
+                filter_tags.iter().all(|tag| {
+                    entry["tags"]
+                        .as_array()
+                        .is_some_and(|t| t.contains(&json!(tag)))
+                })
             })
-            .collect();
-        
-        // Sort by relevance (access count and recency)
-        filtered.sort_by(|a, b| {
-            let a_score = a.access_count as f64 + (a.accessed_at.timestamp() as f64 / 1_000_000.0);
-            let b_score = b.access_count as f64 + (b.accessed_at.timestamp() as f64 / 1_000_000.0);
-            b_score.partial_cmp(&a_score).unwrap()
-        });
-        
-        // Update access info for recalled memories
-        let now = Utc::now();
-        let recalled: Vec<Memory> = filtered.into_iter()
+            .filter_map(|entry| entry["id"].as_str().map(String::from))
             .take(limit)
-            .map(|memory| {
-                memory.accessed_at = now;
-                memory.access_count += 1;
-                memory.clone()
-            })
             .collect();
-        
-        // Save updated memories
-        if !recalled.is_empty() {
-            let json_data = serde_json::to_string_pretty(&memories)
-                .map_err(|e| Error::Other(format!("Failed to serialize memories: {}", e)))?;
-            fs::write(&memories_file, json_data)
-                .map_err(|e| Error::Other(format!("Failed to write memories: {}", e)))?;
+
+        // Fetch each memory individually so its access count is updated
+        let mut recalled = Vec::with_capacity(ids.len());
+        for id in ids {
+            let fetched = run(&self.memory, json!({"action": "get", "id": id})).await?;
+            recalled.push(fetched["entry"].clone());
         }
-        
-        // Format results
+
         if recalled.is_empty() {
-            Ok("No matching memories found.".to_string())
-        } else {
-            let mut result = format!("Found {} memories:\n\n", recalled.len());
-            for (i, memory) in recalled.iter().enumerate() {
-                result.push_str(&format!(
-                    "{}. [{}] {}\n   Tags: {}\n   Created: {}\n   Accessed: {} times\n\n",
-                    i + 1,
-                    memory.id,
-                    memory.content,
-                    memory.tags.join(", "),
-                    memory.created_at.format("%Y-%m-%d %H:%M:%S"),
-                    memory.access_count
-                ));
-            }
-            Ok(result)
+            return Ok("No matching memories found.".to_string());
         }
+
+        let mut result = format!("Found {} memories:\n\n", recalled.len());
+        for (i, memory) in recalled.iter().enumerate() {
+            let tags: Vec<&str> = memory["tags"]
+                .as_array()
+                .into_iter()
+                .flatten()
+                .filter_map(|t| t.as_str())
+                .collect();
+            let created = memory["created_at"]
+                .as_str()
+                .and_then(|s| chrono::DateTime::parse_from_rfc3339(s).ok())
+                .map(|dt| dt.format("%Y-%m-%d %H:%M:%S").to_string())
+                .unwrap_or_default();
+            result.push_str(&format!(
+                "{}. [{}] {}\n   Tags: {}\n   Created: {}\n   Accessed: {} times\n\n",
+                i + 1,
+                memory["id"].as_str().unwrap_or_default(),
+                memory["content"].as_str().unwrap_or_default(),
+                tags.join(", "),
+                created,
+                memory["access_count"].as_u64().unwrap_or_default()
+            ));
+        }
+        Ok(result)
     }
 }
 
-pub struct MemoryDeleteTool;
+/// Deletes memories through a shared [`EnhancedMemoryTool`]
+pub struct MemoryDeleteTool {
+    memory: Arc<EnhancedMemoryTool>,
+}
+
+impl MemoryDeleteTool {
+    pub fn new(memory: Arc<EnhancedMemoryTool>) -> Self {
+        Self { memory }
+    }
+}
 
 #[async_trait]
 impl Tool for MemoryDeleteTool {
     fn name(&self) -> &str {
         "memory_delete"
     }
-    
+
     fn description(&self) -> &str {
         "Delete specific memories by ID"
     }
-    
+
     fn input_schema(&self) -> Value {
         json!({
             "type": "object",
@@ -267,43 +287,26 @@ impl Tool for MemoryDeleteTool {
             "additionalProperties": false
         })
     }
-    
+
     async fn execute(&self, input: Value) -> Result<String> {
-        let memory_ids: Vec<String> = input
-            .get("memory_ids")
-            .and_then(|v| v.as_array())
-            .map(|arr| arr.iter().filter_map(|v| v.as_str().map(String::from)).collect())
-            .ok_or_else(|| Error::Other(
-                "Missing 'memory_ids' field. Example: {\"memory_ids\": [\"mem_123\", \"mem_456\"]}".to_string()
-            ))?;
-        
-        let memory_dir = get_memory_dir();
-        let memories_file = memory_dir.join("memories.json");
-        
-        if !memories_file.exists() {
-            return Ok("No memories found.".to_string());
+        let memory_ids = string_list(&input, "memory_ids").ok_or_else(|| {
+            Error::Other(
+                "Missing 'memory_ids' field. Example: {\"memory_ids\": [\"mem_123\", \"mem_456\"]}"
+                    .to_string(),
+            )
+        })?;
+
+        // Unknown ids are skipped rather than treated as errors
+        let mut deleted_count = 0;
+        for id in memory_ids {
+            if run(&self.memory, json!({"action": "delete", "id": id}))
+                .await
+                .is_ok()
+            {
+                deleted_count += 1;
+            }
         }
-        
-        let data = fs::read_to_string(&memories_file)
-            .map_err(|e| Error::Other(format!("Failed to read memories: {}", e)))?;
-        let mut memories: Vec<Memory> = serde_json::from_str(&data)
-            .map_err(|e| Error::Other(format!("Failed to parse memories: {}", e)))?;
-        
-        let original_count = memories.len();
-        memories.retain(|memory| !memory_ids.contains(&memory.id));
-        let deleted_count = original_count - memories.len();
-        
-        // Save updated memories
-        let json_data = serde_json::to_string_pretty(&memories)
-            .map_err(|e| Error::Other(format!("Failed to serialize memories: {}", e)))?;
-        fs::write(&memories_file, json_data)
-            .map_err(|e| Error::Other(format!("Failed to write memories: {}", e)))?;
-        
+
         Ok(format!("Deleted {} memories", deleted_count))
     }
 }
-
-fn get_memory_dir() -> PathBuf {
-    let home_dir = std::env::home_dir().expect("Unable to determine home directory");
-    home_dir.join(".chatbot_memory")
-}
\ No newline at end of file
@@ -1,35 +1,59 @@
 pub mod bash;
 pub mod calculator;
 pub mod enhanced_memory;
+#[cfg(feature = "firecrawl")]
 pub mod firecrawl_crawl;
+#[cfg(feature = "firecrawl")]
+pub mod firecrawl_error;
+#[cfg(feature = "firecrawl")]
 pub mod firecrawl_extract;
+#[cfg(feature = "firecrawl")]
 pub mod firecrawl_map;
+#[cfg(feature = "firecrawl")]
 pub mod firecrawl_search;
+pub mod git;
 pub mod http_fetch;
+#[cfg(feature = "search")]
 pub mod list_directory;
+pub mod memory;
 pub mod patch_file;
 pub mod read_file;
+#[cfg(feature = "search")]
+pub mod search_files;
 pub mod system_info;
 pub mod think;
 pub mod todo;
 pub mod weather;
 pub mod wikipedia;
+pub mod write_file;
+#[cfg(feature = "z3")]
 pub mod z3_solver;
 
-pub use bash::BashTool;
+pub use bash::{BashTool, DEFAULT_BLOCKLIST, DEFAULT_MAX_STREAM_BYTES};
 pub use calculator::CalculatorTool;
 pub use enhanced_memory::EnhancedMemoryTool;
+#[cfg(feature = "firecrawl")]
 pub use firecrawl_crawl::FirecrawlCrawlTool;
+#[cfg(feature = "firecrawl")]
 pub use firecrawl_extract::FirecrawlExtractTool;
+#[cfg(feature = "firecrawl")]
 pub use firecrawl_map::FirecrawlMapTool;
+#[cfg(feature = "firecrawl")]
 pub use firecrawl_search::FirecrawlSearchTool;
+pub use git::GitTool;
 pub use http_fetch::HttpFetchTool;
+#[cfg(feature = "search")]
 pub use list_directory::ListDirectoryTool;
+pub use memory::{MemoryDeleteTool, MemoryRecallTool, MemorySaveTool};
 pub use patch_file::PatchFileTool;
 pub use read_file::ReadFileTool;
+#[cfg(feature = "search")]
+pub use search_files::SearchFilesTool;
 pub use system_info::SystemInfoTool;
-pub use think::ThinkTool;
+pub use think::{ThinkTool, ThinkingBudget};
 pub use todo::TodoTool;
 pub use weather::WeatherTool;
 pub use wikipedia::WikipediaTool;
+pub use write_file::WriteFileTool;
+#[cfg(feature = "z3")]
 pub use z3_solver::Z3SolverTool;
@@ -1,11 +1,389 @@
-use crate::{Error, Result, Tool};
+use crate::paths::{resolve_within, write_no_follow};
+use crate::{Error, Result, Tool, ToolOutput};
 use async_trait::async_trait;
+use serde::Serialize;
 use serde_json::{json, Value};
-use std::io::Write;
-use std::process::Command;
-use tempfile::NamedTempFile;
+use std::fs;
+use std::path::{Path, PathBuf};
 
-pub struct PatchFileTool;
+/// How far, in lines, a hunk may have drifted from its stated position
+const FUZZ_WINDOW: usize = 20;
+
+/// Applies unified diffs to files
+///
+/// Hunks are located at their stated line first and then, if the context no
+/// longer lines up, searched for within a small window around it, so patches
+/// whose line numbers have drifted still apply. A patch is applied all or
+/// nothing, and the original file is saved as `<path>.orig` before writing.
+///
+/// A diff whose `---` header is `/dev/null` creates the file, along with any
+/// missing parent directories, and one whose `+++` header is `/dev/null`
+/// deletes it. Paths can be confined to a directory with
+/// [`with_root`](Self::with_root).
+///
+/// # Example
+///
+/// ```rust
+/// use claude::tools::PatchFileTool;
+/// use claude::{Tool, ToolOutput};
+/// use serde_json::json;
+///
+/// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+/// let dir = tempfile::tempdir().unwrap();
+/// let path = dir.path().join("notes.txt");
+/// std::fs::write(&path, "one\ntwo\nthree\nfour\nfive\n").unwrap();
+///
+/// // A clean patch applies at the stated line and keeps a backup
+/// let clean = "--- a/notes.txt\n+++ b/notes.txt\n@@ -2,2 +2,2 @@\n two\n-three\n+THREE\n";
+/// let ToolOutput::Json(report) = PatchFileTool::new()
+///     .execute_typed(json!({"path": path, "diff": clean}))
+///     .await?
+/// else {
+///     panic!("expected JSON output")
+/// };
+/// assert_eq!(report["hunks"][0]["status"], "applied");
+/// assert_eq!(std::fs::read_to_string(&path).unwrap(), "one\ntwo\nTHREE\nfour\nfive\n");
+/// assert_eq!(
+///     std::fs::read_to_string(dir.path().join("notes.txt.orig")).unwrap(),
+///     "one\ntwo\nthree\nfour\nfive\n"
+/// );
+///
+/// // Two lines were inserted above, so this hunk's line numbers are stale
+/// std::fs::write(&path, "zero\nhalf\none\ntwo\nTHREE\nfour\nfive\n").unwrap();
+/// let drifted = "@@ -4,2 +4,2 @@\n four\n-five\n+FIVE\n";
+///
+/// // A dry run reports the offset without touching the file
+/// let ToolOutput::Json(report) = PatchFileTool::new()
+///     .execute_typed(json!({"path": path, "diff": drifted, "dry_run": true}))
+///     .await?
+/// else {
+///     panic!("expected JSON output")
+/// };
+/// assert_eq!(report["applied"], false);
+/// assert_eq!(report["hunks"][0]["status"], "applied_with_offset");
+/// assert_eq!(report["hunks"][0]["offset"], 2);
+/// assert!(std::fs::read_to_string(&path).unwrap().ends_with("five\n"));
+///
+/// PatchFileTool::new()
+///     .execute(json!({"path": path, "diff": drifted}))
+///     .await?;
+/// assert!(std::fs::read_to_string(&path).unwrap().ends_with("four\nFIVE\n"));
+///
+/// // A conflicting hunk rejects the whole patch and leaves the file alone
+/// let before = std::fs::read_to_string(&path).unwrap();
+/// let conflict = "@@ -1,1 +1,1 @@\n-zero\n+ZERO\n@@ -3,1 +3,1 @@\n-missing\n+gone\n";
+/// let err = PatchFileTool::new()
+///     .execute(json!({"path": path, "diff": conflict}))
+///     .await
+///     .unwrap_err();
+/// assert!(err.to_string().contains("hunk 2: failed"), "{}", err);
+/// assert_eq!(std::fs::read_to_string(&path).unwrap(), before);
+/// # Ok::<(), claude::Error>(())
+/// # }).unwrap();
+/// ```
+///
+/// Creating and deleting whole files:
+///
+/// ```rust
+/// use claude::tools::PatchFileTool;
+/// use claude::{Tool, ToolOutput};
+/// use serde_json::json;
+///
+/// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+/// let dir = tempfile::tempdir().unwrap();
+/// let path = dir.path().join("src/bin/hello.rs");
+///
+/// let create = "--- /dev/null\n+++ b/src/bin/hello.rs\n@@ -0,0 +1,3 @@\n+fn main() {\n+    println!(\"hi\");\n+}\n";
+/// let ToolOutput::Json(report) = PatchFileTool::new()
+///     .execute_typed(json!({"path": path, "diff": create}))
+///     .await?
+/// else {
+///     panic!("expected JSON output")
+/// };
+/// assert_eq!(report["change"], "create");
+/// assert_eq!(std::fs::read_to_string(&path).unwrap(), "fn main() {\n    println!(\"hi\");\n}\n");
+///
+/// // Creating a file that already exists is refused
+/// let err = PatchFileTool::new()
+///     .execute(json!({"path": path, "diff": create}))
+///     .await
+///     .unwrap_err();
+/// assert!(err.to_string().contains("already exists"), "{}", err);
+///
+/// // Deleting requires the diff to remove every line
+/// let partial = "--- a/src/bin/hello.rs\n+++ /dev/null\n@@ -1,1 +0,0 @@\n-fn main() {\n";
+/// let err = PatchFileTool::new()
+///     .execute(json!({"path": path, "diff": partial}))
+///     .await
+///     .unwrap_err();
+/// assert!(err.to_string().contains("does not remove all"), "{}", err);
+/// assert!(path.exists());
+///
+/// let delete = "--- a/src/bin/hello.rs\n+++ /dev/null\n@@ -1,3 +0,0 @@\n-fn main() {\n-    println!(\"hi\");\n-}\n";
+/// let ToolOutput::Json(report) = PatchFileTool::new()
+///     .execute_typed(json!({"path": path, "diff": delete}))
+///     .await?
+/// else {
+///     panic!("expected JSON output")
+/// };
+/// assert_eq!(report["change"], "delete");
+/// assert!(!path.exists());
+/// assert!(dir.path().join("src/bin/hello.rs.orig").exists());
+/// # Ok::<(), claude::Error>(())
+/// # }).unwrap();
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct PatchFileTool {
+    /// Directory every patched path must be inside, if restricted
+    root: Option<PathBuf>,
+}
+
+impl PatchFileTool {
+    /// Create a tool that may patch any path
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create a tool that only patches files inside `root`
+    ///
+    /// Relative paths are resolved against `root`. A path that leaves it, with
+    /// `..` components, an absolute path, or a symlink, is rejected before
+    /// anything is read or written.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use claude::tools::PatchFileTool;
+    /// use claude::Tool;
+    /// use serde_json::json;
+    ///
+    /// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+    /// let dir = tempfile::tempdir().unwrap();
+    /// let root = dir.path().join("project");
+    /// std::fs::create_dir(&root).unwrap();
+    /// let tool = PatchFileTool::with_root(&root);
+    ///
+    /// let create = "--- /dev/null\n+++ b/notes.txt\n@@ -0,0 +1 @@\n+hello\n";
+    /// tool.execute(json!({"path": "docs/notes.txt", "diff": create})).await?;
+    /// assert_eq!(std::fs::read_to_string(root.join("docs/notes.txt")).unwrap(), "hello\n");
+    ///
+    /// for path in [
+    ///     "../escape.txt".to_string(),
+    ///     "docs/../../escape.txt".to_string(),
+    ///     dir.path().join("escape.txt").display().to_string(),
+    /// ] {
+    ///     let err = tool
+    ///         .execute(json!({"path": path, "diff": create}))
+    ///         .await
+    ///         .unwrap_err();
+    ///     assert!(err.to_string().contains("outside"), "{}", err);
+    /// }
+    /// assert!(!dir.path().join("escape.txt").exists());
+    /// # Ok::<(), claude::Error>(())
+    /// # }).unwrap();
+    /// ```
+    pub fn with_root(root: impl Into<PathBuf>) -> Self {
+        Self {
+            root: Some(root.into()),
+        }
+    }
+
+    /// The directory patched paths are confined to, if any
+    pub fn root(&self) -> Option<&Path> {
+        self.root.as_deref()
+    }
+
+    /// Resolve `path` against the root, rejecting paths that leave it
+    fn resolve(&self, path: &str) -> Result<PathBuf> {
+        let Some(root) = &self.root else {
+            return Ok(PathBuf::from(path));
+        };
+        let resolved = resolve_within(root, path).map_err(|e| {
+            Error::Other(format!(
+                "Failed to resolve patch root {}: {}",
+                root.display(),
+                e
+            ))
+        })?;
+        resolved.ok_or_else(|| {
+            Error::Other(format!(
+                "Refusing to patch {}: it is outside the allowed root {}",
+                path,
+                root.display()
+            ))
+        })
+    }
+}
+
+/// What a diff does to the file as a whole
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+enum FileChange {
+    /// Patch an existing file
+    Modify,
+    /// Create the file; the `---` header is `/dev/null`
+    Create,
+    /// Delete the file; the `+++` header is `/dev/null`
+    Delete,
+}
+
+/// Read the file headers before the first hunk for `/dev/null`
+fn file_change(diff: &str) -> FileChange {
+    let mut change = FileChange::Modify;
+    for line in diff.lines().take_while(|line| !line.starts_with("@@")) {
+        let header_path = |prefix: &str| {
+            line.strip_prefix(prefix)
+                .map(|rest| rest.split('\t').next().unwrap_or_default().trim())
+        };
+        if header_path("--- ") == Some("/dev/null") {
+            change = FileChange::Create;
+        } else if header_path("+++ ") == Some("/dev/null") {
+            change = FileChange::Delete;
+        }
+    }
+    change
+}
+
+/// One `@@` section of a unified diff
+#[derive(Debug)]
+struct Hunk {
+    /// 1-based line the hunk claims to start at in the original file
+    old_start: usize,
+    /// Context and removed lines, in order
+    old_lines: Vec<String>,
+    /// Context and added lines, in order
+    new_lines: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+struct HunkReport {
+    hunk: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    line: Option<usize>,
+    offset: isize,
+    status: &'static str,
+}
+
+/// Parse the start line out of a `@@ -a,b +c,d @@` header
+fn parse_hunk_header(line: &str) -> Result<usize> {
+    line.strip_prefix("@@ -")
+        .and_then(|rest| rest.split([',', ' ']).next())
+        .and_then(|start| start.parse().ok())
+        .ok_or_else(|| Error::Other(format!("Malformed hunk header: {}", line)))
+}
+
+fn parse_hunks(diff: &str) -> Result<Vec<Hunk>> {
+    let mut hunks: Vec<Hunk> = Vec::new();
+
+    for line in diff.lines() {
+        if line.starts_with("@@") {
+            hunks.push(Hunk {
+                old_start: parse_hunk_header(line)?,
+                old_lines: Vec::new(),
+                new_lines: Vec::new(),
+            });
+            continue;
+        }
+
+        // File headers and anything else before the first hunk
+        let Some(hunk) = hunks.last_mut() else {
+            continue;
+        };
+
+        if let Some(text) = line.strip_prefix('+') {
+            hunk.new_lines.push(text.to_string());
+        } else if let Some(text) = line.strip_prefix('-') {
+            hunk.old_lines.push(text.to_string());
+        } else if line.starts_with('\\') {
+            // "\ No newline at end of file"
+        } else {
+            // Context; a blank line is context whose leading space was stripped
+            let text = line.strip_prefix(' ').unwrap_or(line);
+            hunk.old_lines.push(text.to_string());
+            hunk.new_lines.push(text.to_string());
+        }
+    }
+
+    if hunks.is_empty() {
+        return Err(Error::Other(
+            "No hunks found in diff. Expected unified diff format with '@@ -start,count +start,count @@' headers".to_string(),
+        ));
+    }
+
+    Ok(hunks)
+}
+
+/// Whether `expected` appears in `lines` starting at `pos`
+fn matches_at(lines: &[String], expected: &[String], pos: usize) -> bool {
+    pos + expected.len() <= lines.len()
+        && expected
+            .iter()
+            .zip(&lines[pos..])
+            .all(|(want, have)| want == have || want.trim_end() == have.trim_end())
+}
+
+/// Apply `hunks` to `lines`, returning the patched lines and a report per hunk
+///
+/// Patched lines are `None` if any hunk could not be placed.
+fn apply_hunks(lines: &[String], hunks: &[Hunk]) -> (Option<Vec<String>>, Vec<HunkReport>) {
+    let mut output = Vec::with_capacity(lines.len());
+    let mut reports = Vec::with_capacity(hunks.len());
+    // Next unconsumed line of the original, and how far earlier hunks moved things
+    let mut cursor = 0;
+    let mut drift: isize = 0;
+    let mut failed = false;
+
+    for (i, hunk) in hunks.iter().enumerate() {
+        // Pure insertions into an empty region use the line after `old_start`
+        let stated = if hunk.old_lines.is_empty() {
+            hunk.old_start
+        } else {
+            hunk.old_start.saturating_sub(1)
+        };
+        let expected = (stated as isize + drift).max(cursor as isize) as usize;
+
+        let found = (0..=FUZZ_WINDOW)
+            .flat_map(|d| [expected.checked_add(d), expected.checked_sub(d)])
+            .flatten()
+            .filter(|&pos| pos >= cursor)
+            .find(|&pos| matches_at(lines, &hunk.old_lines, pos));
+
+        match found {
+            Some(pos) => {
+                let offset = pos as isize - stated as isize;
+                reports.push(HunkReport {
+                    hunk: i + 1,
+                    line: Some(pos + 1),
+                    offset,
+                    status: if offset == 0 {
+                        "applied"
+                    } else {
+                        "applied_with_offset"
+                    },
+                });
+                output.extend_from_slice(&lines[cursor..pos]);
+                output.extend(hunk.new_lines.iter().cloned());
+                cursor = pos + hunk.old_lines.len();
+                drift = offset;
+            }
+            None => {
+                failed = true;
+                reports.push(HunkReport {
+                    hunk: i + 1,
+                    line: None,
+                    offset: 0,
+                    status: "failed",
+                });
+            }
+        }
+    }
+
+    if failed {
+        return (None, reports);
+    }
+    output.extend_from_slice(&lines[cursor..]);
+    (Some(output), reports)
+}
 
 #[async_trait]
 impl Tool for PatchFileTool {
@@ -14,7 +392,7 @@ impl Tool for PatchFileTool {
     }
 
     fn description(&self) -> &str {
-        "Apply a diff/patch to a file on the filesystem"
+        "Apply a unified diff to a file on the filesystem. Hunks whose line numbers have drifted slightly are still applied; a patch with any conflicting hunk is rejected as a whole. A '--- /dev/null' header creates the file and a '+++ /dev/null' header deletes it. Use dry_run to check which hunks would apply without modifying the file."
     }
 
     fn input_schema(&self) -> Value {
@@ -23,11 +401,15 @@ impl Tool for PatchFileTool {
             "properties": {
                 "path": {
                     "type": "string",
-                    "description": "The file path to patch"
+                    "description": "The file path to patch, created if the diff's '---' header is /dev/null"
                 },
                 "diff": {
                     "type": "string",
                     "description": "The diff/patch content to apply (in unified diff format)"
+                },
+                "dry_run": {
+                    "type": "boolean",
+                    "description": "Report which hunks would apply without modifying the file (default: false)"
                 }
             },
             "required": ["path", "diff"],
@@ -36,6 +418,10 @@ impl Tool for PatchFileTool {
     }
 
     async fn execute(&self, input: Value) -> Result<String> {
+        Ok(self.execute_typed(input).await?.into_text())
+    }
+
+    async fn execute_typed(&self, input: Value) -> Result<ToolOutput> {
         // First check if we got an object at all
         if !input.is_object() {
             return Err(Error::Other(format!(
@@ -61,33 +447,117 @@ impl Tool for PatchFileTool {
             .and_then(|v| v.as_str())
             .ok_or_else(|| Error::Other("Missing 'diff' field".to_string()))?;
 
-        // Create a temporary file with the diff content
-        let mut temp_file = NamedTempFile::new()
-            .map_err(|e| Error::Other(format!("Failed to create temp file: {}", e)))?;
-
-        temp_file
-            .write_all(diff.as_bytes())
-            .map_err(|e| Error::Other(format!("Failed to write diff to temp file: {}", e)))?;
-
-        temp_file
-            .flush()
-            .map_err(|e| Error::Other(format!("Failed to flush temp file: {}", e)))?;
-
-        // Apply the patch using the patch command
-        let output = Command::new("patch")
-            .arg("-u") // Unified diff format
-            .arg(path)
-            .arg("-i")
-            .arg(temp_file.path())
-            .output()
-            .map_err(|e| Error::Other(format!("Failed to execute patch command: {}", e)))?;
-
-        if !output.status.success() {
-            let stderr = String::from_utf8_lossy(&output.stderr);
-            return Err(Error::Other(format!("Failed to apply patch: {}", stderr)));
+        let dry_run = input
+            .get("dry_run")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false);
+
+        let hunks = parse_hunks(diff)?;
+        let change = file_change(diff);
+        let resolved = self.resolve(path)?;
+        let path_ref = resolved.as_path();
+
+        if change == FileChange::Create {
+            if path_ref.exists() {
+                return Err(Error::Other(format!(
+                    "Cannot create {}: the file already exists",
+                    path
+                )));
+            }
+            if hunks.iter().any(|h| !h.old_lines.is_empty()) {
+                return Err(Error::Other(format!(
+                    "The diff creates {} from /dev/null but has context or removed lines",
+                    path
+                )));
+            }
+        }
+
+        // A missing file is only acceptable for a diff that creates it
+        let original = if path_ref.exists() {
+            fs::read_to_string(path_ref)
+                .map_err(|e| Error::Other(format!("Failed to read {}: {}", path, e)))?
+        } else if hunks.iter().all(|h| h.old_lines.is_empty()) {
+            String::new()
+        } else {
+            return Err(Error::Other(format!("File not found: {}", path)));
+        };
+
+        let lines: Vec<String> = original.lines().map(String::from).collect();
+        let (patched, reports) = apply_hunks(&lines, &hunks);
+
+        if dry_run {
+            return Ok(ToolOutput::Json(json!({
+                "path": path,
+                "dry_run": true,
+                "applied": false,
+                "change": change,
+                "would_apply": patched
+                    .as_ref()
+                    .is_some_and(|lines| change != FileChange::Delete || lines.is_empty()),
+                "hunks": reports,
+            })));
+        }
+
+        let Some(patched) = patched else {
+            let summary: Vec<String> = reports
+                .iter()
+                .map(|r| match r.line {
+                    Some(line) => format!("hunk {}: {} at line {}", r.hunk, r.status, line),
+                    None => format!("hunk {}: {} (context not found)", r.hunk, r.status),
+                })
+                .collect();
+            return Err(Error::Other(format!(
+                "Failed to apply patch to {}; the file was not modified.\n{}",
+                path,
+                summary.join("\n")
+            )));
+        };
+
+        if change == FileChange::Delete && !patched.is_empty() {
+            return Err(Error::Other(format!(
+                "The diff deletes {} but does not remove all of its lines ({} would remain); the file was not modified",
+                path,
+                patched.len()
+            )));
+        }
+
+        let mut content = patched.join("\n");
+        if !patched.is_empty() && (original.ends_with('\n') || original.is_empty()) {
+            content.push('\n');
+        }
+
+        let backup = if path_ref.exists() {
+            let mut backup = path_ref.as_os_str().to_owned();
+            backup.push(".orig");
+            let backup = PathBuf::from(backup);
+            fs::read(path_ref)
+                .and_then(|original| write_no_follow(&backup, original))
+                .map_err(|e| Error::Other(format!("Failed to write backup: {}", e)))?;
+            Some(backup)
+        } else {
+            None
+        };
+
+        if change == FileChange::Delete {
+            fs::remove_file(path_ref)
+                .map_err(|e| Error::Other(format!("Failed to delete {}: {}", path, e)))?;
+        } else {
+            if let Some(parent) = path_ref.parent().filter(|p| !p.as_os_str().is_empty()) {
+                fs::create_dir_all(parent).map_err(|e| {
+                    Error::Other(format!("Failed to create directories for {}: {}", path, e))
+                })?;
+            }
+            write_no_follow(path_ref, content)
+                .map_err(|e| Error::Other(format!("Failed to write {}: {}", path, e)))?;
         }
 
-        let stdout = String::from_utf8_lossy(&output.stdout);
-        Ok(format!("Successfully patched {}: {}", path, stdout.trim()))
+        Ok(ToolOutput::Json(json!({
+            "path": path,
+            "dry_run": false,
+            "applied": true,
+            "change": change,
+            "backup": backup,
+            "hunks": reports,
+        })))
     }
 }
@@ -1,9 +1,122 @@
 use crate::{Error, Result, Tool};
 use async_trait::async_trait;
+use serde::Deserialize;
 use serde_json::{json, Value};
+use std::fs::File;
+use std::io::{BufRead, BufReader, Read, Seek, SeekFrom};
 
+/// How much of the file is inspected when deciding whether it is binary
+const SNIFF_BYTES: usize = 8192;
+
+/// Reads text files, whole or in line or byte ranges
+///
+/// Output starts with a bracketed header giving the range returned and the
+/// file's total line count, so callers can page through large files. Binary
+/// files are reported by size instead of being dumped.
+///
+/// # Example
+///
+/// ```rust
+/// use claude::tools::ReadFileTool;
+/// use claude::Tool;
+/// use serde_json::json;
+///
+/// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+/// let dir = tempfile::tempdir().unwrap();
+/// let log = dir.path().join("app.log");
+/// let lines: Vec<String> = (1..=100).map(|i| format!("line {}", i)).collect();
+/// std::fs::write(&log, lines.join("\n") + "\n").unwrap();
+///
+/// let page = ReadFileTool
+///     .execute(json!({"path": log, "start_line": 10, "end_line": 12}))
+///     .await?;
+/// assert_eq!(page, "[lines 10-12 of 100]\nline 10\nline 11\nline 12\n");
+///
+/// // A range running off the end is clamped; one starting past it is an error
+/// let tail = ReadFileTool
+///     .execute(json!({"path": log, "start_line": 99, "end_line": 500}))
+///     .await?;
+/// assert_eq!(tail, "[lines 99-100 of 100]\nline 99\nline 100\n");
+/// let err = ReadFileTool
+///     .execute(json!({"path": log, "start_line": 101}))
+///     .await
+///     .unwrap_err();
+/// assert!(err.to_string().contains("past the end of the file (100 lines)"));
+///
+/// let bytes = ReadFileTool
+///     .execute(json!({"path": log, "offset": 7, "limit": 6}))
+///     .await?;
+/// assert_eq!(bytes, "[bytes 7-12 of 792, 100 lines total]\nline 2");
+///
+/// let binary = dir.path().join("image.png");
+/// std::fs::write(&binary, [0x89, b'P', b'N', b'G', 0, 0, 0, 13]).unwrap();
+/// let message = ReadFileTool.execute(json!({"path": binary})).await?;
+/// assert!(message.starts_with("Binary file, 8 bytes"), "{}", message);
+/// # Ok::<(), claude::Error>(())
+/// # }).unwrap();
+/// ```
 pub struct ReadFileTool;
 
+#[derive(Debug, Deserialize)]
+struct ReadFileInput {
+    path: String,
+    start_line: Option<usize>,
+    end_line: Option<usize>,
+    offset: Option<u64>,
+    limit: Option<u64>,
+}
+
+/// Whether `bytes` look like binary data rather than UTF-8 text
+///
+/// A multi-byte character cut off at the end of `bytes` is not counted as
+/// invalid, since `bytes` may be a prefix of the file.
+fn looks_binary(bytes: &[u8]) -> bool {
+    if bytes.contains(&0) {
+        return true;
+    }
+    match std::str::from_utf8(bytes) {
+        Ok(_) => false,
+        Err(e) => e.error_len().is_some(),
+    }
+}
+
+fn open(path: &str) -> Result<File> {
+    File::open(path).map_err(|e| Error::Other(format!("Failed to read file: {}", e)))
+}
+
+fn read_error(e: std::io::Error) -> Error {
+    Error::Other(format!("Failed to read file: {}", e))
+}
+
+/// Count lines the same way `str::lines` does: a trailing newline does not
+/// start another line
+fn count_lines(path: &str) -> Result<usize> {
+    let mut reader = BufReader::new(open(path)?);
+    let mut count = 0;
+    let mut last = None;
+    loop {
+        let buf = reader.fill_buf().map_err(read_error)?;
+        if buf.is_empty() {
+            break;
+        }
+        count += buf.iter().filter(|&&b| b == b'\n').count();
+        last = buf.last().copied();
+        let len = buf.len();
+        reader.consume(len);
+    }
+    Ok(match last {
+        Some(b'\n') | None => count,
+        Some(_) => count + 1,
+    })
+}
+
+fn binary_message(path: &str, size: u64) -> String {
+    format!(
+        "Binary file, {} bytes: {}. Its contents were not returned",
+        size, path
+    )
+}
+
 #[async_trait]
 impl Tool for ReadFileTool {
     fn name(&self) -> &str {
@@ -11,7 +124,7 @@ impl Tool for ReadFileTool {
     }
 
     fn description(&self) -> &str {
-        "Read content from a file on the filesystem"
+        "Read content from a text file on the filesystem. Use start_line/end_line (1-based, inclusive) or offset/limit (bytes) to read part of a large file; the output header shows the range and the total line count."
     }
 
     fn input_schema(&self) -> Value {
@@ -21,6 +134,26 @@ impl Tool for ReadFileTool {
                 "path": {
                     "type": "string",
                     "description": "The file path to read from"
+                },
+                "start_line": {
+                    "type": "integer",
+                    "minimum": 1,
+                    "description": "First line to read, 1-based (default: 1)"
+                },
+                "end_line": {
+                    "type": "integer",
+                    "minimum": 1,
+                    "description": "Last line to read, inclusive (default: end of file)"
+                },
+                "offset": {
+                    "type": "integer",
+                    "minimum": 0,
+                    "description": "Byte offset to start reading at; use instead of start_line/end_line"
+                },
+                "limit": {
+                    "type": "integer",
+                    "minimum": 0,
+                    "description": "Maximum number of bytes to read from offset (default: to end of file)"
                 }
             },
             "required": ["path"],
@@ -29,15 +162,108 @@ impl Tool for ReadFileTool {
     }
 
     async fn execute(&self, input: Value) -> Result<String> {
-        let path = input.get("path").and_then(|v| v.as_str()).ok_or_else(|| {
-            Error::Other(
-                "Missing 'path' field. Example: {\"path\": \"/home/user/document.txt\"}"
-                    .to_string(),
-            )
+        let params: ReadFileInput = serde_json::from_value(input).map_err(|e| {
+            Error::Other(format!(
+                "Invalid input parameters: {}. Example: {{\"path\": \"/home/user/document.txt\"}}",
+                e
+            ))
         })?;
+        let path = params.path.as_str();
+
+        let byte_range = params.offset.is_some() || params.limit.is_some();
+        let line_range = params.start_line.is_some() || params.end_line.is_some();
+        if byte_range && line_range {
+            return Err(Error::Other(
+                "Use either start_line/end_line or offset/limit, not both".to_string(),
+            ));
+        }
+
+        let mut file = open(path)?;
+        let size = file.metadata().map_err(read_error)?.len();
+
+        let mut sniff = Vec::with_capacity(SNIFF_BYTES);
+        (&mut file)
+            .take(SNIFF_BYTES as u64)
+            .read_to_end(&mut sniff)
+            .map_err(read_error)?;
+        if looks_binary(&sniff) {
+            return Ok(binary_message(path, size));
+        }
+
+        if byte_range {
+            let offset = params.offset.unwrap_or(0);
+            if offset > size {
+                return Err(Error::Other(format!(
+                    "offset {} is past the end of the file ({} bytes)",
+                    offset, size
+                )));
+            }
+            let limit = params.limit.unwrap_or(size - offset);
+
+            let mut bytes = Vec::new();
+            file.seek(SeekFrom::Start(offset)).map_err(read_error)?;
+            file.take(limit)
+                .read_to_end(&mut bytes)
+                .map_err(read_error)?;
+            if bytes.contains(&0) {
+                return Ok(binary_message(path, size));
+            }
+
+            let end = offset + bytes.len() as u64;
+            return Ok(format!(
+                "[bytes {}-{} of {}, {} lines total]\n{}",
+                offset,
+                end.saturating_sub(1).max(offset),
+                size,
+                count_lines(path)?,
+                String::from_utf8_lossy(&bytes)
+            ));
+        }
+
+        let start = params.start_line.unwrap_or(1);
+        let end = params.end_line.unwrap_or(usize::MAX);
+        if start == 0 || end < start {
+            return Err(Error::Other(format!(
+                "Invalid line range {}-{}: lines are 1-based and end_line must not be before start_line",
+                start, end
+            )));
+        }
+
+        file.seek(SeekFrom::Start(0)).map_err(read_error)?;
+        let mut reader = BufReader::new(file);
+        let mut content = String::new();
+        let mut total = 0;
+        let mut line = Vec::new();
+        loop {
+            line.clear();
+            if reader.read_until(b'\n', &mut line).map_err(read_error)? == 0 {
+                break;
+            }
+            total += 1;
+            if (start..=end).contains(&total) {
+                match std::str::from_utf8(&line) {
+                    Ok(text) if !text.contains('\0') => content.push_str(text),
+                    _ => return Ok(binary_message(path, size)),
+                }
+            }
+        }
 
-        use std::fs;
+        if total == 0 {
+            return Ok("[empty file]\n".to_string());
+        }
+        if start > total {
+            return Err(Error::Other(format!(
+                "start_line {} is past the end of the file ({} lines)",
+                start, total
+            )));
+        }
 
-        fs::read_to_string(path).map_err(|e| Error::Other(format!("Failed to read file: {}", e)))
+        Ok(format!(
+            "[lines {}-{} of {}]\n{}",
+            start,
+            end.min(total),
+            total,
+            content
+        ))
     }
 }
@@ -0,0 +1,313 @@
+use crate::{Error, Result, Tool, ToolOutput};
+use async_trait::async_trait;
+use ignore::WalkBuilder;
+use regex::RegexBuilder;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::fs;
+use std::path::Path;
+
+const DEFAULT_MAX_RESULTS: usize = 100;
+const MAX_RESULTS_LIMIT: usize = 1000;
+
+/// Longest line returned, in characters; longer lines are cut with "..."
+const MAX_LINE_CHARS: usize = 300;
+
+/// Files bigger than this are skipped rather than read into memory
+const MAX_FILE_BYTES: u64 = 10 * 1024 * 1024;
+
+/// Searches file contents for a regex, like a small ripgrep
+///
+/// Walks `path` recursively, skipping `.git`, `target` and anything matched by
+/// `.gitignore`/`.ignore` files unless `respect_gitignore` is `false`. Binary
+/// and non-UTF-8 files are skipped. Returns each matching line with its file
+/// and line number, and stops after `max_results` matches with `truncated`
+/// set.
+///
+/// # Example
+///
+/// ```rust
+/// use claude::tools::SearchFilesTool;
+/// use claude::{Tool, ToolOutput};
+/// use serde_json::{json, Value};
+///
+/// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+/// let dir = tempfile::tempdir().unwrap();
+/// let root = dir.path();
+/// std::fs::create_dir_all(root.join("src/nested")).unwrap();
+/// std::fs::create_dir_all(root.join("target")).unwrap();
+/// std::fs::write(root.join("src/lib.rs"), "fn parse(input: &str) {}\nfn main() {\n    parse(\"x\");\n}\n").unwrap();
+/// std::fs::write(root.join("src/nested/util.rs"), "pub fn parse_all() {}\n").unwrap();
+/// std::fs::write(root.join("README.md"), "Call parse( to start.\n").unwrap();
+/// std::fs::write(root.join("target/gen.rs"), "fn parse() {}\n").unwrap();
+/// std::fs::write(root.join("notes.log"), "parse failed\n").unwrap();
+/// std::fs::write(root.join(".gitignore"), "*.log\n").unwrap();
+///
+/// let search = |input: Value| async move {
+///     let ToolOutput::Json(output) = SearchFilesTool.execute_typed(input).await? else {
+///         panic!("expected JSON output")
+///     };
+///     let hits: Vec<String> = output["matches"]
+///         .as_array()
+///         .unwrap()
+///         .iter()
+///         .map(|m| format!("{}:{}", m["path"].as_str().unwrap(), m["line"]))
+///         .collect();
+///     Ok::<_, claude::Error>((hits, output))
+/// };
+///
+/// // A literal pattern, regex characters and all
+/// let (hits, output) = search(json!({"pattern": "parse(", "path": root, "literal": true})).await?;
+/// assert_eq!(hits, ["README.md:1", "src/lib.rs:1", "src/lib.rs:3"]);
+/// assert_eq!(output["matches"][2]["text"], "    parse(\"x\");");
+///
+/// // A regex; target/ and gitignored files are skipped
+/// let (hits, _) = search(json!({"pattern": r"fn parse\w*\(", "path": root})).await?;
+/// assert_eq!(hits, ["src/lib.rs:1", "src/nested/util.rs:1"]);
+///
+/// // Globs narrow the files searched
+/// let (hits, _) = search(json!({"pattern": "parse", "path": root, "globs": ["*.md"]})).await?;
+/// assert_eq!(hits, ["README.md:1"]);
+/// let (hits, _) = search(json!({"pattern": "parse", "path": root, "globs": ["src/*/*.rs"]})).await?;
+/// assert_eq!(hits, ["src/nested/util.rs:1"]);
+///
+/// // Capped output says so
+/// let (hits, output) = search(json!({"pattern": "parse", "path": root, "max_results": 2})).await?;
+/// assert_eq!(hits.len(), 2);
+/// assert_eq!(output["truncated"], true);
+///
+/// let err = SearchFilesTool
+///     .execute(json!({"pattern": "fn (", "path": root}))
+///     .await
+///     .unwrap_err();
+/// assert!(err.to_string().contains("Invalid regex"), "{}", err);
+/// # Ok::<(), claude::Error>(())
+/// # }).unwrap();
+/// ```
+pub struct SearchFilesTool;
+
+#[derive(Debug, Deserialize)]
+struct SearchFilesInput {
+    pattern: String,
+    path: Option<String>,
+    globs: Option<Vec<String>>,
+    #[serde(default)]
+    literal: bool,
+    #[serde(default)]
+    case_insensitive: bool,
+    respect_gitignore: Option<bool>,
+    max_results: Option<usize>,
+}
+
+#[derive(Debug, Serialize)]
+struct SearchMatch {
+    path: String,
+    line: usize,
+    text: String,
+}
+
+/// Whether a file's relative path passes the glob filter
+///
+/// A glob containing '/' is matched against the relative path, any other
+/// against the file name, as `list_directory` does.
+fn matches_globs(globs: &[glob::Pattern], relative: &Path) -> bool {
+    globs.is_empty()
+        || globs.iter().any(|glob| {
+            if glob.as_str().contains('/') {
+                let options = glob::MatchOptions {
+                    require_literal_separator: true,
+                    ..Default::default()
+                };
+                glob.matches_path_with(relative, options)
+            } else {
+                relative
+                    .file_name()
+                    .is_some_and(|name| glob.matches(&name.to_string_lossy()))
+            }
+        })
+}
+
+/// `line` cut to [`MAX_LINE_CHARS`] characters
+fn clip_line(line: &str) -> String {
+    if line.chars().count() > MAX_LINE_CHARS {
+        let clipped: String = line.chars().take(MAX_LINE_CHARS).collect();
+        format!("{}...", clipped)
+    } else {
+        line.to_string()
+    }
+}
+
+#[async_trait]
+impl Tool for SearchFilesTool {
+    fn name(&self) -> &str {
+        "search_files"
+    }
+
+    fn description(&self) -> &str {
+        "Search file contents under a directory for a regex (or literal text), like ripgrep. Skips .git, target, gitignored, and binary files. Returns each matching line with its file path and line number."
+    }
+
+    fn input_schema(&self) -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "pattern": {
+                    "type": "string",
+                    "description": "Regular expression to search for, e.g. 'fn \\w+_test'. Set literal to search for the text as is"
+                },
+                "path": {
+                    "type": "string",
+                    "description": "Directory (or single file) to search (default: current directory)"
+                },
+                "globs": {
+                    "type": "array",
+                    "items": {"type": "string"},
+                    "description": "Only search files matching one of these globs, e.g. ['*.rs', 'docs/*.md']. Matched against the file name, or against the relative path if it contains '/'"
+                },
+                "literal": {
+                    "type": "boolean",
+                    "description": "Treat pattern as plain text rather than a regex (default: false)"
+                },
+                "case_insensitive": {
+                    "type": "boolean",
+                    "description": "Ignore case when matching (default: false)"
+                },
+                "respect_gitignore": {
+                    "type": "boolean",
+                    "description": "Skip .git, target and gitignored paths (default: true)"
+                },
+                "max_results": {
+                    "type": "integer",
+                    "minimum": 1,
+                    "description": "Maximum number of matching lines to return (default: 100, max: 1000)"
+                }
+            },
+            "required": ["pattern"],
+            "additionalProperties": false
+        })
+    }
+
+    async fn execute(&self, input: Value) -> Result<String> {
+        Ok(self.execute_typed(input).await?.into_text())
+    }
+
+    async fn execute_typed(&self, input: Value) -> Result<ToolOutput> {
+        let params: SearchFilesInput = serde_json::from_value(input).map_err(|e| {
+            Error::Other(format!(
+                "Invalid input parameters: {}. Example: {{\"pattern\": \"TODO\", \"path\": \"src\", \"globs\": [\"*.rs\"]}}",
+                e
+            ))
+        })?;
+
+        let path = params.path.as_deref().unwrap_or(".");
+        let root = Path::new(path);
+        if !root.exists() {
+            return Err(Error::Other(format!("Path not found: {}", path)));
+        }
+
+        let pattern = if params.literal {
+            regex::escape(&params.pattern)
+        } else {
+            params.pattern.clone()
+        };
+        let regex = RegexBuilder::new(&pattern)
+            .case_insensitive(params.case_insensitive)
+            .build()
+            .map_err(|e| Error::Other(format!("Invalid regex '{}': {}", params.pattern, e)))?;
+        let globs = params
+            .globs
+            .unwrap_or_default()
+            .iter()
+            .map(|glob| glob::Pattern::new(glob))
+            .collect::<std::result::Result<Vec<_>, _>>()
+            .map_err(|e| Error::Other(format!("Invalid glob: {}", e)))?;
+        let max_results = params
+            .max_results
+            .unwrap_or(DEFAULT_MAX_RESULTS)
+            .clamp(1, MAX_RESULTS_LIMIT);
+        let respect_gitignore = params.respect_gitignore.unwrap_or(true);
+
+        let mut walker = WalkBuilder::new(root);
+        walker
+            .hidden(false)
+            .follow_links(false)
+            .require_git(false)
+            .git_ignore(respect_gitignore)
+            .git_global(respect_gitignore)
+            .git_exclude(respect_gitignore)
+            .ignore(respect_gitignore)
+            .parents(respect_gitignore)
+            .sort_by_file_path(|a, b| a.cmp(b));
+        if respect_gitignore {
+            walker.filter_entry(|entry| {
+                let name = entry.file_name();
+                !(entry.depth() > 0
+                    && entry.file_type().is_some_and(|t| t.is_dir())
+                    && (name == ".git" || name == "target"))
+            });
+        }
+
+        let mut matches = Vec::new();
+        let mut files_searched = 0;
+        let mut truncated = false;
+        'files: for entry in walker.build() {
+            let entry = match entry {
+                Ok(entry) => entry,
+                // Unreadable subdirectories shouldn't sink the whole search
+                Err(_) => continue,
+            };
+            if !entry.file_type().is_some_and(|t| t.is_file()) {
+                continue;
+            }
+            if entry.metadata().is_ok_and(|m| m.len() > MAX_FILE_BYTES) {
+                continue;
+            }
+
+            // A single file given as the path is reported as given
+            let relative = match entry.path().strip_prefix(root) {
+                Ok(relative) if !relative.as_os_str().is_empty() => relative,
+                _ => entry.path(),
+            };
+            if !matches_globs(&globs, relative) {
+                continue;
+            }
+
+            // Binary and non-UTF-8 files are skipped
+            let Ok(contents) = fs::read_to_string(entry.path()) else {
+                continue;
+            };
+            if contents.contains('\0') {
+                continue;
+            }
+            files_searched += 1;
+
+            for (index, line) in contents.lines().enumerate() {
+                if !regex.is_match(line) {
+                    continue;
+                }
+                if matches.len() == max_results {
+                    truncated = true;
+                    break 'files;
+                }
+                matches.push(SearchMatch {
+                    path: relative.to_string_lossy().replace('\\', "/"),
+                    line: index + 1,
+                    text: clip_line(line),
+                });
+            }
+        }
+
+        Ok(ToolOutput::Json(json!({
+            "pattern": params.pattern,
+            "path": path,
+            "count": matches.len(),
+            "files_searched": files_searched,
+            "truncated": truncated,
+            "note": truncated.then(|| format!(
+                "Search stopped after {} matches; narrow it with path or globs, or raise max_results",
+                max_results
+            )),
+            "matches": matches,
+        })))
+    }
+}
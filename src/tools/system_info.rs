@@ -1,10 +1,141 @@
-use crate::{Error, Result, Tool};
+use crate::{Error, Result, Tool, ToolOutput};
 use async_trait::async_trait;
 use chrono::Local;
 use serde_json::{json, Value};
+use sysinfo::{Disks, ProcessesToUpdate, System};
 
+/// Reports the time, OS details, and live CPU, memory and disk usage
+///
+/// `time`, `date` and `datetime` return a sentence; every other `info_type`
+/// returns JSON with sizes in bytes and usage in percent.
+///
+/// # Example
+///
+/// ```rust
+/// use claude::tools::SystemInfoTool;
+/// use claude::{Tool, ToolOutput};
+/// use serde_json::json;
+///
+/// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+/// let ToolOutput::Json(memory) = SystemInfoTool
+///     .execute_typed(json!({"info_type": "memory"}))
+///     .await?
+/// else {
+///     panic!("expected JSON output")
+/// };
+/// let mut keys: Vec<&String> = memory.as_object().unwrap().keys().collect();
+/// keys.sort();
+/// assert_eq!(
+///     keys,
+///     ["available_bytes", "total_bytes", "total_swap_bytes", "used_bytes", "used_percent", "used_swap_bytes"]
+/// );
+/// assert!(memory["total_bytes"].as_u64().unwrap() > 0);
+/// assert!(memory["used_bytes"].as_u64().unwrap() > 0);
+///
+/// let ToolOutput::Json(all) = SystemInfoTool
+///     .execute_typed(json!({"info_type": "all"}))
+///     .await?
+/// else {
+///     panic!("expected JSON output")
+/// };
+/// let mut sections: Vec<&String> = all.as_object().unwrap().keys().collect();
+/// sections.sort();
+/// assert_eq!(sections, ["cpu", "datetime", "disks", "memory", "os"]);
+/// assert!(all["cpu"]["logical_cores"].as_u64().unwrap() > 0);
+/// assert!(all["cpu"]["process_count"].as_u64().unwrap() > 0);
+/// assert!(all["disks"].is_array());
+/// # Ok::<(), claude::Error>(())
+/// # }).unwrap();
+/// ```
 pub struct SystemInfoTool;
 
+fn percent(used: u64, total: u64) -> f64 {
+    if total == 0 {
+        return 0.0;
+    }
+    // Two decimal places is plenty for a usage figure
+    (used as f64 / total as f64 * 10000.0).round() / 100.0
+}
+
+fn os_name() -> &'static str {
+    if cfg!(target_os = "macos") {
+        "macOS"
+    } else if cfg!(target_os = "linux") {
+        "Linux"
+    } else if cfg!(target_os = "windows") {
+        "Windows"
+    } else {
+        "Unknown"
+    }
+}
+
+fn os_info() -> Value {
+    json!({
+        "name": os_name(),
+        "distribution": System::long_os_version(),
+        "kernel_version": System::kernel_version(),
+        "hostname": System::host_name(),
+        "arch": std::env::consts::ARCH,
+        "uptime_secs": System::uptime(),
+    })
+}
+
+/// CPU usage needs two samples, so this waits briefly between refreshes
+async fn cpu_info(sys: &mut System) -> Value {
+    sys.refresh_cpu_usage();
+    tokio::time::sleep(sysinfo::MINIMUM_CPU_UPDATE_INTERVAL).await;
+    sys.refresh_cpu_usage();
+    sys.refresh_processes(ProcessesToUpdate::All, true);
+
+    let load = System::load_average();
+    json!({
+        "brand": sys.cpus().first().map(|cpu| cpu.brand().trim().to_string()),
+        "logical_cores": sys.cpus().len(),
+        "usage_percent": sys.global_cpu_usage(),
+        "per_core_usage_percent": sys.cpus().iter().map(|cpu| cpu.cpu_usage()).collect::<Vec<_>>(),
+        "load_average": {
+            "one": load.one,
+            "five": load.five,
+            "fifteen": load.fifteen,
+        },
+        "process_count": sys.processes().len(),
+    })
+}
+
+fn memory_info(sys: &mut System) -> Value {
+    sys.refresh_memory();
+    json!({
+        "total_bytes": sys.total_memory(),
+        "used_bytes": sys.used_memory(),
+        "available_bytes": sys.available_memory(),
+        "used_percent": percent(sys.used_memory(), sys.total_memory()),
+        "total_swap_bytes": sys.total_swap(),
+        "used_swap_bytes": sys.used_swap(),
+    })
+}
+
+fn disk_info() -> Value {
+    let disks = Disks::new_with_refreshed_list();
+    let mounts: Vec<Value> = disks
+        .iter()
+        .map(|disk| {
+            let total = disk.total_space();
+            let used = total.saturating_sub(disk.available_space());
+            json!({
+                "mount_point": disk.mount_point().to_string_lossy(),
+                "name": disk.name().to_string_lossy(),
+                "file_system": disk.file_system().to_string_lossy(),
+                "total_bytes": total,
+                "used_bytes": used,
+                "available_bytes": disk.available_space(),
+                "used_percent": percent(used, total),
+                "removable": disk.is_removable(),
+            })
+        })
+        .collect();
+    Value::Array(mounts)
+}
+
 #[async_trait]
 impl Tool for SystemInfoTool {
     fn name(&self) -> &str {
@@ -12,7 +143,7 @@ impl Tool for SystemInfoTool {
     }
 
     fn description(&self) -> &str {
-        "Gets system information like current time, date, and OS details"
+        "Gets system information: current time and date, OS details, and live CPU load, memory usage, per-mount disk usage and process count"
     }
 
     fn input_schema(&self) -> Value {
@@ -21,7 +152,7 @@ impl Tool for SystemInfoTool {
             "properties": {
                 "info_type": {
                     "type": "string",
-                    "enum": ["time", "date", "datetime", "os", "all"],
+                    "enum": ["time", "date", "datetime", "os", "cpu", "memory", "disk", "all"],
                     "description": "The type of system information to retrieve"
                 }
             },
@@ -31,6 +162,10 @@ impl Tool for SystemInfoTool {
     }
 
     async fn execute(&self, input: Value) -> Result<String> {
+        Ok(self.execute_typed(input).await?.into_text())
+    }
+
+    async fn execute_typed(&self, input: Value) -> Result<ToolOutput> {
         let info_type = input
             .get("info_type")
             .and_then(|v| v.as_str())
@@ -40,44 +175,34 @@ impl Tool for SystemInfoTool {
                 )
             })?;
 
+        let mut sys = System::new();
         let result = match info_type {
-            "time" => format!("Current time: {}", Local::now().format("%I:%M:%S %p")),
-            "date" => format!("Current date: {}", Local::now().format("%A, %B %d, %Y")),
-            "datetime" => format!(
+            "time" => ToolOutput::Text(format!(
+                "Current time: {}",
+                Local::now().format("%I:%M:%S %p")
+            )),
+            "date" => ToolOutput::Text(format!(
+                "Current date: {}",
+                Local::now().format("%A, %B %d, %Y")
+            )),
+            "datetime" => ToolOutput::Text(format!(
                 "Current date and time: {}",
                 Local::now().format("%Y-%m-%d %I:%M:%S %p")
-            ),
-            "os" => {
-                let os = if cfg!(target_os = "macos") {
-                    "macOS"
-                } else if cfg!(target_os = "linux") {
-                    "Linux"
-                } else if cfg!(target_os = "windows") {
-                    "Windows"
-                } else {
-                    "Unknown"
-                };
-                format!("Operating System: {}", os)
-            }
-            "all" => {
-                let os = if cfg!(target_os = "macos") {
-                    "macOS"
-                } else if cfg!(target_os = "linux") {
-                    "Linux"
-                } else if cfg!(target_os = "windows") {
-                    "Windows"
-                } else {
-                    "Unknown"
-                };
-                format!(
-                    "System Information:\n- {}\n- Operating System: {}",
-                    Local::now().format("%A, %B %d, %Y at %I:%M:%S %p"),
-                    os
-                )
-            }
+            )),
+            "os" => ToolOutput::Json(os_info()),
+            "cpu" => ToolOutput::Json(cpu_info(&mut sys).await),
+            "memory" => ToolOutput::Json(memory_info(&mut sys)),
+            "disk" => ToolOutput::Json(json!({ "disks": disk_info() })),
+            "all" => ToolOutput::Json(json!({
+                "datetime": Local::now().to_rfc3339(),
+                "os": os_info(),
+                "cpu": cpu_info(&mut sys).await,
+                "memory": memory_info(&mut sys),
+                "disks": disk_info(),
+            })),
             _ => {
                 return Err(Error::Other(format!(
-                    "Unknown info_type: '{}'. Valid options: time, date, datetime, os, all",
+                    "Unknown info_type: '{}'. Valid options: time, date, datetime, os, cpu, memory, disk, all",
                     info_type
                 )))
             }
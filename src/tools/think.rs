@@ -1,8 +1,114 @@
 use crate::{Result, Tool};
 use async_trait::async_trait;
 use serde_json::{json, Value};
+use std::sync::{Arc, Mutex};
 
-pub struct ThinkTool;
+/// Thinking budget used when the tool is called without `budget_tokens`
+const DEFAULT_BUDGET_TOKENS: u32 = 8192;
+
+/// Smallest thinking budget the API accepts
+const MIN_BUDGET_TOKENS: u32 = 1024;
+
+/// Largest thinking budget the tool will request
+const MAX_BUDGET_TOKENS: u32 = 32_000;
+
+/// Shared handle through which [`ThinkTool`] requests extended thinking
+///
+/// The tool records a budget; the conversation loop takes it before the next
+/// turn and sends it as the request's [`ThinkingConfig`](crate::ThinkingConfig).
+/// Clones share the same slot. When several budgets are requested before one
+/// is taken, the largest wins.
+///
+/// # Example
+///
+/// ```rust
+/// use claude::tools::ThinkingBudget;
+///
+/// let budget = ThinkingBudget::new();
+/// let handle = budget.clone();
+/// handle.request(4096);
+/// handle.request(2048);
+/// assert_eq!(budget.take(), Some(4096));
+/// assert_eq!(budget.take(), None);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct ThinkingBudget {
+    requested: Arc<Mutex<Option<u32>>>,
+}
+
+impl ThinkingBudget {
+    /// Create an empty budget handle
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Ask for up to `budget_tokens` of thinking on the next turn
+    pub fn request(&self, budget_tokens: u32) {
+        let mut requested = self.requested.lock().unwrap();
+        *requested = Some(requested.map_or(budget_tokens, |b| b.max(budget_tokens)));
+    }
+
+    /// Take the pending request, leaving none behind
+    pub fn take(&self) -> Option<u32> {
+        self.requested.lock().unwrap().take()
+    }
+}
+
+/// Asks for extended thinking on the follow-up turn
+///
+/// Extended thinking can't be switched on halfway through a turn, so the tool
+/// records the requested budget in its [`ThinkingBudget`] and the next turn is
+/// sent with thinking enabled.
+///
+/// # Example
+///
+/// ```rust
+/// use claude::tools::{ThinkTool, ThinkingBudget};
+/// use claude::Tool;
+/// use serde_json::json;
+///
+/// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+/// let budget = ThinkingBudget::new();
+/// let tool = ThinkTool::with_budget(budget.clone());
+///
+/// let reply = tool
+///     .execute(json!({"topic": "the trade-offs of this schema", "budget_tokens": 16000}))
+///     .await?;
+/// assert!(reply.contains("16000 tokens"));
+/// assert_eq!(budget.take(), Some(16000));
+///
+/// // Budgets are clamped to what the API accepts
+/// tool.execute(json!({"topic": "anything", "budget_tokens": 10})).await?;
+/// assert_eq!(budget.take(), Some(1024));
+/// # Ok::<(), claude::Error>(())
+/// # }).unwrap();
+/// ```
+pub struct ThinkTool {
+    budget: ThinkingBudget,
+}
+
+impl ThinkTool {
+    /// Create a think tool with its own budget handle
+    pub fn new() -> Self {
+        Self::with_budget(ThinkingBudget::new())
+    }
+
+    /// Create a think tool that records requests in `budget`
+    pub fn with_budget(budget: ThinkingBudget) -> Self {
+        Self { budget }
+    }
+
+    /// The handle the conversation loop should take budgets from
+    pub fn budget(&self) -> &ThinkingBudget {
+        &self.budget
+    }
+}
+
+impl Default for ThinkTool {
+    fn default() -> Self {
+        Self::new()
+    }
+}
 
 #[async_trait]
 impl Tool for ThinkTool {
@@ -11,7 +117,7 @@ impl Tool for ThinkTool {
     }
 
     fn description(&self) -> &str {
-        "Think more deeply about a topic or problem, exploring different angles, implications, and considerations"
+        "Think more deeply about a topic or problem. Enables extended thinking with a larger reasoning budget for the next turn; use it when a problem needs careful multi-step reasoning"
     }
 
     fn input_schema(&self) -> Value {
@@ -21,6 +127,12 @@ impl Tool for ThinkTool {
                 "topic": {
                     "type": "string",
                     "description": "The topic or problem to think more deeply about"
+                },
+                "budget_tokens": {
+                    "type": "integer",
+                    "minimum": MIN_BUDGET_TOKENS,
+                    "maximum": MAX_BUDGET_TOKENS,
+                    "description": "How many tokens of extended thinking to allow (default: 8192)"
                 }
             },
             "required": ["topic"],
@@ -35,19 +147,17 @@ impl Tool for ThinkTool {
             .ok_or_else(|| crate::Error::Other(
                 "Missing 'topic' field. Example: {\"topic\": \"the implications of this design decision\"}".to_string()
             ))?;
+        let budget_tokens = input
+            .get("budget_tokens")
+            .and_then(|v| v.as_u64())
+            .map(|b| b.clamp(MIN_BUDGET_TOKENS as u64, MAX_BUDGET_TOKENS as u64) as u32)
+            .unwrap_or(DEFAULT_BUDGET_TOKENS);
+
+        self.budget.request(budget_tokens);
 
-        // Return a thoughtful analysis prompt
         Ok(format!(
-            "Let me think more deeply about: {}\n\n\
-            I should consider:\n\
-            - The core aspects and underlying principles\n\
-            - Potential implications and consequences\n\
-            - Alternative perspectives and approaches\n\
-            - Edge cases and potential challenges\n\
-            - How this connects to broader patterns\n\
-            - What questions I should be asking\n\n\
-            Thinking...",
-            topic
+            "Extended thinking with a budget of {} tokens will be enabled for the next turn.\n\nTopic: {}",
+            budget_tokens, topic
         ))
     }
 }
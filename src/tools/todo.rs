@@ -1,12 +1,22 @@
 use crate::error::{Error, Result};
+use crate::paths;
 use crate::tool::Tool;
 use async_trait::async_trait;
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, NaiveDate, Utc};
 use serde::{Deserialize, Serialize};
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use uuid::Uuid;
 
+/// How urgent a todo is
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Priority {
+    Low,
+    Medium,
+    High,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Todo {
     pub id: String,
@@ -14,6 +24,32 @@ pub struct Todo {
     pub completed: bool,
     pub created_at: DateTime<Utc>,
     pub completed_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub due_date: Option<NaiveDate>,
+    #[serde(default)]
+    pub priority: Option<Priority>,
+}
+
+impl Todo {
+    /// Whether the todo is still open and its due date is before `today`
+    pub fn is_overdue(&self, today: NaiveDate) -> bool {
+        !self.completed && self.due_date.is_some_and(|due| due < today)
+    }
+}
+
+/// Criteria for [`TodoList::filtered`]
+#[derive(Debug, Clone, Default)]
+pub struct TodoFilter {
+    /// Include completed todos
+    pub show_completed: bool,
+    /// Only todos with this priority
+    pub priority: Option<Priority>,
+    /// Only overdue todos (`true`) or only todos that are not overdue (`false`)
+    pub overdue: Option<bool>,
+    /// Only todos due on or after this date
+    pub due_after: Option<NaiveDate>,
+    /// Only todos due on or before this date
+    pub due_before: Option<NaiveDate>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -26,7 +62,12 @@ impl TodoList {
         TodoList { todos: Vec::new() }
     }
 
-    fn add(&mut self, title: String) -> String {
+    fn add(
+        &mut self,
+        title: String,
+        due_date: Option<NaiveDate>,
+        priority: Option<Priority>,
+    ) -> String {
         let id = Uuid::new_v4().to_string();
         let todo = Todo {
             id: id.clone(),
@@ -34,6 +75,8 @@ impl TodoList {
             completed: false,
             created_at: Utc::now(),
             completed_at: None,
+            due_date,
+            priority,
         };
         self.todos.push(todo);
         id
@@ -68,11 +111,87 @@ impl TodoList {
         }
     }
 
-    fn list(&self, show_completed: bool) -> Vec<&Todo> {
-        self.todos
+    /// Todos matching `filter`, sorted by due date (undated last) then priority
+    ///
+    /// `today` decides which todos are overdue. Todos without a due date never
+    /// match a date range, and todos that compare equal keep their insertion order.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use chrono::{NaiveDate, Utc};
+    /// use claude::tools::todo::{Priority, Todo, TodoFilter, TodoList};
+    ///
+    /// let date = |d: &str| NaiveDate::parse_from_str(d, "%Y-%m-%d").unwrap();
+    /// let todo = |title: &str, due: Option<&str>, priority: Option<Priority>| Todo {
+    ///     id: title.to_string(),
+    ///     title: title.to_string(),
+    ///     completed: false,
+    ///     created_at: Utc::now(),
+    ///     completed_at: None,
+    ///     due_date: due.map(date),
+    ///     priority,
+    /// };
+    /// let list = TodoList {
+    ///     todos: vec![
+    ///         todo("someday", None, Some(Priority::High)),
+    ///         todo("taxes", Some("2026-04-15"), Some(Priority::Low)),
+    ///         todo("report", Some("2026-05-01"), Some(Priority::Low)),
+    ///         todo("review", Some("2026-05-01"), Some(Priority::High)),
+    ///         todo("call", Some("2026-05-01"), None),
+    ///     ],
+    /// };
+    /// let today = date("2026-04-20");
+    /// let titles = |todos: Vec<&Todo>| todos.iter().map(|t| t.title.clone()).collect::<Vec<_>>();
+    ///
+    /// // Due date first, then high before low, with no priority last
+    /// assert_eq!(
+    ///     titles(list.filtered(&TodoFilter::default(), today)),
+    ///     ["taxes", "review", "report", "call", "someday"]
+    /// );
+    ///
+    /// let overdue = TodoFilter { overdue: Some(true), ..Default::default() };
+    /// assert_eq!(titles(list.filtered(&overdue, today)), ["taxes"]);
+    ///
+    /// let high = TodoFilter { priority: Some(Priority::High), ..Default::default() };
+    /// assert_eq!(titles(list.filtered(&high, today)), ["review", "someday"]);
+    ///
+    /// let range = TodoFilter { due_after: Some(date("2026-04-16")), ..Default::default() };
+    /// assert_eq!(titles(list.filtered(&range, today)), ["review", "report", "call"]);
+    /// ```
+    pub fn filtered(&self, filter: &TodoFilter, today: NaiveDate) -> Vec<&Todo> {
+        let mut todos: Vec<&Todo> = self
+            .todos
             .iter()
-            .filter(|t| show_completed || !t.completed)
-            .collect()
+            .filter(|t| filter.show_completed || !t.completed)
+            .filter(|t| filter.priority.is_none() || t.priority == filter.priority)
+            .filter(|t| {
+                filter
+                    .overdue
+                    .is_none_or(|overdue| t.is_overdue(today) == overdue)
+            })
+            .filter(|t| match filter.due_after {
+                Some(after) => t.due_date.is_some_and(|due| due >= after),
+                None => true,
+            })
+            .filter(|t| match filter.due_before {
+                Some(before) => t.due_date.is_some_and(|due| due <= before),
+                None => true,
+            })
+            .collect();
+
+        todos.sort_by(|a, b| {
+            let by_due = match (a.due_date, b.due_date) {
+                (Some(a), Some(b)) => a.cmp(&b),
+                (Some(_), None) => std::cmp::Ordering::Less,
+                (None, Some(_)) => std::cmp::Ordering::Greater,
+                (None, None) => std::cmp::Ordering::Equal,
+            };
+            // Higher priority first; `None` sorts below every priority
+            by_due.then_with(|| b.priority.cmp(&a.priority))
+        });
+
+        todos
     }
 
     fn clear_completed(&mut self) {
@@ -80,30 +199,127 @@ impl TodoList {
     }
 }
 
-pub struct TodoTool;
+/// Manages a todo list persisted as JSON
+///
+/// By default the list lives in `~/.chatbot_todos.json`, or in the file named
+/// by the `CLAUDE_TODO_FILE` environment variable when it is set.
+///
+/// # Example
+///
+/// ```rust
+/// use claude::tools::TodoTool;
+/// use claude::Tool;
+/// use serde_json::json;
+///
+/// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+/// let dir = tempfile::tempdir().unwrap();
+/// let path = dir.path().join("todos.json");
+/// std::env::set_var("CLAUDE_TODO_FILE", &path);
+///
+/// let todo = TodoTool::new();
+/// assert_eq!(todo.path(), path.as_path());
+/// todo.execute(json!({"action": "add", "title": "Water the plants"}))
+///     .await?;
+///
+/// // The write landed in the configured file
+/// let saved = std::fs::read_to_string(&path).unwrap();
+/// assert!(saved.contains("Water the plants"));
+///
+/// // And a fresh tool reads it back from there
+/// let listed = TodoTool::new()
+///     .execute(json!({"action": "list"}))
+///     .await?;
+/// assert!(listed.contains("Water the plants"));
+/// # std::env::remove_var("CLAUDE_TODO_FILE");
+/// # Ok::<(), claude::Error>(())
+/// # }).unwrap();
+/// ```
+pub struct TodoTool {
+    path: PathBuf,
+}
+
+impl Default for TodoTool {
+    fn default() -> Self {
+        Self::new()
+    }
+}
 
 impl TodoTool {
-    fn get_todo_file_path() -> PathBuf {
-        let mut path = PathBuf::from(".");
-        path.push("todos.json");
-        path
+    /// Create a todo tool backed by `$CLAUDE_TODO_FILE` or `~/.chatbot_todos.json`
+    ///
+    /// A `todos.json` left in the current directory by older versions is moved
+    /// to the new location if nothing is stored there yet.
+    pub fn new() -> Self {
+        let path = Self::default_path();
+        Self::migrate_legacy(Path::new("todos.json"), &path);
+        Self { path }
+    }
+
+    /// Create a todo tool that persists to `path` instead of the default location
+    pub fn with_path(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+
+    /// The file this tool reads and writes
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// The file [`new`](Self::new) uses: `CLAUDE_TODO_FILE` if set, otherwise
+    /// `~/.chatbot_todos.json`
+    pub fn default_path() -> PathBuf {
+        if let Some(path) = std::env::var_os("CLAUDE_TODO_FILE").filter(|p| !p.is_empty()) {
+            return PathBuf::from(path);
+        }
+        paths::in_home(".chatbot_todos.json")
+    }
+
+    fn migrate_legacy(legacy_path: &Path, path: &Path) {
+        if !legacy_path.exists() || path.exists() {
+            return;
+        }
+        let moved = fs::rename(legacy_path, path).or_else(|_| {
+            // `rename` cannot cross filesystems, so fall back to copy and remove
+            fs::copy(legacy_path, path).and_then(|_| fs::remove_file(legacy_path))
+        });
+        if let Err(e) = moved {
+            tracing::warn!(
+                from = %legacy_path.display(),
+                to = %path.display(),
+                error = %e,
+                "failed to move the legacy todo file"
+            );
+        }
     }
 
-    fn load_todos() -> Result<TodoList> {
-        let path = Self::get_todo_file_path();
+    fn parse_date(field: &str, value: Option<String>) -> Result<Option<NaiveDate>> {
+        value
+            .map(|v| {
+                NaiveDate::parse_from_str(v.trim(), "%Y-%m-%d").map_err(|_| {
+                    Error::Other(format!(
+                        "Invalid '{}': '{}'. Use an ISO date such as 2026-01-31",
+                        field, v
+                    ))
+                })
+            })
+            .transpose()
+    }
+
+    fn load_todos(&self) -> Result<TodoList> {
+        let path = &self.path;
         if !path.exists() {
             return Ok(TodoList::new());
         }
 
-        let content = fs::read_to_string(&path)
+        let content = fs::read_to_string(path)
             .map_err(|e| Error::Other(format!("Failed to read todo file: {}", e)))?;
 
         serde_json::from_str(&content)
             .map_err(|e| Error::Other(format!("Failed to parse todo file: {}", e)))
     }
 
-    fn save_todos(todos: &TodoList) -> Result<()> {
-        let path = Self::get_todo_file_path();
+    fn save_todos(&self, todos: &TodoList) -> Result<()> {
+        let path = &self.path;
 
         if let Some(parent) = path.parent() {
             fs::create_dir_all(parent)
@@ -113,7 +329,7 @@ impl TodoTool {
         let content = serde_json::to_string_pretty(todos)
             .map_err(|e| Error::Other(format!("Failed to serialize todos: {}", e)))?;
 
-        fs::write(&path, content)
+        fs::write(path, content)
             .map_err(|e| Error::Other(format!("Failed to write todo file: {}", e)))
     }
 }
@@ -122,7 +338,11 @@ impl TodoTool {
 #[serde(tag = "action")]
 enum TodoAction {
     #[serde(rename = "add")]
-    Add { title: String },
+    Add {
+        title: String,
+        due_date: Option<String>,
+        priority: Option<Priority>,
+    },
     #[serde(rename = "remove")]
     Remove { id: String },
     #[serde(rename = "complete")]
@@ -130,7 +350,13 @@ enum TodoAction {
     #[serde(rename = "uncomplete")]
     Uncomplete { id: String },
     #[serde(rename = "list")]
-    List { show_completed: Option<bool> },
+    List {
+        show_completed: Option<bool>,
+        priority: Option<Priority>,
+        overdue: Option<bool>,
+        due_after: Option<String>,
+        due_before: Option<String>,
+    },
     #[serde(rename = "clear_completed")]
     ClearCompleted,
 }
@@ -142,7 +368,7 @@ impl Tool for TodoTool {
     }
 
     fn description(&self) -> &'static str {
-        "Manage a simple sequential todo list. Actions: add, remove, complete, uncomplete, list, clear_completed"
+        "Manage a todo list with optional due dates and priorities. Actions: add, remove, complete, uncomplete, list (sorted by due date then priority, with filters), clear_completed"
     }
 
     fn input_schema(&self) -> serde_json::Value {
@@ -162,9 +388,30 @@ impl Tool for TodoTool {
                     "type": "string",
                     "description": "ID of the todo item (required for 'remove', 'complete', 'uncomplete' actions)"
                 },
+                "due_date": {
+                    "type": "string",
+                    "description": "Due date as YYYY-MM-DD (optional for 'add' action)"
+                },
+                "priority": {
+                    "type": "string",
+                    "enum": ["low", "medium", "high"],
+                    "description": "Priority of the item for 'add', or only list items with this priority for 'list'"
+                },
                 "show_completed": {
                     "type": "boolean",
                     "description": "Whether to show completed items (optional for 'list' action, default: false)"
+                },
+                "overdue": {
+                    "type": "boolean",
+                    "description": "For 'list': true to show only overdue items, false to hide them"
+                },
+                "due_after": {
+                    "type": "string",
+                    "description": "For 'list': only items due on or after this YYYY-MM-DD date"
+                },
+                "due_before": {
+                    "type": "string",
+                    "description": "For 'list': only items due on or before this YYYY-MM-DD date"
                 }
             },
             "required": ["action"]
@@ -175,17 +422,22 @@ impl Tool for TodoTool {
         let action: TodoAction = serde_json::from_value(input)
             .map_err(|e| Error::Other(format!("Invalid parameters: {}", e)))?;
 
-        let mut todos = Self::load_todos()?;
+        let mut todos = self.load_todos()?;
 
         match action {
-            TodoAction::Add { title } => {
-                let id = todos.add(title.clone());
-                Self::save_todos(&todos)?;
+            TodoAction::Add {
+                title,
+                due_date,
+                priority,
+            } => {
+                let due_date = Self::parse_date("due_date", due_date)?;
+                let id = todos.add(title.clone(), due_date, priority);
+                self.save_todos(&todos)?;
                 Ok(format!("Added todo '{}' with id: {}", title, id))
             }
             TodoAction::Remove { id } => {
                 if todos.remove(&id) {
-                    Self::save_todos(&todos)?;
+                    self.save_todos(&todos)?;
                     Ok(format!("Removed todo with id: {}", id))
                 } else {
                     Err(Error::Other(format!("Todo with id {} not found", id)))
@@ -193,7 +445,7 @@ impl Tool for TodoTool {
             }
             TodoAction::Complete { id } => {
                 if todos.complete(&id) {
-                    Self::save_todos(&todos)?;
+                    self.save_todos(&todos)?;
                     Ok(format!("Marked todo {} as complete", id))
                 } else {
                     Err(Error::Other(format!("Todo with id {} not found", id)))
@@ -201,15 +453,28 @@ impl Tool for TodoTool {
             }
             TodoAction::Uncomplete { id } => {
                 if todos.uncomplete(&id) {
-                    Self::save_todos(&todos)?;
+                    self.save_todos(&todos)?;
                     Ok(format!("Marked todo {} as incomplete", id))
                 } else {
                     Err(Error::Other(format!("Todo with id {} not found", id)))
                 }
             }
-            TodoAction::List { show_completed } => {
-                let show_completed = show_completed.unwrap_or(false);
-                let items = todos.list(show_completed);
+            TodoAction::List {
+                show_completed,
+                priority,
+                overdue,
+                due_after,
+                due_before,
+            } => {
+                let filter = TodoFilter {
+                    show_completed: show_completed.unwrap_or(false),
+                    priority,
+                    overdue,
+                    due_after: Self::parse_date("due_after", due_after)?,
+                    due_before: Self::parse_date("due_before", due_before)?,
+                };
+                let today = chrono::Local::now().date_naive();
+                let items = todos.filtered(&filter, today);
 
                 if items.is_empty() {
                     Ok("No todos found".to_string())
@@ -222,11 +487,24 @@ impl Tool for TodoTool {
                         } else {
                             &todo.id
                         };
+                        let mut details = Vec::new();
+                        if let Some(due) = todo.due_date {
+                            details.push(format!("due {}", due));
+                        }
+                        if let Some(priority) = todo.priority {
+                            details.push(format!("{:?} priority", priority).to_lowercase());
+                        }
+                        if todo.is_overdue(today) {
+                            details.push("OVERDUE".to_string());
+                        }
+                        let details = if details.is_empty() {
+                            String::new()
+                        } else {
+                            format!(" ({})", details.join(", "))
+                        };
                         output.push_str(&format!(
-                            "{} [{}] {}\n",
-                            status,
-                            short_id,
-                            todo.title
+                            "{} [{}] {}{}\n",
+                            status, short_id, todo.title, details
                         ));
                     }
                     Ok(output.trim_end().to_string())
@@ -236,7 +514,7 @@ impl Tool for TodoTool {
                 let before_count = todos.todos.len();
                 todos.clear_completed();
                 let removed_count = before_count - todos.todos.len();
-                Self::save_todos(&todos)?;
+                self.save_todos(&todos)?;
                 Ok(format!("Cleared {} completed todo(s)", removed_count))
             }
         }
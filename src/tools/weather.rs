@@ -1,66 +1,416 @@
-use crate::{Error, Result, Tool};
+use crate::{Error, Result, Tool, ToolOutput};
 use async_trait::async_trait;
-use serde_json::{json, Value};
+use futures::stream::{self, StreamExt};
+use serde::Deserialize;
+use serde_json::{json, Map, Value};
+use std::collections::HashSet;
+use std::time::Duration;
 
-pub struct WeatherTool;
+const GEOCODING_URL: &str = "https://geocoding-api.open-meteo.com/v1/search";
+const FORECAST_URL: &str = "https://api.open-meteo.com/v1/forecast";
 
-#[async_trait]
-impl Tool for WeatherTool {
-    fn name(&self) -> &str {
-        "weather"
+const DEFAULT_DAYS: u64 = 3;
+const MAX_DAYS: u64 = 16;
+
+/// Most cities a single call may ask for
+const MAX_CITIES: usize = 20;
+/// Most cities looked up at the same time
+const MAX_CONCURRENT_CITIES: usize = 4;
+
+/// Shortest name prefix tried when looking for close matches
+const MIN_PREFIX_CHARS: usize = 3;
+/// How long a single Open-Meteo request may take
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+const MAX_CLOSE_MATCHES: usize = 5;
+
+const CURRENT_FIELDS: &str =
+    "temperature_2m,apparent_temperature,weather_code,wind_speed_10m,relative_humidity_2m";
+const DAILY_FIELDS: &str = "weather_code,temperature_2m_max,temperature_2m_min,precipitation_sum,precipitation_probability_max,wind_speed_10m_max";
+
+/// Current conditions and a daily forecast from Open-Meteo
+///
+/// Geocodes the city, then returns JSON with `location`, `units`, `current`
+/// and one `daily` entry per forecast day. No API key is needed.
+///
+/// Given `cities` instead of `city`, looks them up concurrently and returns a
+/// `results` object keyed by city name. A city that can't be found gets an
+/// `error` entry of its own rather than failing the whole call.
+///
+/// # Example
+///
+/// This talks to the real Open-Meteo API, so it only runs when
+/// `CLAUDE_NETWORK_TESTS` is set.
+///
+/// ```rust
+/// use claude::tools::WeatherTool;
+/// use claude::{Tool, ToolOutput};
+/// use serde_json::json;
+///
+/// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+/// if std::env::var_os("CLAUDE_NETWORK_TESTS").is_none() {
+///     return Ok(());
+/// }
+///
+/// let ToolOutput::Json(weather) = WeatherTool::new()
+///     .execute_typed(json!({"city": "Berlin", "days": 5, "units": "fahrenheit", "windspeed_unit": "mph"}))
+///     .await?
+/// else {
+///     panic!("expected JSON output")
+/// };
+/// assert_eq!(weather["location"]["country"], "Germany");
+/// assert_eq!(weather["units"]["temperature"], "°F");
+/// assert!(weather["units"]["wind_speed"].as_str().unwrap().starts_with("mp"));
+/// assert!(weather["current"]["temperature"].is_number());
+/// assert_eq!(weather["daily"].as_array().unwrap().len(), 5);
+///
+/// let err = WeatherTool::new()
+///     .execute(json!({"city": "Londonn"}))
+///     .await
+///     .unwrap_err();
+/// assert!(err.to_string().contains("London"), "{}", err);
+/// # Ok::<(), claude::Error>(())
+/// # }).unwrap();
+/// ```
+///
+/// Several cities at once, against a mock of Open-Meteo that knows Paris and
+/// Rome but not Atlantis:
+///
+/// ```rust
+/// use claude::tools::WeatherTool;
+/// use claude::{Tool, ToolOutput};
+/// use serde_json::json;
+/// use claude::test_util::MockServer;
+/// use claude::transport::RawResponse;
+///
+/// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+/// let server = MockServer::start(|request| {
+///     let place = |name: &str, country: &str, latitude: f64| json!({"results": [{
+///         "name": name, "country": country, "latitude": latitude, "longitude": 10.0
+///     }]});
+///     let body = if request.url.contains("/forecast?") {
+///         let temperature = if request.url.contains("latitude=48.8") { 21.5 } else { 27.0 };
+///         json!({
+///             "timezone": "Europe/Paris",
+///             "current": {"time": "2024-05-01T12:00", "temperature_2m": temperature, "weather_code": 0},
+///             "daily": {"time": ["2024-05-01"], "weather_code": [0]}
+///         })
+///     } else if request.url.contains("name=Paris") {
+///         place("Paris", "France", 48.8)
+///     } else if request.url.contains("name=Rome") {
+///         place("Rome", "Italy", 41.9)
+///     } else {
+///         json!({})
+///     };
+///     RawResponse::new(200, body.to_string())
+/// })
+/// .await?;
+/// let base = server.url();
+///
+/// let weather = WeatherTool::new().with_urls(format!("{}/search", base), format!("{}/forecast", base));
+///
+/// let ToolOutput::Json(all_found) = weather
+///     .execute_typed(json!({"cities": ["Paris", "Rome"], "days": 1}))
+///     .await?
+/// else {
+///     panic!("expected JSON output")
+/// };
+/// assert_eq!(all_found["succeeded"], 2);
+/// assert_eq!(all_found["failed"], 0);
+/// assert_eq!(all_found["results"]["Paris"]["location"]["country"], "France");
+/// assert_eq!(all_found["results"]["Paris"]["current"]["temperature"], 21.5);
+/// assert_eq!(all_found["results"]["Rome"]["current"]["temperature"], 27.0);
+///
+/// // One unknown city doesn't spoil the others
+/// let ToolOutput::Json(mixed) = weather
+///     .execute_typed(json!({"cities": ["Paris", "Atlantis"]}))
+///     .await?
+/// else {
+///     panic!("expected JSON output")
+/// };
+/// assert_eq!(mixed["succeeded"], 1);
+/// assert_eq!(mixed["failed"], 1);
+/// assert_eq!(mixed["results"]["Paris"]["current"]["conditions"], "Clear sky");
+/// let error = mixed["results"]["Atlantis"]["error"].as_str().unwrap();
+/// assert!(error.contains("City 'Atlantis' not found"), "{}", error);
+///
+/// // Close matches are looked for under two prefixes, "Atlanti" and "Atla"
+/// let lookups: Vec<String> = server
+///     .requests()
+///     .into_iter()
+///     .filter(|request| request.url.contains("/search?") && request.url.contains("name=Atla"))
+///     .map(|request| request.url)
+///     .collect();
+/// assert_eq!(lookups.len(), 3, "{:?}", lookups);
+/// assert!(lookups[1].contains("name=Atlanti&"));
+/// assert!(lookups[2].contains("name=Atla&"));
+///
+/// // Exactly one of `city` and `cities` is accepted
+/// let err = weather
+///     .execute(json!({"city": "Paris", "cities": ["Rome"]}))
+///     .await
+///     .unwrap_err();
+/// assert!(err.to_string().contains("either 'city' or 'cities'"), "{}", err);
+/// # Ok::<(), claude::Error>(())
+/// # }).unwrap();
+/// ```
+#[derive(Debug, Clone)]
+pub struct WeatherTool {
+    /// Open-Meteo geocoding endpoint
+    geocoding_url: String,
+    /// Open-Meteo forecast endpoint
+    forecast_url: String,
+}
+
+impl Default for WeatherTool {
+    fn default() -> Self {
+        Self {
+            geocoding_url: GEOCODING_URL.to_string(),
+            forecast_url: FORECAST_URL.to_string(),
+        }
     }
+}
 
-    fn description(&self) -> &str {
-        "Get current weather information for a city using Open-Meteo API"
+#[derive(Debug, Deserialize)]
+struct WeatherInput {
+    city: Option<String>,
+    cities: Option<Vec<String>>,
+    days: Option<u64>,
+    units: Option<String>,
+    windspeed_unit: Option<String>,
+}
+
+/// Human-readable description of a WMO weather code
+fn describe(code: i64) -> &'static str {
+    match code {
+        0 => "Clear sky",
+        1..=3 => "Partly cloudy",
+        45 | 48 => "Foggy",
+        51..=57 => "Drizzle",
+        61..=67 => "Rain",
+        71..=77 => "Snow",
+        80..=82 => "Rain showers",
+        85 | 86 => "Snow showers",
+        95 => "Thunderstorm",
+        96 | 99 => "Thunderstorm with hail",
+        _ => "Unknown",
     }
+}
 
-    fn input_schema(&self) -> Value {
-        json!({
-            "type": "object",
-            "properties": {
-                "city": {
-                    "type": "string",
-                    "description": "The city name to get weather for"
-                }
-            },
-            "required": ["city"],
-            "additionalProperties": false
+/// "Name, Region, Country" for a geocoding result
+fn place_name(location: &Value) -> String {
+    ["name", "admin1", "country"]
+        .iter()
+        .filter_map(|key| location.get(key).and_then(|v| v.as_str()))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Reshape an Open-Meteo forecast response into `units`, `current` and `daily`
+///
+/// The API returns the daily forecast as parallel arrays; this turns them into
+/// one object per day, with the weather code described in words.
+///
+/// # Example
+///
+/// ```rust
+/// use claude::tools::weather::parse_forecast;
+/// use serde_json::json;
+///
+/// let response = json!({
+///     "current_units": {"temperature_2m": "°C", "wind_speed_10m": "km/h"},
+///     "current": {
+///         "time": "2024-05-01T12:00",
+///         "temperature_2m": 18.4,
+///         "apparent_temperature": 17.9,
+///         "relative_humidity_2m": 52,
+///         "wind_speed_10m": 11.2,
+///         "weather_code": 2
+///     },
+///     "daily_units": {"precipitation_sum": "mm"},
+///     "daily": {
+///         "time": ["2024-05-01", "2024-05-02"],
+///         "weather_code": [2, 61],
+///         "temperature_2m_max": [19.0, 15.5],
+///         "temperature_2m_min": [9.1, 8.7],
+///         "precipitation_sum": [0.0, 6.3],
+///         "precipitation_probability_max": [5, 80],
+///         "wind_speed_10m_max": [14.0, 22.3]
+///     }
+/// });
+///
+/// let parsed = parse_forecast(&response).unwrap();
+/// assert_eq!(parsed["units"], json!({"temperature": "°C", "wind_speed": "km/h", "precipitation": "mm"}));
+/// assert_eq!(parsed["current"]["temperature"], 18.4);
+/// assert_eq!(parsed["current"]["conditions"], "Partly cloudy");
+/// assert_eq!(parsed["daily"][1], json!({
+///     "date": "2024-05-02",
+///     "weather_code": 61,
+///     "conditions": "Rain",
+///     "temperature_max": 15.5,
+///     "temperature_min": 8.7,
+///     "precipitation_sum": 6.3,
+///     "precipitation_probability_max": 80,
+///     "wind_speed_max": 22.3
+/// }));
+///
+/// assert!(parse_forecast(&json!({"error": true})).is_err());
+/// ```
+pub fn parse_forecast(data: &Value) -> Result<Value> {
+    let current = data
+        .get("current")
+        .ok_or_else(|| Error::Other("No current weather data".to_string()))?;
+    let daily = data
+        .get("daily")
+        .ok_or_else(|| Error::Other("No daily forecast data".to_string()))?;
+
+    let code = current.get("weather_code").and_then(|v| v.as_i64());
+    let current = json!({
+        "time": current.get("time"),
+        "temperature": current.get("temperature_2m"),
+        "apparent_temperature": current.get("apparent_temperature"),
+        "relative_humidity": current.get("relative_humidity_2m"),
+        "wind_speed": current.get("wind_speed_10m"),
+        "weather_code": code,
+        "conditions": code.map(describe),
+    });
+
+    let dates = daily
+        .get("time")
+        .and_then(|v| v.as_array())
+        .ok_or_else(|| Error::Other("Daily forecast has no dates".to_string()))?;
+    let column = |name: &str, i: usize| daily.get(name).and_then(|v| v.get(i)).cloned();
+    let days: Vec<Value> = dates
+        .iter()
+        .enumerate()
+        .map(|(i, date)| {
+            let code = column("weather_code", i).and_then(|v| v.as_i64());
+            json!({
+                "date": date,
+                "weather_code": code,
+                "conditions": code.map(describe),
+                "temperature_max": column("temperature_2m_max", i),
+                "temperature_min": column("temperature_2m_min", i),
+                "precipitation_sum": column("precipitation_sum", i),
+                "precipitation_probability_max": column("precipitation_probability_max", i),
+                "wind_speed_max": column("wind_speed_10m_max", i),
+            })
         })
-    }
+        .collect();
 
-    async fn execute(&self, input: Value) -> Result<String> {
-        let city = input.get("city").and_then(|v| v.as_str()).ok_or_else(|| {
-            Error::Other("Missing 'city' field. Example: {\"city\": \"London\"}".to_string())
-        })?;
+    let unit = |section: &str, field: &str| data.get(section).and_then(|u| u.get(field)).cloned();
+    Ok(json!({
+        "units": {
+            "temperature": unit("current_units", "temperature_2m"),
+            "wind_speed": unit("current_units", "wind_speed_10m"),
+            "precipitation": unit("daily_units", "precipitation_sum"),
+        },
+        "current": current,
+        "daily": days,
+    }))
+}
 
-        // First, get coordinates using geocoding API
-        let geocoding_url = format!(
-            "https://geocoding-api.open-meteo.com/v1/search?name={}&count=1&language=en&format=json",
-            urlencoding::encode(city)
-        );
+/// Forecast settings shared by every city of a call
+struct ForecastOptions<'a> {
+    days: u64,
+    units: &'a str,
+    windspeed_unit: &'a str,
+}
+
+impl WeatherTool {
+    /// Create a tool that queries the public Open-Meteo API
+    pub fn new() -> Self {
+        Self::default()
+    }
 
-        let client = reqwest::Client::new();
-        let geocoding_response = client
-            .get(&geocoding_url)
+    /// Send geocoding and forecast requests to other endpoints, such as a
+    /// self-hosted Open-Meteo or a mock server in tests
+    pub fn with_urls(
+        mut self,
+        geocoding_url: impl Into<String>,
+        forecast_url: impl Into<String>,
+    ) -> Self {
+        self.geocoding_url = geocoding_url.into();
+        self.forecast_url = forecast_url.into();
+        self
+    }
+
+    async fn geocode(
+        &self,
+        client: &reqwest::Client,
+        name: &str,
+        count: usize,
+    ) -> Result<Vec<Value>> {
+        let count = count.to_string();
+        let response = client
+            .get(&self.geocoding_url)
+            .query(&[
+                ("name", name),
+                ("count", count.as_str()),
+                ("language", "en"),
+                ("format", "json"),
+            ])
             .send()
             .await
             .map_err(|e| Error::Other(format!("Failed to fetch geocoding data: {}", e)))?;
 
-        let geocoding_data: Value = geocoding_response
+        let data: Value = response
             .json()
             .await
             .map_err(|e| Error::Other(format!("Failed to parse geocoding response: {}", e)))?;
 
-        let results = geocoding_data
+        Ok(data
             .get("results")
             .and_then(|r| r.as_array())
-            .ok_or_else(|| Error::Other("City not found".to_string()))?;
+            .cloned()
+            .unwrap_or_default())
+    }
 
-        if results.is_empty() {
-            return Err(Error::Other("City not found".to_string()));
+    /// Build the "city not found" error, suggesting places whose names share a
+    /// prefix with the query
+    ///
+    /// At most two prefixes are looked up, so a name with no close matches
+    /// costs a bounded number of requests: all but the last character, which
+    /// catches a stray trailing letter, then the first half of the name.
+    async fn not_found(&self, client: &reqwest::Client, city: &str) -> Error {
+        let chars: Vec<char> = city.trim().chars().collect();
+        let mut lengths = vec![
+            chars.len().saturating_sub(1),
+            (chars.len() / 2).max(MIN_PREFIX_CHARS),
+        ];
+        lengths.dedup();
+        lengths.retain(|&len| len >= MIN_PREFIX_CHARS && len < chars.len());
+        for len in lengths {
+            let prefix: String = chars[..len].iter().collect();
+            let matches = match self.geocode(client, &prefix, MAX_CLOSE_MATCHES).await {
+                Ok(matches) => matches,
+                Err(e) => return e,
+            };
+            if !matches.is_empty() {
+                let names: Vec<String> = matches.iter().map(place_name).collect();
+                return Error::Other(format!(
+                    "City '{}' not found. Close matches: {}",
+                    city,
+                    names.join("; ")
+                ));
+            }
         }
+        Error::Other(format!(
+            "City '{}' not found and no similar place names were found",
+            city
+        ))
+    }
 
-        let location = &results[0];
+    /// Geocode `city` and fetch its forecast
+    async fn forecast(
+        &self,
+        client: &reqwest::Client,
+        city: &str,
+        options: &ForecastOptions<'_>,
+    ) -> Result<Value> {
+        let location = match self.geocode(client, city, 1).await?.pop() {
+            Some(location) => location,
+            None => return Err(self.not_found(client, city).await),
+        };
         let lat = location
             .get("latitude")
             .and_then(|v| v.as_f64())
@@ -69,23 +419,19 @@ impl Tool for WeatherTool {
             .get("longitude")
             .and_then(|v| v.as_f64())
             .ok_or_else(|| Error::Other("Invalid longitude".to_string()))?;
-        let location_name = location
-            .get("name")
-            .and_then(|v| v.as_str())
-            .unwrap_or(city);
-        let country = location
-            .get("country")
-            .and_then(|v| v.as_str())
-            .unwrap_or("");
-
-        // Now get weather data
-        let weather_url = format!(
-            "https://api.open-meteo.com/v1/forecast?latitude={}&longitude={}&current=temperature_2m,apparent_temperature,weather_code,wind_speed_10m,relative_humidity_2m&temperature_unit=celsius",
-            lat, lon
-        );
 
         let weather_response = client
-            .get(&weather_url)
+            .get(&self.forecast_url)
+            .query(&[
+                ("latitude", lat.to_string()),
+                ("longitude", lon.to_string()),
+                ("current", CURRENT_FIELDS.to_string()),
+                ("daily", DAILY_FIELDS.to_string()),
+                ("forecast_days", options.days.to_string()),
+                ("temperature_unit", options.units.to_string()),
+                ("wind_speed_unit", options.windspeed_unit.to_string()),
+                ("timezone", "auto".to_string()),
+            ])
             .send()
             .await
             .map_err(|e| Error::Other(format!("Failed to fetch weather data: {}", e)))?;
@@ -94,53 +440,156 @@ impl Tool for WeatherTool {
             .json()
             .await
             .map_err(|e| Error::Other(format!("Failed to parse weather response: {}", e)))?;
+        if let Some(reason) = weather_data.get("reason").and_then(|v| v.as_str()) {
+            return Err(Error::Other(format!("Open-Meteo error: {}", reason)));
+        }
 
-        let current = weather_data
-            .get("current")
-            .ok_or_else(|| Error::Other("No current weather data".to_string()))?;
+        let mut forecast = parse_forecast(&weather_data)?;
+        forecast["location"] = json!({
+            "name": location.get("name"),
+            "admin1": location.get("admin1"),
+            "country": location.get("country"),
+            "latitude": lat,
+            "longitude": lon,
+            "timezone": weather_data.get("timezone"),
+        });
 
-        let temp = current
-            .get("temperature_2m")
-            .and_then(|v| v.as_f64())
-            .unwrap_or(0.0);
-        let feels_like = current
-            .get("apparent_temperature")
-            .and_then(|v| v.as_f64())
-            .unwrap_or(0.0);
-        let humidity = current
-            .get("relative_humidity_2m")
-            .and_then(|v| v.as_f64())
-            .unwrap_or(0.0);
-        let wind_speed = current
-            .get("wind_speed_10m")
-            .and_then(|v| v.as_f64())
-            .unwrap_or(0.0);
-        let weather_code = current
-            .get("weather_code")
-            .and_then(|v| v.as_i64())
-            .unwrap_or(0);
-
-        let weather_desc = match weather_code {
-            0 => "Clear sky",
-            1..=3 => "Partly cloudy",
-            45 | 48 => "Foggy",
-            51..=57 => "Drizzle",
-            61..=67 => "Rain",
-            71..=77 => "Snow",
-            80..=82 => "Rain showers",
-            85 | 86 => "Snow showers",
-            95 => "Thunderstorm",
-            96 | 99 => "Thunderstorm with hail",
-            _ => "Unknown",
+        Ok(forecast)
+    }
+}
+
+#[async_trait]
+impl Tool for WeatherTool {
+    fn name(&self) -> &str {
+        "weather"
+    }
+
+    fn description(&self) -> &str {
+        "Get current weather and a daily forecast (up to 16 days) for a city, or several cities at once, using the Open-Meteo API. Returns structured current conditions and per-day highs, lows, precipitation and wind."
+    }
+
+    fn input_schema(&self) -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "city": {
+                    "type": "string",
+                    "description": "The city name to get weather for"
+                },
+                "cities": {
+                    "type": "array",
+                    "items": {"type": "string"},
+                    "minItems": 1,
+                    "maxItems": MAX_CITIES,
+                    "description": "Several city names to compare, instead of 'city'; results are keyed by name"
+                },
+                "days": {
+                    "type": "integer",
+                    "minimum": 1,
+                    "maximum": MAX_DAYS,
+                    "description": "Number of forecast days, including today (default: 3)"
+                },
+                "units": {
+                    "type": "string",
+                    "enum": ["celsius", "fahrenheit"],
+                    "description": "Temperature unit (default: celsius)"
+                },
+                "windspeed_unit": {
+                    "type": "string",
+                    "enum": ["kmh", "ms", "mph", "kn"],
+                    "description": "Wind speed unit (default: kmh)"
+                }
+            },
+            "additionalProperties": false
+        })
+    }
+
+    async fn execute(&self, input: Value) -> Result<String> {
+        Ok(self.execute_typed(input).await?.into_text())
+    }
+
+    async fn execute_typed(&self, input: Value) -> Result<ToolOutput> {
+        let params: WeatherInput = serde_json::from_value(input).map_err(|e| {
+            Error::Other(format!(
+                "Invalid input parameters: {}. Example: {{\"city\": \"London\"}}",
+                e
+            ))
+        })?;
+
+        let units = params.units.as_deref().unwrap_or("celsius");
+        if !matches!(units, "celsius" | "fahrenheit") {
+            return Err(Error::Other(format!(
+                "Unknown units: '{}'. Valid options: celsius, fahrenheit",
+                units
+            )));
+        }
+        let windspeed_unit = params.windspeed_unit.as_deref().unwrap_or("kmh");
+        if !matches!(windspeed_unit, "kmh" | "ms" | "mph" | "kn") {
+            return Err(Error::Other(format!(
+                "Unknown windspeed_unit: '{}'. Valid options: kmh, ms, mph, kn",
+                windspeed_unit
+            )));
+        }
+        let options = ForecastOptions {
+            days: params.days.unwrap_or(DEFAULT_DAYS).clamp(1, MAX_DAYS),
+            units,
+            windspeed_unit,
         };
 
-        Ok(format!(
-            "Weather in {}, {}:\n\
-            🌡️  Temperature: {:.1}°C (feels like {:.1}°C)\n\
-            ☁️  Conditions: {}\n\
-            💨 Wind: {:.1} km/h\n\
-            💧 Humidity: {:.0}%",
-            location_name, country, temp, feels_like, weather_desc, wind_speed, humidity
-        ))
+        let client = reqwest::Client::builder()
+            .timeout(REQUEST_TIMEOUT)
+            .build()
+            .map_err(|e| Error::Other(format!("Failed to create HTTP client: {}", e)))?;
+        let mut cities = match (params.city, params.cities) {
+            (Some(city), None) => {
+                let forecast = self.forecast(&client, &city, &options).await?;
+                return Ok(ToolOutput::Json(forecast));
+            }
+            (None, Some(cities)) if !cities.is_empty() => cities,
+            _ => {
+                return Err(Error::Other(
+                    "Give either 'city' or 'cities' (a non-empty list), not both. Example: {\"cities\": [\"Paris\", \"Rome\"]}"
+                        .to_string(),
+                ))
+            }
+        };
+        // Asking twice for the same city would only overwrite its result
+        let mut seen = HashSet::new();
+        cities.retain(|city| seen.insert(city.clone()));
+        if cities.len() > MAX_CITIES {
+            return Err(Error::Other(format!(
+                "Too many cities: {} (at most {} per call)",
+                cities.len(),
+                MAX_CITIES
+            )));
+        }
+
+        let forecasts: Vec<(String, Result<Value>)> = stream::iter(cities)
+            .map(|city| {
+                let (client, options) = (&client, &options);
+                async move {
+                    let forecast = self.forecast(client, &city, options).await;
+                    (city, forecast)
+                }
+            })
+            .buffered(MAX_CONCURRENT_CITIES)
+            .collect()
+            .await;
+
+        let failed = forecasts.iter().filter(|(_, f)| f.is_err()).count();
+        let succeeded = forecasts.len() - failed;
+        let results: Map<String, Value> = forecasts
+            .into_iter()
+            .map(|(city, forecast)| match forecast {
+                Ok(forecast) => (city, forecast),
+                Err(e) => (city, json!({"error": e.to_string()})),
+            })
+            .collect();
+
+        Ok(ToolOutput::Json(json!({
+            "results": results,
+            "succeeded": succeeded,
+            "failed": failed,
+        })))
     }
 }
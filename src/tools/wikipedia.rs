@@ -9,17 +9,35 @@ pub struct WikipediaTool;
 
 #[derive(Debug, Deserialize)]
 struct WikipediaInput {
+    #[serde(default)]
     query: String,
     action: Option<String>,
     limit: Option<u32>,
     language: Option<String>,
+    lat: Option<f64>,
+    lon: Option<f64>,
+    radius: Option<u32>,
+    section: Option<Value>,
+    full: Option<bool>,
+    max_chars: Option<usize>,
+    langlinks: Option<bool>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
-struct WikipediaSearchResult {
-    title: String,
-    snippet: String,
-    wordcount: Option<u32>,
+/// An article found by a search, summary, or geosearch
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WikipediaSearchResult {
+    /// Title of the article
+    pub title: String,
+    /// Canonical URL of the article
+    #[serde(default)]
+    pub url: String,
+    /// Matching text, the summary, or where the article is located
+    pub snippet: String,
+    /// Number of words in the article or in the returned text, if known
+    pub wordcount: Option<u32>,
+    /// Distance from the searched coordinates, for geosearch results
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub distance_meters: Option<f64>,
 }
 
 #[derive(Debug, Serialize)]
@@ -29,6 +47,400 @@ struct WikipediaResponse {
     language: String,
     results: Vec<WikipediaSearchResult>,
     summary: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    sections: Option<Vec<WikipediaSection>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    langlinks: Option<Vec<LanguageLink>>,
+}
+
+/// The same article in another language edition of Wikipedia
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct LanguageLink {
+    /// Language code of the other edition, e.g. `de`
+    pub language: String,
+    /// Title of the article in that edition
+    pub title: String,
+    /// Canonical URL of the article in that edition
+    pub url: String,
+}
+
+#[derive(Debug, Serialize)]
+struct WikipediaSection {
+    index: usize,
+    title: String,
+    level: usize,
+}
+
+/// A section of a plaintext extract, split on its `== Heading ==` lines
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExtractSection {
+    /// Heading text, or `Introduction` for the text before the first heading
+    pub title: String,
+    /// Number of `=` around the heading; 2 for top-level sections
+    pub level: usize,
+    /// Text up to the next heading, without its subsections
+    pub text: String,
+}
+
+/// Whether `code` looks like a Wikipedia language subdomain
+///
+/// Accepts 2 to 12 lowercase ASCII letters, optionally split into parts by
+/// single hyphens, which covers codes such as `en`, `simple`, `pt-br`,
+/// `zh-yue`, `nds-nl`, and `zh-classical`. Anything else can't be a
+/// subdomain and is rejected before a request is made.
+///
+/// # Example
+///
+/// ```rust
+/// use claude::tools::wikipedia::is_valid_language_code;
+/// use claude::{Tool, tools::WikipediaTool};
+/// use serde_json::json;
+///
+/// for code in ["en", "simple", "zh-yue", "pt-br", "nds-nl", "zh-min-nan"] {
+///     assert!(is_valid_language_code(code), "{}", code);
+/// }
+/// for code in ["EN!", "", "e", "en-", "-en", "zh--yue", "en.org", "abcdefghijklm"] {
+///     assert!(!is_valid_language_code(code), "{}", code);
+/// }
+///
+/// // The tool rejects an invalid code without making a request
+/// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+/// let err = WikipediaTool
+///     .execute(json!({"query": "Rust", "language": "EN!"}))
+///     .await
+///     .unwrap_err();
+/// assert!(err.to_string().contains("Invalid Wikipedia language code 'EN!'"));
+/// # });
+/// ```
+pub fn is_valid_language_code(code: &str) -> bool {
+    (2..=12).contains(&code.len())
+        && code
+            .split('-')
+            .all(|part| !part.is_empty() && part.chars().all(|c| c.is_ascii_lowercase()))
+}
+
+/// Canonical URL of the article `title` on the `language` edition of Wikipedia
+///
+/// Spaces become underscores, as in Wikipedia's own links, and everything
+/// else outside the unreserved URL characters is percent-encoded.
+///
+/// # Example
+///
+/// ```rust
+/// use claude::tools::wikipedia::article_url;
+///
+/// assert_eq!(
+///     article_url("en", "Rust (programming language)"),
+///     "https://en.wikipedia.org/wiki/Rust_%28programming_language%29"
+/// );
+/// assert_eq!(article_url("fr", "Café de Flore"), "https://fr.wikipedia.org/wiki/Caf%C3%A9_de_Flore");
+/// ```
+pub fn article_url(language: &str, title: &str) -> String {
+    format!(
+        "https://{}.wikipedia.org/wiki/{}",
+        language,
+        urlencoding::encode(&title.replace(' ', "_"))
+    )
+}
+
+/// Language links of the first page in a `prop=langlinks` query response
+///
+/// Links are returned in the order the API lists them, each with the
+/// article's [`article_url`] in its language.
+///
+/// # Errors
+///
+/// Returns an error if the response has no `query.pages` object. A page
+/// without language links gives an empty list.
+///
+/// # Example
+///
+/// ```rust
+/// use claude::tools::wikipedia::parse_langlinks;
+/// use serde_json::json;
+///
+/// let response = json!({
+///     "query": {
+///         "pages": {
+///             "25659": {
+///                 "pageid": 25659,
+///                 "title": "Red panda",
+///                 "langlinks": [
+///                     {"lang": "de", "*": "Kleiner Panda"},
+///                     {"lang": "es", "*": "Ailurus fulgens"},
+///                     {"lang": "ja", "*": "レッサーパンダ"}
+///                 ]
+///             }
+///         }
+///     }
+/// });
+///
+/// let links = parse_langlinks(&response)?;
+/// assert_eq!(links.len(), 3);
+/// assert_eq!(links[0].language, "de");
+/// assert_eq!(links[0].title, "Kleiner Panda");
+/// assert_eq!(links[0].url, "https://de.wikipedia.org/wiki/Kleiner_Panda");
+/// assert_eq!(links[1].url, "https://es.wikipedia.org/wiki/Ailurus_fulgens");
+/// assert_eq!(
+///     links[2].url,
+///     "https://ja.wikipedia.org/wiki/%E3%83%AC%E3%83%83%E3%82%B5%E3%83%BC%E3%83%91%E3%83%B3%E3%83%80"
+/// );
+///
+/// let no_links = json!({"query": {"pages": {"1": {"title": "Stub"}}}});
+/// assert!(parse_langlinks(&no_links)?.is_empty());
+/// assert!(parse_langlinks(&json!({"batchcomplete": ""})).is_err());
+/// # Ok::<(), claude::Error>(())
+/// ```
+pub fn parse_langlinks(json_response: &Value) -> Result<Vec<LanguageLink>> {
+    let pages = json_response["query"]["pages"]
+        .as_object()
+        .ok_or_else(|| Error::Other("Invalid Wikipedia langlinks response format".to_string()))?;
+
+    let links = pages
+        .values()
+        .next()
+        .and_then(|page| page["langlinks"].as_array())
+        .map(|links| {
+            links
+                .iter()
+                .filter_map(|link| {
+                    let language = link["lang"].as_str()?;
+                    let title = link["*"].as_str()?;
+                    Some(LanguageLink {
+                        language: language.to_string(),
+                        title: title.to_string(),
+                        url: article_url(language, title),
+                    })
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+
+    Ok(links)
+}
+
+/// Articles in a `list=geosearch` query response, nearest first
+///
+/// Each result's snippet gives the article's coordinates and
+/// `distance_meters` its distance from the searched point. Results are sorted
+/// by distance, with any missing one last.
+///
+/// # Errors
+///
+/// Returns an error if the response has no `query.geosearch` list.
+///
+/// # Example
+///
+/// ```rust
+/// use claude::tools::wikipedia::parse_geosearch_results;
+/// use serde_json::json;
+///
+/// // A `list=geosearch` response for `gscoord=51.5007|-0.1246`, out of distance order
+/// let response = json!({
+///     "batchcomplete": "",
+///     "query": {
+///         "geosearch": [
+///             {
+///                 "pageid": 33217, "ns": 0, "title": "Westminster Bridge",
+///                 "lat": 51.500833, "lon": -0.121667, "dist": 209.8, "primary": ""
+///             },
+///             {
+///                 "pageid": 57914, "ns": 0, "title": "Big Ben",
+///                 "lat": 51.500729, "lon": -0.124625, "dist": 1.8, "primary": ""
+///             },
+///             {
+///                 "pageid": 19730, "ns": 0, "title": "Palace of Westminster",
+///                 "lat": 51.499722, "lon": -0.124722, "dist": 111.3, "primary": ""
+///             }
+///         ]
+///     }
+/// });
+///
+/// let results = parse_geosearch_results(&response, "en")?;
+/// let titles: Vec<&str> = results.iter().map(|r| r.title.as_str()).collect();
+/// assert_eq!(titles, ["Big Ben", "Palace of Westminster", "Westminster Bridge"]);
+/// assert_eq!(results[0].distance_meters, Some(1.8));
+/// assert_eq!(results[0].snippet, "Located at 51.500729, -0.124625");
+/// assert_eq!(results[0].url, "https://en.wikipedia.org/wiki/Big_Ben");
+/// assert_eq!(results[0].wordcount, None);
+///
+/// // Nothing nearby is an empty list; a malformed response is an error
+/// let empty = json!({"batchcomplete": "", "query": {"geosearch": []}});
+/// assert!(parse_geosearch_results(&empty, "en")?.is_empty());
+/// let error = json!({"error": {"code": "invalid-coord", "info": "Invalid coordinate provided"}});
+/// assert!(parse_geosearch_results(&error, "en").is_err());
+/// # Ok::<(), claude::Error>(())
+/// ```
+pub fn parse_geosearch_results(
+    json_response: &Value,
+    language: &str,
+) -> Result<Vec<WikipediaSearchResult>> {
+    let places = json_response["query"]["geosearch"]
+        .as_array()
+        .ok_or_else(|| Error::Other("Invalid Wikipedia geosearch response format".to_string()))?;
+
+    let mut results: Vec<WikipediaSearchResult> = places
+        .iter()
+        .map(|place| {
+            let title = place["title"].as_str().unwrap_or("").to_string();
+            let snippet = match (place["lat"].as_f64(), place["lon"].as_f64()) {
+                (Some(lat), Some(lon)) => format!("Located at {}, {}", lat, lon),
+                _ => String::new(),
+            };
+            WikipediaSearchResult {
+                url: article_url(language, &title),
+                title,
+                snippet,
+                wordcount: None,
+                distance_meters: place["dist"].as_f64(),
+            }
+        })
+        .collect();
+
+    results.sort_by(|a, b| {
+        a.distance_meters
+            .unwrap_or(f64::MAX)
+            .total_cmp(&b.distance_meters.unwrap_or(f64::MAX))
+    });
+
+    Ok(results)
+}
+
+/// Split a wiki-formatted extract into sections; index 0 is the introduction
+///
+/// Expects an extract fetched with `exsectionformat=wiki`, where each heading
+/// is a line such as `== History ==` or `=== Early years ===`. The sections
+/// come in article order, so their indices match the ones MediaWiki uses.
+///
+/// # Example
+///
+/// ```rust
+/// use claude::tools::wikipedia::{find_section, section_text, split_sections};
+/// use serde_json::json;
+///
+/// let extract = "The red panda is a small mammal.\n\
+///                \n\
+///                == Taxonomy ==\n\
+///                First described in 1825.\n\
+///                \n\
+///                === Subspecies ===\n\
+///                Two are recognised.\n\
+///                \n\
+///                == Conservation ==\n\
+///                It is listed as Endangered.\n\
+///                ==\n";
+///
+/// let sections = split_sections(extract);
+/// let headings: Vec<(&str, usize)> =
+///     sections.iter().map(|s| (s.title.as_str(), s.level)).collect();
+/// assert_eq!(
+///     headings,
+///     [("Introduction", 1), ("Taxonomy", 2), ("Subspecies", 3), ("Conservation", 2)]
+/// );
+/// assert_eq!(sections[0].text, "The red panda is a small mammal.");
+/// assert_eq!(sections[1].text, "First described in 1825.");
+/// // A bare `==` isn't a heading, so it stays in the text
+/// assert_eq!(sections[3].text, "It is listed as Endangered.\n==");
+///
+/// // Sections are found by index or by title, ignoring case
+/// assert_eq!(find_section(&sections, &json!(3)), Some(3));
+/// assert_eq!(find_section(&sections, &json!("2")), Some(2));
+/// assert_eq!(find_section(&sections, &json!("conservation")), Some(3));
+/// assert_eq!(find_section(&sections, &json!("Habitat")), None);
+/// assert_eq!(find_section(&sections, &json!(4)), None);
+/// assert_eq!(find_section(&sections, &json!(true)), None);
+///
+/// // A section's text takes in its subsections, up to the next section at
+/// // its level
+/// assert_eq!(
+///     section_text(&sections, 1),
+///     "First described in 1825.\n\n=== Subspecies ===\nTwo are recognised."
+/// );
+/// assert_eq!(section_text(&sections, 3), "It is listed as Endangered.\n==");
+/// // The introduction is only the text before the first heading
+/// assert_eq!(section_text(&sections, 0), "The red panda is a small mammal.");
+///
+/// // An extract without headings is all introduction
+/// let sections = split_sections("Just one paragraph.");
+/// assert_eq!(sections.len(), 1);
+/// assert_eq!(section_text(&sections, 0), "Just one paragraph.");
+/// ```
+pub fn split_sections(extract: &str) -> Vec<ExtractSection> {
+    let mut sections = vec![ExtractSection {
+        title: "Introduction".to_string(),
+        level: 1,
+        text: String::new(),
+    }];
+
+    for line in extract.lines() {
+        let trimmed = line.trim();
+        let level = trimmed.chars().take_while(|c| *c == '=').count();
+        let is_heading =
+            level >= 2 && trimmed.len() > 2 * level && trimmed.ends_with(&"=".repeat(level));
+
+        if is_heading {
+            let title = trimmed[level..trimmed.len() - level].trim().to_string();
+            sections.push(ExtractSection {
+                title,
+                level,
+                text: String::new(),
+            });
+        } else if let Some(current) = sections.last_mut() {
+            current.text.push_str(line);
+            current.text.push('\n');
+        }
+    }
+
+    for section in &mut sections {
+        section.text = section.text.trim().to_string();
+    }
+    sections
+}
+
+/// Resolve a section index or (case-insensitive) title
+///
+/// Returns `None` if there is no such section; see [`split_sections`] for an
+/// example.
+pub fn find_section(sections: &[ExtractSection], selector: &Value) -> Option<usize> {
+    let index = match selector {
+        Value::Number(n) => n.as_u64().map(|n| n as usize),
+        Value::String(s) => s.trim().parse::<usize>().ok(),
+        _ => None,
+    };
+    if let Some(index) = index {
+        return (index < sections.len()).then_some(index);
+    }
+
+    let wanted = selector.as_str()?.trim();
+    sections
+        .iter()
+        .position(|section| section.title.eq_ignore_ascii_case(wanted))
+}
+
+/// Text of a section together with its subsections
+///
+/// Panics if `index` is out of range; see [`split_sections`] for an example.
+pub fn section_text(sections: &[ExtractSection], index: usize) -> String {
+    let section = &sections[index];
+    let mut text = section.text.clone();
+
+    // The introduction has no subsections; everything after it is a new section
+    if index > 0 {
+        for sub in sections[index + 1..]
+            .iter()
+            .take_while(|sub| sub.level > section.level)
+        {
+            text.push_str(&format!(
+                "\n\n{} {} {}\n",
+                "=".repeat(sub.level),
+                sub.title,
+                "=".repeat(sub.level)
+            ));
+            text.push_str(&sub.text);
+        }
+    }
+
+    text.trim().to_string()
 }
 
 #[async_trait]
@@ -38,7 +450,7 @@ impl Tool for WikipediaTool {
     }
 
     fn description(&self) -> &str {
-        "Search Wikipedia articles and get article summaries. Supports multiple languages and can either search for articles or get detailed summaries of specific pages."
+        "Search Wikipedia articles, get article summaries, or find articles near a location. Supports multiple languages and can search for articles, get detailed summaries of specific pages, or list articles near given coordinates."
     }
 
     fn input_schema(&self) -> Value {
@@ -47,12 +459,12 @@ impl Tool for WikipediaTool {
             "properties": {
                 "query": {
                     "type": "string",
-                    "description": "Search query or article title"
+                    "description": "Search query or article title (required for 'search' and 'summary')"
                 },
                 "action": {
                     "type": "string",
-                    "enum": ["search", "summary"],
-                    "description": "Action to perform: 'search' to find articles, 'summary' to get article content (default: search)"
+                    "enum": ["search", "summary", "sections", "geosearch"],
+                    "description": "Action to perform: 'search' to find articles, 'summary' to get article content, 'sections' to list an article's sections or read one, 'geosearch' to find articles near 'lat'/'lon' (default: search)"
                 },
                 "limit": {
                     "type": "integer",
@@ -62,10 +474,46 @@ impl Tool for WikipediaTool {
                 },
                 "language": {
                     "type": "string",
-                    "description": "Wikipedia language code (default: en). Examples: en, es, fr, de, it, pt, ru, ja, zh"
+                    "description": "Wikipedia language code (default: en). Examples: en, es, fr, de, ja, zh, simple, pt-br, zh-yue"
+                },
+                "lat": {
+                    "type": "number",
+                    "minimum": -90,
+                    "maximum": 90,
+                    "description": "Latitude in degrees (required for 'geosearch')"
+                },
+                "lon": {
+                    "type": "number",
+                    "minimum": -180,
+                    "maximum": 180,
+                    "description": "Longitude in degrees (required for 'geosearch')"
+                },
+                "section": {
+                    "type": ["integer", "string"],
+                    "description": "For 'sections': index (0 is the introduction) or title of the section to read. Omit to list the article's sections"
+                },
+                "full": {
+                    "type": "boolean",
+                    "description": "For 'summary': return the whole article instead of just the introduction (default: false)"
+                },
+                "langlinks": {
+                    "type": "boolean",
+                    "description": "For 'summary': also list the article's titles and URLs in other language editions (default: false)"
+                },
+                "max_chars": {
+                    "type": "integer",
+                    "minimum": 100,
+                    "maximum": 100000,
+                    "description": "Maximum characters of article text to return for 'summary' and 'sections' (default: 2000)"
+                },
+                "radius": {
+                    "type": "integer",
+                    "minimum": 10,
+                    "maximum": 10000,
+                    "description": "Search radius in meters for 'geosearch' (default: 1000, min: 10, max: 10000)"
                 }
             },
-            "required": ["query"],
+            "required": [],
             "additionalProperties": false
         })
     }
@@ -73,19 +521,18 @@ impl Tool for WikipediaTool {
     async fn execute(&self, input: Value) -> Result<String> {
         let params: WikipediaInput = serde_json::from_value(input)
             .map_err(|e| Error::Other(format!(
-                "Invalid input parameters: {}. Example: {{\"query\": \"artificial intelligence\", \"action\": \"search\"}}", e
+                "Invalid input parameters: {}. Example: {{\"query\": \"artificial intelligence\", \"action\": \"search\"}} or {{\"action\": \"geosearch\", \"lat\": 51.5, \"lon\": -0.12}}", e
             )))?;
 
         let action = params.action.as_deref().unwrap_or("search");
         let language = params.language.as_deref().unwrap_or("en");
         let limit = params.limit.unwrap_or(5).min(20).max(1);
 
-        // Validate language code (basic validation)
-        if language.len() != 2 || !language.chars().all(|c| c.is_ascii_lowercase()) {
-            return Err(Error::Other(
-                "Language code must be a 2-letter lowercase code (e.g., 'en', 'es', 'fr')"
-                    .to_string(),
-            ));
+        if !is_valid_language_code(language) {
+            return Err(Error::Other(format!(
+                "Invalid Wikipedia language code '{}': expected a lowercase subdomain such as 'en', 'simple', 'pt-br', or 'zh-yue'",
+                language
+            )));
         }
 
         let client = reqwest::Client::builder()
@@ -94,17 +541,65 @@ impl Tool for WikipediaTool {
             .build()
             .map_err(|e| Error::Other(format!("Failed to create HTTP client: {}", e)))?;
 
+        let max_chars = params.max_chars.unwrap_or(2000).clamp(100, 100_000);
+
+        if matches!(action, "search" | "summary" | "sections") && params.query.trim().is_empty() {
+            return Err(Error::Other(format!(
+                "The '{}' action requires a non-empty 'query'",
+                action
+            )));
+        }
+
         match action {
             "search" => {
                 self.search_wikipedia(&client, &params.query, language, limit)
                     .await
             }
             "summary" => {
-                self.get_wikipedia_summary(&client, &params.query, language)
+                let full = params.full.unwrap_or(false);
+                let langlinks = params.langlinks.unwrap_or(false);
+                self.get_wikipedia_summary(
+                    &client,
+                    &params.query,
+                    language,
+                    full,
+                    langlinks,
+                    max_chars,
+                )
+                .await
+            }
+            "sections" => {
+                self.get_wikipedia_sections(
+                    &client,
+                    &params.query,
+                    language,
+                    params.section.as_ref(),
+                    max_chars,
+                )
+                .await
+            }
+            "geosearch" => {
+                let (lat, lon) = match (params.lat, params.lon) {
+                    (Some(lat), Some(lon)) => (lat, lon),
+                    _ => {
+                        return Err(Error::Other(
+                            "The 'geosearch' action requires both 'lat' and 'lon'".to_string(),
+                        ))
+                    }
+                };
+                if !(-90.0..=90.0).contains(&lat) || !(-180.0..=180.0).contains(&lon) {
+                    return Err(Error::Other(
+                        "Latitude must be between -90 and 90 and longitude between -180 and 180"
+                            .to_string(),
+                    ));
+                }
+                let radius = params.radius.unwrap_or(1000).clamp(10, 10000);
+                self.geosearch_wikipedia(&client, lat, lon, radius, language, limit)
                     .await
             }
             _ => Err(Error::Other(
-                "Invalid action. Supported actions: 'search', 'summary'".to_string(),
+                "Invalid action. Supported actions: 'search', 'summary', 'sections', 'geosearch'"
+                    .to_string(),
             )),
         }
     }
@@ -166,9 +661,11 @@ impl WikipediaTool {
             let wordcount = result["wordcount"].as_u64().map(|w| w as u32);
 
             results.push(WikipediaSearchResult {
+                url: article_url(language, &title),
                 title,
                 snippet,
                 wordcount,
+                distance_meters: None,
             });
         }
 
@@ -178,6 +675,8 @@ impl WikipediaTool {
             language: language.to_string(),
             results,
             summary: None,
+            sections: None,
+            langlinks: None,
         };
 
         serde_json::to_string_pretty(&wiki_response)
@@ -189,16 +688,138 @@ impl WikipediaTool {
         client: &reqwest::Client,
         title: &str,
         language: &str,
+        full: bool,
+        langlinks: bool,
+        max_chars: usize,
+    ) -> Result<String> {
+        let (actual_title, extract, links) = self
+            .fetch_extract(client, title, language, !full, "plain", langlinks)
+            .await?;
+
+        // Limit summary length to prevent overly long responses
+        let summary = Self::truncate_chars(&extract, max_chars);
+
+        let wiki_response = WikipediaResponse {
+            action: "summary".to_string(),
+            query: title.to_string(),
+            language: language.to_string(),
+            results: vec![WikipediaSearchResult {
+                url: article_url(language, &actual_title),
+                title: actual_title,
+                snippet: summary.clone(),
+                wordcount: Some(summary.split_whitespace().count() as u32),
+                distance_meters: None,
+            }],
+            summary: Some(summary),
+            sections: None,
+            langlinks: langlinks.then_some(links),
+        };
+
+        serde_json::to_string_pretty(&wiki_response)
+            .map_err(|e| Error::Other(format!("Failed to serialize response: {}", e)))
+    }
+
+    /// List an article's sections, or return the text of one section
+    async fn get_wikipedia_sections(
+        &self,
+        client: &reqwest::Client,
+        title: &str,
+        language: &str,
+        section: Option<&Value>,
+        max_chars: usize,
     ) -> Result<String> {
+        // The wiki-formatted extract keeps "== Heading ==" lines, which lets us
+        // split the plaintext into the same sections MediaWiki numbers
+        let (actual_title, extract, _) = self
+            .fetch_extract(client, title, language, false, "wiki", false)
+            .await?;
+        let sections = split_sections(&extract);
+
+        let wiki_response = match section {
+            None => WikipediaResponse {
+                action: "sections".to_string(),
+                query: title.to_string(),
+                language: language.to_string(),
+                results: Vec::new(),
+                summary: None,
+                langlinks: None,
+                sections: Some(
+                    sections
+                        .iter()
+                        .enumerate()
+                        .map(|(index, section)| WikipediaSection {
+                            index,
+                            title: section.title.clone(),
+                            level: section.level,
+                        })
+                        .collect(),
+                ),
+            },
+            Some(selector) => {
+                let index = find_section(&sections, selector).ok_or_else(|| {
+                    let available: Vec<String> = sections
+                        .iter()
+                        .enumerate()
+                        .map(|(i, s)| format!("{}: {}", i, s.title))
+                        .collect();
+                    Error::Other(format!(
+                        "Section {} not found in '{}'. Available sections: {}",
+                        selector,
+                        actual_title,
+                        available.join(", ")
+                    ))
+                })?;
+                let text = Self::truncate_chars(&section_text(&sections, index), max_chars);
+
+                WikipediaResponse {
+                    action: "sections".to_string(),
+                    query: title.to_string(),
+                    language: language.to_string(),
+                    results: vec![WikipediaSearchResult {
+                        title: format!("{} - {}", actual_title, sections[index].title),
+                        url: article_url(language, &actual_title),
+                        snippet: text.clone(),
+                        wordcount: Some(text.split_whitespace().count() as u32),
+                        distance_meters: None,
+                    }],
+                    summary: Some(text),
+                    sections: None,
+                    langlinks: None,
+                }
+            }
+        };
+
+        serde_json::to_string_pretty(&wiki_response)
+            .map_err(|e| Error::Other(format!("Failed to serialize response: {}", e)))
+    }
+
+    /// Fetch an article's plaintext extract, returning the resolved title, the
+    /// text, and, if `langlinks` is set, its links to other languages
+    async fn fetch_extract(
+        &self,
+        client: &reqwest::Client,
+        title: &str,
+        language: &str,
+        intro_only: bool,
+        section_format: &str,
+        langlinks: bool,
+    ) -> Result<(String, String, Vec<LanguageLink>)> {
         let url = format!("https://{}.wikipedia.org/w/api.php", language);
 
         let mut params = HashMap::new();
         params.insert("action", "query");
         params.insert("format", "json");
-        params.insert("prop", "extracts");
-        params.insert("exintro", "true");
+        if langlinks {
+            params.insert("prop", "extracts|langlinks");
+            params.insert("lllimit", "max");
+        } else {
+            params.insert("prop", "extracts");
+        }
+        if intro_only {
+            params.insert("exintro", "true");
+        }
         params.insert("explaintext", "true");
-        params.insert("exsectionformat", "plain");
+        params.insert("exsectionformat", section_format);
         params.insert("titles", title);
         params.insert("redirects", "true");
 
@@ -245,25 +866,78 @@ impl WikipediaTool {
             .ok_or_else(|| Error::Other("No extract found in Wikipedia response".to_string()))?;
 
         let actual_title = page["title"].as_str().unwrap_or(title);
+        let links = if langlinks {
+            parse_langlinks(&json_response)?
+        } else {
+            Vec::new()
+        };
 
-        // Limit summary length to prevent overly long responses
-        let summary = if extract.chars().count() > 2000 {
-            let truncated: String = extract.chars().take(2000).collect();
+        Ok((actual_title.to_string(), extract.to_string(), links))
+    }
+
+    fn truncate_chars(text: &str, max_chars: usize) -> String {
+        if text.chars().count() > max_chars {
+            let truncated: String = text.chars().take(max_chars).collect();
             format!("{}...", truncated)
         } else {
-            extract.to_string()
-        };
+            text.to_string()
+        }
+    }
+
+    async fn geosearch_wikipedia(
+        &self,
+        client: &reqwest::Client,
+        lat: f64,
+        lon: f64,
+        radius: u32,
+        language: &str,
+        limit: u32,
+    ) -> Result<String> {
+        let url = format!("https://{}.wikipedia.org/w/api.php", language);
+
+        let coord = format!("{}|{}", lat, lon);
+        let radius_str = radius.to_string();
+        let limit_str = limit.to_string();
+        let mut params = HashMap::new();
+        params.insert("action", "query");
+        params.insert("format", "json");
+        params.insert("list", "geosearch");
+        params.insert("gscoord", &coord);
+        params.insert("gsradius", &radius_str);
+        params.insert("gslimit", &limit_str);
+
+        let response = client
+            .get(&url)
+            .query(&params)
+            .send()
+            .await
+            .map_err(|e| Error::Other(format!("Wikipedia API request failed: {}", e)))?;
+
+        if !response.status().is_success() {
+            return Err(Error::Other(format!(
+                "Wikipedia API returned status: {}",
+                response.status()
+            )));
+        }
+
+        let response_text = response
+            .text()
+            .await
+            .map_err(|e| Error::Other(format!("Failed to read Wikipedia response: {}", e)))?;
+
+        let json_response: Value = serde_json::from_str(&response_text)
+            .map_err(|e| Error::Other(format!("Failed to parse Wikipedia response: {}", e)))?;
+
+        let results = parse_geosearch_results(&json_response, language)?;
 
         let wiki_response = WikipediaResponse {
-            action: "summary".to_string(),
-            query: title.to_string(),
+            action: "geosearch".to_string(),
+            query: format!("{}, {}", lat, lon),
             language: language.to_string(),
-            results: vec![WikipediaSearchResult {
-                title: actual_title.to_string(),
-                snippet: summary.clone(),
-                wordcount: Some(summary.split_whitespace().count() as u32),
-            }],
-            summary: Some(summary),
+            results,
+            summary: None,
+            sections: None,
+            langlinks: None,
         };
 
         serde_json::to_string_pretty(&wiki_response)
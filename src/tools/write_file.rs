@@ -0,0 +1,253 @@
+use crate::paths::{resolve_within, write_no_follow};
+use crate::{Error, Result, Tool, ToolOutput};
+use async_trait::async_trait;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Writes whole files, for when a diff would be more trouble than the content
+///
+/// Missing parent directories are created. An existing file is left alone
+/// unless `overwrite` is set, and the output says whether the file was
+/// created or overwritten along with the number of bytes written. Paths can be
+/// confined to a directory with [`with_root`](Self::with_root), and a symlink
+/// at the path itself is never written through.
+///
+/// # Example
+///
+/// ```rust
+/// use claude::tools::WriteFileTool;
+/// use claude::{Tool, ToolOutput};
+/// use serde_json::json;
+///
+/// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+/// let dir = tempfile::tempdir().unwrap();
+/// let path = dir.path().join("src/bin/hello.rs");
+///
+/// // Creates the file and its parent directories
+/// let ToolOutput::Json(report) = WriteFileTool::new()
+///     .execute_typed(json!({"path": path, "content": "fn main() {}\n"}))
+///     .await?
+/// else {
+///     panic!("expected JSON output")
+/// };
+/// assert_eq!(report["created"], true);
+/// assert_eq!(report["overwrote"], false);
+/// assert_eq!(report["bytes_written"], 13);
+/// assert_eq!(std::fs::read_to_string(&path).unwrap(), "fn main() {}\n");
+///
+/// // An existing file is refused unless overwrite is set
+/// let err = WriteFileTool::new()
+///     .execute(json!({"path": path, "content": "fn main() { todo!() }\n"}))
+///     .await
+///     .unwrap_err();
+/// assert!(err.to_string().contains("already exists"), "{}", err);
+/// assert_eq!(std::fs::read_to_string(&path).unwrap(), "fn main() {}\n");
+///
+/// let ToolOutput::Json(report) = WriteFileTool::new()
+///     .execute_typed(json!({"path": path, "content": "fn main() { todo!() }\n", "overwrite": true}))
+///     .await?
+/// else {
+///     panic!("expected JSON output")
+/// };
+/// assert_eq!(report["created"], false);
+/// assert_eq!(report["overwrote"], true);
+/// assert_eq!(std::fs::read_to_string(&path).unwrap(), "fn main() { todo!() }\n");
+///
+/// // A symlink at the path is never written through
+/// # #[cfg(unix)] {
+/// let link = dir.path().join("link.rs");
+/// std::os::unix::fs::symlink(&path, &link).unwrap();
+/// let result = WriteFileTool::new()
+///     .execute(json!({"path": link, "content": "gotcha", "overwrite": true}))
+///     .await;
+/// assert!(result.is_err());
+/// assert_eq!(std::fs::read_to_string(&path).unwrap(), "fn main() { todo!() }\n");
+/// # }
+/// # Ok::<(), claude::Error>(())
+/// # }).unwrap();
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct WriteFileTool {
+    /// Directory every written path must be inside, if restricted
+    root: Option<PathBuf>,
+}
+
+impl WriteFileTool {
+    /// Create a tool that may write any path
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create a tool that only writes files inside `root`
+    ///
+    /// Relative paths are resolved against `root`. A path that leaves it, with
+    /// `..` components, an absolute path, or a symlink, is rejected before
+    /// anything is written.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use claude::tools::WriteFileTool;
+    /// use claude::Tool;
+    /// use serde_json::json;
+    ///
+    /// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+    /// let dir = tempfile::tempdir().unwrap();
+    /// let root = dir.path().join("project");
+    /// std::fs::create_dir(&root).unwrap();
+    /// let tool = WriteFileTool::with_root(&root);
+    ///
+    /// tool.execute(json!({"path": "docs/notes.txt", "content": "hello\n"})).await?;
+    /// assert_eq!(std::fs::read_to_string(root.join("docs/notes.txt")).unwrap(), "hello\n");
+    ///
+    /// for path in [
+    ///     "../escape.txt".to_string(),
+    ///     "docs/../../escape.txt".to_string(),
+    ///     dir.path().join("escape.txt").display().to_string(),
+    /// ] {
+    ///     let err = tool
+    ///         .execute(json!({"path": path, "content": "gotcha", "overwrite": true}))
+    ///         .await
+    ///         .unwrap_err();
+    ///     assert!(err.to_string().contains("outside"), "{}", err);
+    /// }
+    /// assert!(!dir.path().join("escape.txt").exists());
+    ///
+    /// // Nor can a link to a file that doesn't exist yet
+    /// # #[cfg(unix)] {
+    /// std::os::unix::fs::symlink(dir.path().join("escape.txt"), root.join("link")).unwrap();
+    /// let err = tool
+    ///     .execute(json!({"path": "link", "content": "gotcha"}))
+    ///     .await
+    ///     .unwrap_err();
+    /// assert!(err.to_string().contains("outside"), "{}", err);
+    /// assert!(!dir.path().join("escape.txt").exists());
+    /// # }
+    /// # Ok::<(), claude::Error>(())
+    /// # }).unwrap();
+    /// ```
+    pub fn with_root(root: impl Into<PathBuf>) -> Self {
+        Self {
+            root: Some(root.into()),
+        }
+    }
+
+    /// The directory written paths are confined to, if any
+    pub fn root(&self) -> Option<&Path> {
+        self.root.as_deref()
+    }
+
+    /// Resolve `path` against the root, rejecting paths that leave it
+    fn resolve(&self, path: &str) -> Result<PathBuf> {
+        let Some(root) = &self.root else {
+            return Ok(PathBuf::from(path));
+        };
+        let resolved = resolve_within(root, path).map_err(|e| {
+            Error::Other(format!(
+                "Failed to resolve write root {}: {}",
+                root.display(),
+                e
+            ))
+        })?;
+        resolved.ok_or_else(|| {
+            Error::Other(format!(
+                "Refusing to write {}: it is outside the allowed root {}",
+                path,
+                root.display()
+            ))
+        })
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct WriteFileInput {
+    path: String,
+    content: String,
+    #[serde(default)]
+    overwrite: bool,
+}
+
+#[async_trait]
+impl Tool for WriteFileTool {
+    fn name(&self) -> &str {
+        "write_file"
+    }
+
+    fn description(&self) -> &str {
+        "Write content to a file, replacing it entirely. Creates missing parent directories. Fails if the file already exists unless overwrite is true; use patch_file for targeted edits to existing files."
+    }
+
+    fn input_schema(&self) -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "path": {
+                    "type": "string",
+                    "description": "The file path to write"
+                },
+                "content": {
+                    "type": "string",
+                    "description": "The complete contents of the file"
+                },
+                "overwrite": {
+                    "type": "boolean",
+                    "description": "Replace the file if it already exists (default: false)"
+                }
+            },
+            "required": ["path", "content"],
+            "additionalProperties": false
+        })
+    }
+
+    async fn execute(&self, input: Value) -> Result<String> {
+        Ok(self.execute_typed(input).await?.into_text())
+    }
+
+    async fn execute_typed(&self, input: Value) -> Result<ToolOutput> {
+        let params: WriteFileInput = serde_json::from_value(input).map_err(|e| {
+            Error::Other(format!(
+                "Invalid input parameters: {}. Example: {{\"path\": \"notes/todo.md\", \"content\": \"# TODO\\n\", \"overwrite\": false}}",
+                e
+            ))
+        })?;
+
+        let path = params.path.as_str();
+        let resolved = self.resolve(path)?;
+
+        if resolved.is_dir() {
+            return Err(Error::Other(format!(
+                "Cannot write {}: it is a directory",
+                path
+            )));
+        }
+        // A dangling symlink counts as existing; it's refused below
+        let existed = fs::symlink_metadata(&resolved).is_ok();
+        if existed && !params.overwrite {
+            return Err(Error::Other(format!(
+                "Cannot write {}: the file already exists. Set overwrite to true to replace it",
+                path
+            )));
+        }
+
+        if let Some(parent) = resolved.parent().filter(|p| !p.as_os_str().is_empty()) {
+            fs::create_dir_all(parent).map_err(|e| {
+                Error::Other(format!(
+                    "Failed to create directory {}: {}",
+                    parent.display(),
+                    e
+                ))
+            })?;
+        }
+        write_no_follow(&resolved, &params.content)
+            .map_err(|e| Error::Other(format!("Failed to write {}: {}", path, e)))?;
+
+        Ok(ToolOutput::Json(json!({
+            "path": path,
+            "bytes_written": params.content.len(),
+            "created": !existed,
+            "overwrote": existed,
+        })))
+    }
+}
@@ -1,10 +1,77 @@
-use crate::{Error, Result, Tool};
+use crate::{Error, Result, Tool, ToolOutput};
 use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
 use std::collections::HashMap;
 
 /// Z3 SMT/SAT solver tool for constraint solving and verification
+///
+/// Variables may be `Bool`, `Int`, `Real`, bitvectors (`"BitVec 32"`) or arrays
+/// (`"Array Int Int"`). Bitvector values in the returned model are reported
+/// as unsigned decimals. With `"unsat_core": true`, an unsatisfiable `solve`
+/// reports the labels (`c0`, `c1`, ...) of the conflicting constraints.
+/// Problems are solved in-process with the linked Z3 library, falling back
+/// to the `z3` binary on `PATH` if that fails.
+///
+/// # Example
+///
+/// ```rust
+/// use claude::tools::Z3SolverTool;
+/// use claude::Tool;
+/// use serde_json::json;
+///
+/// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+/// // Bitvector arithmetic wraps around: 255 + 1 overflows to 0 in 8 bits
+/// let output = Z3SolverTool
+///     .execute(json!({
+///         "variables": {"x": "BitVec 8"},
+///         "constraints": ["(= (bvadd x #x01) #x00)"]
+///     }))
+///     .await?;
+/// let response: serde_json::Value = serde_json::from_str(&output)?;
+/// assert_eq!(response["result"], "satisfiable");
+/// assert_eq!(response["model"]["x"], "255");
+///
+/// // Reading back a stored array element
+/// let output = Z3SolverTool
+///     .execute(json!({
+///         "variables": {"a": "Array Int Int", "v": "Int"},
+///         "constraints": ["(= v (select (store a 3 42) 3))"]
+///     }))
+///     .await?;
+/// let response: serde_json::Value = serde_json::from_str(&output)?;
+/// assert_eq!(response["result"], "satisfiable");
+/// assert_eq!(response["model"]["v"], "42");
+/// assert!(response["model"]["a"].is_string());
+///
+/// // Asking which constraints conflict when there is no solution
+/// let output = Z3SolverTool
+///     .execute(json!({
+///         "variables": {"x": "Int", "y": "Int"},
+///         "constraints": ["(> x 10)", "(> y 0)", "(< x 5)"],
+///         "unsat_core": true
+///     }))
+///     .await?;
+/// let response: serde_json::Value = serde_json::from_str(&output)?;
+/// assert_eq!(response["result"], "unsatisfiable");
+/// assert_eq!(response["unsat_core"], json!(["c0", "c2"]));
+///
+/// // Concurrent solves each get their own answer
+/// let solve = |value: i64| {
+///     let input = json!({
+///         "variables": {"x": "Int"},
+///         "constraints": [format!("(= x {})", value)]
+///     });
+///     tokio::spawn(async move { Z3SolverTool.execute(input).await })
+/// };
+/// let (first, second) = tokio::join!(solve(1), solve(2));
+/// let first: serde_json::Value = serde_json::from_str(&first.unwrap()?)?;
+/// let second: serde_json::Value = serde_json::from_str(&second.unwrap()?)?;
+/// assert_eq!(first["model"]["x"], "1");
+/// assert_eq!(second["model"]["x"], "2");
+/// # Ok::<(), claude::Error>(())
+/// # }).unwrap();
+/// ```
 pub struct Z3SolverTool;
 
 #[derive(Debug, Deserialize, Clone)]
@@ -17,6 +84,7 @@ struct Z3Input {
     optimize: Option<HashMap<String, String>>,
     hypothesis: Option<Vec<String>>,
     conclusion: Option<String>,
+    unsat_core: Option<bool>,
 }
 
 #[derive(Debug, Serialize)]
@@ -25,6 +93,7 @@ struct Z3Response {
     result: String,
     satisfiable: bool,
     model: Option<HashMap<String, String>>,
+    unsat_core: Option<Vec<String>>,
     execution_time_ms: u64,
     solver_info: HashMap<String, String>,
     z3_output: Option<String>,
@@ -51,10 +120,9 @@ impl Tool for Z3SolverTool {
                 },
                 "variables": {
                     "type": "object",
-                    "description": "Variable declarations as name->type pairs",
+                    "description": "Variable declarations as name->type pairs. Types: 'Bool', 'Int', 'Real', 'BitVec N' (e.g. 'BitVec 32'), or 'Array <index> <element>' (e.g. 'Array Int Int', 'Array (BitVec 8) Bool')",
                     "additionalProperties": {
-                        "type": "string",
-                        "enum": ["Bool", "Int", "Real"]
+                        "type": "string"
                     }
                 },
                 "constraints": {
@@ -95,6 +163,10 @@ impl Tool for Z3SolverTool {
                 "conclusion": {
                     "type": "string",
                     "description": "Conclusion in SMT-LIB format to prove (for 'prove' action)"
+                },
+                "unsat_core": {
+                    "type": "boolean",
+                    "description": "For 'solve'/'check_sat': if unsatisfiable, report which constraints conflict. Constraints are labelled c0, c1, ... in the order given (default: false)"
                 }
             },
             "required": [],
@@ -102,7 +174,32 @@ impl Tool for Z3SolverTool {
         })
     }
 
+    fn examples(&self) -> Vec<Value> {
+        vec![
+            json!({
+                "variables": {"x": "Int", "y": "Int"},
+                "constraints": ["(> x 0)", "(= (+ x y) 10)", "(< y x)"]
+            }),
+            json!({
+                "action": "optimize",
+                "variables": {"x": "Int"},
+                "constraints": ["(<= (* 3 x) 20)"],
+                "optimize": {"x": "maximize"}
+            }),
+            json!({
+                "action": "prove",
+                "variables": {"p": "Bool", "q": "Bool"},
+                "hypothesis": ["(=> p q)", "p"],
+                "conclusion": "q"
+            }),
+        ]
+    }
+
     async fn execute(&self, input: Value) -> Result<String> {
+        Ok(self.execute_typed(input).await?.into_text())
+    }
+
+    async fn execute_typed(&self, input: Value) -> Result<ToolOutput> {
         let start_time = std::time::Instant::now();
 
         let params: Z3Input = serde_json::from_value(input)
@@ -113,12 +210,13 @@ impl Tool for Z3SolverTool {
         let action = params.action.clone().unwrap_or_else(|| "solve".to_string());
         let timeout = params.timeout.unwrap_or(5000).min(60000);
 
-        // Use Z3 command-line interface for simplicity and thread safety
+        // Solving blocks, so run it off the async runtime. `run_z3` uses the
+        // linked Z3 library and falls back to the `z3` binary.
         let result = tokio::task::spawn_blocking(move || -> Result<Z3Response> {
             match action.as_str() {
-                "solve" | "check_sat" => Self::solve_with_z3_cli(&params, timeout),
-                "optimize" => Self::optimize_with_z3_cli(&params, timeout),
-                "prove" => Self::prove_with_z3_cli(&params, timeout),
+                "solve" | "check_sat" => Self::solve_with_z3(&params, timeout),
+                "optimize" => Self::optimize_with_z3(&params, timeout),
+                "prove" => Self::prove_with_z3(&params, timeout),
                 _ => Err(Error::Other(format!("Unknown action: {}", action))),
             }
         })
@@ -130,23 +228,25 @@ impl Tool for Z3SolverTool {
         let mut response = result;
         response.execution_time_ms = execution_time;
 
-        serde_json::to_string_pretty(&response)
+        serde_json::to_value(&response)
+            .map(ToolOutput::Json)
             .map_err(|e| Error::Other(format!("Failed to serialize response: {}", e)))
     }
 }
 
 impl Z3SolverTool {
-    fn solve_with_z3_cli(params: &Z3Input, timeout: u64) -> Result<Z3Response> {
+    fn solve_with_z3(params: &Z3Input, timeout: u64) -> Result<Z3Response> {
         let smt_program = Self::build_smt_program(params)?;
-        let output = Self::run_z3(&smt_program, timeout)?;
-
-        let satisfiable = output.contains("sat") && !output.contains("unsat");
-        let result = if satisfiable {
-            "satisfiable".to_string()
-        } else if output.contains("unsat") {
-            "unsatisfiable".to_string()
-        } else {
-            "unknown".to_string()
+        let (output, backend) = Self::run_z3(&smt_program, timeout)?;
+
+        // Only look at the check-sat answer: when cores are requested, a satisfiable
+        // problem makes (get-unsat-core) print an error that mentions "unsat"
+        let answer = output.lines().map(str::trim).find(|line| !line.is_empty());
+        let satisfiable = answer == Some("sat");
+        let result = match answer {
+            Some("sat") => "satisfiable".to_string(),
+            Some("unsat") => "unsatisfiable".to_string(),
+            _ => "unknown".to_string(),
         };
 
         // Extract model if available
@@ -156,8 +256,15 @@ impl Z3SolverTool {
             None
         };
 
+        let unsat_core = if answer == Some("unsat") && params.unsat_core.unwrap_or(false) {
+            Self::extract_unsat_core(&output)
+        } else {
+            None
+        };
+
         let mut solver_info = HashMap::new();
-        solver_info.insert("version".to_string(), "Z3 CLI".to_string());
+        solver_info.insert("version".to_string(), "Z3".to_string());
+        solver_info.insert("backend".to_string(), backend.to_string());
         solver_info.insert(
             "logic".to_string(),
             params.logic.clone().unwrap_or("AUTO".to_string()),
@@ -168,15 +275,16 @@ impl Z3SolverTool {
             result,
             satisfiable,
             model,
+            unsat_core,
             execution_time_ms: 0, // Will be set by caller
             solver_info,
             z3_output: Some(output),
         })
     }
 
-    fn optimize_with_z3_cli(params: &Z3Input, timeout: u64) -> Result<Z3Response> {
+    fn optimize_with_z3(params: &Z3Input, timeout: u64) -> Result<Z3Response> {
         let smt_program = Self::build_optimization_program(params)?;
-        let output = Self::run_z3(&smt_program, timeout)?;
+        let (output, backend) = Self::run_z3(&smt_program, timeout)?;
 
         let satisfiable = output.contains("sat") && !output.contains("unsat");
         let result = if satisfiable {
@@ -195,6 +303,7 @@ impl Z3SolverTool {
 
         let mut solver_info = HashMap::new();
         solver_info.insert("version".to_string(), "Z3 Optimize".to_string());
+        solver_info.insert("backend".to_string(), backend.to_string());
         solver_info.insert(
             "logic".to_string(),
             params.logic.clone().unwrap_or("AUTO".to_string()),
@@ -205,15 +314,16 @@ impl Z3SolverTool {
             result,
             satisfiable,
             model,
+            unsat_core: None,
             execution_time_ms: 0,
             solver_info,
             z3_output: Some(output),
         })
     }
 
-    fn prove_with_z3_cli(params: &Z3Input, timeout: u64) -> Result<Z3Response> {
+    fn prove_with_z3(params: &Z3Input, timeout: u64) -> Result<Z3Response> {
         let smt_program = Self::build_proof_program(params)?;
-        let output = Self::run_z3(&smt_program, timeout)?;
+        let (output, backend) = Self::run_z3(&smt_program, timeout)?;
 
         // For proofs, unsat means theorem is proven
         let theorem_proven = output.contains("unsat");
@@ -234,6 +344,7 @@ impl Z3SolverTool {
 
         let mut solver_info = HashMap::new();
         solver_info.insert("version".to_string(), "Z3 Theorem Prover".to_string());
+        solver_info.insert("backend".to_string(), backend.to_string());
         solver_info.insert("method".to_string(), "negation_satisfiability".to_string());
 
         Ok(Z3Response {
@@ -241,14 +352,72 @@ impl Z3SolverTool {
             result,
             satisfiable: theorem_proven,
             model,
+            unsat_core: None,
             execution_time_ms: 0,
             solver_info,
             z3_output: Some(output),
         })
     }
 
+    /// Translate a variable type such as `"BitVec 32"` or `"Array Int Int"` into an SMT-LIB sort
+    fn smt_sort(var_type: &str) -> Result<String> {
+        let tokens = var_type
+            .replace('(', " ( ")
+            .replace(')', " ) ")
+            .split_whitespace()
+            .map(str::to_string)
+            .collect::<Vec<_>>();
+        let mut pos = 0;
+        let sort = Self::parse_sort(&tokens, &mut pos)
+            .ok_or_else(|| Error::Other(format!("Unsupported variable type: {}", var_type)))?;
+        if pos != tokens.len() {
+            return Err(Error::Other(format!(
+                "Unsupported variable type: {}",
+                var_type
+            )));
+        }
+        Ok(sort)
+    }
+
+    fn parse_sort(tokens: &[String], pos: &mut usize) -> Option<String> {
+        let token = tokens.get(*pos)?.as_str();
+        *pos += 1;
+        match token {
+            "Bool" | "Int" | "Real" => Some(token.to_string()),
+            "BitVec" => {
+                let width = tokens.get(*pos)?.parse::<u32>().ok().filter(|w| *w > 0)?;
+                *pos += 1;
+                Some(format!("(_ BitVec {})", width))
+            }
+            "Array" => {
+                let index = Self::parse_sort(tokens, pos)?;
+                let element = Self::parse_sort(tokens, pos)?;
+                Some(format!("(Array {} {})", index, element))
+            }
+            "(" => {
+                // Accept the SMT-LIB spelling "(_ BitVec 32)" as well as "(BitVec 32)"
+                if tokens.get(*pos).map(String::as_str) == Some("_") {
+                    *pos += 1;
+                }
+                let sort = Self::parse_sort(tokens, pos)?;
+                if tokens.get(*pos).map(String::as_str) != Some(")") {
+                    return None;
+                }
+                *pos += 1;
+                Some(sort)
+            }
+            _ => None,
+        }
+    }
+
     fn build_smt_program(params: &Z3Input) -> Result<String> {
         let mut program = String::new();
+        let unsat_core = params.unsat_core.unwrap_or(false);
+
+        if unsat_core {
+            program.push_str("(set-option :produce-unsat-cores true)\n");
+            program.push_str("(set-option :smt.core.minimize true)\n");
+        }
 
         // Set logic
         if let Some(logic) = &params.logic {
@@ -258,24 +427,14 @@ impl Z3SolverTool {
         // Declare variables
         if let Some(variables) = &params.variables {
             for (name, var_type) in variables {
-                let smt_type = match var_type.as_str() {
-                    "Bool" => "Bool",
-                    "Int" => "Int",
-                    "Real" => "Real",
-                    _ => {
-                        return Err(Error::Other(format!(
-                            "Unsupported variable type: {}",
-                            var_type
-                        )))
-                    }
-                };
+                let smt_type = Self::smt_sort(var_type)?;
                 program.push_str(&format!("(declare-const {} {})\n", name, smt_type));
             }
         }
 
         // Add constraints - expect proper SMT-LIB format
         if let Some(constraints) = &params.constraints {
-            for constraint in constraints {
+            for (i, constraint) in constraints.iter().enumerate() {
                 // Validate basic SMT-LIB format
                 let trimmed = constraint.trim();
                 if !trimmed.starts_with('(') || !trimmed.ends_with(')') {
@@ -284,12 +443,19 @@ impl Z3SolverTool {
                         constraint
                     )));
                 }
-                program.push_str(&format!("(assert {})\n", trimmed));
+                if unsat_core {
+                    program.push_str(&format!("(assert (! {} :named c{}))\n", trimmed, i));
+                } else {
+                    program.push_str(&format!("(assert {})\n", trimmed));
+                }
             }
         }
 
         program.push_str("(check-sat)\n");
         program.push_str("(get-model)\n");
+        if unsat_core {
+            program.push_str("(get-unsat-core)\n");
+        }
 
         Ok(program)
     }
@@ -305,17 +471,7 @@ impl Z3SolverTool {
         // Declare variables
         if let Some(variables) = &params.variables {
             for (name, var_type) in variables {
-                let smt_type = match var_type.as_str() {
-                    "Bool" => "Bool",
-                    "Int" => "Int",
-                    "Real" => "Real",
-                    _ => {
-                        return Err(Error::Other(format!(
-                            "Unsupported variable type: {}",
-                            var_type
-                        )))
-                    }
-                };
+                let smt_type = Self::smt_sort(var_type)?;
                 program.push_str(&format!("(declare-const {} {})\n", name, smt_type));
             }
         }
@@ -368,17 +524,7 @@ impl Z3SolverTool {
         // Declare variables
         if let Some(variables) = &params.variables {
             for (name, var_type) in variables {
-                let smt_type = match var_type.as_str() {
-                    "Bool" => "Bool",
-                    "Int" => "Int",
-                    "Real" => "Real",
-                    _ => {
-                        return Err(Error::Other(format!(
-                            "Unsupported variable type: {}",
-                            var_type
-                        )))
-                    }
-                };
+                let smt_type = Self::smt_sort(var_type)?;
                 program.push_str(&format!("(declare-const {} {})\n", name, smt_type));
             }
         }
@@ -434,21 +580,96 @@ impl Z3SolverTool {
         Ok(program)
     }
 
+    /// Run an SMT-LIB program, returning Z3's output and the backend that produced it
+    ///
+    /// Uses the linked Z3 library and falls back to the `z3` binary if that fails.
+    fn run_z3(program: &str, timeout: u64) -> Result<(String, &'static str)> {
+        match Self::run_z3_native(program, timeout) {
+            Ok(output) => Ok((output, "native")),
+            Err(native_error) => match Self::run_z3_cli(program, timeout) {
+                Ok(output) => Ok((output, "cli")),
+                Err(cli_error) => Err(Error::Other(format!(
+                    "{} (CLI fallback also failed: {})",
+                    native_error, cli_error
+                ))),
+            },
+        }
+    }
+
+    /// Evaluate the program in a fresh in-process Z3 context
+    fn run_z3_native(program: &str, timeout: u64) -> Result<String> {
+        use std::ffi::{CStr, CString};
+        use z3_sys::*;
+
+        let script = CString::new(program)
+            .map_err(|e| Error::Other(format!("Invalid SMT-LIB program: {}", e)))?;
+        let timeout_key = CString::new("timeout").expect("static string has no NUL");
+        let timeout_value = CString::new(timeout.to_string()).expect("digits have no NUL");
+
+        // SAFETY: every pointer passed to Z3 outlives the call that uses it, the
+        // context is only used on this thread, and it is deleted before returning.
+        // Clearing the error handler makes Z3 report errors through the error code
+        // instead of aborting the process.
+        unsafe {
+            let cfg = Z3_mk_config();
+            if timeout > 0 {
+                Z3_set_param_value(cfg, timeout_key.as_ptr(), timeout_value.as_ptr());
+            }
+            let ctx = Z3_mk_context(cfg);
+            Z3_del_config(cfg);
+            Z3_set_error_handler(ctx, None);
+
+            let raw = Z3_eval_smtlib2_string(ctx, script.as_ptr());
+            let output = if raw.is_null() {
+                String::new()
+            } else {
+                CStr::from_ptr(raw).to_string_lossy().into_owned()
+            };
+
+            // As with the CLI, errors such as "model is not available" after an
+            // unsat result are part of the output rather than a failure
+            let code = Z3_get_error_code(ctx);
+            let result = if output.trim().is_empty() && code != ErrorCode::OK {
+                let msg = Z3_get_error_msg(ctx, code);
+                let msg = if msg.is_null() {
+                    format!("{:?}", code)
+                } else {
+                    CStr::from_ptr(msg).to_string_lossy().into_owned()
+                };
+                Err(Error::Other(format!("Z3 evaluation failed: {}", msg)))
+            } else {
+                Ok(output)
+            };
 
-    fn run_z3(program: &str, timeout: u64) -> Result<String> {
-        use std::fs;
+            Z3_del_context(ctx);
+            result
+        }
+    }
+
+    /// Run the program through the `z3` binary
+    fn run_z3_cli(program: &str, timeout: u64) -> Result<String> {
+        use std::io::Write;
         use std::process::{Command, Stdio};
 
-        // Write program to temporary file since Z3 -in flag doesn't work as expected
-        let temp_file = format!("/tmp/z3_input_{}.smt2", std::process::id());
-        fs::write(&temp_file, program)
+        // Write program to a uniquely named temporary file since Z3 -in flag doesn't
+        // work as expected; the file is removed when `temp_file` is dropped
+        let mut temp_file = tempfile::Builder::new()
+            .prefix("z3_input_")
+            .suffix(".smt2")
+            .tempfile()
+            .map_err(|e| Error::Other(format!("Failed to create temporary file: {}", e)))?;
+        temp_file
+            .write_all(program.as_bytes())
+            .and_then(|_| temp_file.flush())
             .map_err(|e| Error::Other(format!("Failed to write temporary file: {}", e)))?;
 
         let mut cmd = Command::new("z3");
-        cmd.arg(&temp_file);
+        cmd.arg(temp_file.path());
 
         if timeout > 0 {
-            cmd.arg(format!("-T:{}", timeout / 1000)); // Z3 timeout in seconds
+            // Soft per-query limit in milliseconds, plus a hard limit in whole seconds
+            cmd.arg(format!("-t:{}", timeout));
+            cmd.arg(format!("-T:{}", timeout.div_ceil(1000)));
         }
 
         let output = cmd
@@ -462,9 +683,6 @@ impl Z3SolverTool {
                 ))
             })?;
 
-        // Clean up temp file
-        let _ = fs::remove_file(&temp_file);
-
         let stdout = String::from_utf8_lossy(&output.stdout);
         let stderr = String::from_utf8_lossy(&output.stderr);
 
@@ -488,19 +706,16 @@ impl Z3SolverTool {
     }
 
     fn extract_model(output: &str) -> Option<HashMap<String, String>> {
+        // Z3 prints each definition as "(define-fun x () Int\n    5)", often across
+        // several lines, so read the output as s-expressions instead of line by line
         let mut model = HashMap::new();
-        let lines: Vec<&str> = output.lines().collect();
-
-        for line in lines {
-            if line.trim().starts_with("(define-fun ") {
-                // Parse Z3 model output: "(define-fun x () Int 5)"
-                let parts: Vec<&str> = line.split_whitespace().collect();
-                if parts.len() >= 5 {
-                    let var_name = parts[1].to_string();
-                    let value = parts[4].trim_end_matches(')').to_string();
-                    model.insert(var_name, value);
-                }
-            }
+        let mut definitions = Vec::new();
+        for expr in SExpr::parse_all(output) {
+            expr.collect_definitions(&mut definitions);
+        }
+
+        for (name, value) in definitions {
+            model.insert(name, Self::format_value(value));
         }
 
         if model.is_empty() {
@@ -509,4 +724,155 @@ impl Z3SolverTool {
             Some(model)
         }
     }
+
+    /// Find the `(c0 c2 ...)` list printed by `(get-unsat-core)`
+    fn extract_unsat_core(output: &str) -> Option<Vec<String>> {
+        let is_label = |atom: &str| {
+            atom.strip_prefix('c')
+                .is_some_and(|n| !n.is_empty() && n.chars().all(|c| c.is_ascii_digit()))
+        };
+
+        for expr in SExpr::parse_all(output) {
+            let SExpr::List(items) = expr else { continue };
+            if items.is_empty() {
+                continue;
+            }
+            let labels: Option<Vec<String>> = items
+                .into_iter()
+                .map(|item| match item {
+                    SExpr::Atom(atom) if is_label(&atom) => Some(atom),
+                    _ => None,
+                })
+                .collect();
+            if let Some(mut labels) = labels {
+                labels.sort_by_key(|label| label[1..].parse::<usize>().unwrap_or(usize::MAX));
+                return Some(labels);
+            }
+        }
+        None
+    }
+
+    /// Render a model value, converting bitvector literals to unsigned decimals
+    fn format_value(value: &SExpr) -> String {
+        match value {
+            SExpr::Atom(atom) => {
+                let parsed = if let Some(hex) = atom.strip_prefix("#x") {
+                    u128::from_str_radix(hex, 16).ok()
+                } else if let Some(bits) = atom.strip_prefix("#b") {
+                    u128::from_str_radix(bits, 2).ok()
+                } else {
+                    None
+                };
+                parsed.map_or_else(|| atom.clone(), |n| n.to_string())
+            }
+            // Negative numbers are printed as "(- 5)"
+            SExpr::List(items) => match items.as_slice() {
+                [SExpr::Atom(op), SExpr::Atom(n)] if op == "-" => format!("-{}", n),
+                _ => value.to_string(),
+            },
+        }
+    }
+}
+
+/// Minimal s-expression reader for Z3 output
+#[derive(Debug, Clone, PartialEq)]
+enum SExpr {
+    Atom(String),
+    List(Vec<SExpr>),
+}
+
+impl SExpr {
+    /// Parse every top-level expression in `text`, ignoring unbalanced parentheses
+    fn parse_all(text: &str) -> Vec<SExpr> {
+        let mut stack: Vec<Vec<SExpr>> = vec![Vec::new()];
+        let mut chars = text.chars().peekable();
+
+        while let Some(c) = chars.next() {
+            match c {
+                '(' => stack.push(Vec::new()),
+                ')' => {
+                    if stack.len() > 1 {
+                        let items = stack.pop().unwrap_or_default();
+                        if let Some(parent) = stack.last_mut() {
+                            parent.push(SExpr::List(items));
+                        }
+                    }
+                }
+                c if c.is_whitespace() => {}
+                '"' | '|' => {
+                    // Quoted strings and symbols may contain spaces and parentheses
+                    let mut atom = c.to_string();
+                    for next in chars.by_ref() {
+                        atom.push(next);
+                        if next == c {
+                            break;
+                        }
+                    }
+                    if let Some(current) = stack.last_mut() {
+                        current.push(SExpr::Atom(atom));
+                    }
+                }
+                _ => {
+                    let mut atom = c.to_string();
+                    while let Some(&next) = chars.peek() {
+                        if next.is_whitespace() || next == '(' || next == ')' {
+                            break;
+                        }
+                        atom.push(next);
+                        chars.next();
+                    }
+                    if let Some(current) = stack.last_mut() {
+                        current.push(SExpr::Atom(atom));
+                    }
+                }
+            }
+        }
+
+        // Close anything left open by truncated output
+        while stack.len() > 1 {
+            let items = stack.pop().unwrap_or_default();
+            if let Some(parent) = stack.last_mut() {
+                parent.push(SExpr::List(items));
+            }
+        }
+        stack.pop().unwrap_or_default()
+    }
+
+    /// Collect `(define-fun name () Sort value)` constants, skipping functions with arguments
+    fn collect_definitions<'a>(&'a self, out: &mut Vec<(String, &'a SExpr)>) {
+        if let SExpr::List(items) = self {
+            match items.as_slice() {
+                [SExpr::Atom(keyword), SExpr::Atom(name), SExpr::List(args), _sort, value]
+                    if keyword == "define-fun" =>
+                {
+                    if args.is_empty() {
+                        out.push((name.clone(), value));
+                    }
+                }
+                _ => {
+                    for item in items {
+                        item.collect_definitions(out);
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl std::fmt::Display for SExpr {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SExpr::Atom(atom) => write!(f, "{}", atom),
+            SExpr::List(items) => {
+                write!(f, "(")?;
+                for (i, item) in items.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, " ")?;
+                    }
+                    write!(f, "{}", item)?;
+                }
+                write!(f, ")")
+            }
+        }
+    }
 }
@@ -0,0 +1,206 @@
+use crate::message::{ContentBlock, Message, ToolResultContent};
+use serde_json::Value;
+use std::collections::{HashMap, HashSet};
+
+/// One logical step of a conversation
+///
+/// Produced by [`Transcript`]. Borrows from the messages it was built from.
+#[derive(Debug, Clone, PartialEq)]
+pub enum TranscriptEntry<'a> {
+    /// Text the user typed
+    UserText(&'a str),
+    /// Text Claude wrote
+    AssistantText(&'a str),
+    /// A tool call Claude made
+    ToolCall {
+        /// Tool use id, matched by the result
+        id: &'a str,
+        /// Name of the tool
+        name: &'a str,
+        /// Input Claude passed to the tool
+        input: &'a Value,
+    },
+    /// The result of a tool call
+    ToolResult {
+        /// Tool use id of the call this answers
+        id: &'a str,
+        /// Name of the tool that was called, if the call is in the transcript
+        name: Option<&'a str>,
+        /// What the tool returned
+        content: &'a [ToolResultContent],
+        /// Whether the call failed or was denied
+        is_error: bool,
+    },
+}
+
+/// A conversation read as a sequence of [`TranscriptEntry`]s
+///
+/// Walks the messages in order, turning text and tool blocks into entries
+/// and pairing each tool result with the call it answers. Images,
+/// documents, and thinking blocks are skipped.
+///
+/// # Example
+///
+/// ```rust
+/// use claude::{ContentBlock, Message, Transcript, TranscriptEntry};
+/// use serde_json::json;
+///
+/// let messages = vec![
+///     Message::user(vec!["What's the weather in Paris and Rome?".to_string().into()]),
+///     Message::assistant(vec![
+///         "Let me check both.".to_string().into(),
+///         ContentBlock::ToolUse {
+///             id: "toolu_1".to_string(),
+///             name: "weather".to_string(),
+///             input: json!({"city": "Paris"}),
+///         },
+///         ContentBlock::ToolUse {
+///             id: "toolu_2".to_string(),
+///             name: "weather".to_string(),
+///             input: json!({"city": "Rome"}),
+///         },
+///     ]),
+///     // Results can come back in any order
+///     Message::user(vec![
+///         ContentBlock::ToolResult {
+///             tool_use_id: "toolu_2".to_string(),
+///             content: vec!["Rain".to_string().into()],
+///             is_error: None,
+///             cache_control: None,
+///         },
+///         ContentBlock::ToolResult {
+///             tool_use_id: "toolu_1".to_string(),
+///             content: vec!["Service unavailable".to_string().into()],
+///             is_error: Some(true),
+///             cache_control: None,
+///         },
+///     ]),
+///     Message::assistant(vec!["Rome has rain; Paris is unknown.".to_string().into()]),
+/// ];
+///
+/// let transcript = Transcript::new(&messages);
+/// let entries: Vec<TranscriptEntry> = transcript.into_iter().collect();
+/// assert_eq!(entries.len(), 7);
+/// assert_eq!(entries[0], TranscriptEntry::UserText("What's the weather in Paris and Rome?"));
+/// assert_eq!(entries[1], TranscriptEntry::AssistantText("Let me check both."));
+/// assert!(matches!(
+///     entries[2],
+///     TranscriptEntry::ToolCall { id: "toolu_1", name: "weather", input } if input["city"] == "Paris"
+/// ));
+/// assert!(matches!(
+///     entries[4],
+///     TranscriptEntry::ToolResult { id: "toolu_2", name: Some("weather"), is_error: false, .. }
+/// ));
+/// assert_eq!(entries[6], TranscriptEntry::AssistantText("Rome has rain; Paris is unknown."));
+///
+/// // Look up a call's result by id
+/// match transcript.result_for("toolu_1") {
+///     Some(TranscriptEntry::ToolResult { content, is_error, .. }) => {
+///         assert!(is_error);
+///         assert_eq!(claude::ToolResultContent::text_of(content), "Service unavailable");
+///     }
+///     other => panic!("expected a tool result, got {:?}", other),
+/// }
+/// assert!(transcript.unanswered_calls().is_empty());
+///
+/// // A call still waiting for its result
+/// let pending = Transcript::new(&messages[..2]);
+/// assert_eq!(pending.unanswered_calls(), ["toolu_1", "toolu_2"]);
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct Transcript<'a> {
+    messages: &'a [Message],
+}
+
+impl<'a> Transcript<'a> {
+    /// Read `messages` as a transcript
+    pub fn new(messages: &'a [Message]) -> Self {
+        Self { messages }
+    }
+
+    /// Names of the tools called, by tool use id
+    fn tool_names(&self) -> HashMap<&'a str, &'a str> {
+        self.messages
+            .iter()
+            .flat_map(|message| &message.content)
+            .filter_map(|block| match block {
+                ContentBlock::ToolUse { id, name, .. } => Some((id.as_str(), name.as_str())),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Every entry of the conversation, in order
+    pub fn entries(&self) -> Vec<TranscriptEntry<'a>> {
+        let names = self.tool_names();
+        let mut entries = Vec::new();
+        for message in self.messages {
+            for block in &message.content {
+                let entry = match block {
+                    ContentBlock::Text { text } if message.role == "assistant" => {
+                        TranscriptEntry::AssistantText(text)
+                    }
+                    ContentBlock::Text { text } => TranscriptEntry::UserText(text),
+                    ContentBlock::ToolUse { id, name, input } => {
+                        TranscriptEntry::ToolCall { id, name, input }
+                    }
+                    ContentBlock::ToolResult {
+                        tool_use_id,
+                        content,
+                        is_error,
+                        ..
+                    } => TranscriptEntry::ToolResult {
+                        id: tool_use_id,
+                        name: names.get(tool_use_id.as_str()).copied(),
+                        content,
+                        is_error: *is_error == Some(true),
+                    },
+                    _ => continue,
+                };
+                entries.push(entry);
+            }
+        }
+        entries
+    }
+
+    /// The result entry answering the call with `tool_use_id`, if any
+    pub fn result_for(&self, tool_use_id: &str) -> Option<TranscriptEntry<'a>> {
+        self.entries().into_iter().find(
+            |entry| matches!(entry, TranscriptEntry::ToolResult { id, .. } if *id == tool_use_id),
+        )
+    }
+
+    /// Ids of tool calls that have no result yet, in the order they were made
+    pub fn unanswered_calls(&self) -> Vec<&'a str> {
+        let entries = self.entries();
+        let answered: HashSet<&str> = entries
+            .iter()
+            .filter_map(|entry| match entry {
+                TranscriptEntry::ToolResult { id, .. } => Some(*id),
+                _ => None,
+            })
+            .collect();
+        entries
+            .iter()
+            .filter_map(|entry| match entry {
+                TranscriptEntry::ToolCall { id, .. } if !answered.contains(id) => Some(*id),
+                _ => None,
+            })
+            .collect()
+    }
+}
+
+impl<'a> From<&'a [Message]> for Transcript<'a> {
+    fn from(messages: &'a [Message]) -> Self {
+        Self::new(messages)
+    }
+}
+
+impl<'a> IntoIterator for Transcript<'a> {
+    type Item = TranscriptEntry<'a>;
+    type IntoIter = std::vec::IntoIter<TranscriptEntry<'a>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.entries().into_iter()
+    }
+}
@@ -0,0 +1,114 @@
+use crate::error::Result;
+use async_trait::async_trait;
+use reqwest::header::HeaderMap;
+use reqwest::Method;
+
+/// An HTTP request ready to be sent to the API
+///
+/// Query parameters are already part of `url`.
+#[derive(Debug, Clone)]
+pub struct RawRequest {
+    /// HTTP method
+    pub method: Method,
+    /// Full URL, including any query string
+    pub url: String,
+    /// Request headers, including the API key
+    pub headers: HeaderMap,
+    /// Request body, usually JSON
+    pub body: Option<Vec<u8>>,
+}
+
+impl RawRequest {
+    /// Body as UTF-8 text, or an empty string if there's no body
+    pub fn body_text(&self) -> String {
+        self.body
+            .as_deref()
+            .map(|body| String::from_utf8_lossy(body).into_owned())
+            .unwrap_or_default()
+    }
+}
+
+/// An HTTP response as received from the API
+#[derive(Debug, Clone)]
+pub struct RawResponse {
+    /// HTTP status code
+    pub status: u16,
+    /// Response headers
+    pub headers: HeaderMap,
+    /// Response body
+    pub body: String,
+}
+
+impl RawResponse {
+    /// Create a response with no headers
+    pub fn new(status: u16, body: impl Into<String>) -> Self {
+        Self {
+            status,
+            headers: HeaderMap::new(),
+            body: body.into(),
+        }
+    }
+
+    /// Check if the status is 2xx
+    pub fn is_success(&self) -> bool {
+        (200..300).contains(&self.status)
+    }
+}
+
+/// How [`Claude`](crate::Claude) sends HTTP requests
+///
+/// The default is [`ReqwestTransport`]. Swap it out with
+/// [`Claude::with_transport`](crate::Claude::with_transport), for example for a
+/// [`MockTransport`](crate::test_util::MockTransport) in tests, or record
+/// real traffic with a [`RecordingTransport`](crate::test_util::RecordingTransport)
+/// and play it back with a [`ReplayTransport`](crate::test_util::ReplayTransport).
+///
+/// A transport only moves bytes: non-success statuses are returned as
+/// responses, and the client turns them into [`Error`](crate::Error)s.
+#[async_trait]
+pub trait Transport: Send + Sync {
+    /// Send a request and return the response, whatever its status
+    async fn send(&self, request: RawRequest) -> Result<RawResponse>;
+}
+
+/// [`Transport`] backed by a `reqwest::Client`
+#[derive(Debug, Clone, Default)]
+pub struct ReqwestTransport {
+    client: reqwest::Client,
+}
+
+impl ReqwestTransport {
+    /// Create a transport with a default `reqwest::Client`
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create a transport using a configured `reqwest::Client`, e.g. one with
+    /// a proxy or timeouts
+    pub fn with_client(client: reqwest::Client) -> Self {
+        Self { client }
+    }
+}
+
+#[async_trait]
+impl Transport for ReqwestTransport {
+    async fn send(&self, request: RawRequest) -> Result<RawResponse> {
+        let mut builder = self
+            .client
+            .request(request.method, request.url)
+            .headers(request.headers);
+        if let Some(body) = request.body {
+            builder = builder.body(body);
+        }
+
+        let response = builder.send().await?;
+        let status = response.status().as_u16();
+        let headers = response.headers().clone();
+        let body = response.text().await?;
+        Ok(RawResponse {
+            status,
+            headers,
+            body,
+        })
+    }
+}